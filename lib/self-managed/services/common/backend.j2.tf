@@ -0,0 +1,18 @@
+terraform {
+  backend "s3" {
+    access_key     = "{{ s3_compatible_backend_access_key }}"
+    secret_key     = "{{ s3_compatible_backend_secret_key }}"
+    bucket         = "{{ s3_compatible_backend_bucket }}"
+    key            = "{{ s3_compatible_backend_key }}"
+    region         = "{{ s3_compatible_backend_region }}"
+    endpoints      = { s3 = "{{ s3_compatible_backend_endpoint }}" }
+    use_path_style = {{ s3_compatible_backend_use_path_style }}
+
+    # the storage is not AWS, there is neither STS nor EC2 metadata to query
+    skip_credentials_validation = true
+    skip_region_validation      = true
+    skip_requesting_account_id  = true
+    skip_metadata_api_check     = true
+    skip_s3_checksum            = true
+  }
+}
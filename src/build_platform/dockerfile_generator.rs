@@ -0,0 +1,421 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+/// Name of the Dockerfile generated for zero-config builds, written at the root of the build context
+pub const GENERATED_DOCKERFILE_NAME: &str = "Dockerfile.qovery";
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum NodePackageManager {
+    Npm,
+    Yarn,
+    Pnpm,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum JavaBuildTool {
+    Maven,
+    Gradle,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Language {
+    Node(NodePackageManager),
+    Python,
+    Go,
+    Rust,
+    Java(JavaBuildTool),
+    StaticSite,
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Language::Node(_) => "Node.js",
+            Language::Python => "Python",
+            Language::Go => "Go",
+            Language::Rust => "Rust",
+            Language::Java(_) => "Java",
+            Language::StaticSite => "static site",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Detect the language of the application from the files present at the root of the build context.
+/// `hint` is the buildpack language requested by the user (i.e: `heroku/nodejs@20`), and takes precedence over the detection
+/// when it matches one of the supported languages.
+pub fn detect_language(build_context: &Path, hint: Option<&str>) -> Option<Language> {
+    let exists = |file: &str| build_context.join(file).is_file();
+    let node = || {
+        Language::Node(if exists("pnpm-lock.yaml") {
+            NodePackageManager::Pnpm
+        } else if exists("yarn.lock") {
+            NodePackageManager::Yarn
+        } else {
+            NodePackageManager::Npm
+        })
+    };
+    let java = || {
+        Language::Java(if exists("pom.xml") {
+            JavaBuildTool::Maven
+        } else {
+            JavaBuildTool::Gradle
+        })
+    };
+
+    if let Some(hint) = hint {
+        let hint = hint.split('@').next().unwrap_or_default().to_lowercase();
+        let hint = hint.rsplit('/').next().unwrap_or_default();
+        match hint {
+            "node" | "nodejs" | "javascript" | "typescript" => return Some(node()),
+            "python" => return Some(Language::Python),
+            "go" | "golang" => return Some(Language::Go),
+            "rust" => return Some(Language::Rust),
+            "java" | "jvm" | "kotlin" => return Some(java()),
+            "static" | "html" => return Some(Language::StaticSite),
+            _ => {}
+        }
+    }
+
+    if exists("package.json") {
+        Some(node())
+    } else if exists("requirements.txt") || exists("pyproject.toml") || exists("Pipfile") {
+        Some(Language::Python)
+    } else if exists("go.mod") {
+        Some(Language::Go)
+    } else if exists("Cargo.toml") {
+        Some(Language::Rust)
+    } else if exists("pom.xml") || exists("build.gradle") || exists("build.gradle.kts") {
+        Some(java())
+    } else if exists("index.html") {
+        Some(Language::StaticSite)
+    } else {
+        None
+    }
+}
+
+/// Generate a Dockerfile building the application located in `build_context`.
+/// `build_args` are declared as ARG in the build stage, so the build environment variables are available like with buildpacks.
+/// `build_secrets` are mounted as environment variables of the install and build steps, so they never end up in the image.
+/// Returns an error message when the command to start the application cannot be detected.
+pub fn generate_dockerfile(
+    language: Language,
+    build_context: &Path,
+    build_args: &[&str],
+    build_secrets: &[&str],
+) -> Result<String, String> {
+    let args = build_args.iter().map(|arg| format!("ARG {arg}\n")).collect::<String>();
+    // secret mounts exposed as env variables require the dockerfile frontend 1.10
+    let syntax = match build_secrets.is_empty() {
+        true => "",
+        false => "# syntax=docker/dockerfile:1.10\n",
+    };
+    let mounts = build_secrets
+        .iter()
+        .map(|secret| format!("--mount=type=secret,id={secret},env={secret} "))
+        .collect::<String>();
+    let web_process = procfile_web_process(build_context);
+    let start_command = |default: &str| match &web_process {
+        Some(process) => exec_form(&["sh", "-c", process]),
+        None => default.to_string(),
+    };
+
+    let dockerfile = match language {
+        Language::Node(package_manager) => {
+            let (install, run) = match package_manager {
+                NodePackageManager::Npm => ("npm ci || npm install", "npm run"),
+                NodePackageManager::Yarn => ("corepack enable && yarn install", "yarn run"),
+                NodePackageManager::Pnpm => ("corepack enable && pnpm install", "pnpm run"),
+            };
+            let build = match node_has_script(build_context, "build") {
+                true => format!("RUN {mounts}{run} build\n"),
+                false => String::new(),
+            };
+            // without start script, npm runs server.js
+            if web_process.is_none()
+                && !node_has_script(build_context, "start")
+                && !build_context.join("server.js").is_file()
+            {
+                return Err(
+                    "Cannot detect how to start your Node.js application, please add a `start` script to your package.json or a `web` process to your Procfile".to_string(),
+                );
+            }
+            let start = start_command(&exec_form(&["sh", "-c", &format!("{run} start")]));
+            format!(
+                "FROM node:22-slim
+WORKDIR /app
+{args}COPY . .
+RUN {mounts}{install}
+{build}ENV NODE_ENV=production
+RUN chown -R node:node /app
+USER node
+CMD {start}
+"
+            )
+        }
+        Language::Python => {
+            let install = if build_context.join("requirements.txt").is_file() {
+                "pip install --no-cache-dir -r requirements.txt"
+            } else if build_context.join("Pipfile").is_file() {
+                "pip install --no-cache-dir pipenv && pipenv install --system --deploy"
+            } else {
+                "pip install --no-cache-dir ."
+            };
+            let entrypoint = ["main.py", "app.py", "server.py"]
+                .into_iter()
+                .find(|file| build_context.join(file).is_file());
+            let start = match (&web_process, entrypoint) {
+                (Some(_), _) => start_command(""),
+                (None, Some(entrypoint)) => exec_form(&["python", entrypoint]),
+                (None, None) => return Err(
+                    "Cannot detect how to start your Python application, please add a main.py, app.py or server.py file, or a `web` process to your Procfile".to_string(),
+                ),
+            };
+            format!(
+                "FROM python:3.12-slim
+ENV PYTHONUNBUFFERED=1 PYTHONDONTWRITEBYTECODE=1
+WORKDIR /app
+{args}COPY . .
+RUN {mounts}{install}
+RUN useradd --create-home app && chown -R app:app /app
+USER app
+CMD {start}
+"
+            )
+        }
+        Language::Go => {
+            // distroless image has no shell to run the Procfile command
+            let start = exec_form(&["/app"]);
+            format!(
+                "FROM golang:1.23 AS builder
+WORKDIR /src
+{args}COPY go.* ./
+RUN {mounts}go mod download
+COPY . .
+RUN {mounts}CGO_ENABLED=0 go build -o /app .
+
+FROM gcr.io/distroless/static-debian12:nonroot
+COPY --from=builder /app /app
+USER nonroot:nonroot
+CMD {start}
+"
+            )
+        }
+        Language::Rust => {
+            let Some(binary) = cargo_package_name(build_context) else {
+                return Err(
+                    "Cannot detect the binary of your Rust application, please add a [package] section with a name to your Cargo.toml".to_string(),
+                );
+            };
+            let start = start_command(&exec_form(&["/app"]));
+            format!(
+                "FROM rust:1.83 AS builder
+WORKDIR /src
+{args}COPY . .
+RUN {mounts}cargo build --release && cp target/release/{binary} /app
+
+FROM debian:12-slim
+RUN apt-get update && apt-get install -y --no-install-recommends ca-certificates && rm -rf /var/lib/apt/lists/*
+COPY --from=builder /app /app
+USER nobody
+CMD {start}
+"
+            )
+        }
+        Language::Java(build_tool) => {
+            let (builder_image, build, jar) = match build_tool {
+                JavaBuildTool::Maven => ("maven:3.9-eclipse-temurin-21", "mvn -B package -DskipTests", "target/*.jar"),
+                JavaBuildTool::Gradle => ("gradle:8.10-jdk21", "gradle build -x test --no-daemon", "build/libs/*.jar"),
+            };
+            let start = start_command(&exec_form(&["java", "-jar", "/app.jar"]));
+            format!(
+                "FROM {builder_image} AS builder
+WORKDIR /src
+{args}COPY . .
+RUN {mounts}{build} && cp $(ls {jar} | grep -v -e '-plain.jar$' -e '/original-' | head -n 1) /app.jar
+
+FROM eclipse-temurin:21-jre
+COPY --from=builder /app.jar /app.jar
+USER nobody
+CMD {start}
+"
+            )
+        }
+        Language::StaticSite => {
+            // nginx-unprivileged listens on port 8080
+            "FROM nginxinc/nginx-unprivileged:1.27-alpine
+COPY . /usr/share/nginx/html
+USER nginx
+"
+            .to_string()
+        }
+    };
+
+    Ok(format!("{syntax}{dockerfile}"))
+}
+
+fn exec_form(args: &[&str]) -> String {
+    serde_json::to_string(args).unwrap_or_default()
+}
+
+// `web: <command>` entry of the Procfile, if any
+fn procfile_web_process(build_context: &Path) -> Option<String> {
+    let procfile = fs::read_to_string(build_context.join("Procfile")).ok()?;
+    procfile
+        .lines()
+        .find_map(|line| line.strip_prefix("web:"))
+        .map(|process| process.trim().to_string())
+        .filter(|process| !process.is_empty())
+}
+
+fn node_has_script(build_context: &Path, script: &str) -> bool {
+    fs::read_to_string(build_context.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .is_some_and(|package| package["scripts"][script].is_string())
+}
+
+// name of the package, which is the name of the default binary
+fn cargo_package_name(build_context: &Path) -> Option<String> {
+    let manifest = fs::read_to_string(build_context.join("Cargo.toml")).ok()?;
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            if in_package && key.trim() == "name" {
+                return Some(value.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_platform::dockerfile_linter::lint_dockerfile;
+    use crate::build_platform::dockerfile_utils::extract_dockerfile_secrets;
+    use std::collections::HashSet;
+    use tempfile::TempDir;
+
+    fn build_context(files: &[(&str, &str)]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for (name, content) in files {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let cases = [
+            (vec![("package.json", "{}")], Language::Node(NodePackageManager::Npm)),
+            (
+                vec![("package.json", "{}"), ("yarn.lock", "")],
+                Language::Node(NodePackageManager::Yarn),
+            ),
+            (
+                vec![("package.json", "{}"), ("pnpm-lock.yaml", "")],
+                Language::Node(NodePackageManager::Pnpm),
+            ),
+            (vec![("requirements.txt", "flask")], Language::Python),
+            (vec![("pyproject.toml", "")], Language::Python),
+            (vec![("go.mod", "module foo")], Language::Go),
+            (vec![("Cargo.toml", "")], Language::Rust),
+            (vec![("pom.xml", "")], Language::Java(JavaBuildTool::Maven)),
+            (vec![("build.gradle.kts", "")], Language::Java(JavaBuildTool::Gradle)),
+            (vec![("index.html", "")], Language::StaticSite),
+        ];
+
+        for (files, expected) in cases {
+            let dir = build_context(&files);
+            assert_eq!(detect_language(dir.path(), None), Some(expected), "{files:?}");
+        }
+
+        let dir = build_context(&[("README.md", "")]);
+        assert_eq!(detect_language(dir.path(), None), None);
+
+        // user hint takes precedence over the detection
+        let dir = build_context(&[("package.json", "{}"), ("requirements.txt", "")]);
+        assert_eq!(detect_language(dir.path(), Some("heroku/python@3.12")), Some(Language::Python));
+        assert_eq!(
+            detect_language(dir.path(), Some("unknown")),
+            Some(Language::Node(NodePackageManager::Npm))
+        );
+    }
+
+    #[test]
+    fn test_generated_dockerfiles_pass_the_linter() {
+        let cases = [
+            vec![(
+                "package.json",
+                r#"{"scripts": {"build": "tsc", "start": "node dist/index.js"}}"#,
+            )],
+            vec![("requirements.txt", "flask"), ("app.py", "")],
+            vec![("go.mod", "module foo")],
+            vec![("Cargo.toml", "[package]\nname = \"my-app\"\n")],
+            vec![("pom.xml", "")],
+            vec![("index.html", "")],
+        ];
+
+        for files in cases {
+            let dir = build_context(&files);
+            let language = detect_language(dir.path(), None).unwrap();
+            let dockerfile = generate_dockerfile(language, dir.path(), &["FOO"], &["NPM_TOKEN"]).unwrap();
+            assert!(lint_dockerfile(&dockerfile).is_empty(), "{dockerfile}");
+        }
+    }
+
+    #[test]
+    fn test_generate_dockerfile() {
+        let dir = build_context(&[
+            ("package.json", r#"{"scripts": {"build": "tsc"}}"#),
+            ("yarn.lock", ""),
+            ("Procfile", "web: node dist/server.js\nworker: node dist/worker.js\n"),
+        ]);
+        let dockerfile =
+            generate_dockerfile(detect_language(dir.path(), None).unwrap(), dir.path(), &["FOO", "BAR"], &[]).unwrap();
+        assert!(!dockerfile.contains("# syntax"));
+        assert!(dockerfile.contains("ARG FOO\nARG BAR\n"));
+        assert!(dockerfile.contains("RUN corepack enable && yarn install\n"));
+        assert!(dockerfile.contains("RUN yarn run build\n"));
+        assert!(dockerfile.contains(r#"CMD ["sh","-c","node dist/server.js"]"#));
+
+        let dir = build_context(&[(
+            "Cargo.toml",
+            "[workspace]\nname = \"workspace\"\n\n[package]\nversion = \"0.1.0\"\nname = \"my-app\"\n",
+        )]);
+        let dockerfile = generate_dockerfile(Language::Rust, dir.path(), &[], &["TOKEN"]).unwrap();
+        assert!(dockerfile.starts_with("# syntax=docker/dockerfile:1.10\n"));
+        assert!(dockerfile.contains("RUN --mount=type=secret,id=TOKEN,env=TOKEN cargo build --release"));
+        assert!(dockerfile.contains("cp target/release/my-app /app"));
+        assert!(dockerfile.contains(r#"CMD ["/app"]"#));
+        assert_eq!(
+            extract_dockerfile_secrets(dockerfile.as_bytes()).unwrap(),
+            HashSet::from(["TOKEN".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_generate_dockerfile_without_entrypoint() {
+        let dir = build_context(&[("requirements.txt", "flask"), ("wsgi.py", "")]);
+        let err = generate_dockerfile(Language::Python, dir.path(), &[], &[]).unwrap_err();
+        assert!(err.contains("Cannot detect how to start your Python application"));
+
+        let dir = build_context(&[("requirements.txt", "flask"), ("Procfile", "web: gunicorn wsgi:app\n")]);
+        let dockerfile = generate_dockerfile(Language::Python, dir.path(), &[], &[]).unwrap();
+        assert!(dockerfile.contains(r#"CMD ["sh","-c","gunicorn wsgi:app"]"#));
+
+        let dir = build_context(&[("package.json", r#"{"scripts": {"build": "tsc"}}"#)]);
+        assert!(generate_dockerfile(Language::Node(NodePackageManager::Npm), dir.path(), &[], &[]).is_err());
+
+        let dir = build_context(&[("Cargo.toml", "[workspace]\nmembers = [\"app\"]\n")]);
+        assert!(generate_dockerfile(Language::Rust, dir.path(), &[], &[]).is_err());
+    }
+}
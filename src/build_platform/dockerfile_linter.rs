@@ -0,0 +1,453 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+/// A Dockerfile instruction, with its line continuations already joined
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Instruction {
+    // line number (starting at 1) where the instruction begins
+    pub line: usize,
+    // instruction keyword, always uppercase (i.e: FROM, RUN, ...)
+    pub keyword: String,
+    pub arguments: String,
+}
+
+impl Instruction {
+    /// Arguments of the instruction without its leading flags (i.e: `--platform=...`, `--chown=...`)
+    fn arguments_without_flags(&self) -> Vec<&str> {
+        self.arguments
+            .split_whitespace()
+            .skip_while(|arg| arg.starts_with("--"))
+            .collect()
+    }
+}
+
+/// Parse the instructions of a Dockerfile content
+/// Comments and empty lines are dropped, and lines ending with `\` are joined with the next one
+pub fn parse_dockerfile(dockerfile_content: &str) -> Vec<Instruction> {
+    let mut instructions = vec![];
+    let mut current: Option<(usize, String)> = None;
+
+    for (idx, line) in dockerfile_content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (continues, content) = match trimmed.strip_suffix('\\') {
+            Some(content) => (true, content.trim_end()),
+            None => (false, trimmed),
+        };
+
+        let (line_number, mut text) = current.take().unwrap_or((idx + 1, String::new()));
+        if !text.is_empty() && !content.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(content);
+
+        if continues {
+            current = Some((line_number, text));
+        } else if let Some(instruction) = to_instruction(line_number, &text) {
+            instructions.push(instruction);
+        }
+    }
+
+    // Dangling line continuation at the end of the file
+    if let Some((line_number, text)) = current {
+        instructions.extend(to_instruction(line_number, &text));
+    }
+
+    instructions
+}
+
+fn to_instruction(line: usize, text: &str) -> Option<Instruction> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let (keyword, arguments) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    Some(Instruction {
+        line,
+        keyword: keyword.to_uppercase(),
+        arguments: arguments.trim().to_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    LatestBaseImage,
+    MissingUser,
+    RemoteAdd,
+    SecretInEnv,
+    AptCacheNotCleaned,
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rule = match self {
+            LintRule::LatestBaseImage => "latest_base_image",
+            LintRule::MissingUser => "missing_user",
+            LintRule::RemoteAdd => "remote_add",
+            LintRule::SecretInEnv => "secret_in_env",
+            LintRule::AptCacheNotCleaned => "apt_cache_not_cleaned",
+        };
+        f.write_str(rule)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for LintFinding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {} ({})", self.line, self.message, self.rule)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LintLevel {
+    // Dockerfile is not analyzed
+    Disabled,
+    // Findings are only reported as warnings
+    #[default]
+    Warn,
+    // Findings of the enforced rules fail the build
+    Error,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct DockerfileLintPolicy {
+    pub level: LintLevel,
+    // rules failing the build with LintLevel::Error, all rules are enforced when empty
+    pub enforced_rules: BTreeSet<LintRule>,
+}
+
+impl DockerfileLintPolicy {
+    pub fn is_enforced(&self, rule: LintRule) -> bool {
+        self.level == LintLevel::Error && (self.enforced_rules.is_empty() || self.enforced_rules.contains(&rule))
+    }
+}
+
+// Suffixes of variable names holding secrets, i.e: DB_PASSWORD, GITHUB_TOKEN, AWS_SECRET_ACCESS_KEY
+const SECRET_NAME_SUFFIXES: [&str; 8] = [
+    "PASSWORD",
+    "PASSWD",
+    "SECRET",
+    "TOKEN",
+    "API_KEY",
+    "PRIVATE_KEY",
+    "ACCESS_KEY",
+    "SECRET_KEY",
+];
+
+/// Run all the lint rules against the Dockerfile content
+pub fn lint_dockerfile(dockerfile_content: &str) -> Vec<LintFinding> {
+    let instructions = parse_dockerfile(dockerfile_content);
+
+    let mut findings = vec![];
+    let mut stage_names: Vec<String> = vec![];
+    for instruction in &instructions {
+        match instruction.keyword.as_str() {
+            "FROM" => {
+                findings.extend(check_base_image(instruction, &stage_names));
+                let args = instruction.arguments_without_flags();
+                if let (Some(as_keyword), Some(stage_name)) = (args.get(1), args.get(2)) {
+                    if as_keyword.eq_ignore_ascii_case("as") {
+                        stage_names.push(stage_name.to_lowercase());
+                    }
+                }
+            }
+            "ADD" => findings.extend(check_remote_add(instruction)),
+            "ENV" => findings.extend(check_secret_in_env(instruction)),
+            "RUN" => findings.extend(check_apt_cache(instruction)),
+            _ => {}
+        }
+    }
+    findings.extend(check_final_user(&instructions));
+
+    findings.sort_by_key(|finding| finding.line);
+    findings
+}
+
+fn check_base_image(instruction: &Instruction, stage_names: &[String]) -> Option<LintFinding> {
+    let image = *instruction.arguments_without_flags().first()?;
+
+    // scratch, previous build stages and images coming from build args cannot be checked
+    // Images pinned by digest are fine too
+    if image.eq_ignore_ascii_case("scratch")
+        || stage_names.contains(&image.to_lowercase())
+        || image.contains('$')
+        || image.contains('@')
+    {
+        return None;
+    }
+
+    // The registry host may contain a port, so only look for the tag in the last path segment
+    let last_segment = image.rsplit('/').next().unwrap_or(image);
+    let tag = last_segment.split_once(':').map(|(_, tag)| tag);
+    match tag {
+        Some(tag) if tag != "latest" => None,
+        _ => Some(LintFinding {
+            rule: LintRule::LatestBaseImage,
+            line: instruction.line,
+            message: format!("base image `{image}` is not pinned to a version, builds are not reproducible"),
+        }),
+    }
+}
+
+fn check_remote_add(instruction: &Instruction) -> Option<LintFinding> {
+    let args = instruction.arguments_without_flags();
+    // Last argument is the destination
+    let (_, sources) = args.split_last()?;
+    let remote_source = sources
+        .iter()
+        .find(|source| source.starts_with("http://") || source.starts_with("https://"))?;
+
+    Some(LintFinding {
+        rule: LintRule::RemoteAdd,
+        line: instruction.line,
+        message: format!(
+            "ADD downloads `{remote_source}` without checksum verification, prefer RUN curl/wget with an integrity check"
+        ),
+    })
+}
+
+fn check_secret_in_env(instruction: &Instruction) -> Vec<LintFinding> {
+    let names: Vec<&str> = match instruction.arguments.split_once(char::is_whitespace) {
+        // Legacy `ENV KEY value` syntax
+        Some((name, _)) if !name.contains('=') => vec![name],
+        _ => instruction
+            .arguments
+            .split_whitespace()
+            .filter_map(|pair| pair.split_once('='))
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(name, _)| name)
+            .collect(),
+    };
+
+    names
+        .into_iter()
+        .filter(|name| {
+            let name = name.to_uppercase();
+            SECRET_NAME_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+        })
+        .map(|name| LintFinding {
+            rule: LintRule::SecretInEnv,
+            line: instruction.line,
+            message: format!(
+                "ENV {name} looks like a secret and is stored in the image layers, use `RUN --mount=type=secret,id={name}` instead"
+            ),
+        })
+        .collect()
+}
+
+fn check_apt_cache(instruction: &Instruction) -> Option<LintFinding> {
+    let command = &instruction.arguments;
+    let installs = command.contains("apt-get install") || command.contains("apt install");
+    let cleans = command.contains("rm -rf /var/lib/apt/lists");
+    let uses_cache_mount = command.contains("--mount=type=cache")
+        && (command.contains("/var/lib/apt") || command.contains("/var/cache/apt"));
+    if !installs || cleans || uses_cache_mount {
+        return None;
+    }
+
+    Some(LintFinding {
+        rule: LintRule::AptCacheNotCleaned,
+        line: instruction.line,
+        message: "apt lists are left in the image, add `&& rm -rf /var/lib/apt/lists/*` to the same RUN instruction"
+            .to_string(),
+    })
+}
+
+fn check_final_user(instructions: &[Instruction]) -> Option<LintFinding> {
+    let final_stage_idx = instructions.iter().rposition(|i| i.keyword == "FROM")?;
+    let final_stage = &instructions[final_stage_idx..];
+
+    match final_stage.iter().rev().find(|i| i.keyword == "USER") {
+        None => Some(LintFinding {
+            rule: LintRule::MissingUser,
+            line: final_stage[0].line,
+            message: "no USER instruction in the final stage, the container will run as root".to_string(),
+        }),
+        Some(user) => {
+            let name = user.arguments.split(':').next().unwrap_or_default();
+            match name == "root" || name == "0" {
+                true => Some(LintFinding {
+                    rule: LintRule::MissingUser,
+                    line: user.line,
+                    message: "the final stage runs as root, the container will run as root".to_string(),
+                }),
+                false => None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(findings: &[LintFinding]) -> Vec<LintRule> {
+        findings.iter().map(|finding| finding.rule).collect()
+    }
+
+    #[test]
+    fn test_parse_dockerfile() {
+        let dockerfile = "
+# syntax=docker/dockerfile:1
+FROM node:20 as builder
+
+run apt-get update \\
+    # comment inside a continuation
+    && apt-get install -y curl \\
+    && rm -rf /var/lib/apt/lists/*
+COPY . .
+";
+
+        let instructions = parse_dockerfile(dockerfile);
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(
+            instructions[0],
+            Instruction {
+                line: 3,
+                keyword: "FROM".to_string(),
+                arguments: "node:20 as builder".to_string(),
+            }
+        );
+        assert_eq!(instructions[1].line, 5);
+        assert_eq!(instructions[1].keyword, "RUN");
+        assert_eq!(
+            instructions[1].arguments,
+            "apt-get update && apt-get install -y curl && rm -rf /var/lib/apt/lists/*"
+        );
+        assert_eq!(instructions[2].line, 9);
+        assert_eq!(instructions[2].keyword, "COPY");
+    }
+
+    #[test]
+    fn test_lint_clean_dockerfile() {
+        let dockerfile = "
+FROM --platform=$BUILDPLATFORM golang:1.22 AS builder
+RUN go build -o /app
+FROM builder AS tester
+RUN go test ./...
+FROM localhost:5000/distroless/static:nonroot
+COPY --from=builder /app /app
+USER 65532:65532
+";
+
+        assert!(lint_dockerfile(dockerfile).is_empty());
+    }
+
+    #[test]
+    fn test_lint_latest_base_image() {
+        let dockerfile = "
+FROM node
+FROM node:latest
+FROM localhost:5000/node
+FROM node@sha256:1234
+FROM $BASE_IMAGE
+FROM scratch
+USER app
+";
+
+        let findings = lint_dockerfile(dockerfile);
+        assert_eq!(rules(&findings), vec![LintRule::LatestBaseImage; 3]);
+        assert_eq!(findings.iter().map(|f| f.line).collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_lint_missing_user() {
+        // USER in a previous stage does not count
+        let dockerfile = "
+FROM node:20 AS builder
+USER node
+FROM node:20-slim
+COPY --from=builder /app /app
+";
+        let findings = lint_dockerfile(dockerfile);
+        assert_eq!(rules(&findings), vec![LintRule::MissingUser]);
+        assert_eq!(findings[0].line, 4);
+
+        let dockerfile = "
+FROM node:20
+USER node
+USER root:root
+";
+        let findings = lint_dockerfile(dockerfile);
+        assert_eq!(rules(&findings), vec![LintRule::MissingUser]);
+        assert_eq!(findings[0].line, 4);
+    }
+
+    #[test]
+    fn test_lint_remote_add() {
+        let dockerfile = "
+FROM node:20
+ADD --chown=node https://example.com/archive.tar.gz /tmp/
+ADD archive.tar.gz /tmp/
+COPY https-config.json /etc/
+USER node
+";
+
+        let findings = lint_dockerfile(dockerfile);
+        assert_eq!(rules(&findings), vec![LintRule::RemoteAdd]);
+        assert_eq!(findings[0].line, 3);
+    }
+
+    #[test]
+    fn test_lint_secret_in_env() {
+        let dockerfile = "
+FROM node:20
+ENV NODE_ENV=production DB_PASSWORD=$DB_PASSWORD
+ENV GITHUB_TOKEN ghp_1234
+ENV API_KEY=
+ENV TOKEN_URL=https://example.com PORT=8080
+USER node
+";
+
+        let findings = lint_dockerfile(dockerfile);
+        assert_eq!(rules(&findings), vec![LintRule::SecretInEnv; 2]);
+        assert!(findings[0].message.contains("DB_PASSWORD"));
+        assert!(findings[1].message.contains("GITHUB_TOKEN"));
+    }
+
+    #[test]
+    fn test_lint_apt_cache_not_cleaned() {
+        let dockerfile = "
+FROM debian:12
+RUN apt-get update && apt-get install -y curl
+RUN apt-get update && apt-get install -y git && rm -rf /var/lib/apt/lists/*
+RUN --mount=type=cache,target=/var/cache/apt apt-get update && apt-get install -y jq
+USER nobody
+";
+
+        let findings = lint_dockerfile(dockerfile);
+        assert_eq!(rules(&findings), vec![LintRule::AptCacheNotCleaned]);
+        assert_eq!(findings[0].line, 3);
+    }
+
+    #[test]
+    fn test_lint_policy() {
+        let mut policy = DockerfileLintPolicy::default();
+        assert!(!policy.is_enforced(LintRule::MissingUser));
+
+        policy.level = LintLevel::Error;
+        assert!(policy.is_enforced(LintRule::MissingUser));
+        assert!(policy.is_enforced(LintRule::RemoteAdd));
+
+        policy.enforced_rules = BTreeSet::from([LintRule::RemoteAdd]);
+        assert!(!policy.is_enforced(LintRule::MissingUser));
+        assert!(policy.is_enforced(LintRule::RemoteAdd));
+
+        let rule: LintRule = serde_json::from_str("\"apt_cache_not_cleaned\"").unwrap();
+        assert_eq!(rule, LintRule::AptCacheNotCleaned);
+        assert_eq!(rule.to_string(), "apt_cache_not_cleaned");
+    }
+}
@@ -32,6 +32,48 @@ pub fn extract_dockerfile_args(dockerfile_content: Vec<u8>) -> Result<HashSet<St
     Ok(used_args)
 }
 
+/// Extract secret ids mounted by RUN instructions from a Dockerfile content
+/// E.g
+/// ```dockerfile
+/// FROM node
+///
+/// RUN --mount=type=secret,id=NPM_TOKEN npm ci
+/// RUN --mount=type=secret,target=/run/secrets/API_KEY ./fetch.sh
+/// ...
+/// ```
+///
+/// will return a set of "NPM_TOKEN" and "API_KEY" strings. When no id is specified, buildkit defaults it to the basename of the target path
+pub fn extract_dockerfile_secrets(dockerfile_content: &[u8]) -> Result<HashSet<String>, Utf8Error> {
+    let content = std::str::from_utf8(dockerfile_content)?;
+
+    let used_secrets = content
+        .split_whitespace()
+        .filter_map(|token| token.strip_prefix("--mount="))
+        .filter_map(|mount| {
+            let options: Vec<(&str, &str)> = mount
+                .trim_matches(|c| c == '"' || c == '\'')
+                .split(',')
+                .filter_map(|opt| opt.split_once('='))
+                .collect();
+            if !options.iter().any(|(k, v)| *k == "type" && *v == "secret") {
+                return None;
+            }
+
+            let id = options.iter().find(|(k, _)| *k == "id").map(|(_, v)| v.to_string());
+            id.or_else(|| {
+                options
+                    .iter()
+                    .find(|(k, _)| matches!(*k, "target" | "dst" | "destination"))
+                    .and_then(|(_, v)| v.rsplit('/').next())
+                    .map(|v| v.to_string())
+            })
+        })
+        .filter(|id| !id.is_empty())
+        .collect::<HashSet<String>>();
+
+    Ok(used_secrets)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,6 +109,38 @@ mod tests {
         assert_eq!(res.unwrap().len(), 0);
     }
 
+    #[test]
+    fn test_extract_dockerfile_secrets() {
+        let dockerfile = b"
+        FROM node
+
+        ARG foo
+        RUN --mount=type=secret,id=NPM_TOKEN npm ci
+        RUN --mount=type=cache,target=/root/.npm \\
+            --mount=type=secret,target=/run/secrets/API_KEY,required=true ./fetch.sh
+        RUN --mount=type=secret,id=aws,env=AWS_ACCESS_KEY_ID aws s3 ls
+        RUN --mount=type=bind,source=.,target=/src ls
+        COPY . .
+        ";
+
+        let res = extract_dockerfile_secrets(dockerfile).unwrap();
+        assert_eq!(res.len(), 3);
+        assert!(res.contains("NPM_TOKEN"));
+        assert!(res.contains("API_KEY"));
+        assert!(res.contains("aws"));
+
+        let dockerfile = b"
+        FROM node
+
+        ARG NPM_TOKEN
+        COPY . .
+        RUN ls -lh
+        ";
+
+        let res = extract_dockerfile_secrets(dockerfile);
+        assert_eq!(res.unwrap().len(), 0);
+    }
+
     #[test]
     fn test_match_used_env_var_args() {
         let dockerfile = b"
@@ -2,31 +2,23 @@
 
 use std::io::{Error, ErrorKind};
 use std::num::NonZeroUsize;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{fs, thread};
 
-use git2::{Cred, CredentialType, ErrorClass};
 use itertools::Itertools;
-use retry::delay::Fibonacci;
-use retry::OperationResult;
 use time::Instant;
 use uuid::Uuid;
 
-use crate::build_platform::dockerfile_utils::extract_dockerfile_args;
-use crate::build_platform::{to_build_error, Build, BuildError, BuildPlatform, Kind};
+use crate::build_platform::{attestation, to_build_error, utils, Build, BuildError, BuildOutcome, BuildPlatform, Kind};
 use crate::cmd::command::CommandError::Killed;
 use crate::cmd::command::{CommandKiller, ExecutableCommand, QoveryCommand};
 use crate::cmd::docker::{Architecture, BuilderHandle, ContainerImage};
-use crate::cmd::git_lfs::{GitLfs, GitLfsError};
 use crate::cmd::{command, docker};
 use crate::deployment_report::logger::EnvLogger;
 
-use crate::fs::workspace_directory;
-use crate::git;
-use crate::io_models::container::Registry;
 use crate::io_models::context::Context;
 use crate::metrics_registry::{MetricsRegistry, StepLabel, StepName, StepStatus};
 use crate::models::abort::Abort;
@@ -40,15 +32,6 @@ const BUILDPACKS_BUILDERS: [&str; 1] = [
     //"paketobuildpacks/builder:base",
 ];
 
-const DOCKER_IGNORE: &str = r#"
-# Ignore all logs
-*.log
-
-# Ignore git repository files
-.git
-.gitignore
-"#;
-
 /// use Docker in local
 pub struct LocalDocker {
     context: Context,
@@ -59,9 +42,6 @@ pub struct LocalDocker {
     metrics_registry: Box<dyn MetricsRegistry>,
 }
 
-const MAX_GIT_LFS_SIZE_GB: u64 = 5;
-const MAX_GIT_LFS_SIZE_KB: u64 = MAX_GIT_LFS_SIZE_GB * 1024 * 1024; // 5GB
-
 impl LocalDocker {
     pub fn new(
         context: Context,
@@ -95,7 +75,7 @@ impl LocalDocker {
         logger: &EnvLogger,
         metrics_registry: Arc<dyn MetricsRegistry>,
         abort: &dyn Abort,
-    ) -> Result<(), BuildError> {
+    ) -> Result<BuildOutcome, BuildError> {
         // Going to inject only env var that are used by the dockerfile
         // so extracting it and modifying the image tag and env variables
         let build_record =
@@ -105,20 +85,17 @@ impl LocalDocker {
             action_description: "reading dockerfile content".to_string(),
             raw_error: err,
         })?;
-        let dockerfile_args = match extract_dockerfile_args(dockerfile_content) {
-            Ok(dockerfile_args) => dockerfile_args,
-            Err(err) => {
-                build_record.stop(StepStatus::Error);
-                return Err(BuildError::InvalidConfig {
-                    application: build.image.service_id.clone(),
-                    raw_error_message: format!("Cannot extract env vars from your dockerfile {err}"),
-                });
-            }
-        };
+        if let Err(err) = utils::check_dockerfile_policy(build, &dockerfile_content, logger) {
+            build_record.stop(StepStatus::Error);
+            return Err(err);
+        }
 
         // Keep only the env variables we want for our build
         // and force re-compute the image tag
-        build.environment_variables.retain(|k, _| dockerfile_args.contains(k));
+        if let Err(err) = utils::retain_dockerfile_variables(build, dockerfile_content) {
+            build_record.stop(StepStatus::Error);
+            return Err(err);
+        }
         build.compute_image_tag();
 
         // Prepare image we want to build
@@ -128,8 +105,7 @@ impl LocalDocker {
             vec![build.image.tag.clone(), "latest".to_string()],
         );
 
-        let image_cache =
-            ContainerImage::new(build.image.registry_url.clone(), build.image.name(), vec!["cache".to_string()]);
+        let build_cache = build.build_cache();
 
         // Check if the image does not exist already remotely, if yes, we skip the build
         let image_name = image_to_build.image_name();
@@ -138,65 +114,17 @@ impl LocalDocker {
             logger.send_progress(format!("🎯 Skipping build. Image already exists in the registry {image_name}"));
             build_record.stop(StepStatus::Skip);
             // skip build
-            return Ok(());
+            return Ok(BuildOutcome::AlreadyExists);
         }
 
         logger.send_progress(format!("⛏️ Building image. It does not exist remotely {image_name}"));
 
         // login if there are some private registries used
-        for registry in &build.registries {
-            // TODO(benjaminch): To handle GCP Artifact Registry login, credentials to be injected, maybe this whole login should be done later on or delegated to container registry objects
-            // Method to be called for GCP: cmd::docker::Docker::login_artifact_registry()
-            if let Registry::GcpArtifactRegistry { url, .. } = registry {
-                logger.send_warning(format!(
-                    "Skipping logging at this step for Artifact Registry `{}`",
-                    url.host_str().unwrap_or_default()
-                ));
-                continue;
-            }
-
-            let url = registry
-                .get_url_with_credentials()
-                .map_err(|_| BuildError::CannotGetCredentials {
-                    raw_error_message: "Cannot get the registry credentials".to_string(),
-                })?;
-            if url.password().is_none() {
-                continue;
-            }
-
-            logger.send_progress(format!(
-                "🔓 Login to registry {} as user {}",
-                url.host_str().unwrap_or_default(),
-                url.username()
-            ));
-
-            let login_ret = retry::retry(Fibonacci::from(Duration::from_secs(1)).take(4), || {
-                self.context.docker.login(&url).map_err(|err| {
-                    logger.send_warning("🔓 Retrying to login to registry due to error...".to_string());
-                    err
-                })
-            });
-
-            if let Err(err) = login_ret {
-                logger.send_warning(format!(
-                    "❌ Failed to login to registry {} due to {}",
-                    url.host_str().unwrap_or_default(),
-                    err
-                ));
-                let err = BuildError::DockerError {
-                    application: build.image.service_id.clone(),
-                    raw_error: err.error,
-                };
-                return Err(err);
-            }
-        }
+        utils::login_to_registries(&self.context.docker, build, logger)?;
 
         // Actually do the build of the image
-        let env_vars: Vec<(&str, &str)> = build
-            .environment_variables
-            .iter()
-            .map(|(k, v)| (k.as_str(), v.as_str()))
-            .collect();
+        let env_vars = build.build_args();
+        let secrets = build.build_secrets();
 
         let arch: Vec<Architecture> = build
             .architectures
@@ -213,7 +141,8 @@ impl LocalDocker {
             Path::new(into_dir_docker_style),
             &image_to_build,
             &env_vars,
-            &image_cache,
+            &secrets,
+            &build_cache,
             true,
             &arch,
             &mut |line| logger.send_progress(line),
@@ -226,7 +155,9 @@ impl LocalDocker {
             return Err(to_build_error(build.image.service_id.clone(), err));
         }
         build_record.stop(StepStatus::Success);
-        Ok(())
+
+        attestation::attest_pushed_image(build, self.context.docker.config_path(), logger, metrics_registry, abort)?;
+        Ok(BuildOutcome::Built)
     }
 
     fn provision_builder(
@@ -377,9 +308,9 @@ impl LocalDocker {
             buildpacks_args.extend(vec!["--path", into_dir_docker_style]);
 
             let mut args_buffer = Vec::with_capacity(build.environment_variables.len());
-            for (key, value) in &build.environment_variables {
+            for (key, variable) in &build.environment_variables {
                 args_buffer.push("--env".to_string());
-                args_buffer.push(format!("{key}={value}"));
+                args_buffer.push(format!("{key}={}", variable.value));
             }
             buildpacks_args.extend(args_buffer.iter().map(|value| value.as_str()).collect::<Vec<&str>>());
 
@@ -459,19 +390,6 @@ impl LocalDocker {
             }),
         }
     }
-
-    fn get_repository_build_root_path(&self, build: &Build) -> Result<PathBuf, BuildError> {
-        workspace_directory(
-            self.context.workspace_root_dir(),
-            self.context.execution_id(),
-            format!("build/{}", build.image.service_id.as_str()),
-        )
-        .map_err(|err| BuildError::IoError {
-            application: build.image.service_id.clone(),
-            action_description: "when creating build workspace".to_string(),
-            raw_error: err,
-        })
-    }
 }
 
 impl BuildPlatform for LocalDocker {
@@ -497,7 +415,7 @@ impl BuildPlatform for LocalDocker {
         logger: &EnvLogger,
         metrics_registry: Arc<dyn MetricsRegistry>,
         abort: &dyn Abort,
-    ) -> Result<(), BuildError> {
+    ) -> Result<BuildOutcome, BuildError> {
         // check if we should already abort the task
         if abort.status().should_cancel() {
             return Err(BuildError::Aborted {
@@ -505,226 +423,20 @@ impl BuildPlatform for LocalDocker {
             });
         }
 
-        // LOGGING
-        let repository_root_path = self.get_repository_build_root_path(build)?;
-        logger.send_progress(format!("📥 Cloning repository {}", build.git_repository.url));
-
-        // Retrieve git credentials
-        let git_user_creds = match build.git_repository.credentials() {
-            None => None,
-            Some(Ok(creds)) => Some(creds),
-            Some(Err(err)) => {
-                logger.send_warning(format!("🗝️ Unable to get credentials for git repository: {err}"));
-                None
-            }
-        };
-
-        // Create callback that will be called by git to provide credentials per user
-        // If people use submodule, they need to provide us their ssh key
-        let get_credentials = |user: &str| {
-            let mut creds: Vec<(CredentialType, Cred)> = Vec::with_capacity(build.git_repository.ssh_keys.len() + 1);
-            for ssh_key in build.git_repository.ssh_keys.iter() {
-                let public_key = ssh_key.public_key.as_deref();
-                let passphrase = ssh_key.passphrase.as_deref();
-                if let Ok(cred) = Cred::ssh_key_from_memory(user, public_key, &ssh_key.private_key, passphrase) {
-                    creds.push((CredentialType::SSH_MEMORY, cred));
-                }
-            }
-
-            if let Some(git_creds) = &git_user_creds {
-                creds.push((
-                    CredentialType::USER_PASS_PLAINTEXT,
-                    Cred::userpass_plaintext(&git_creds.login, &git_creds.password).unwrap(),
-                ));
-            }
-
-            creds
-        };
-
-        // Cleanup, mono repo can require to clone multiple time the same repo
-        // FIXME: re-use the same repo and just checkout at the correct commit
-        if repository_root_path.exists() {
-            let app_id = build.image.service_id.clone();
-            fs::remove_dir_all(&repository_root_path).map_err(|err| BuildError::IoError {
-                application: app_id,
-                action_description: "cleaning old repository".to_string(),
-                raw_error: err,
-            })?;
-        }
-
-        // Do the real git clone
-        let git_clone_record =
-            metrics_registry.start_record(build.image.service_long_id, StepLabel::Service, StepName::GitClone);
-        if let Err(error) = retry::retry(retry::delay::Fixed::from_millis(10_000).take(3), || {
-            if let Err(BuildError::GitError {
-                application: _,
-                git_cmd,
-                context,
-                raw_error,
-            }) = git::clone_at_commit(
-                &build.git_repository.url,
-                &build.git_repository.commit_id,
-                &repository_root_path,
-                &get_credentials,
-            ) {
-                let message = raw_error.message();
-                let git_error_class = raw_error.class();
-                // Some errors can happen "randomly":
-                // - SSL error: syscall failure: Resource temporarily unavailable
-                // - Timeout on git clone
-                debug!("Error on git clone: git_error_class={:?}, message={}", git_error_class, message);
-                return if git_error_class == ErrorClass::Os
-                    || git_error_class == ErrorClass::Ssl
-                    || (git_error_class == ErrorClass::Net && message.contains("timed out"))
-                {
-                    debug!("Retrying git clone...");
-                    logger.send_warning(format!(
-                        "⚠️ Retrying cloning your git repository, due to following error: {}",
-                        message
-                    ));
-                    OperationResult::Retry(BuildError::GitError {
-                        application: build.image.service_id.clone(),
-                        git_cmd,
-                        context,
-                        raw_error,
-                    })
-                } else {
-                    OperationResult::Err(BuildError::GitError {
-                        application: build.image.service_id.clone(),
-                        git_cmd,
-                        context,
-                        raw_error,
-                    })
-                };
-            }
-            OperationResult::Ok(())
-        }) {
-            git_clone_record.stop(StepStatus::Error);
-            return Err(error.error);
-        }
-        git_clone_record.stop(StepStatus::Success);
-
+        let repository_root_path = utils::get_repository_build_root_path(&self.context, build)?;
         let _git_cleanup = scopeguard::guard(&repository_root_path, |path| {
             info!("Removing git repository at path: {:?}", path);
             let _ = fs::remove_dir_all(path);
         });
-
-        if abort.status().should_cancel() {
-            return Err(BuildError::Aborted {
-                application: build.image.service_id.clone(),
-            });
-        }
-
-        let app_id = build.image.service_id.clone();
-
-        // Fetch git-lfs/big files for the repository if necessary
-        let git_lfs = if let Some(creds) = git_user_creds {
-            GitLfs::new(creds.login, creds.password)
-        } else {
-            GitLfs::default()
-        };
-        let cmd_killer = CommandKiller::from_cancelable(abort);
-        let size_estimate_kb = git_lfs
-            .files_size_estimate_in_kb(&repository_root_path, &build.git_repository.commit_id, &cmd_killer)
-            .unwrap_or(0);
-
-        if size_estimate_kb > 0 {
-            if size_estimate_kb > MAX_GIT_LFS_SIZE_KB {
-                return Err(BuildError::InvalidConfig {
-                    application: app_id,
-                    raw_error_message: format!(
-                        "GIT LFS files size are too big and are over the max allowed size of {MAX_GIT_LFS_SIZE_GB} GB"
-                    ),
-                });
-            }
-
-            info!("fetching git-lfs files");
-            logger.send_progress("🗜️ Fetching git-lfs files for repository".to_string());
-            match git_lfs.checkout_files_for_commit(&repository_root_path, &build.git_repository.commit_id, &cmd_killer)
-            {
-                Ok(_) => {}
-                Err(GitLfsError::Aborted { .. }) => return Err(BuildError::Aborted { application: app_id }),
-                Err(GitLfsError::Timeout { .. }) => return Err(BuildError::Aborted { application: app_id }),
-                Err(GitLfsError::ExecutionError { raw_error }) => {
-                    return Err(BuildError::IoError {
-                        application: app_id,
-                        action_description: "git lfs checkout".to_string(),
-                        raw_error,
-                    })
-                }
-                Err(GitLfsError::ExitStatusError { .. }) => {
-                    return Err(BuildError::IoError {
-                        application: app_id,
-                        action_description: "git lfs checkout".to_string(),
-                        raw_error: Error::new(ErrorKind::Other, "git lfs checkout failed"),
-                    })
-                }
-            }
-        }
-
-        // Check that the build context is correct
-        let build_context_path = repository_root_path.join(&build.git_repository.root_path);
-        if !build_context_path.is_dir() {
-            return Err(BuildError::InvalidConfig {
-                application: app_id,
-                raw_error_message: format!(
-                    "Specified build context path {:?} does not exist within the repository",
-                    &build.git_repository.root_path
-                ),
-            });
-        }
-
-        // Safety check to ensure we can't go up in the directory
-        if !build_context_path
-            .canonicalize()
-            .unwrap_or_default()
-            .starts_with(repository_root_path.canonicalize().unwrap_or_default())
-        {
-            return Err(BuildError::InvalidConfig {
-                application: app_id,
-                raw_error_message: format!(
-                    "Specified build context path {:?} tries to access directory outside of his git repository",
-                    &build.git_repository.root_path,
-                ),
-            });
-        }
+        let build_context_path =
+            utils::checkout_repository(build, &repository_root_path, logger, metrics_registry.clone(), abort)?;
 
         // now we have to decide if we use buildpack or docker to build our application
         // If no Dockerfile specified, we should use BuildPacks
         if let Some(dockerfile_path) = &build.git_repository.dockerfile_path {
             // build container from the provided Dockerfile
-
-            let dockerfile_absolute_path = repository_root_path.join(dockerfile_path);
-
-            // if the dockerfile content is provided, write it to the file before building
-            if let Some(dockerfile_content) = &build.git_repository.dockerfile_content {
-                fs::write(&dockerfile_absolute_path, dockerfile_content).map_err(|err| BuildError::IoError {
-                    application: app_id.clone(),
-                    action_description: "writing dockerfile content".to_string(),
-                    raw_error: err,
-                })?;
-
-                if let Some(dockerfile_directory) = dockerfile_absolute_path.parent() {
-                    let docker_ignore_path = dockerfile_directory.join(".dockerignore");
-
-                    fs::write(docker_ignore_path, DOCKER_IGNORE).map_err(|err| BuildError::IoError {
-                        application: app_id.clone(),
-                        action_description: "writing .dockerignore content".to_string(),
-                        raw_error: err,
-                    })?;
-                }
-            }
-
-            // If the dockerfile does not exist, abort
-            if !dockerfile_absolute_path.is_file() {
-                return Err(BuildError::InvalidConfig {
-                    application: app_id,
-                    raw_error_message: format!(
-                        "Specified dockerfile path {:?} does not exist within the repository",
-                        &dockerfile_path
-                    ),
-                });
-            }
+            let dockerfile_absolute_path =
+                utils::prepare_dockerfile(build, &repository_root_path, dockerfile_path, logger)?;
 
             self.build_image_with_docker(
                 build,
@@ -750,7 +462,16 @@ impl BuildPlatform for LocalDocker {
             } else {
                 StepStatus::Error
             });
-            build_result
+            build_result?;
+
+            attestation::attest_pushed_image(
+                build,
+                self.context.docker.config_path(),
+                logger,
+                metrics_registry,
+                abort,
+            )?;
+            Ok(BuildOutcome::Built)
         }
     }
 }
@@ -1,10 +1,18 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 
+use crate::build_platform::attestation::SbomFormat;
+use crate::build_platform::dockerfile_linter::DockerfileLintPolicy;
+use crate::build_platform::source_archive::SourceArchive;
 use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
+use crate::cmd::buildctl::BuildCtlError;
 use crate::cmd::command::CommandError;
-use crate::cmd::docker::DockerError;
+use crate::cmd::docker::{BuildCache, CacheMode, ContainerImage, DockerError};
+use crate::cmd::vulnerability_scanner::VulnerabilitySeverity;
+use crate::container_registry::ImagePromotion;
 use crate::deployment_report::logger::EnvLogger;
+use crate::errors;
 use crate::errors::EngineError;
 use crate::events::EventDetails;
 
@@ -21,8 +29,14 @@ use std::time::Duration;
 use url::Url;
 use uuid::Uuid;
 
+pub mod attestation;
+pub mod dockerfile_generator;
+pub mod dockerfile_linter;
 pub mod dockerfile_utils;
 pub mod local_docker;
+pub mod remote_buildkit;
+pub mod source_archive;
+mod utils;
 
 #[derive(Debug)]
 pub enum GitCmd {
@@ -30,6 +44,7 @@ pub enum GitCmd {
     Checkout,
     Submodule,
     SubmoduleUpdate,
+    LsTree,
 }
 
 impl Display for GitCmd {
@@ -39,6 +54,7 @@ impl Display for GitCmd {
             GitCmd::Checkout => "git checkout",
             GitCmd::Submodule => "git submodule",
             GitCmd::SubmoduleUpdate => "git submodule update",
+            GitCmd::LsTree => "git ls-tree",
         };
         f.write_str(msg)
     }
@@ -78,12 +94,37 @@ pub enum BuildError {
         raw_error: DockerError,
     },
 
+    #[error("Cannot build Application {application:?} due to an error with remote buildkit: {raw_error:?}")]
+    BuildKitError {
+        application: String,
+        raw_error: BuildCtlError,
+    },
+
     #[error("Cannot build Application {application:?} due to an error with buildpack: {raw_error:?}")]
     BuildpackError {
         application: String,
         raw_error: CommandError,
     },
 
+    #[error("Cannot generate attestations of Application {application:?}: {raw_error_message:?}")]
+    AttestationError {
+        application: String,
+        raw_error_message: String,
+    },
+
+    #[error("Cannot get the source archive of Application {application:?}: {raw_error_message:?}")]
+    SourceArchiveError {
+        application: String,
+        raw_error_message: String,
+    },
+
+    #[error("Image {image_name:?} of Application {application:?} did not pass the vulnerability scan: {raw_error_message:?}")]
+    VulnerabilityScanFailed {
+        application: String,
+        image_name: String,
+        raw_error_message: String,
+    },
+
     #[error("Cannot get credentials error.")]
     CannotGetCredentials { raw_error_message: String },
 }
@@ -103,6 +144,10 @@ pub fn to_build_error(service_id: String, err: DockerError) -> BuildError {
 pub fn to_engine_error(event_details: EventDetails, err: BuildError, user_message: String) -> EngineError {
     match err {
         BuildError::Aborted { .. } => EngineError::new_task_cancellation_requested(event_details),
+        BuildError::VulnerabilityScanFailed { ref image_name, .. } => {
+            let image_name = image_name.clone();
+            EngineError::new_vulnerability_scan_failed(event_details, image_name, errors::CommandError::from(err))
+        }
         _ => EngineError::new_build_error(event_details, err, user_message),
     }
 }
@@ -118,13 +163,22 @@ pub trait BuildPlatform: Send + Sync {
         logger: &EnvLogger,
         metrics_registry: Arc<dyn MetricsRegistry>,
         cancellation_requested: &dyn Abort,
-    ) -> Result<(), BuildError>;
+    ) -> Result<BuildOutcome, BuildError>;
+}
+
+/// How the image of a build has been provided by the build platform
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum BuildOutcome {
+    /// The image has been built and pushed by this build
+    Built,
+    /// The image already exists in the registry, it has not been built by this build
+    AlreadyExists,
 }
 
 pub struct Build {
     pub git_repository: GitRepository,
     pub image: Image,
-    pub environment_variables: BTreeMap<String, String>,
+    pub environment_variables: BTreeMap<String, BuildEnvironmentVariable>,
     pub disable_cache: bool,
     pub timeout: Duration,
     pub architectures: Vec<CpuArchitecture>,
@@ -132,24 +186,151 @@ pub struct Build {
     pub max_ram_in_gib: u32,
     // registries used by the build where we need to login to pull image
     pub registries: Vec<Registry>,
+    // when set, an SBOM and a build provenance are attached to the pushed image
+    pub sbom_format: Option<SbomFormat>,
+    pub tag_strategy: ImageTagStrategy,
+    // git id of the sources used by the build, only resolved with ImageTagStrategy::GitTree
+    pub source_tree_id: Option<String>,
+    pub cache_settings: CacheSettings,
+    pub dockerfile_lint_policy: DockerfileLintPolicy,
+    // when set, sources are downloaded from this archive instead of being cloned from the git repository
+    pub source_archive: Option<SourceArchive>,
+    // when set, the image is copied from another registry instead of being built
+    pub image_promotion: Option<ImagePromotion>,
+    // when the cluster has a vulnerability scanner, the deployment is blocked by vulnerabilities at or above this severity
+    pub vulnerability_scan_fail_on_severity: Option<VulnerabilitySeverity>,
+}
+
+/// Registry cache of a build.
+/// Cache is imported from the branch cache, then from the default branch cache, and finally from the legacy `cache` tag
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct CacheSettings {
+    pub branch: Option<String>,
+    pub default_branch: Option<String>,
+    pub mode: CacheMode,
+    // when false, the cache is only imported, never updated by the build
+    pub export: bool,
+}
+
+/// How the image tag of a build is derived
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum ImageTagStrategy {
+    /// A new image is built for every commit
+    #[default]
+    CommitId,
+    /// For monorepos, the image is only rebuilt when the content of the root path, the Dockerfile
+    /// or one of the watch paths changes
+    GitTree { watch_paths: Vec<PathBuf> },
+}
+
+const LEGACY_CACHE_TAG: &str = "cache";
+const BRANCH_HASH_LENGTH: usize = 8;
+
+// A docker tag is at most 128 chars of [A-Za-z0-9_.-].
+// A short hash of the raw branch name is appended, as distinct branches can be the same once sanitized and truncated
+fn cache_tag(branch: &str) -> String {
+    let branch_hash = format!("{:x}", Sha256::digest(branch.as_bytes()));
+    let sanitized_branch = branch
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-' {
+            true => c,
+            false => '-',
+        })
+        .take(128 - LEGACY_CACHE_TAG.len() - BRANCH_HASH_LENGTH - 2)
+        .collect::<String>();
+
+    format!("{LEGACY_CACHE_TAG}-{sanitized_branch}-{}", &branch_hash[..BRANCH_HASH_LENGTH])
 }
 
 impl Build {
     pub fn compute_image_tag(&mut self) {
+        // Secret values must never be part of the hash input, only their names are taken into account
+        let environment_variables = self
+            .environment_variables
+            .iter()
+            .map(|(k, v)| (k.clone(), if v.is_secret { String::new() } else { v.value.clone() }))
+            .collect::<BTreeMap<_, _>>();
+
         self.image.tag = compute_image_tag(
             &self.git_repository.root_path,
             &self.git_repository.dockerfile_path,
             &self.git_repository.dockerfile_content,
-            &self.environment_variables,
-            &self.git_repository.commit_id,
+            &environment_variables,
+            self.source_tree_id.as_deref().unwrap_or(&self.git_repository.commit_id),
         );
     }
 
+    /// With ImageTagStrategy::GitTree, resolve the git id of the sources of the build and re-compute the image tag,
+    /// so the tag stays the same as long as the sources of the service are untouched.
+    pub fn resolve_source_tree_id(&mut self, logger: &EnvLogger) -> Result<(), BuildError> {
+        let ImageTagStrategy::GitTree { watch_paths } = &self.tag_strategy else {
+            return Ok(());
+        };
+
+        let source_tree_id = utils::compute_source_tree_id(self, watch_paths, logger)?;
+        self.source_tree_id = Some(source_tree_id);
+        self.compute_image_tag();
+
+        Ok(())
+    }
+
+    pub fn build_cache(&self) -> BuildCache {
+        let mut cache_tags: Vec<String> = vec![];
+        for branch in [&self.cache_settings.branch, &self.cache_settings.default_branch]
+            .into_iter()
+            .flatten()
+        {
+            let tag = cache_tag(branch);
+            if !cache_tags.contains(&tag) {
+                cache_tags.push(tag);
+            }
+        }
+        if !cache_tags.iter().any(|tag| tag == LEGACY_CACHE_TAG) {
+            cache_tags.push(LEGACY_CACHE_TAG.to_string());
+        }
+
+        let cache_image =
+            |tag: &String| ContainerImage::new(self.image.registry_url.clone(), self.image.name(), vec![tag.clone()]);
+
+        BuildCache {
+            import_from: cache_tags.iter().map(cache_image).collect(),
+            export_to: match self.cache_settings.export {
+                true => cache_tags.first().map(cache_image),
+                false => None,
+            },
+            mode: self.cache_settings.mode,
+        }
+    }
+
+    /// Non secret variables, to be injected as build arguments
+    pub fn build_args(&self) -> Vec<(&str, &str)> {
+        self.environment_variables
+            .iter()
+            .filter(|(_, v)| !v.is_secret)
+            .map(|(k, v)| (k.as_str(), v.value.as_str()))
+            .collect()
+    }
+
+    /// Secret variables, to be injected as buildkit secrets (i.e: RUN --mount=type=secret,id=...)
+    pub fn build_secrets(&self) -> Vec<(&str, &str)> {
+        self.environment_variables
+            .iter()
+            .filter(|(_, v)| v.is_secret)
+            .map(|(k, v)| (k.as_str(), v.value.as_str()))
+            .collect()
+    }
+
     pub fn use_buildpacks(&self) -> bool {
         self.git_repository.dockerfile_path.is_none()
     }
 }
 
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BuildEnvironmentVariable {
+    pub value: String,
+    pub is_secret: bool,
+}
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct EnvironmentVariable {
     pub key: String,
@@ -178,6 +359,8 @@ pub struct GitRepository {
     pub dockerfile_content: Option<String>,
     pub root_path: PathBuf,
     pub buildpack_language: Option<String>,
+    // zero-config build: the dockerfile is generated from the detected language of the application
+    pub generate_dockerfile: bool,
 }
 impl GitRepository {
     fn credentials(&self) -> Option<anyhow::Result<Credentials>> {
@@ -203,6 +386,8 @@ pub struct Image {
     pub repository_name: String,
     pub shared_repository_name: String,
     pub shared_image_feature_enabled: bool,
+    // digest of the image built or verified by the deployment, the deployed image is pinned to it
+    pub digest: Option<String>,
 }
 
 impl Image {
@@ -243,6 +428,14 @@ impl Image {
         }
     }
 
+    // Reference of the image to deploy, pinned to its digest once known as the tag can be moved in the meantime
+    pub fn full_image_reference(&self) -> String {
+        match &self.digest {
+            Some(digest) => format!("{}@{}", self.full_image_name_with_tag(), digest),
+            None => self.full_image_name_with_tag(),
+        }
+    }
+
     pub fn full_image_name(&self) -> String {
         match self.registry_url.port_or_known_default() {
             None | Some(443) => {
@@ -285,6 +478,7 @@ impl Default for Image {
             repository_name: "".to_string(),
             shared_repository_name: "".to_string(),
             shared_image_feature_enabled: false,
+            digest: None,
         }
     }
 }
@@ -303,4 +497,168 @@ impl Display for Image {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Kind {
     LocalDocker,
+    RemoteBuildkit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build of the engine testing repository, to be tweaked by each test
+    pub(super) fn test_build() -> Build {
+        Build {
+            git_repository: GitRepository {
+                url: Url::parse("https://github.com/Qovery/engine-testing.git").unwrap(),
+                get_credentials: None,
+                ssh_keys: vec![],
+                commit_id: "commit_id".to_string(),
+                dockerfile_path: Some(PathBuf::from("Dockerfile")),
+                dockerfile_content: None,
+                root_path: PathBuf::from("."),
+                buildpack_language: None,
+                generate_dockerfile: false,
+            },
+            image: Image::default(),
+            environment_variables: BTreeMap::new(),
+            disable_cache: false,
+            timeout: Duration::from_secs(60),
+            architectures: vec![],
+            max_cpu_in_milli: 1000,
+            max_ram_in_gib: 1,
+            registries: vec![],
+            sbom_format: None,
+            tag_strategy: ImageTagStrategy::CommitId,
+            source_tree_id: None,
+            cache_settings: CacheSettings::default(),
+            dockerfile_lint_policy: DockerfileLintPolicy::default(),
+            source_archive: None,
+            image_promotion: None,
+            vulnerability_scan_fail_on_severity: None,
+        }
+    }
+
+    fn variable(value: &str, is_secret: bool) -> BuildEnvironmentVariable {
+        BuildEnvironmentVariable {
+            value: value.to_string(),
+            is_secret,
+        }
+    }
+
+    #[test]
+    fn test_secret_values_are_not_part_of_image_tag() {
+        let mut build = test_build();
+        build.environment_variables = BTreeMap::from([
+            ("FOO".to_string(), variable("foo", false)),
+            ("TOKEN".to_string(), variable("secret_1", true)),
+        ]);
+        build.compute_image_tag();
+        let tag = build.image.tag.clone();
+
+        // changing a secret value must not change the tag
+        build
+            .environment_variables
+            .insert("TOKEN".to_string(), variable("secret_2", true));
+        build.compute_image_tag();
+        assert_eq!(build.image.tag, tag);
+
+        // changing a non secret value must change the tag
+        build
+            .environment_variables
+            .insert("FOO".to_string(), variable("bar", false));
+        build.compute_image_tag();
+        assert_ne!(build.image.tag, tag);
+
+        assert_eq!(build.build_args(), vec![("FOO", "bar")]);
+        assert_eq!(build.build_secrets(), vec![("TOKEN", "secret_2")]);
+    }
+
+    #[test]
+    fn test_secrets_consumed_through_arg_are_refused() {
+        let mut build = test_build();
+        build.environment_variables = BTreeMap::from([
+            ("FOO".to_string(), variable("foo", false)),
+            ("UNUSED".to_string(), variable("unused", false)),
+            ("TOKEN".to_string(), variable("secret", true)),
+            ("UNUSED_TOKEN".to_string(), variable("unused_secret", true)),
+        ]);
+
+        let mounted_secret = b"FROM alpine\nARG FOO\nRUN --mount=type=secret,id=TOKEN cat /run/secrets/TOKEN\n";
+        utils::retain_dockerfile_variables(&mut build, mounted_secret.to_vec()).unwrap();
+        assert_eq!(build.build_args(), vec![("FOO", "foo")]);
+        assert_eq!(build.build_secrets(), vec![("TOKEN", "secret")]);
+
+        let arg_secret = b"FROM alpine\nARG FOO\nARG TOKEN\nRUN echo $TOKEN\n";
+        let err = utils::retain_dockerfile_variables(&mut build, arg_secret.to_vec()).unwrap_err();
+        assert!(
+            matches!(err, BuildError::InvalidConfig { raw_error_message, .. } if raw_error_message.contains("TOKEN"))
+        );
+    }
+
+    #[test]
+    fn test_source_tree_id_replaces_commit_id_in_image_tag() {
+        let mut build = test_build();
+        build.compute_image_tag();
+        assert!(build.image.tag.ends_with("-commit_id"));
+
+        build.source_tree_id = Some("tree_id".to_string());
+        build.compute_image_tag();
+        assert!(build.image.tag.ends_with("-tree_id"));
+        let tag = build.image.tag.clone();
+
+        // a new commit that does not touch the sources of the service keeps the same tag
+        build.git_repository.commit_id = "another_commit_id".to_string();
+        build.compute_image_tag();
+        assert_eq!(build.image.tag, tag);
+    }
+
+    #[test]
+    fn test_build_cache_chain() {
+        let mut build = test_build();
+        let cache_tags = |build: &Build| {
+            build
+                .build_cache()
+                .import_from
+                .into_iter()
+                .map(|image| image.image_name().rsplit_once(':').unwrap().1.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // no branch, only the legacy cache is used
+        assert_eq!(cache_tags(&build), vec!["cache"]);
+        assert!(build.build_cache().export_to.is_none());
+
+        build.cache_settings = CacheSettings {
+            branch: Some("feat/my-feature".to_string()),
+            default_branch: Some("main".to_string()),
+            mode: CacheMode::Min,
+            export: true,
+        };
+        assert_eq!(
+            cache_tags(&build),
+            vec![
+                "cache-feat-my-feature-2e342779".to_string(),
+                "cache-main-0d6e4079".to_string(),
+                "cache".to_string()
+            ]
+        );
+        let build_cache = build.build_cache();
+        assert!(build_cache
+            .export_to
+            .unwrap()
+            .image_name()
+            .ends_with(":cache-feat-my-feature-2e342779"));
+        assert_eq!(build_cache.mode, CacheMode::Min);
+
+        // building the default branch
+        build.cache_settings.branch = Some("main".to_string());
+        assert_eq!(cache_tags(&build), vec!["cache-main-0d6e4079".to_string(), "cache".to_string()]);
+
+        // tags are limited to 128 chars
+        build.cache_settings.branch = Some("a".repeat(200));
+        assert_eq!(cache_tags(&build)[0].len(), 128);
+
+        // branches being the same once sanitized or truncated don't share their cache
+        assert_ne!(cache_tag("feat/my-feature"), cache_tag("feat-my-feature"));
+        assert_ne!(cache_tag(&"a".repeat(200)), cache_tag(&"a".repeat(201)));
+    }
 }
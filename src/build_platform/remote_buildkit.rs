@@ -0,0 +1,245 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use url::Url;
+use uuid::Uuid;
+
+use crate::build_platform::{attestation, utils, Build, BuildError, BuildOutcome, BuildPlatform, Kind};
+use crate::cmd::buildctl::{BuildCtl, BuildCtlError, BuildKitTls};
+use crate::cmd::command::CommandKiller;
+use crate::cmd::docker::{Architecture, ContainerImage};
+use crate::deployment_report::logger::EnvLogger;
+use crate::io_models::context::Context;
+use crate::metrics_registry::{MetricsRegistry, StepLabel, StepName, StepStatus};
+use crate::models::abort::Abort;
+use crate::utilities::to_short_id;
+
+pub fn to_build_error(service_id: String, err: BuildCtlError) -> BuildError {
+    match err {
+        BuildCtlError::Aborted { .. } => BuildError::Aborted {
+            application: service_id,
+        },
+        _ => BuildError::BuildKitError {
+            application: service_id,
+            raw_error: err,
+        },
+    }
+}
+
+/// use a standalone buildkitd (i.e: a shared build farm) reached through buildctl, no docker daemon is involved in the build
+pub struct RemoteBuildkit {
+    context: Context,
+    id: String,
+    long_id: Uuid,
+    name: String,
+    buildctl: BuildCtl,
+    metrics_registry: Box<dyn MetricsRegistry>,
+}
+
+impl RemoteBuildkit {
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        name: &str,
+        address: Url,
+        tls: Option<BuildKitTls>,
+        metrics_registry: Box<dyn MetricsRegistry>,
+    ) -> Result<Self, BuildError> {
+        let buildctl = BuildCtl::new(address, tls).map_err(|err| BuildError::InvalidConfig {
+            application: name.to_string(),
+            raw_error_message: format!("Cannot configure remote buildkit client: {err}"),
+        })?;
+
+        Ok(RemoteBuildkit {
+            context,
+            id: to_short_id(&long_id),
+            long_id,
+            name: name.to_string(),
+            buildctl,
+            metrics_registry,
+        })
+    }
+
+    fn build_image_with_buildkit(
+        &self,
+        build: &mut Build,
+        dockerfile_complete_path: &Path,
+        into_dir_docker_style: &Path,
+        logger: &EnvLogger,
+        metrics_registry: Arc<dyn MetricsRegistry>,
+        abort: &dyn Abort,
+    ) -> Result<BuildOutcome, BuildError> {
+        // Going to inject only env var that are used by the dockerfile
+        // so extracting it and modifying the image tag and env variables
+        let build_record =
+            metrics_registry.start_record(build.image.service_long_id, StepLabel::Service, StepName::Build);
+        let dockerfile_content = fs::read(dockerfile_complete_path).map_err(|err| BuildError::IoError {
+            application: build.image.service_id.clone(),
+            action_description: "reading dockerfile content".to_string(),
+            raw_error: err,
+        })?;
+        if let Err(err) = utils::check_dockerfile_policy(build, &dockerfile_content, logger) {
+            build_record.stop(StepStatus::Error);
+            return Err(err);
+        }
+
+        // Keep only the env variables we want for our build
+        // and force re-compute the image tag
+        if let Err(err) = utils::retain_dockerfile_variables(build, dockerfile_content) {
+            build_record.stop(StepStatus::Error);
+            return Err(err);
+        }
+        build.compute_image_tag();
+
+        // Prepare image we want to build
+        let image_to_build = ContainerImage::new(
+            build.image.registry_url.clone(),
+            build.image.name(),
+            vec![build.image.tag.clone(), "latest".to_string()],
+        );
+
+        let build_cache = build.build_cache();
+
+        // Check if the image does not exist already remotely, if yes, we skip the build
+        let image_name = image_to_build.image_name();
+        logger.send_progress(format!("🕵️ Checking if image already exists remotely {image_name}"));
+        if let Ok(true) = self.context.docker.does_image_exist_remotely(&image_to_build) {
+            logger.send_progress(format!("🎯 Skipping build. Image already exists in the registry {image_name}"));
+            build_record.stop(StepStatus::Skip);
+            // skip build
+            return Ok(BuildOutcome::AlreadyExists);
+        }
+
+        logger.send_progress(format!(
+            "⛏️ Building image on remote buildkit {}. It does not exist remotely {image_name}",
+            self.buildctl.address()
+        ));
+
+        // login if there are some private registries used
+        // credentials end up in the docker config file, which buildctl is reading
+        utils::login_to_registries(&self.context.docker, build, logger)?;
+
+        // Actually do the build of the image
+        let env_vars = build.build_args();
+        let secrets = build.build_secrets();
+
+        let arch: Vec<Architecture> = build.architectures.iter().map(Architecture::from).collect();
+
+        let exit_status = self.buildctl.build(
+            dockerfile_complete_path,
+            into_dir_docker_style,
+            &image_to_build,
+            &env_vars,
+            &secrets,
+            &build_cache,
+            true,
+            build.image.registry_insecure || build.image.registry_url.scheme() == "http",
+            &arch,
+            self.context.docker.config_path(),
+            &mut |line| logger.send_progress(line),
+            &mut |line| logger.send_progress(line),
+            &CommandKiller::from(build.timeout, abort),
+        );
+
+        if let Err(err) = exit_status {
+            build_record.stop(StepStatus::Error);
+            return Err(to_build_error(build.image.service_id.clone(), err));
+        }
+        build_record.stop(StepStatus::Success);
+
+        attestation::attest_pushed_image(build, self.context.docker.config_path(), logger, metrics_registry, abort)?;
+        Ok(BuildOutcome::Built)
+    }
+
+    fn check_buildkit_is_reachable(&self, build: &Build, abort: &dyn Abort) -> Result<(), BuildError> {
+        let record = self.metrics_registry.start_record(
+            build.image.service_long_id,
+            StepLabel::Service,
+            StepName::ProvisionBuilder,
+        );
+
+        match self.buildctl.check_workers(&CommandKiller::from_cancelable(abort)) {
+            Ok(_) => {
+                record.stop(StepStatus::Success);
+                Ok(())
+            }
+            Err(err) => {
+                record.stop(if err.is_aborted() {
+                    StepStatus::Cancel
+                } else {
+                    StepStatus::Error
+                });
+                Err(to_build_error(build.image.service_id.clone(), err))
+            }
+        }
+    }
+}
+
+impl BuildPlatform for RemoteBuildkit {
+    fn kind(&self) -> Kind {
+        Kind::RemoteBuildkit
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn build(
+        &self,
+        build: &mut Build,
+        logger: &EnvLogger,
+        metrics_registry: Arc<dyn MetricsRegistry>,
+        abort: &dyn Abort,
+    ) -> Result<BuildOutcome, BuildError> {
+        // check if we should already abort the task
+        if abort.status().should_cancel() {
+            return Err(BuildError::Aborted {
+                application: build.image.service_id.clone(),
+            });
+        }
+
+        // Buildpacks require a docker daemon, only Dockerfile builds can be done on a remote buildkit
+        let Some(dockerfile_path) = build.git_repository.dockerfile_path.clone() else {
+            return Err(BuildError::InvalidConfig {
+                application: build.image.service_id.clone(),
+                raw_error_message:
+                    "Remote buildkit build platform only supports Dockerfile builds, buildpacks are not supported"
+                        .to_string(),
+            });
+        };
+
+        let repository_root_path = utils::get_repository_build_root_path(&self.context, build)?;
+        let _git_cleanup = scopeguard::guard(&repository_root_path, |path| {
+            info!("Removing git repository at path: {:?}", path);
+            let _ = fs::remove_dir_all(path);
+        });
+        let build_context_path =
+            utils::checkout_repository(build, &repository_root_path, logger, metrics_registry.clone(), abort)?;
+        let dockerfile_absolute_path =
+            utils::prepare_dockerfile(build, &repository_root_path, &dockerfile_path, logger)?;
+
+        logger.send_progress(format!(
+            "🧑‍🏭 Connecting to remote buildkit {} for the build",
+            self.buildctl.address()
+        ));
+        self.check_buildkit_is_reachable(build, abort)?;
+
+        self.build_image_with_buildkit(
+            build,
+            &dockerfile_absolute_path,
+            &build_context_path,
+            logger,
+            metrics_registry,
+            abort,
+        )
+    }
+}
@@ -0,0 +1,526 @@
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::str::Utf8Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use git2::{Cred, CredentialType, ErrorClass, ObjectType, Oid};
+use itertools::Itertools;
+use retry::delay::Fibonacci;
+use retry::OperationResult;
+use tempfile::TempDir;
+
+use crate::build_platform::dockerfile_generator::{detect_language, generate_dockerfile};
+use crate::build_platform::dockerfile_linter::{lint_dockerfile, LintLevel};
+use crate::build_platform::dockerfile_utils::{extract_dockerfile_args, extract_dockerfile_secrets};
+use crate::build_platform::source_archive::fetch_source_archive;
+use crate::build_platform::{Build, BuildError, Credentials, GitCmd};
+use crate::cmd::command::CommandKiller;
+use crate::cmd::docker::Docker;
+use crate::cmd::git_lfs::{GitLfs, GitLfsError};
+use crate::deployment_report::logger::EnvLogger;
+use crate::fs::workspace_directory;
+use crate::git;
+use crate::io_models::container::Registry;
+use crate::io_models::context::Context;
+use crate::metrics_registry::{MetricsRegistry, StepLabel, StepName, StepStatus};
+use crate::models::abort::Abort;
+
+const MAX_GIT_LFS_SIZE_GB: u64 = 5;
+const MAX_GIT_LFS_SIZE_KB: u64 = MAX_GIT_LFS_SIZE_GB * 1024 * 1024; // 5GB
+
+const DOCKER_IGNORE: &str = r#"
+# Ignore all logs
+*.log
+
+# Ignore git repository files
+.git
+.gitignore
+"#;
+
+pub(crate) fn get_repository_build_root_path(context: &Context, build: &Build) -> Result<PathBuf, BuildError> {
+    workspace_directory(
+        context.workspace_root_dir(),
+        context.execution_id(),
+        format!("build/{}", build.image.service_id.as_str()),
+    )
+    .map_err(|err| BuildError::IoError {
+        application: build.image.service_id.clone(),
+        action_description: "when creating build workspace".to_string(),
+        raw_error: err,
+    })
+}
+
+fn git_user_credentials(build: &Build, logger: &EnvLogger) -> Option<Credentials> {
+    match build.git_repository.credentials() {
+        None => None,
+        Some(Ok(creds)) => Some(creds),
+        Some(Err(err)) => {
+            logger.send_warning(format!("🗝️ Unable to get credentials for git repository: {err}"));
+            None
+        }
+    }
+}
+
+// Create callback that will be called by git to provide credentials per user
+// If people use submodule, they need to provide us their ssh key
+fn git_credentials_callback<'a>(
+    build: &'a Build,
+    git_user_creds: &'a Option<Credentials>,
+) -> impl Fn(&str) -> Vec<(CredentialType, Cred)> + 'a {
+    move |user: &str| {
+        let mut creds: Vec<(CredentialType, Cred)> = Vec::with_capacity(build.git_repository.ssh_keys.len() + 1);
+        for ssh_key in build.git_repository.ssh_keys.iter() {
+            let public_key = ssh_key.public_key.as_deref();
+            let passphrase = ssh_key.passphrase.as_deref();
+            if let Ok(cred) = Cred::ssh_key_from_memory(user, public_key, &ssh_key.private_key, passphrase) {
+                creds.push((CredentialType::SSH_MEMORY, cred));
+            }
+        }
+
+        if let Some(git_creds) = git_user_creds {
+            creds.push((
+                CredentialType::USER_PASS_PLAINTEXT,
+                Cred::userpass_plaintext(&git_creds.login, &git_creds.password).unwrap(),
+            ));
+        }
+
+        creds
+    }
+}
+
+/// Compute a version of the build sources, from the git ids of the build root path, its Dockerfile and the extra `watch_paths`.
+/// It only changes when one of those paths is modified, so unrelated commits of a monorepo do not trigger a new build.
+/// Only the commit objects are fetched, into a temporary directory removed once the version is computed: nothing is left
+/// behind when the build is then skipped.
+pub(crate) fn compute_source_tree_id(
+    build: &Build,
+    watch_paths: &[PathBuf],
+    logger: &EnvLogger,
+) -> Result<String, BuildError> {
+    let app_id = build.image.service_id.clone();
+    let commit_id = &build.git_repository.commit_id;
+    logger.send_progress(format!(
+        "🌳 Computing sources version of {:?} at commit {commit_id}",
+        build.git_repository.root_path
+    ));
+
+    let repository_dir = TempDir::with_prefix("git-tree-").map_err(|err| BuildError::IoError {
+        application: app_id.clone(),
+        action_description: "creating git tree workspace".to_string(),
+        raw_error: err,
+    })?;
+
+    let git_user_creds = git_user_credentials(build, logger);
+    let get_credentials = git_credentials_callback(build, &git_user_creds);
+    git::fetch_commit(&build.git_repository.url, commit_id, repository_dir.path(), &get_credentials).map_err(
+        |err| match err {
+            BuildError::GitError {
+                git_cmd,
+                context,
+                raw_error,
+                ..
+            } => BuildError::GitError {
+                application: app_id.clone(),
+                git_cmd,
+                context,
+                raw_error,
+            },
+            err => err,
+        },
+    )?;
+
+    let mut paths = vec![build.git_repository.root_path.as_path()];
+    paths.extend(build.git_repository.dockerfile_path.as_deref());
+    paths.extend(watch_paths.iter().map(|path| path.as_path()));
+    let ids = git::tree_entry_ids_at_commit(repository_dir.path(), commit_id, &paths).map_err(|err| {
+        BuildError::GitError {
+            application: app_id.clone(),
+            git_cmd: GitCmd::LsTree,
+            context: format!("paths {paths:?} at commit {commit_id}"),
+            raw_error: err,
+        }
+    })?;
+
+    // Missing paths (i.e: a watch path not yet created) are kept as empty, so creating them changes the version
+    let ids = ids
+        .iter()
+        .map(|id| id.map(|id| id.to_string()).unwrap_or_default())
+        .join(":");
+    let source_tree_id = Oid::hash_object(ObjectType::Blob, ids.as_bytes()).map_err(|err| BuildError::GitError {
+        application: app_id.clone(),
+        git_cmd: GitCmd::LsTree,
+        context: "hashing sources version".to_string(),
+        raw_error: err,
+    })?;
+
+    Ok(source_tree_id.to_string())
+}
+
+/// Clone the git repository of the build at the requested commit into `repository_root_path`,
+/// fetch git-lfs files if any, and return the validated build context path.
+/// When the build uses a source archive, the archive is unpacked instead of cloning the repository.
+pub(crate) fn checkout_repository(
+    build: &Build,
+    repository_root_path: &Path,
+    logger: &EnvLogger,
+    metrics_registry: Arc<dyn MetricsRegistry>,
+    abort: &dyn Abort,
+) -> Result<PathBuf, BuildError> {
+    // Cleanup, mono repo can require to clone multiple time the same repo
+    // FIXME: re-use the same repo and just checkout at the correct commit
+    if repository_root_path.exists() {
+        let app_id = build.image.service_id.clone();
+        fs::remove_dir_all(repository_root_path).map_err(|err| BuildError::IoError {
+            application: app_id,
+            action_description: "cleaning old repository".to_string(),
+            raw_error: err,
+        })?;
+    }
+
+    if let Some(source_archive) = &build.source_archive {
+        fetch_source_archive(build, source_archive, repository_root_path, logger, metrics_registry, abort)?;
+        return check_build_context_path(build, repository_root_path);
+    }
+
+    logger.send_progress(format!("📥 Cloning repository {}", build.git_repository.url));
+
+    let git_user_creds = git_user_credentials(build, logger);
+    let get_credentials = git_credentials_callback(build, &git_user_creds);
+
+    // Do the real git clone
+    let git_clone_record =
+        metrics_registry.start_record(build.image.service_long_id, StepLabel::Service, StepName::GitClone);
+    if let Err(error) = retry::retry(retry::delay::Fixed::from_millis(10_000).take(3), || {
+        if let Err(BuildError::GitError {
+            application: _,
+            git_cmd,
+            context,
+            raw_error,
+        }) = git::clone_at_commit(
+            &build.git_repository.url,
+            &build.git_repository.commit_id,
+            repository_root_path,
+            &get_credentials,
+        ) {
+            let message = raw_error.message();
+            let git_error_class = raw_error.class();
+            // Some errors can happen "randomly":
+            // - SSL error: syscall failure: Resource temporarily unavailable
+            // - Timeout on git clone
+            debug!("Error on git clone: git_error_class={:?}, message={}", git_error_class, message);
+            return if git_error_class == ErrorClass::Os
+                || git_error_class == ErrorClass::Ssl
+                || (git_error_class == ErrorClass::Net && message.contains("timed out"))
+            {
+                debug!("Retrying git clone...");
+                logger.send_warning(format!(
+                    "⚠️ Retrying cloning your git repository, due to following error: {}",
+                    message
+                ));
+                OperationResult::Retry(BuildError::GitError {
+                    application: build.image.service_id.clone(),
+                    git_cmd,
+                    context,
+                    raw_error,
+                })
+            } else {
+                OperationResult::Err(BuildError::GitError {
+                    application: build.image.service_id.clone(),
+                    git_cmd,
+                    context,
+                    raw_error,
+                })
+            };
+        }
+        OperationResult::Ok(())
+    }) {
+        git_clone_record.stop(StepStatus::Error);
+        return Err(error.error);
+    }
+    git_clone_record.stop(StepStatus::Success);
+
+    if abort.status().should_cancel() {
+        return Err(BuildError::Aborted {
+            application: build.image.service_id.clone(),
+        });
+    }
+
+    let app_id = build.image.service_id.clone();
+
+    // Fetch git-lfs/big files for the repository if necessary
+    let git_lfs = if let Some(creds) = git_user_creds {
+        GitLfs::new(creds.login, creds.password)
+    } else {
+        GitLfs::default()
+    };
+    let cmd_killer = CommandKiller::from_cancelable(abort);
+    let size_estimate_kb = git_lfs
+        .files_size_estimate_in_kb(repository_root_path, &build.git_repository.commit_id, &cmd_killer)
+        .unwrap_or(0);
+
+    if size_estimate_kb > 0 {
+        if size_estimate_kb > MAX_GIT_LFS_SIZE_KB {
+            return Err(BuildError::InvalidConfig {
+                application: app_id,
+                raw_error_message: format!(
+                    "GIT LFS files size are too big and are over the max allowed size of {MAX_GIT_LFS_SIZE_GB} GB"
+                ),
+            });
+        }
+
+        info!("fetching git-lfs files");
+        logger.send_progress("🗜️ Fetching git-lfs files for repository".to_string());
+        match git_lfs.checkout_files_for_commit(repository_root_path, &build.git_repository.commit_id, &cmd_killer) {
+            Ok(_) => {}
+            Err(GitLfsError::Aborted { .. }) => return Err(BuildError::Aborted { application: app_id }),
+            Err(GitLfsError::Timeout { .. }) => return Err(BuildError::Aborted { application: app_id }),
+            Err(GitLfsError::ExecutionError { raw_error }) => {
+                return Err(BuildError::IoError {
+                    application: app_id,
+                    action_description: "git lfs checkout".to_string(),
+                    raw_error,
+                })
+            }
+            Err(GitLfsError::ExitStatusError { .. }) => {
+                return Err(BuildError::IoError {
+                    application: app_id,
+                    action_description: "git lfs checkout".to_string(),
+                    raw_error: Error::new(ErrorKind::Other, "git lfs checkout failed"),
+                })
+            }
+        }
+    }
+
+    check_build_context_path(build, repository_root_path)
+}
+
+/// Check that the build context exists within the repository, and returns its path
+fn check_build_context_path(build: &Build, repository_root_path: &Path) -> Result<PathBuf, BuildError> {
+    let app_id = build.image.service_id.clone();
+
+    // Check that the build context is correct
+    let build_context_path = repository_root_path.join(&build.git_repository.root_path);
+    if !build_context_path.is_dir() {
+        return Err(BuildError::InvalidConfig {
+            application: app_id,
+            raw_error_message: format!(
+                "Specified build context path {:?} does not exist within the repository",
+                &build.git_repository.root_path
+            ),
+        });
+    }
+
+    // Safety check to ensure we can't go up in the directory
+    if !build_context_path
+        .canonicalize()
+        .unwrap_or_default()
+        .starts_with(repository_root_path.canonicalize().unwrap_or_default())
+    {
+        return Err(BuildError::InvalidConfig {
+            application: app_id,
+            raw_error_message: format!(
+                "Specified build context path {:?} tries to access directory outside of his git repository",
+                &build.git_repository.root_path,
+            ),
+        });
+    }
+
+    Ok(build_context_path)
+}
+
+/// Write the user provided (or generated for zero-config builds) dockerfile content, if any,
+/// and check the dockerfile exists within the repository.
+/// Returns the absolute path of the dockerfile.
+pub(crate) fn prepare_dockerfile(
+    build: &Build,
+    repository_root_path: &Path,
+    dockerfile_path: &Path,
+    logger: &EnvLogger,
+) -> Result<PathBuf, BuildError> {
+    let app_id = build.image.service_id.clone();
+    let dockerfile_absolute_path = repository_root_path.join(dockerfile_path);
+
+    let dockerfile_content = if build.git_repository.generate_dockerfile {
+        Some(generate_zero_config_dockerfile(build, repository_root_path, logger)?)
+    } else {
+        build.git_repository.dockerfile_content.clone()
+    };
+
+    // if the dockerfile content is provided, write it to the file before building
+    if let Some(dockerfile_content) = &dockerfile_content {
+        fs::write(&dockerfile_absolute_path, dockerfile_content).map_err(|err| BuildError::IoError {
+            application: app_id.clone(),
+            action_description: "writing dockerfile content".to_string(),
+            raw_error: err,
+        })?;
+
+        if let Some(dockerfile_directory) = dockerfile_absolute_path.parent() {
+            let docker_ignore_path = dockerfile_directory.join(".dockerignore");
+
+            fs::write(docker_ignore_path, DOCKER_IGNORE).map_err(|err| BuildError::IoError {
+                application: app_id.clone(),
+                action_description: "writing .dockerignore content".to_string(),
+                raw_error: err,
+            })?;
+        }
+    }
+
+    // If the dockerfile does not exist, abort
+    if !dockerfile_absolute_path.is_file() {
+        return Err(BuildError::InvalidConfig {
+            application: app_id,
+            raw_error_message: format!(
+                "Specified dockerfile path {:?} does not exist within the repository",
+                &dockerfile_path
+            ),
+        });
+    }
+
+    Ok(dockerfile_absolute_path)
+}
+
+fn generate_zero_config_dockerfile(
+    build: &Build,
+    repository_root_path: &Path,
+    logger: &EnvLogger,
+) -> Result<String, BuildError> {
+    let build_context = repository_root_path.join(&build.git_repository.root_path);
+    let Some(language) = detect_language(&build_context, build.git_repository.buildpack_language.as_deref()) else {
+        return Err(BuildError::InvalidConfig {
+            application: build.image.service_id.clone(),
+            raw_error_message: "Cannot detect the language of your application to generate its Dockerfile. Supported languages are Node.js, Python, Go, Rust, Java and static sites, otherwise please provide a Dockerfile".to_string(),
+        });
+    };
+
+    logger.send_progress(format!("🪄 Generating a Dockerfile for your {language} application"));
+    let build_args = build.build_args().into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+    let build_secrets = build.build_secrets().into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+
+    generate_dockerfile(language, &build_context, &build_args, &build_secrets).map_err(|raw_error_message| {
+        BuildError::InvalidConfig {
+            application: build.image.service_id.clone(),
+            raw_error_message,
+        }
+    })
+}
+
+/// Login to the private registries used by the build, so base images can be pulled.
+/// Credentials are stored in the docker config file, which is also used by buildkit clients.
+pub(crate) fn login_to_registries(docker: &Docker, build: &Build, logger: &EnvLogger) -> Result<(), BuildError> {
+    for registry in &build.registries {
+        // TODO(benjaminch): To handle GCP Artifact Registry login, credentials to be injected, maybe this whole login should be done later on or delegated to container registry objects
+        // Method to be called for GCP: cmd::docker::Docker::login_artifact_registry()
+        if let Registry::GcpArtifactRegistry { url, .. } = registry {
+            logger.send_warning(format!(
+                "Skipping logging at this step for Artifact Registry `{}`",
+                url.host_str().unwrap_or_default()
+            ));
+            continue;
+        }
+
+        let url = registry
+            .get_url_with_credentials()
+            .map_err(|_| BuildError::CannotGetCredentials {
+                raw_error_message: "Cannot get the registry credentials".to_string(),
+            })?;
+        if url.password().is_none() {
+            continue;
+        }
+
+        logger.send_progress(format!(
+            "🔓 Login to registry {} as user {}",
+            url.host_str().unwrap_or_default(),
+            url.username()
+        ));
+
+        let login_ret = retry::retry(Fibonacci::from(Duration::from_secs(1)).take(4), || {
+            docker.login(&url).map_err(|err| {
+                logger.send_warning("🔓 Retrying to login to registry due to error...".to_string());
+                err
+            })
+        });
+
+        if let Err(err) = login_ret {
+            logger.send_warning(format!(
+                "❌ Failed to login to registry {} due to {}",
+                url.host_str().unwrap_or_default(),
+                err
+            ));
+            let err = BuildError::DockerError {
+                application: build.image.service_id.clone(),
+                raw_error: err.error,
+            };
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the Dockerfile linter, findings are reported as warnings unless the lint policy of the build enforces them
+pub(crate) fn check_dockerfile_policy(
+    build: &Build,
+    dockerfile_content: &[u8],
+    logger: &EnvLogger,
+) -> Result<(), BuildError> {
+    let policy = &build.dockerfile_lint_policy;
+    if policy.level == LintLevel::Disabled {
+        return Ok(());
+    }
+
+    let findings = lint_dockerfile(&String::from_utf8_lossy(dockerfile_content));
+    let (enforced, warnings): (Vec<_>, Vec<_>) = findings.into_iter().partition(|f| policy.is_enforced(f.rule));
+    for finding in &warnings {
+        logger.send_warning(format!("🐳 Dockerfile {finding}"));
+    }
+
+    if enforced.is_empty() {
+        return Ok(());
+    }
+
+    Err(BuildError::InvalidConfig {
+        application: build.image.service_id.clone(),
+        raw_error_message: format!(
+            "Dockerfile does not comply with the lint policy:\n{}",
+            enforced.iter().map(|finding| finding.to_string()).join("\n")
+        ),
+    })
+}
+
+/// Keep only the env variables used by the dockerfile.
+/// Non secret variables must be declared as ARG, secret ones must be mounted as secret (i.e: RUN --mount=type=secret).
+/// A secret only consumed through ARG is refused, as build arguments end up in the image history.
+pub(crate) fn retain_dockerfile_variables(build: &mut Build, dockerfile_content: Vec<u8>) -> Result<(), BuildError> {
+    let to_error = |err: Utf8Error| BuildError::InvalidConfig {
+        application: build.image.service_id.clone(),
+        raw_error_message: format!("Cannot extract env vars from your dockerfile {err}"),
+    };
+    let dockerfile_secrets = extract_dockerfile_secrets(&dockerfile_content).map_err(to_error)?;
+    let dockerfile_args = extract_dockerfile_args(dockerfile_content).map_err(to_error)?;
+
+    let secret_args = build
+        .environment_variables
+        .iter()
+        .filter(|(k, v)| v.is_secret && dockerfile_args.contains(*k) && !dockerfile_secrets.contains(*k))
+        .map(|(k, _)| k.as_str())
+        .sorted()
+        .collect::<Vec<_>>();
+    if !secret_args.is_empty() {
+        return Err(BuildError::InvalidConfig {
+            application: build.image.service_id.clone(),
+            raw_error_message: format!(
+                "Secret variables {} are consumed as ARG in your Dockerfile, they would end up in the image history. Use `RUN --mount=type=secret,id=<VARIABLE_NAME>` to access them during the build",
+                secret_args.join(", ")
+            ),
+        });
+    }
+
+    build.environment_variables.retain(|k, v| match v.is_secret {
+        true => dockerfile_secrets.contains(k),
+        false => dockerfile_args.contains(k),
+    });
+
+    Ok(())
+}
@@ -15,7 +15,8 @@ use crate::io_models::engine_request::{ChartValuesOverrideName, ChartValuesOverr
 use crate::logger::Logger;
 use crate::models::ToCloudProviderFormat;
 use crate::object_storage::s3::S3;
-use crate::secret_manager::vault::QVaultClient;
+use crate::object_storage::ObjectStorage;
+use crate::secret_manager::secret_backend_from_env;
 use crate::utilities::to_short_id;
 use base64::engine::general_purpose;
 use base64::Engine;
@@ -185,10 +186,10 @@ impl Kubernetes for EC2 {
         mut cluster_secrets: ClusterSecrets,
         kubeconfig_file_path: Option<&Path>,
     ) -> Result<(), Box<EngineError>> {
-        // send cluster info to vault if info mismatch
-        // create vault connection (Vault connectivity should not be on the critical deployment path,
+        // send cluster info to the secret backend if info mismatch
+        // create secret backend connection (its connectivity should not be on the critical deployment path,
         // if it temporarily fails, just ignore it, data will be pushed on the next sync)
-        let Ok(vault_conn) = QVaultClient::new(event_details.clone()) else {
+        let Ok(secret_backend) = secret_backend_from_env(event_details.clone()) else {
             return Ok(());
         };
 
@@ -209,11 +210,15 @@ impl Kubernetes for EC2 {
             cluster_secrets.set_kubeconfig_b64(kubeconfig_b64);
         }
 
-        cluster_secrets.create_or_update_secret(&vault_conn, true, event_details)?;
+        cluster_secrets.create_or_update_secret(secret_backend.as_ref(), true, event_details)?;
 
         Ok(())
     }
 
+    fn object_storage(&self) -> Option<&dyn ObjectStorage> {
+        Some(&self.s3)
+    }
+
     fn advanced_settings(&self) -> &ClusterAdvancedSettings {
         &self.advanced_settings
     }
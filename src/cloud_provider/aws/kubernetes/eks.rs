@@ -16,7 +16,8 @@ use crate::io_models::engine_request::{ChartValuesOverrideName, ChartValuesOverr
 use crate::logger::Logger;
 use crate::models::ToCloudProviderFormat;
 use crate::object_storage::s3::S3;
-use crate::secret_manager::vault::QVaultClient;
+use crate::object_storage::ObjectStorage;
+use crate::secret_manager::secret_backend_from_env;
 use base64::engine::general_purpose;
 use base64::Engine;
 use std::borrow::Borrow;
@@ -171,11 +172,11 @@ impl Kubernetes for EKS {
         cluster_secrets: crate::cloud_provider::vault::ClusterSecrets,
         kubeconfig_file_path: Option<&Path>,
     ) -> Result<(), Box<EngineError>> {
-        let vault_conn = match QVaultClient::new(event_details.clone()) {
+        let secret_backend = match secret_backend_from_env(event_details.clone()) {
             Ok(x) => Some(x),
             Err(_) => None,
         };
-        if let Some(vault) = vault_conn {
+        if let Some(secret_backend) = secret_backend {
             // encode base64 kubeconfig
             let kubeconfig = match kubeconfig_file_path {
                 Some(x) => fs::read_to_string(x)
@@ -197,12 +198,17 @@ impl Kubernetes for EKS {
             cluster_secrets_update.set_kubeconfig_b64(kubeconfig_b64);
 
             // update info without taking care of the kubeconfig because we don't have it yet
-            let _ = cluster_secrets_update.create_or_update_secret(&vault, false, event_details.clone());
+            let _ =
+                cluster_secrets_update.create_or_update_secret(secret_backend.as_ref(), false, event_details.clone());
         };
 
         Ok(())
     }
 
+    fn object_storage(&self) -> Option<&dyn ObjectStorage> {
+        Some(&self.s3)
+    }
+
     fn advanced_settings(&self) -> &ClusterAdvancedSettings {
         &self.advanced_settings
     }
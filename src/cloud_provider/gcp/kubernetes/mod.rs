@@ -20,7 +20,8 @@ use crate::models::gcp::JsonCredentials;
 use crate::models::ToCloudProviderFormat;
 use crate::object_storage::errors::ObjectStorageError;
 use crate::object_storage::google_object_storage::GoogleOS;
-use crate::secret_manager::vault::QVaultClient;
+use crate::object_storage::ObjectStorage;
+use crate::secret_manager::secret_backend_from_env;
 use crate::services::gcp::auth_service::GoogleAuthService;
 use crate::services::gcp::object_storage_regions::GcpStorageRegion;
 use crate::services::gcp::object_storage_service::ObjectStorageService;
@@ -318,12 +319,12 @@ impl Gke {
         event_details: EventDetails,
         cluster_secrets: ClusterSecrets,
     ) -> Result<(), Box<EngineError>> {
-        let vault_conn = match QVaultClient::new(event_details.clone()) {
+        let secret_backend = match secret_backend_from_env(event_details.clone()) {
             Ok(x) => Some(x),
             Err(_) => None,
         };
-        if let Some(vault) = vault_conn {
-            let _ = cluster_secrets.create_or_update_secret(&vault, false, event_details.clone());
+        if let Some(secret_backend) = secret_backend {
+            let _ = cluster_secrets.create_or_update_secret(secret_backend.as_ref(), false, event_details.clone());
         };
 
         Ok(())
@@ -393,6 +394,10 @@ impl Kubernetes for Gke {
         self.update_gke_vault_config(event_details, cluster_secrets)
     }
 
+    fn object_storage(&self) -> Option<&dyn ObjectStorage> {
+        Some(&self.object_storage)
+    }
+
     fn advanced_settings(&self) -> &ClusterAdvancedSettings {
         &self.advanced_settings
     }
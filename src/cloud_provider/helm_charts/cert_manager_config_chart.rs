@@ -88,7 +88,7 @@ impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
                             DnsProviderConfiguration::Cloudflare(cloudflare_config) => {
                                 cloudflare_config.cloudflare_api_token.to_string()
                             }
-                            DnsProviderConfiguration::QoveryDns(_) => "not-set".to_string(),
+                            _ => "not-set".to_string(),
                         },
                     },
                     ChartSetValue {
@@ -97,7 +97,7 @@ impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
                             DnsProviderConfiguration::Cloudflare(cloudflare_config) => {
                                 cloudflare_config.cloudflare_email.to_string()
                             }
-                            DnsProviderConfiguration::QoveryDns(_) => "not-set".to_string(),
+                            _ => "not-set".to_string(),
                         },
                     },
                     // Qovery DNS
@@ -126,7 +126,7 @@ impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
                                 // }
                                 format!("\"{}\"", qovery_dns_config.api_url_port)
                             }
-                            DnsProviderConfiguration::Cloudflare(_) => "no-set".to_string(),
+                            _ => "no-set".to_string(),
                         },
                     },
                     ChartSetValue {
@@ -135,7 +135,7 @@ impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
                             DnsProviderConfiguration::QoveryDns(qovery_dns_config) => {
                                 qovery_dns_config.api_url_scheme_and_domain.to_string()
                             }
-                            DnsProviderConfiguration::Cloudflare(_) => "not-set".to_string(),
+                            _ => "not-set".to_string(),
                         },
                     },
                     ChartSetValue {
@@ -144,7 +144,75 @@ impl ToCommonHelmChart for CertManagerConfigsChart<'_> {
                             DnsProviderConfiguration::QoveryDns(qovery_dns_config) => {
                                 qovery_dns_config.api_key.to_string()
                             }
-                            DnsProviderConfiguration::Cloudflare(_) => "not-set".to_string(),
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    // Route53
+                    ChartSetValue {
+                        key: "provider.route53.accessKeyId".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(route53_config) => {
+                                route53_config.access_key_id.to_string()
+                            }
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "provider.route53.secretAccessKey".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(route53_config) => {
+                                route53_config.secret_access_key.to_string()
+                            }
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "provider.route53.region".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(route53_config) => route53_config.region.to_string(),
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "provider.route53.hostedZoneId".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(route53_config) => {
+                                route53_config.hosted_zone_id.to_string()
+                            }
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    // RFC2136
+                    ChartSetValue {
+                        key: "provider.rfc2136.nameserver".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Rfc2136(rfc2136_config) => rfc2136_config.nameserver(),
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "provider.rfc2136.tsigKeyName".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Rfc2136(rfc2136_config) => {
+                                rfc2136_config.tsig_key_name.to_string()
+                            }
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "provider.rfc2136.tsigAlgorithm".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Rfc2136(rfc2136_config) => {
+                                rfc2136_config.tsig_algorithm.to_cert_manager_format().to_string()
+                            }
+                            _ => "not-set".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "provider.rfc2136.tsigSecret".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Rfc2136(rfc2136_config) => rfc2136_config.tsig_secret.to_string(),
+                            _ => "not-set".to_string(),
                         },
                     },
                 ],
@@ -144,6 +144,80 @@ impl ToCommonHelmChart for ExternalDNSChart {
                             _ => "".to_string(),
                         },
                     },
+                    // Route53
+                    ChartSetValue {
+                        key: "aws.credentials.accessKey".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(config) => config.access_key_id.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "aws.credentials.secretKey".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(config) => config.secret_access_key.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "aws.region".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(config) => config.region.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "zoneIdFilters".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Route53(config) => format!("{{{}}}", config.hosted_zone_id),
+                            _ => "{}".to_string(),
+                        },
+                    },
+                    // RFC2136
+                    ChartSetValue {
+                        key: "rfc2136.host".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Rfc2136(config) => config.nameserver_host.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "rfc2136.port".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Rfc2136(config) => config.nameserver_port.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "rfc2136.zone".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Rfc2136(config) => config.zone.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "rfc2136.tsigKeyname".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Rfc2136(config) => config.tsig_key_name.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "rfc2136.tsigSecret".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Rfc2136(config) => config.tsig_secret.to_string(),
+                            _ => "".to_string(),
+                        },
+                    },
+                    ChartSetValue {
+                        key: "rfc2136.tsigSecretAlg".to_string(),
+                        value: match &self.dns_provider_configuration {
+                            DnsProviderConfiguration::Rfc2136(config) => {
+                                config.tsig_algorithm.to_external_dns_format().to_string()
+                            }
+                            _ => "".to_string(),
+                        },
+                    },
                 ],
                 ..Default::default()
             },
@@ -1,11 +1,16 @@
 use crate::cloud_provider::helm_charts::nginx_ingress_chart::LogFormatEscaping as LogFormatEscapingModel;
 use crate::cloud_provider::models::StorageClass as StorageClassModel;
+use crate::cmd::vulnerability_scanner::VulnerabilityScanner;
+use crate::container_registry::retention::RetentionSweeperMode;
+use crate::container_registry::vulnerability_scan::VulnerabilityScanRuntime;
+use crate::deployment_action::check_dns::DnsCheckMode;
 use crate::models::types::Percentage;
 use crate::{cloud_provider::Kind as KindModel, errors::EngineError, events::EventDetails};
 use base64::engine::general_purpose;
 use base64::Engine;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::str;
 use std::time::Duration;
 
@@ -100,6 +105,17 @@ pub struct ClusterAdvancedSettings {
     pub load_balancer_size: String,
     #[serde(alias = "registry.image_retention_time")]
     pub registry_image_retention_time_sec: u32,
+    // engine side deletion of the images older than registry_image_retention_time_sec, for registries not enforcing it
+    #[serde(alias = "registry.retention_sweeper.mode")]
+    pub registry_retention_sweeper_mode: RetentionSweeperMode,
+    // a repository is swept at most once per interval, whatever the number of deployments
+    #[serde(alias = "registry.retention_sweeper.interval")]
+    pub registry_retention_sweeper_interval_sec: u32,
+    // when set, built and mirrored images are scanned for vulnerabilities before being deployed
+    #[serde(alias = "registry.vulnerability_scan.scanner")]
+    pub registry_vulnerability_scan_scanner: Option<VulnerabilityScanner>,
+    #[serde(alias = "registry.vulnerability_scan.runtime")]
+    pub registry_vulnerability_scan_runtime: VulnerabilityScanRuntime,
     #[serde(alias = "pleco.resources_ttl")]
     pub pleco_resources_ttl: i32,
     #[serde(alias = "loki.log_retention_in_week")]
@@ -150,6 +166,9 @@ pub struct ClusterAdvancedSettings {
     pub database_mongodb_deny_any_access: bool,
     #[serde(alias = "database.mongodb.allowed_cidrs")]
     pub database_mongodb_allowed_cidrs: Vec<String>,
+    // after a credentials rotation, delay before the previous password is removed from databases supporting dual passwords
+    #[serde(alias = "database.credentials_rotation.discard_previous_password_delay_sec")]
+    pub database_credentials_rotation_discard_previous_password_delay_sec: u32,
     #[serde(alias = "registry.mirroring_mode", default = "default_registry_mirroring_mode")]
     pub registry_mirroring_mode: RegistryMirroringMode,
     #[serde(alias = "nginx.vcpu.request_in_milli_cpu")]
@@ -191,6 +210,18 @@ pub struct ClusterAdvancedSettings {
     pub k8s_api_allowed_public_access_cidrs: Option<Vec<String>>,
     #[serde(alias = "storageclass.fast_ssd")]
     pub k8s_storage_class_fast_ssd: StorageClass,
+    // resolvers used to check domains propagation, public and system resolvers are used when empty
+    #[serde(alias = "dns.check.resolvers")]
+    pub dns_check_resolvers: Vec<IpAddr>,
+    #[serde(alias = "dns.check.mode")]
+    pub dns_check_mode: DnsCheckMode,
+    // number of resolvers which must agree on the records of a domain, in consistency mode
+    #[serde(alias = "dns.check.consistency_min_resolvers")]
+    pub dns_check_consistency_min_resolvers: u32,
+    #[serde(alias = "dns.check.timeout_sec")]
+    pub dns_check_timeout_sec: u32,
+    #[serde(alias = "dns.check.frequency_sec")]
+    pub dns_check_frequency_sec: u32,
 }
 
 impl Default for ClusterAdvancedSettings {
@@ -200,6 +231,10 @@ impl Default for ClusterAdvancedSettings {
         ClusterAdvancedSettings {
             load_balancer_size: "lb-s".to_string(),
             registry_image_retention_time_sec: 31536000,
+            registry_retention_sweeper_mode: RetentionSweeperMode::Disabled,
+            registry_retention_sweeper_interval_sec: 86400,
+            registry_vulnerability_scan_scanner: None,
+            registry_vulnerability_scan_runtime: VulnerabilityScanRuntime::LocalBinary,
             pleco_resources_ttl: -1,
             loki_log_retention_in_week: 12,
             aws_iam_user_mapper_group_enabled: true,
@@ -220,6 +255,7 @@ impl Default for ClusterAdvancedSettings {
             database_redis_allowed_cidrs: default_database_cirds.clone(),
             database_mongodb_deny_any_access: false,
             database_mongodb_allowed_cidrs: default_database_cirds,
+            database_credentials_rotation_discard_previous_password_delay_sec: 3600,
             registry_mirroring_mode: RegistryMirroringMode::Service,
             nginx_vcpu_request_in_milli_cpu: 100,
             nginx_vcpu_limit_in_milli_cpu: 500,
@@ -244,6 +280,11 @@ impl Default for ClusterAdvancedSettings {
             aws_eks_alb_controller_vpa_min_memory_in_mib: 128,
             aws_eks_alb_controller_vpa_max_memory_in_mib: 2000,
             k8s_storage_class_fast_ssd: StorageClass("".to_string()),
+            dns_check_resolvers: vec![],
+            dns_check_mode: DnsCheckMode::AnyResolver,
+            dns_check_consistency_min_resolvers: 2,
+            dns_check_timeout_sec: 60 * 5,
+            dns_check_frequency_sec: 30,
         }
     }
 }
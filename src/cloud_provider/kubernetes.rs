@@ -19,6 +19,7 @@ use crate::io_models::context::Context;
 use crate::io_models::QoveryIdentifier;
 use crate::logger::Logger;
 use crate::models::types::VersionsNumber;
+use crate::object_storage::ObjectStorage;
 use k8s_openapi::api::core::v1::{Namespace, Secret, Service};
 use kube::api::{ListParams, ObjectMeta, Patch, PatchParams, PostParams};
 use kube::core::ObjectList;
@@ -460,6 +461,8 @@ pub trait Kubernetes: Send + Sync {
     ) -> Result<(), Box<EngineError>>;
 
     fn advanced_settings(&self) -> &ClusterAdvancedSettings;
+    /// Object storage of the cluster, used for logs, archives and terraform states
+    fn object_storage(&self) -> Option<&dyn ObjectStorage>;
     fn is_karpenter_enabled(&self) -> bool {
         false
     }
@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use crate::cloud_provider::environment::Environment;
 use crate::cloud_provider::kubernetes::Kubernetes;
 use crate::cloud_provider::service::Service;
+use crate::cmd::cosign::Cosign;
 use crate::cmd::docker::Docker;
 use crate::cmd::helm::{to_engine_error, Helm};
 use crate::container_registry::ContainerRegistry;
@@ -146,6 +147,8 @@ pub struct DeploymentTarget<'a> {
     pub abort: &'a dyn Abort,
     logger: Arc<Box<dyn Logger>>,
     pub metrics_registry: Arc<dyn MetricsRegistry>,
+    // when set, images coming from a registry must be signed by this key pair before being deployed
+    pub image_signer: Option<&'a Cosign>,
     pub is_dry_run_deploy: bool,
     pub is_test_cluster: bool,
 }
@@ -191,6 +194,7 @@ impl<'a> DeploymentTarget<'a> {
             is_dry_run_deploy: kubernetes.context().is_dry_run_deploy(),
             is_test_cluster: kubernetes.context().is_test_cluster(),
             metrics_registry: Arc::from(infra_ctx.metrics_registry().clone_dyn()),
+            image_signer: infra_ctx.image_signer(),
         })
     }
 
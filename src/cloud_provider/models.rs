@@ -11,6 +11,17 @@ pub struct EnvironmentVariable {
     pub key: String,
     pub value: String,
     pub is_secret: bool,
+    /// Set when the value is not rendered by the engine but pulled by External Secrets Operator
+    pub external_secret: Option<ExternalSecretRemoteRef>,
+}
+
+/// Remote reference of an External Secrets Operator `ExternalSecret` data entry
+#[derive(Serialize, Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ExternalSecretRemoteRef {
+    pub cluster_secret_store: String,
+    pub key: String,
+    pub version: Option<String>,
+    pub property: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -20,8 +20,9 @@ use crate::infrastructure_action::InfrastructureAction;
 use crate::models::domain::ToTerraformString;
 use crate::models::scaleway::ScwZone;
 use crate::object_storage::scaleway_object_storage::ScalewayOS;
+use crate::object_storage::ObjectStorage;
 use crate::runtime::block_on;
-use crate::secret_manager::vault::QVaultClient;
+use crate::secret_manager::secret_backend_from_env;
 use crate::utilities::to_short_id;
 use base64::engine::general_purpose;
 use base64::Engine;
@@ -379,11 +380,11 @@ impl Kubernetes for Kapsule {
         cluster_secrets: ClusterSecrets,
         kubeconfig_file_path: Option<&Path>,
     ) -> Result<(), Box<EngineError>> {
-        let vault_conn = match QVaultClient::new(event_details.clone()) {
+        let secret_backend = match secret_backend_from_env(event_details.clone()) {
             Ok(x) => Some(x),
             Err(_) => None,
         };
-        if let Some(vault) = vault_conn {
+        if let Some(secret_backend) = secret_backend {
             // encode base64 kubeconfig
             let kubeconfig = match kubeconfig_file_path {
                 Some(x) => fs::read_to_string(x)
@@ -404,11 +405,15 @@ impl Kubernetes for Kapsule {
             cluster_secrets_update.set_kubeconfig_b64(kubeconfig_b64);
 
             // update info without taking care of the kubeconfig because we don't have it yet
-            let _ = cluster_secrets_update.create_or_update_secret(&vault, false, event_details);
+            let _ = cluster_secrets_update.create_or_update_secret(secret_backend.as_ref(), false, event_details);
         };
         Ok(())
     }
 
+    fn object_storage(&self) -> Option<&dyn ObjectStorage> {
+        Some(&self.object_storage)
+    }
+
     fn advanced_settings(&self) -> &ClusterAdvancedSettings {
         &self.advanced_settings
     }
@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 
 use uuid::Uuid;
 
+use crate::cloud_provider::helm_charts::loki_chart::LokiObjectBucketConfiguration;
 use crate::cloud_provider::io::ClusterAdvancedSettings;
 use crate::cloud_provider::kubeconfig_helper::write_kubeconfig_on_disk;
 use crate::cloud_provider::kubernetes::{self, Kind, Kubernetes, KubernetesVersion};
@@ -18,9 +19,12 @@ use crate::events::Stage::Infrastructure;
 use crate::infrastructure_action::InfrastructureAction;
 use crate::io_models::context::Context;
 use crate::logger::Logger;
+use crate::object_storage::s3_compatible::{S3CompatibleOS, S3CompatibleStorageOptions};
+use crate::object_storage::ObjectStorage;
 use crate::utilities::to_short_id;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use tera::Context as TeraContext;
 
 pub struct SelfManaged {
     context: Context,
@@ -30,8 +34,9 @@ pub struct SelfManaged {
     name: String,
     version: KubernetesVersion,
     region: String,
-    #[allow(dead_code)] //TODO(pmavro): not yet implemented
     options: SelfManagedOptions,
+    // brought by the user for logs, archives and terraform states, as there is no cloud provider one
+    object_storage: Option<S3CompatibleOS>,
     logger: Box<dyn Logger>,
     advanced_settings: ClusterAdvancedSettings,
     kubeconfig: Option<String>,
@@ -52,7 +57,7 @@ impl SelfManaged {
         kubeconfig: Option<String>,
         temp_dir: PathBuf,
     ) -> Result<SelfManaged, Box<EngineError>> {
-        let cluster = SelfManaged {
+        let mut cluster = SelfManaged {
             context,
             id: to_short_id(&long_id),
             kind,
@@ -61,6 +66,7 @@ impl SelfManaged {
             version,
             region: cloud_provider.region(),
             options,
+            object_storage: None,
             logger,
             advanced_settings,
             kubeconfig,
@@ -75,8 +81,62 @@ impl SelfManaged {
             )?;
         }
 
+        let object_storage = cluster
+            .options
+            .object_storage
+            .as_ref()
+            .map(|options| {
+                S3CompatibleOS::from_options(
+                    format!("{}-object-storage", cluster.id),
+                    format!("{} object storage", cluster.name),
+                    options,
+                )
+            })
+            .transpose();
+        cluster.object_storage = match object_storage {
+            Ok(object_storage) => object_storage,
+            Err(err) => {
+                return Err(Box::new(EngineError::new_object_storage_error(
+                    cluster.get_event_details(Infrastructure(InfrastructureStep::LoadConfiguration)),
+                    err,
+                )))
+            }
+        };
+
         Ok(cluster)
     }
+
+    pub fn logs_bucket_name(&self) -> String {
+        format!("qovery-logs-{}", self.id)
+    }
+
+    pub fn tfstates_bucket_name(&self) -> String {
+        format!("qovery-tfstates-{}", self.id)
+    }
+
+    /// Loki storage configuration, None when the user didn't bring an object storage
+    pub fn loki_object_bucket_configuration(&self) -> Option<LokiObjectBucketConfiguration> {
+        self.object_storage.as_ref().map(|object_storage| {
+            LokiObjectBucketConfiguration::S3(object_storage.loki_chart_configuration(&self.logs_bucket_name()))
+        })
+    }
+
+    /// Terraform `s3` backend of `lib/self-managed/services/common/backend.j2.tf`, states are kept in
+    /// Kubernetes secrets when the user didn't bring an object storage
+    pub fn insert_terraform_backend_into_teracontext<'a>(
+        &self,
+        context: &'a mut TeraContext,
+        state_key: &str,
+    ) -> &'a mut TeraContext {
+        match &self.object_storage {
+            Some(object_storage) => object_storage.insert_terraform_backend_into_teracontext(
+                context,
+                &self.tfstates_bucket_name(),
+                state_key,
+            ),
+            None => context,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -87,6 +147,9 @@ pub struct SelfManagedOptions {
     pub qovery_engine_url: String,
     pub jwt_token: String,
     pub qovery_engine_location: EngineLocation,
+    // S3-compatible storage (i.e: MinIO), optional as on-premise clusters don't always have one
+    #[serde(default)]
+    pub object_storage: Option<S3CompatibleStorageOptions>,
 }
 
 impl Kubernetes for SelfManaged {
@@ -165,6 +228,12 @@ impl Kubernetes for SelfManaged {
         Ok(())
     }
 
+    fn object_storage(&self) -> Option<&dyn ObjectStorage> {
+        self.object_storage
+            .as_ref()
+            .map(|object_storage| object_storage as &dyn ObjectStorage)
+    }
+
     fn advanced_settings(&self) -> &ClusterAdvancedSettings {
         &self.advanced_settings
     }
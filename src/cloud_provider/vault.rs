@@ -4,9 +4,8 @@ use crate::errors::{CommandError, EngineError};
 use crate::events::EventDetails;
 use crate::models::gcp::io::JsonCredentials;
 use crate::secret_manager::vault::get_vault_mount_name;
-use crate::secret_manager::vault::QVaultClient;
+use crate::secret_manager::{SecretBackend, SecretMetadata};
 use serde::{Deserialize, Serialize};
-use vaultrs::api::kv2::responses::SecretVersionMetadata;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(untagged)]
@@ -247,41 +246,42 @@ impl ClusterSecrets {
     }
 
     pub fn get_secret(
-        qvault_client: &QVaultClient,
+        secret_backend: &dyn SecretBackend,
         cloud_provider: Kind,
         cluster_id: &str,
         is_test_cluster: bool,
         event_details: EventDetails,
     ) -> Result<ClusterSecrets, Box<EngineError>> {
         let mount = get_vault_mount_name(is_test_cluster);
-        let err = |e| {
+        let err = |e: String| {
             Box::new(EngineError::new_vault_secret_could_not_be_retrieved(
                 event_details.clone(),
                 CommandError::new(
                     format!("Vault secret couldn't be retrieved ({cloud_provider}/{cluster_id})"),
-                    Some(format!("{e}")),
+                    Some(e),
                     None,
                 ),
             ))
         };
+        let get_secret = || -> Result<serde_json::Value, Box<EngineError>> {
+            secret_backend
+                .get(mount.as_str(), cluster_id)
+                .map_err(|e| err(e.to_string()))
+        };
 
         match cloud_provider {
-            Kind::Eks | Kind::EksSelfManaged => match qvault_client.get_secret(mount.as_str(), cluster_id) {
-                Ok(x) => Ok(ClusterSecrets::Eks(x)),
-                Err(e) => Err(err(e)),
-            },
-            Kind::Ec2 => match qvault_client.get_secret(mount.as_str(), cluster_id) {
-                Ok(x) => Ok(ClusterSecrets::Ec2(x)),
-                Err(e) => Err(err(e)),
-            },
-            Kind::ScwKapsule | Kind::ScwSelfManaged => match qvault_client.get_secret(mount.as_str(), cluster_id) {
-                Ok(x) => Ok(ClusterSecrets::Scaleway(x)),
-                Err(e) => Err(err(e)),
-            },
-            Kind::Gke | Kind::GkeSelfManaged => match qvault_client.get_secret(mount.as_str(), cluster_id) {
-                Ok(x) => Ok(ClusterSecrets::Gke(x)),
-                Err(e) => Err(err(e)),
-            },
+            Kind::Eks | Kind::EksSelfManaged => serde_json::from_value(get_secret()?)
+                .map(ClusterSecrets::Eks)
+                .map_err(|e| err(e.to_string())),
+            Kind::Ec2 => serde_json::from_value(get_secret()?)
+                .map(ClusterSecrets::Ec2)
+                .map_err(|e| err(e.to_string())),
+            Kind::ScwKapsule | Kind::ScwSelfManaged => serde_json::from_value(get_secret()?)
+                .map(ClusterSecrets::Scaleway)
+                .map_err(|e| err(e.to_string())),
+            Kind::Gke | Kind::GkeSelfManaged => serde_json::from_value(get_secret()?)
+                .map(ClusterSecrets::Gke)
+                .map_err(|e| err(e.to_string())),
             Kind::OnPremiseSelfManaged => Err(Box::new(EngineError::new_vault_secret_could_not_be_retrieved(
                 event_details,
                 CommandError::new(
@@ -348,19 +348,19 @@ impl ClusterSecrets {
         }
     }
 
-    /// Create or update a secret in vault
+    /// Create or update a secret in the secret backend
     /// If the secret already exists and has the same content, no update will be made
     /// ignore_kubeconfig_compare is used to avoid to compare kubeconfig_b64. Useful for EC2 when k3s is not ready yet but EC2 instance is
     pub fn create_or_update_secret(
         &self,
-        qvault_client: &QVaultClient,
+        secret_backend: &dyn SecretBackend,
         ignore_kubeconfig_compare: bool,
         event_details: EventDetails,
-    ) -> Result<Option<SecretVersionMetadata>, Box<EngineError>> {
+    ) -> Result<Option<SecretMetadata>, Box<EngineError>> {
         // check if secret already exists and has the same content to avoid to create a new version
         // then update if needed
         match Self::get_secret(
-            qvault_client,
+            secret_backend,
             self.get_cloud_provider(),
             self.get_cluster_id(),
             self.get_test_cluster(),
@@ -401,7 +401,13 @@ impl ClusterSecrets {
             ClusterSecrets::SelfManaged(x) => (x.vault_mount_name.as_str(), x.cluster_id.as_str()),
         };
 
-        match qvault_client.crate_update_secret(vault_mount_name, secret_name, self) {
+        let secret_content = serde_json::to_value(self).map_err(|e| {
+            Box::new(EngineError::new_vault_secret_could_not_be_created_or_updated(
+                event_details.clone(),
+                CommandError::new("Vault secret couldn't be serialized".to_string(), Some(e.to_string()), None),
+            ))
+        })?;
+        match secret_backend.put(vault_mount_name, secret_name, &secret_content) {
             Ok(x) => Ok(Some(x)),
             Err(e) => Err(Box::new(EngineError::new_vault_secret_could_not_be_created_or_updated(
                 event_details,
@@ -418,7 +424,7 @@ impl ClusterSecrets {
     #[allow(dead_code)]
     pub fn delete_secret(
         &self,
-        qvault_client: &QVaultClient,
+        secret_backend: &dyn SecretBackend,
         event_details: EventDetails,
     ) -> Result<(), Box<EngineError>> {
         let (vault_mount_name, secret_name) = match self {
@@ -428,7 +434,7 @@ impl ClusterSecrets {
             ClusterSecrets::SelfManaged(x) => (x.vault_mount_name.as_str(), x.cluster_id.as_str()),
         };
 
-        match qvault_client.delete_secret(vault_mount_name, secret_name) {
+        match secret_backend.delete(vault_mount_name, secret_name) {
             Ok(_) => Ok(()),
             Err(e) => Err(Box::new(EngineError::new_vault_secret_could_not_be_created_or_updated(
                 event_details,
@@ -0,0 +1,345 @@
+use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::cmd::docker::{Architecture, BuildCache, ContainerImage};
+use itertools::Itertools;
+use std::fs;
+use std::path::Path;
+use std::process::ExitStatus;
+use std::time::Duration;
+use tempfile::TempDir;
+use url::Url;
+
+#[derive(thiserror::Error, Debug)]
+pub enum BuildCtlError {
+    #[error("Buildctl Invalid configuration: {raw_error_message:?}")]
+    InvalidConfig { raw_error_message: String },
+
+    #[error("Buildctl terminated with an unknown error: {raw_error:?}")]
+    ExecutionError { raw_error: std::io::Error },
+
+    #[error("Buildctl terminated with a non success exit status code: {exit_status:?}")]
+    ExitStatusError { exit_status: ExitStatus },
+
+    #[error("Buildctl aborted due to user cancel request: {raw_error_message:?}")]
+    Aborted { raw_error_message: String },
+
+    #[error("Buildctl command terminated due to timeout: {raw_error_message:?}")]
+    Timeout { raw_error_message: String },
+}
+
+impl BuildCtlError {
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, BuildCtlError::Aborted { .. })
+    }
+}
+
+/// mTLS materials, in PEM format, used to authenticate against a remote buildkitd
+#[derive(Clone)]
+pub struct BuildKitTls {
+    pub ca_cert: String,
+    pub client_cert: String,
+    pub client_key: String,
+    pub server_name: Option<String>,
+}
+
+/// Client for a standalone buildkitd daemon, reachable through tcp:// or unix://
+#[derive(Debug)]
+pub struct BuildCtl {
+    address: Url,
+    // Directory holding ca.pem/cert.pem/key.pem, removed on drop
+    tls_dir: Option<TempDir>,
+    tls_server_name: Option<String>,
+    common_envs: Vec<(String, String)>,
+}
+
+impl BuildCtl {
+    pub fn new(address: Url, tls: Option<BuildKitTls>) -> Result<Self, BuildCtlError> {
+        if !matches!(address.scheme(), "tcp" | "unix") {
+            return Err(BuildCtlError::InvalidConfig {
+                raw_error_message: format!(
+                    "buildkitd address `{address}` must use tcp:// or unix:// scheme, got `{}`",
+                    address.scheme()
+                ),
+            });
+        }
+
+        let (tls_dir, tls_server_name) = match tls {
+            None => (None, None),
+            Some(tls) => {
+                let Ok(tmp_dir) = TempDir::with_prefix("buildkit-tls-") else {
+                    return Err(BuildCtlError::InvalidConfig {
+                        raw_error_message: "Cannot create temporary directory to store buildkit certificates"
+                            .to_string(),
+                    });
+                };
+
+                for (file_name, content) in [
+                    ("ca.pem", &tls.ca_cert),
+                    ("cert.pem", &tls.client_cert),
+                    ("key.pem", &tls.client_key),
+                ] {
+                    fs::write(tmp_dir.path().join(file_name), content).map_err(|err| BuildCtlError::InvalidConfig {
+                        raw_error_message: format!("Cannot write buildkit certificate {file_name}: {err}"),
+                    })?;
+                }
+
+                (Some(tmp_dir), tls.server_name)
+            }
+        };
+
+        let buildctl = BuildCtl {
+            address,
+            tls_dir,
+            tls_server_name,
+            common_envs: vec![],
+        };
+
+        // First check that buildctl is correctly installed
+        let buildctl_cmd_exist = buildctl_exec(
+            &["--version"],
+            &buildctl.get_all_envs(&[]),
+            &mut |_| {},
+            &mut |_| {},
+            &CommandKiller::never(),
+        );
+        if buildctl_cmd_exist.is_err() {
+            return Err(BuildCtlError::InvalidConfig {
+                raw_error_message: "buildctl binary for buildkit is not correctly installed".to_string(),
+            });
+        }
+
+        Ok(buildctl)
+    }
+
+    pub fn address(&self) -> &Url {
+        &self.address
+    }
+
+    fn get_all_envs<'a>(&'a self, envs: &'a [(&'a str, &'a str)]) -> Vec<(&'a str, &'a str)> {
+        let mut all_envs: Vec<(&str, &str)> = self.common_envs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        all_envs.append(&mut envs.to_vec());
+
+        all_envs
+    }
+
+    fn global_args(&self) -> Vec<String> {
+        let mut args = vec!["--addr".to_string(), self.address.to_string()];
+        if let Some(tls_dir) = &self.tls_dir {
+            args.push(format!("--tlscacert={}", tls_dir.path().join("ca.pem").to_string_lossy()));
+            args.push(format!("--tlscert={}", tls_dir.path().join("cert.pem").to_string_lossy()));
+            args.push(format!("--tlskey={}", tls_dir.path().join("key.pem").to_string_lossy()));
+        }
+        if let Some(server_name) = &self.tls_server_name {
+            args.push(format!("--tlsservername={}", server_name));
+        }
+
+        args
+    }
+
+    /// Check that the remote buildkitd is reachable and has at least one worker
+    pub fn check_workers(&self, should_abort: &CommandKiller) -> Result<(), BuildCtlError> {
+        let mut args = self.global_args();
+        args.extend(["debug".to_string(), "workers".to_string()]);
+
+        buildctl_exec(
+            &args.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
+            &self.get_all_envs(&[]),
+            &mut |line| info!("{}", line),
+            &mut |line| warn!("{}", line),
+            should_abort,
+        )
+    }
+
+    /// Build the image with the dockerfile frontend of the remote buildkitd.
+    /// Registry credentials are read from the docker config located at `docker_config_path`.
+    pub fn build<Stdout, Stderr>(
+        &self,
+        dockerfile: &Path,
+        context: &Path,
+        image_to_build: &ContainerImage,
+        build_args: &[(&str, &str)],
+        secrets: &[(&str, &str)],
+        cache: &BuildCache,
+        push_after_build: bool,
+        insecure_registry: bool,
+        architectures: &[Architecture],
+        docker_config_path: &Path,
+        stdout_output: &mut Stdout,
+        stderr_output: &mut Stderr,
+        should_abort: &CommandKiller,
+    ) -> Result<(), BuildCtlError>
+    where
+        Stdout: FnMut(String),
+        Stderr: FnMut(String),
+    {
+        // Do some checks
+        if !dockerfile.is_file() {
+            return Err(BuildCtlError::InvalidConfig {
+                raw_error_message: format!("provided dockerfile `{dockerfile:?}` is not a valid file"),
+            });
+        }
+
+        if !context.is_dir() {
+            return Err(BuildCtlError::InvalidConfig {
+                raw_error_message: format!("provided buildkit build context `{context:?}` is not a valid directory"),
+            });
+        }
+
+        info!("Buildctl build {:?} on {}", image_to_build.image_name(), self.address);
+
+        let dockerfile_dir = dockerfile.parent().unwrap_or(context);
+        let dockerfile_name = dockerfile
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("Dockerfile");
+
+        let mut args_string = self.global_args();
+        args_string.extend([
+            "build".to_string(),
+            "--progress=plain".to_string(),
+            "--frontend=dockerfile.v0".to_string(),
+            "--local".to_string(),
+            format!("context={}", context.to_str().unwrap_or_default()),
+            "--local".to_string(),
+            format!("dockerfile={}", dockerfile_dir.to_str().unwrap_or_default()),
+            "--opt".to_string(),
+            format!("filename={}", dockerfile_name),
+        ]);
+
+        for import_cache in cache.import_args() {
+            args_string.push("--import-cache".to_string());
+            args_string.push(import_cache);
+        }
+
+        // Names containing a comma must be quoted, as buildctl parses output attributes as CSV
+        let mut output = format!("type=image,\"name={}\"", image_to_build.image_names().join(","));
+        if push_after_build {
+            output.push_str(",push=true");
+            if insecure_registry {
+                output.push_str(",registry.insecure=true");
+            }
+        }
+        args_string.push("--output".to_string());
+        args_string.push(output);
+
+        if push_after_build {
+            if let Some(export_cache) = cache.export_args() {
+                args_string.push("--export-cache".to_string());
+                args_string.push(export_cache);
+            }
+        }
+
+        // Build for all requested architectures, if empty build for the architecture of the buildkitd worker
+        if !architectures.is_empty() {
+            args_string.push("--opt".to_string());
+            args_string.push(format!(
+                "platform={}",
+                architectures.iter().map(|arch| arch.to_platform()).join(",")
+            ));
+        };
+
+        for (k, v) in build_args {
+            args_string.push("--opt".to_string());
+            args_string.push(format!("build-arg:{k}={v}"));
+        }
+
+        // Secrets are never passed on the command line, they are mounted by buildkit with RUN --mount=type=secret,id=...
+        let secrets_dir = TempDir::with_prefix("buildkit-secrets-")
+            .map_err(|err| BuildCtlError::ExecutionError { raw_error: err })?;
+        for (k, v) in secrets {
+            let secret_path = secrets_dir.path().join(k);
+            fs::write(&secret_path, v).map_err(|err| BuildCtlError::ExecutionError { raw_error: err })?;
+            args_string.push("--secret".to_string());
+            args_string.push(format!("id={k},src={}", secret_path.to_string_lossy()));
+        }
+
+        let docker_config = docker_config_path.to_str().unwrap_or_default();
+        buildctl_exec(
+            &args_string.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
+            &self.get_all_envs(&[("DOCKER_CONFIG", docker_config)]),
+            stdout_output,
+            stderr_output,
+            should_abort,
+        )
+    }
+}
+
+fn buildctl_exec<F, X>(
+    args: &[&str],
+    envs: &[(&str, &str)],
+    stdout_output: &mut F,
+    stderr_output: &mut X,
+    cmd_killer: &CommandKiller,
+) -> Result<(), BuildCtlError>
+where
+    F: FnMut(String),
+    X: FnMut(String),
+{
+    let mut cmd = QoveryCommand::new("buildctl", args, envs);
+    cmd.set_kill_grace_period(Duration::from_secs(0));
+    let ret = cmd.exec_with_abort(stdout_output, stderr_output, cmd_killer);
+
+    match ret {
+        Ok(_) => Ok(()),
+        Err(CommandError::TimeoutError(msg)) => Err(BuildCtlError::Timeout { raw_error_message: msg }),
+        Err(CommandError::Killed(msg)) => Err(BuildCtlError::Aborted { raw_error_message: msg }),
+        Err(CommandError::ExitStatusError(err)) => Err(BuildCtlError::ExitStatusError { exit_status: err }),
+        Err(CommandError::ExecutionError(err)) => Err(BuildCtlError::ExecutionError { raw_error: err }),
+    }
+}
+
+// start a local registry and a local buildkitd to run this test
+// docker run --rm -d -p 5000:5000 --name registry registry:2
+// docker run --rm -d --privileged --network host --name buildkitd moby/buildkit --addr tcp://0.0.0.0:1234
+#[cfg(feature = "test-local-docker")]
+#[cfg(test)]
+mod tests {
+    use crate::cmd::buildctl::BuildCtl;
+    use crate::cmd::command::CommandKiller;
+    use crate::cmd::docker::{Architecture, BuildCache, ContainerImage, Docker};
+    use std::path::Path;
+    use url::Url;
+
+    #[cfg(target_arch = "x86_64")]
+    static CPU_ARCHITECTURE: &[Architecture] = &[Architecture::AMD64];
+    #[cfg(target_arch = "aarch64")]
+    static CPU_ARCHITECTURE: &[Architecture] = &[Architecture::ARM64];
+
+    #[test]
+    fn test_buildctl_build() {
+        let registry = Url::parse("http://localhost:5000").unwrap();
+        let buildctl = BuildCtl::new(Url::parse("tcp://localhost:1234").unwrap(), None).unwrap();
+        let docker = Docker::new(None).unwrap();
+        buildctl.check_workers(&CommandKiller::never()).unwrap();
+
+        let image_to_build = ContainerImage::new(
+            registry.clone(),
+            "local-repo/buildctl".to_string(),
+            vec!["v42.42".to_string(), "latest".to_string()],
+        );
+        let image_cache = BuildCache::new(ContainerImage::new(
+            registry,
+            "local-repo/buildctl".to_string(),
+            vec!["cache".to_string()],
+        ));
+
+        let ret = buildctl.build(
+            Path::new("tests/docker/multi_stage_simple/Dockerfile"),
+            Path::new("tests/docker/multi_stage_simple/"),
+            &image_to_build,
+            &[("foo", "bar")],
+            &[("my_secret", "my_secret_value")],
+            &image_cache,
+            true,
+            true,
+            CPU_ARCHITECTURE,
+            docker.config_path(),
+            &mut |msg| println!("{msg}"),
+            &mut |msg| eprintln!("{msg}"),
+            &CommandKiller::never(),
+        );
+        assert!(ret.is_ok());
+
+        let ret = docker.does_image_exist_remotely(&image_to_build);
+        assert!(matches!(ret, Ok(true)));
+    }
+}
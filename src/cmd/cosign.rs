@@ -0,0 +1,199 @@
+use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::cmd::docker::ContainerImage;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CosignError {
+    #[error("Cosign Invalid configuration: {raw_error_message:?}")]
+    InvalidConfig { raw_error_message: String },
+
+    #[error("Cosign terminated with an unknown error: {raw_error:?}")]
+    ExecutionError { raw_error: std::io::Error },
+
+    #[error("Cosign terminated with a non success exit status code: {exit_status:?}")]
+    ExitStatusError { exit_status: ExitStatus },
+
+    #[error("Cosign aborted due to user cancel request: {raw_error_message:?}")]
+    Aborted { raw_error_message: String },
+
+    #[error("Cosign command terminated due to timeout: {raw_error_message:?}")]
+    Timeout { raw_error_message: String },
+}
+
+impl CosignError {
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, CosignError::Aborted { .. })
+    }
+}
+
+/// Cosign key pair, in PEM format, as generated by `cosign generate-key-pair`.
+/// The private key is only required to sign images, verification only needs the public key.
+#[derive(Clone)]
+pub struct CosignKeys {
+    pub private_key: Option<String>,
+    pub private_key_password: Option<String>,
+    pub public_key: String,
+}
+
+/// Sign and verify images with cosign.
+/// Signatures are stored in the registry next to the image (`sha256-<digest>.sig`), so any cosign tooling can verify them.
+/// Transparency log is not used, as signed images are most of the time in private registries.
+pub struct Cosign {
+    // Directory holding cosign.key/cosign.pub, removed on drop
+    _keys_dir: TempDir,
+    private_key_path: Option<PathBuf>,
+    private_key_password: String,
+    public_key_path: PathBuf,
+}
+
+impl Cosign {
+    pub fn new(keys: CosignKeys) -> Result<Self, CosignError> {
+        let Ok(keys_dir) = TempDir::with_prefix("cosign-") else {
+            return Err(CosignError::InvalidConfig {
+                raw_error_message: "Cannot create temporary directory to store cosign keys".to_string(),
+            });
+        };
+
+        let write_key = |file_name: &str, content: &str| -> Result<PathBuf, CosignError> {
+            let path = keys_dir.path().join(file_name);
+            fs::write(&path, content).map_err(|err| CosignError::InvalidConfig {
+                raw_error_message: format!("Cannot write cosign key {file_name}: {err}"),
+            })?;
+            Ok(path)
+        };
+
+        let public_key_path = write_key("cosign.pub", &keys.public_key)?;
+        let private_key_path = match &keys.private_key {
+            Some(private_key) => Some(write_key("cosign.key", private_key)?),
+            None => None,
+        };
+
+        let cosign = Cosign {
+            _keys_dir: keys_dir,
+            private_key_path,
+            private_key_password: keys.private_key_password.unwrap_or_default(),
+            public_key_path,
+        };
+
+        // First check that cosign is correctly installed
+        if cosign_exec(&["version"], &[], &mut |_| {}, &mut |_| {}, &CommandKiller::never()).is_err() {
+            return Err(CosignError::InvalidConfig {
+                raw_error_message: "cosign binary is not correctly installed".to_string(),
+            });
+        }
+
+        Ok(cosign)
+    }
+
+    pub fn can_sign(&self) -> bool {
+        self.private_key_path.is_some()
+    }
+
+    fn registry_args(image: &ContainerImage, tls_verify: bool) -> Vec<String> {
+        let mut args = vec![];
+        if image.registry.scheme() == "http" {
+            args.push("--allow-http-registry=true".to_string());
+        }
+        if !tls_verify || image.registry.scheme() == "http" {
+            args.push("--allow-insecure-registry=true".to_string());
+        }
+
+        args
+    }
+
+    /// Sign the image `digest`, and push the signature into the image repository
+    pub fn sign(
+        &self,
+        image: &ContainerImage,
+        digest: &str,
+        docker_config_path: &Path,
+        tls_verify: bool,
+        should_abort: &CommandKiller,
+    ) -> Result<(), CosignError> {
+        let Some(private_key_path) = &self.private_key_path else {
+            return Err(CosignError::InvalidConfig {
+                raw_error_message: "No private key configured to sign images".to_string(),
+            });
+        };
+
+        let subject = format!("{}@{}", image.repository_with_host(), digest);
+        info!("Cosign signing image {}", subject);
+
+        let mut args = vec![
+            "sign".to_string(),
+            "--yes".to_string(),
+            format!("--key={}", private_key_path.to_string_lossy()),
+            "--tlog-upload=false".to_string(),
+        ];
+        args.extend(Self::registry_args(image, tls_verify));
+        args.push(subject);
+
+        cosign_exec(
+            &args.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
+            &[
+                ("COSIGN_PASSWORD", self.private_key_password.as_str()),
+                ("DOCKER_CONFIG", docker_config_path.to_str().unwrap_or_default()),
+            ],
+            &mut |line| info!("{}", line),
+            &mut |line| info!("{}", line),
+            should_abort,
+        )
+    }
+
+    /// Verify that the image has a valid signature made by the configured key pair
+    pub fn verify(
+        &self,
+        image: &ContainerImage,
+        docker_config_path: &Path,
+        tls_verify: bool,
+        should_abort: &CommandKiller,
+    ) -> Result<(), CosignError> {
+        let image_name = image.image_name();
+        info!("Cosign verifying signature of image {}", image_name);
+
+        let mut args = vec![
+            "verify".to_string(),
+            format!("--key={}", self.public_key_path.to_string_lossy()),
+            "--insecure-ignore-tlog=true".to_string(),
+            "--output=text".to_string(),
+        ];
+        args.extend(Self::registry_args(image, tls_verify));
+        args.push(image_name);
+
+        cosign_exec(
+            &args.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
+            &[("DOCKER_CONFIG", docker_config_path.to_str().unwrap_or_default())],
+            &mut |line| info!("{}", line),
+            &mut |line| info!("{}", line),
+            should_abort,
+        )
+    }
+}
+
+fn cosign_exec<F, X>(
+    args: &[&str],
+    envs: &[(&str, &str)],
+    stdout_output: &mut F,
+    stderr_output: &mut X,
+    cmd_killer: &CommandKiller,
+) -> Result<(), CosignError>
+where
+    F: FnMut(String),
+    X: FnMut(String),
+{
+    let mut cmd = QoveryCommand::new("cosign", args, envs);
+    cmd.set_kill_grace_period(Duration::from_secs(0));
+    let ret = cmd.exec_with_abort(stdout_output, stderr_output, cmd_killer);
+
+    match ret {
+        Ok(_) => Ok(()),
+        Err(CommandError::TimeoutError(msg)) => Err(CosignError::Timeout { raw_error_message: msg }),
+        Err(CommandError::Killed(msg)) => Err(CosignError::Aborted { raw_error_message: msg }),
+        Err(CommandError::ExitStatusError(err)) => Err(CosignError::ExitStatusError { exit_status: err }),
+        Err(CommandError::ExecutionError(err)) => Err(CosignError::ExecutionError { raw_error: err }),
+    }
+}
@@ -2,6 +2,7 @@ use crate::cloud_provider::models::CpuArchitecture;
 use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use std::cmp::max;
 use std::fmt::{Display, Formatter};
 use std::io::Write;
@@ -93,7 +94,6 @@ impl Display for Architecture {
 
 #[derive(Debug, Clone)]
 enum ImageId {
-    #[allow(dead_code)]
     Digest(String),
     Tags(Vec<String>),
 }
@@ -116,7 +116,7 @@ impl ContainerImage {
         }
     }
 
-    fn _new_for_digest(registry: Url, name: String, digest: String) -> Self {
+    pub fn new_for_digest(registry: Url, name: String, digest: String) -> Self {
         ContainerImage {
             registry,
             name,
@@ -154,6 +154,62 @@ impl ContainerImage {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheMode {
+    // only layers of the final image are exported
+    Min,
+    // layers of all the intermediate stages are exported too
+    #[default]
+    Max,
+}
+
+impl CacheMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheMode::Min => "min",
+            CacheMode::Max => "max",
+        }
+    }
+}
+
+/// Registry caches of a build. Caches are imported by order of preference,
+/// and the cache of the build is exported to `export_to` once the image is pushed.
+#[derive(Debug, Clone)]
+pub struct BuildCache {
+    pub import_from: Vec<ContainerImage>,
+    pub export_to: Option<ContainerImage>,
+    pub mode: CacheMode,
+}
+
+impl BuildCache {
+    /// Import and export the cache from/to the same image
+    pub fn new(cache: ContainerImage) -> Self {
+        BuildCache {
+            import_from: vec![cache.clone()],
+            export_to: Some(cache),
+            mode: CacheMode::Max,
+        }
+    }
+
+    pub fn export_args(&self) -> Option<String> {
+        self.export_to.as_ref().map(|cache| {
+            format!(
+                "type=registry,mode={},image-manifest=true,oci-mediatypes=true,ref={}",
+                self.mode.as_str(),
+                cache.image_name()
+            )
+        })
+    }
+
+    pub fn import_args(&self) -> Vec<String> {
+        self.import_from
+            .iter()
+            .map(|cache| format!("type=registry,ref={}", cache.image_name()))
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 enum BuilderLocation {
     Local,
@@ -440,6 +496,11 @@ impl Docker {
         &self.socket_location
     }
 
+    /// Directory of the docker config holding registries credentials, usable as DOCKER_CONFIG by other clients
+    pub fn config_path(&self) -> &Path {
+        self.config_path.path()
+    }
+
     fn get_all_envs<'a>(&'a self, envs: &'a [(&'a str, &'a str)]) -> Vec<(&'a str, &'a str)> {
         let mut all_envs: Vec<(&str, &str)> = self.common_envs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
         all_envs.append(&mut envs.to_vec());
@@ -658,7 +719,8 @@ impl Docker {
         context: &Path,
         image_to_build: &ContainerImage,
         build_args: &[(&str, &str)],
-        cache: &ContainerImage,
+        secrets: &[(&str, &str)],
+        cache: &BuildCache,
         push_after_build: bool,
         architectures: &[Architecture],
         stdout_output: &mut Stdout,
@@ -688,6 +750,7 @@ impl Docker {
             context,
             image_to_build,
             build_args,
+            secrets,
             cache,
             push_after_build,
             architectures,
@@ -704,7 +767,8 @@ impl Docker {
         context: &Path,
         image_to_build: &ContainerImage,
         build_args: &[(&str, &str)],
-        cache: &ContainerImage,
+        secrets: &[(&str, &str)],
+        cache: &BuildCache,
         push_after_build: bool,
         architectures: &[Architecture],
         stdout_output: &mut Stdout,
@@ -733,18 +797,20 @@ impl Docker {
             } else {
                 "--output=type=docker".to_string() // tell buildkit to load the image into docker after build
             },
-            "--cache-from".to_string(),
-            format!("type=registry,ref={}", cache.image_name()),
             "-f".to_string(),
             dockerfile.to_str().unwrap_or_default().to_string(),
         ];
 
+        for cache_from in cache.import_args() {
+            args_string.push("--cache-from".to_string());
+            args_string.push(cache_from);
+        }
+
         if push_after_build {
-            args_string.push("--cache-to".to_string());
-            args_string.push(format!(
-                "type=registry,mode=max,image-manifest=true,oci-mediatypes=true,ref={}",
-                cache.image_name()
-            ));
+            if let Some(cache_to) = cache.export_args() {
+                args_string.push("--cache-to".to_string());
+                args_string.push(cache_to);
+            }
         }
 
         // Build for all requested architectures, if empty build for the current architecture the engine is running on
@@ -764,6 +830,17 @@ impl Docker {
             args_string.push("--build-arg".to_string());
             args_string.push(format!("{k}={v}"));
         }
+
+        // Secrets are never passed on the command line, nor kept in the image history.
+        // They are written into files only readable by us, and mounted by buildkit with RUN --mount=type=secret,id=...
+        let secrets_dir =
+            TempDir::with_prefix("docker-secrets-").map_err(|err| DockerError::ExecutionError { raw_error: err })?;
+        for (k, v) in secrets {
+            let secret_path = secrets_dir.path().join(k);
+            fs::write(&secret_path, v).map_err(|err| DockerError::ExecutionError { raw_error: err })?;
+            args_string.push("--secret".to_string());
+            args_string.push(format!("id={k},src={}", secret_path.to_string_lossy()));
+        }
         args_string.push(context.to_str().unwrap_or_default().to_string());
 
         // Hack
@@ -979,7 +1056,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::cmd::command::CommandKiller;
-    use crate::cmd::docker::{Architecture, ContainerImage, Docker, DockerError};
+    use crate::cmd::docker::{Architecture, BuildCache, ContainerImage, Docker, DockerError};
     use std::num::NonZeroUsize;
     use std::path::Path;
     use std::time::Duration;
@@ -1048,11 +1125,11 @@ mod tests {
             "local-repo/alpine".to_string(),
             vec!["3.15".to_string()],
         );
-        let image_cache = ContainerImage::new(
+        let image_cache = BuildCache::new(ContainerImage::new(
             private_registry_url(),
             "local-repo/alpine".to_string(),
             vec!["cache".to_string()],
-        );
+        ));
 
         // It should work
         let ret = docker.build_with_buildkit(
@@ -1061,6 +1138,7 @@ mod tests {
             Path::new("tests/docker/multi_stage_simple/"),
             &image_to_build,
             &[],
+            &[],
             &image_cache,
             false,
             CPU_ARCHITECTURE,
@@ -1077,6 +1155,7 @@ mod tests {
             Path::new("tests/docker/multi_stage_simple/"),
             &image_to_build,
             &[],
+            &[],
             &image_cache,
             false,
             CPU_ARCHITECTURE,
@@ -1098,11 +1177,11 @@ mod tests {
             "local-repo/alpine".to_string(),
             vec!["v42.42".to_string()],
         );
-        let image_cache = ContainerImage::new(
+        let image_cache = BuildCache::new(ContainerImage::new(
             private_registry_url(),
             "local-repo/alpine".to_string(),
             vec!["cache".to_string()],
-        );
+        ));
 
         // It should work
         let ret = docker.build_with_buildkit(
@@ -1111,6 +1190,7 @@ mod tests {
             Path::new("tests/docker/multi_stage_simple/"),
             &image_to_build,
             &[],
+            &[],
             &image_cache,
             false,
             CPU_ARCHITECTURE,
@@ -1228,11 +1308,11 @@ mod tests {
             "local-repo/alpine".to_string(),
             vec!["3.15".to_string()],
         );
-        let image_cache = ContainerImage::new(
+        let image_cache = BuildCache::new(ContainerImage::new(
             private_registry_url(),
             "local-repo/alpine".to_string(),
             vec!["cache".to_string()],
-        );
+        ));
 
         // It should work
         let ret = docker.build_with_buildkit(
@@ -1241,6 +1321,7 @@ mod tests {
             Path::new("tests/docker/multi_stage_simple/"),
             &image_to_build,
             &[],
+            &[],
             &image_cache,
             false,
             &[Architecture::AMD64],
@@ -1,10 +1,15 @@
+pub mod buildctl;
 pub mod command;
+pub mod cosign;
 pub mod docker;
 pub mod git_lfs;
 pub mod helm;
 pub mod helm_utils;
 pub mod kubectl;
+pub mod oras;
 pub mod skopeo;
 pub mod structs;
+pub mod syft;
 pub mod terraform;
 pub mod terraform_validators;
+pub mod vulnerability_scanner;
@@ -0,0 +1,153 @@
+use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::cmd::docker::ContainerImage;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::Duration;
+
+#[derive(thiserror::Error, Debug)]
+pub enum OrasError {
+    #[error("Oras terminated with a non success exit status code: {exit_status:?}")]
+    ExitStatusError { exit_status: ExitStatus },
+
+    #[error("Oras terminated with an unknown error: {raw_error:?}")]
+    ExecutionError { raw_error: std::io::Error },
+
+    #[error("Oras aborted due to user cancel request: {raw_error_message:?}")]
+    Aborted { raw_error_message: String },
+
+    #[error("Oras command terminated due to timeout: {raw_error_message:?}")]
+    Timeout { raw_error_message: String },
+}
+
+impl OrasError {
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, Self::Aborted { .. })
+    }
+}
+
+/// Client to push OCI artifacts (sbom, signatures, attestations, ...) next to images
+#[derive(Debug)]
+pub struct Oras {
+    // docker config.json holding the registries credentials
+    registry_config: PathBuf,
+}
+
+impl Oras {
+    pub fn new(docker_config_path: &Path) -> Self {
+        Self {
+            registry_config: docker_config_path.join("config.json"),
+        }
+    }
+
+    fn common_args(&self, image: &ContainerImage, tls_verify: bool) -> Vec<String> {
+        let mut args = vec![format!("--registry-config={}", self.registry_config.to_string_lossy())];
+        if image.registry.scheme() == "http" {
+            args.push("--plain-http".to_string());
+        }
+        if !tls_verify {
+            args.push("--insecure".to_string());
+        }
+
+        args
+    }
+
+    /// Resolve the digest of the image, with format `sha256:d35dfc2fe3ef66bcc085ca00d3152b482e6cafb23cdda1864154caf3b19094ba`
+    pub fn resolve_digest(&self, image: &ContainerImage, tls_verify: bool) -> Result<String, OrasError> {
+        let image_name = image.image_name();
+        info!("Oras resolving digest of image {}", image_name);
+
+        let mut args = vec!["resolve".to_string(), image_name];
+        args.extend(self.common_args(image, tls_verify));
+        let mut digest = String::new();
+        oras_exec(
+            &args.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
+            &mut |line| digest = line.trim().to_string(),
+            &mut |line| info!("{}", line),
+            &CommandKiller::from_timeout(Duration::from_secs(60)),
+        )?;
+
+        Ok(digest)
+    }
+
+    /// Attach the file as an OCI referrer of the image `digest`
+    pub fn attach(
+        &self,
+        image: &ContainerImage,
+        digest: &str,
+        artifact_type: &str,
+        file: &Path,
+        media_type: &str,
+        tls_verify: bool,
+        should_abort: &CommandKiller,
+    ) -> Result<(), OrasError> {
+        let subject = format!("{}@{}", image.repository_with_host(), digest);
+        info!("Oras attaching {:?} to {}", file, subject);
+
+        let mut args = vec![
+            "attach".to_string(),
+            subject,
+            format!("--artifact-type={}", artifact_type),
+            "--disable-path-validation".to_string(),
+            format!("{}:{}", file.to_string_lossy(), media_type),
+        ];
+        args.extend(self.common_args(image, tls_verify));
+        oras_exec(
+            &args.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
+            &mut |line| info!("{}", line),
+            &mut |line| info!("{}", line),
+            should_abort,
+        )
+    }
+
+    /// Push the file as a standalone artifact, tagged with the image tag of `image`
+    pub fn push(
+        &self,
+        image: &ContainerImage,
+        artifact_type: &str,
+        file: &Path,
+        media_type: &str,
+        tls_verify: bool,
+        should_abort: &CommandKiller,
+    ) -> Result<(), OrasError> {
+        let image_name = image.image_name();
+        info!("Oras pushing {:?} to {}", file, image_name);
+
+        let mut args = vec![
+            "push".to_string(),
+            image_name,
+            format!("--artifact-type={}", artifact_type),
+            "--disable-path-validation".to_string(),
+            format!("{}:{}", file.to_string_lossy(), media_type),
+        ];
+        args.extend(self.common_args(image, tls_verify));
+        oras_exec(
+            &args.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
+            &mut |line| info!("{}", line),
+            &mut |line| info!("{}", line),
+            should_abort,
+        )
+    }
+}
+
+fn oras_exec<F, X>(
+    args: &[&str],
+    stdout_output: &mut F,
+    stderr_output: &mut X,
+    cmd_killer: &CommandKiller,
+) -> Result<(), OrasError>
+where
+    F: FnMut(String),
+    X: FnMut(String),
+{
+    let mut cmd = QoveryCommand::new("oras", args, &[]);
+    cmd.set_kill_grace_period(Duration::from_secs(0));
+    let ret = cmd.exec_with_abort(stdout_output, stderr_output, cmd_killer);
+
+    match ret {
+        Ok(_) => Ok(()),
+        Err(CommandError::TimeoutError(msg)) => Err(OrasError::Timeout { raw_error_message: msg }),
+        Err(CommandError::Killed(msg)) => Err(OrasError::Aborted { raw_error_message: msg }),
+        Err(CommandError::ExitStatusError(err)) => Err(OrasError::ExitStatusError { exit_status: err }),
+        Err(CommandError::ExecutionError(err)) => Err(OrasError::ExecutionError { raw_error: err }),
+    }
+}
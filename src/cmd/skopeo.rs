@@ -1,6 +1,7 @@
 use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
 use crate::cmd::docker::ContainerImage;
 use std::collections::HashSet;
+use std::path::Path;
 
 use std::process::ExitStatus;
 
@@ -44,15 +45,18 @@ impl Skopeo {
         })
     }
 
+    fn creds_arg(&self) -> String {
+        match &self.credentials {
+            Some((user, pass)) => format!("--creds={}:{}", user, pass),
+            None => "--no-creds".to_string(),
+        }
+    }
+
     pub fn delete_image(&self, image: &ContainerImage, tls_verify: bool) -> Result<(), SkopeoError> {
         let uri = format!("docker://{}", image.image_name());
         info!("Deleting image {}", uri);
         let tls = format!("--tls-verify={}", tls_verify);
-        let creds = if let Some((user, pass)) = &self.credentials {
-            format!("--creds={}:{}", user, pass)
-        } else {
-            "--no-creds".to_string()
-        };
+        let creds = self.creds_arg();
 
         let args = &["delete", &tls, &creds, "--retry-times=5", &uri];
         skopeo_exec(
@@ -69,11 +73,7 @@ impl Skopeo {
         info!("listing image tags {}", uri);
 
         let tls = format!("--tls-verify={}", tls_verify);
-        let creds = if let Some((user, pass)) = &self.credentials {
-            format!("--creds={}:{}", user, pass)
-        } else {
-            "--no-creds".to_string()
-        };
+        let creds = self.creds_arg();
         let args = &["list-tags", &tls, &creds, "--retry-times=5", &uri];
         let mut output: Vec<String> = vec![];
         skopeo_exec(
@@ -111,11 +111,7 @@ impl Skopeo {
         info!("listing digest of image {}", uri);
 
         let tls = format!("--tls-verify={}", tls_verify);
-        let creds = if let Some((user, pass)) = &self.credentials {
-            format!("--creds={}:{}", user, pass)
-        } else {
-            "--no-creds".to_string()
-        };
+        let creds = self.creds_arg();
 
         // We need --raw because else skopeo is only returning the digest for the current arch and not of the whole image tag
         // https://github.com/containers/skopeo/issues/1345
@@ -179,6 +175,51 @@ impl Skopeo {
         Ok(digests)
     }
 
+    /// Copy an image with all its architectures, keeping the exact same digest (and so the same config and labels).
+    /// Source is read with the skopeo credentials, destination is written using the docker config authfile.
+    pub fn copy(
+        &self,
+        source: &ContainerImage,
+        source_tls_verify: bool,
+        destination: &ContainerImage,
+        destination_authfile: &Path,
+        destination_tls_verify: bool,
+        cmd_killer: &CommandKiller,
+    ) -> Result<(), SkopeoError> {
+        let source_uri = format!("docker://{}", source.image_name());
+        let destination_uri = format!("docker://{}", destination.image_name());
+        info!("Copying image {} to {}", source_uri, destination_uri);
+
+        let source_tls = format!("--src-tls-verify={}", source_tls_verify);
+        let source_creds = if let Some((user, pass)) = &self.credentials {
+            format!("--src-creds={}:{}", user, pass)
+        } else {
+            "--src-no-creds".to_string()
+        };
+        let destination_tls = format!("--dest-tls-verify={}", destination_tls_verify);
+        let destination_authfile = format!("--dest-authfile={}", destination_authfile.to_string_lossy());
+
+        let args = &[
+            "copy",
+            "--all",
+            "--preserve-digests",
+            "--retry-times=5",
+            &source_tls,
+            &source_creds,
+            &destination_tls,
+            &destination_authfile,
+            &source_uri,
+            &destination_uri,
+        ];
+        skopeo_exec(
+            args,
+            &self.get_all_envs(&[]),
+            &mut |line| info!("{}", line),
+            &mut |line| info!("{}", line),
+            cmd_killer,
+        )
+    }
+
     fn get_all_envs<'a>(&'a self, envs: &'a [(&'a str, &'a str)]) -> Vec<(&'a str, &'a str)> {
         let mut all_envs: Vec<(&str, &str)> = self.common_envs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
         all_envs.append(&mut envs.to_vec());
@@ -0,0 +1,66 @@
+use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::cmd::docker::ContainerImage;
+use std::path::Path;
+use std::process::ExitStatus;
+use std::time::Duration;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SyftError {
+    #[error("Syft terminated with a non success exit status code: {exit_status:?}")]
+    ExitStatusError { exit_status: ExitStatus },
+
+    #[error("Syft terminated with an unknown error: {raw_error:?}")]
+    ExecutionError { raw_error: std::io::Error },
+
+    #[error("Syft aborted due to user cancel request: {raw_error_message:?}")]
+    Aborted { raw_error_message: String },
+
+    #[error("Syft command terminated due to timeout: {raw_error_message:?}")]
+    Timeout { raw_error_message: String },
+}
+
+impl SyftError {
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, Self::Aborted { .. })
+    }
+}
+
+/// Generate the SBOM of a remote image, without pulling it through a docker daemon.
+/// `output_format` is one of syft output formats, i.e: `spdx-json` or `cyclonedx-json`.
+/// Registry credentials are read from the docker config located at `docker_config_path`.
+pub fn generate_sbom(
+    image: &ContainerImage,
+    output_format: &str,
+    output_file: &Path,
+    docker_config_path: &Path,
+    tls_verify: bool,
+    should_abort: &CommandKiller,
+) -> Result<(), SyftError> {
+    let image_name = image.image_name();
+    info!("Syft generating {} sbom of image {}", output_format, image_name);
+
+    let source = format!("registry:{}", image_name);
+    let output = format!("{}={}", output_format, output_file.to_string_lossy());
+    let args = vec!["scan", source.as_str(), "--quiet", "--output", output.as_str()];
+
+    let docker_config = docker_config_path.to_str().unwrap_or_default();
+    let mut envs = vec![("DOCKER_CONFIG", docker_config)];
+    if image.registry.scheme() == "http" {
+        envs.push(("SYFT_REGISTRY_INSECURE_USE_HTTP", "true"));
+    }
+    if !tls_verify {
+        envs.push(("SYFT_REGISTRY_INSECURE_SKIP_TLS_VERIFY", "true"));
+    }
+
+    let mut cmd = QoveryCommand::new("syft", &args, &envs);
+    cmd.set_kill_grace_period(Duration::from_secs(0));
+    let ret = cmd.exec_with_abort(&mut |line| info!("{}", line), &mut |line| info!("{}", line), should_abort);
+
+    match ret {
+        Ok(_) => Ok(()),
+        Err(CommandError::TimeoutError(msg)) => Err(SyftError::Timeout { raw_error_message: msg }),
+        Err(CommandError::Killed(msg)) => Err(SyftError::Aborted { raw_error_message: msg }),
+        Err(CommandError::ExitStatusError(err)) => Err(SyftError::ExitStatusError { exit_status: err }),
+        Err(CommandError::ExecutionError(err)) => Err(SyftError::ExecutionError { raw_error: err }),
+    }
+}
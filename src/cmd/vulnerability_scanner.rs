@@ -0,0 +1,564 @@
+use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::cmd::docker::ContainerImage;
+use crate::constants::KUBECONFIG;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitStatus;
+use std::time::Duration;
+use tempfile::{NamedTempFile, TempDir};
+use uuid::Uuid;
+
+const TRIVY_POD_IMAGE: &str = "aquasec/trivy:0.56.2";
+const GRYPE_POD_IMAGE: &str = "anchore/grype:v0.82.0";
+
+#[derive(thiserror::Error, Debug)]
+pub enum VulnerabilityScannerError {
+    #[error("{scanner} terminated with a non success exit status code: {exit_status:?}")]
+    ExitStatusError {
+        scanner: VulnerabilityScanner,
+        exit_status: ExitStatus,
+    },
+
+    #[error("{scanner} terminated with an unknown error: {raw_error:?}")]
+    ExecutionError {
+        scanner: VulnerabilityScanner,
+        raw_error: std::io::Error,
+    },
+
+    #[error("{scanner} aborted due to user cancel request: {raw_error_message:?}")]
+    Aborted {
+        scanner: VulnerabilityScanner,
+        raw_error_message: String,
+    },
+
+    #[error("{scanner} command terminated due to timeout: {raw_error_message:?}")]
+    Timeout {
+        scanner: VulnerabilityScanner,
+        raw_error_message: String,
+    },
+
+    #[error("{scanner} report cannot be read: {raw_error_message}")]
+    InvalidReport {
+        scanner: VulnerabilityScanner,
+        raw_error_message: String,
+    },
+}
+
+impl VulnerabilityScannerError {
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, Self::Aborted { .. })
+    }
+}
+
+/// Scanners producing a json report we know how to read
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum VulnerabilityScanner {
+    Trivy,
+    Grype,
+}
+
+impl Display for VulnerabilityScanner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VulnerabilityScanner::Trivy => write!(f, "Trivy"),
+            VulnerabilityScanner::Grype => write!(f, "Grype"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VulnerabilitySeverity {
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl VulnerabilitySeverity {
+    // Trivy reports severities in upper case, Grype capitalized and with an extra `Negligible` level
+    fn from_report(severity: &str) -> Self {
+        match severity.to_ascii_lowercase().as_str() {
+            "negligible" | "low" => VulnerabilitySeverity::Low,
+            "medium" => VulnerabilitySeverity::Medium,
+            "high" => VulnerabilitySeverity::High,
+            "critical" => VulnerabilitySeverity::Critical,
+            _ => VulnerabilitySeverity::Unknown,
+        }
+    }
+}
+
+impl Display for VulnerabilitySeverity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            VulnerabilitySeverity::Unknown => "UNKNOWN",
+            VulnerabilitySeverity::Low => "LOW",
+            VulnerabilitySeverity::Medium => "MEDIUM",
+            VulnerabilitySeverity::High => "HIGH",
+            VulnerabilitySeverity::Critical => "CRITICAL",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct Vulnerability {
+    pub id: String,
+    pub package_name: String,
+    pub installed_version: String,
+    pub fixed_version: Option<String>,
+    pub severity: VulnerabilitySeverity,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyReport {
+    #[serde(default)]
+    results: Option<Vec<TrivyResult>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyResult {
+    #[serde(default)]
+    vulnerabilities: Option<Vec<TrivyVulnerability>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyVulnerability {
+    #[serde(rename = "VulnerabilityID")]
+    vulnerability_id: String,
+    pkg_name: String,
+    #[serde(default)]
+    installed_version: String,
+    #[serde(default)]
+    fixed_version: Option<String>,
+    #[serde(default)]
+    severity: String,
+}
+
+#[derive(Deserialize)]
+struct GrypeReport {
+    #[serde(default)]
+    matches: Vec<GrypeMatch>,
+}
+
+#[derive(Deserialize)]
+struct GrypeMatch {
+    vulnerability: GrypeVulnerability,
+    artifact: GrypeArtifact,
+}
+
+#[derive(Deserialize)]
+struct GrypeVulnerability {
+    id: String,
+    #[serde(default)]
+    severity: String,
+    #[serde(default)]
+    fix: Option<GrypeFix>,
+}
+
+#[derive(Deserialize)]
+struct GrypeFix {
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GrypeArtifact {
+    name: String,
+    #[serde(default)]
+    version: String,
+}
+
+pub fn parse_report(scanner: VulnerabilityScanner, report: &str) -> Result<Vec<Vulnerability>, serde_json::Error> {
+    match scanner {
+        VulnerabilityScanner::Trivy => {
+            let report: TrivyReport = serde_json::from_str(report)?;
+            Ok(report
+                .results
+                .into_iter()
+                .flatten()
+                .flat_map(|result| result.vulnerabilities.into_iter().flatten())
+                .map(|vuln| Vulnerability {
+                    id: vuln.vulnerability_id,
+                    package_name: vuln.pkg_name,
+                    installed_version: vuln.installed_version,
+                    fixed_version: vuln.fixed_version.filter(|version| !version.is_empty()),
+                    severity: VulnerabilitySeverity::from_report(&vuln.severity),
+                })
+                .collect())
+        }
+        VulnerabilityScanner::Grype => {
+            let report: GrypeReport = serde_json::from_str(report)?;
+            Ok(report
+                .matches
+                .into_iter()
+                .map(|m| Vulnerability {
+                    id: m.vulnerability.id,
+                    package_name: m.artifact.name,
+                    installed_version: m.artifact.version,
+                    fixed_version: m.vulnerability.fix.and_then(|fix| fix.versions.into_iter().next()),
+                    severity: VulnerabilitySeverity::from_report(&m.vulnerability.severity),
+                })
+                .collect())
+        }
+    }
+}
+
+// Arguments of the scanner to print the json report of a remote image on stdout, without a docker daemon
+fn scanner_args(scanner: VulnerabilityScanner, image_name: &str) -> Vec<String> {
+    match scanner {
+        VulnerabilityScanner::Trivy => vec![
+            "image".to_string(),
+            "--quiet".to_string(),
+            "--format".to_string(),
+            "json".to_string(),
+            image_name.to_string(),
+        ],
+        VulnerabilityScanner::Grype => vec![
+            format!("registry:{}", image_name),
+            "--quiet".to_string(),
+            "--output".to_string(),
+            "json".to_string(),
+        ],
+    }
+}
+
+fn insecure_envs(scanner: VulnerabilityScanner, image: &ContainerImage, tls_verify: bool) -> Vec<(String, String)> {
+    let use_http = image.registry.scheme() == "http";
+    let mut envs = vec![];
+    match scanner {
+        VulnerabilityScanner::Trivy => {
+            if use_http || !tls_verify {
+                envs.push(("TRIVY_INSECURE".to_string(), "true".to_string()));
+            }
+        }
+        VulnerabilityScanner::Grype => {
+            if use_http {
+                envs.push(("GRYPE_REGISTRY_INSECURE_USE_HTTP".to_string(), "true".to_string()));
+            }
+            if !tls_verify {
+                envs.push(("GRYPE_REGISTRY_INSECURE_SKIP_TLS_VERIFY".to_string(), "true".to_string()));
+            }
+        }
+    }
+    envs
+}
+
+fn credentials_envs(
+    scanner: VulnerabilityScanner,
+    image: &ContainerImage,
+    username: &str,
+    password: &str,
+) -> Vec<(String, String)> {
+    match scanner {
+        VulnerabilityScanner::Trivy => vec![
+            ("TRIVY_USERNAME".to_string(), username.to_string()),
+            ("TRIVY_PASSWORD".to_string(), password.to_string()),
+        ],
+        VulnerabilityScanner::Grype => vec![
+            (
+                "GRYPE_REGISTRY_AUTH_AUTHORITY".to_string(),
+                image.registry.host_str().unwrap_or_default().to_string(),
+            ),
+            ("GRYPE_REGISTRY_AUTH_USERNAME".to_string(), username.to_string()),
+            ("GRYPE_REGISTRY_AUTH_PASSWORD".to_string(), password.to_string()),
+        ],
+    }
+}
+
+fn exec_scanner(
+    scanner: VulnerabilityScanner,
+    binary: &str,
+    args: &[String],
+    envs: &[(String, String)],
+    should_abort: &CommandKiller,
+) -> Result<String, VulnerabilityScannerError> {
+    let args = args.iter().map(|arg| arg.as_str()).collect::<Vec<_>>();
+    let envs = envs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect::<Vec<_>>();
+
+    let mut report = String::new();
+    let mut cmd = QoveryCommand::new(binary, &args, &envs);
+    cmd.set_kill_grace_period(Duration::from_secs(0));
+    let ret = cmd.exec_with_abort(
+        &mut |line| {
+            report.push_str(&line);
+            report.push('\n');
+        },
+        &mut |line| info!("{}", line),
+        should_abort,
+    );
+
+    match ret {
+        Ok(_) => Ok(report),
+        Err(CommandError::TimeoutError(msg)) => Err(VulnerabilityScannerError::Timeout {
+            scanner,
+            raw_error_message: msg,
+        }),
+        Err(CommandError::Killed(msg)) => Err(VulnerabilityScannerError::Aborted {
+            scanner,
+            raw_error_message: msg,
+        }),
+        Err(CommandError::ExitStatusError(err)) => Err(VulnerabilityScannerError::ExitStatusError {
+            scanner,
+            exit_status: err,
+        }),
+        Err(CommandError::ExecutionError(err)) => Err(VulnerabilityScannerError::ExecutionError {
+            scanner,
+            raw_error: err,
+        }),
+    }
+}
+
+fn to_vulnerabilities(
+    scanner: VulnerabilityScanner,
+    report: &str,
+) -> Result<Vec<Vulnerability>, VulnerabilityScannerError> {
+    parse_report(scanner, report).map_err(|err| VulnerabilityScannerError::InvalidReport {
+        scanner,
+        raw_error_message: err.to_string(),
+    })
+}
+
+/// Scan a remote image with the scanner binary installed next to the engine.
+/// Registry credentials are read from the docker config located at `docker_config_path`.
+pub fn scan_image_locally(
+    scanner: VulnerabilityScanner,
+    image: &ContainerImage,
+    docker_config_path: &Path,
+    tls_verify: bool,
+    should_abort: &CommandKiller,
+) -> Result<Vec<Vulnerability>, VulnerabilityScannerError> {
+    let image_name = image.image_name();
+    info!("{} scanning image {} for vulnerabilities", scanner, image_name);
+
+    // Scanners cache their vulnerability database, keep it away from other builds running in parallel
+    let cache_dir =
+        TempDir::with_prefix("vulnerability-scan-").map_err(|err| VulnerabilityScannerError::ExecutionError {
+            scanner,
+            raw_error: err,
+        })?;
+    let mut envs = vec![("DOCKER_CONFIG".to_string(), docker_config_path.to_string_lossy().to_string())];
+    envs.extend(insecure_envs(scanner, image, tls_verify));
+    let binary = match scanner {
+        VulnerabilityScanner::Trivy => {
+            envs.push(("TRIVY_CACHE_DIR".to_string(), cache_dir.path().to_string_lossy().to_string()));
+            "trivy"
+        }
+        VulnerabilityScanner::Grype => {
+            envs.push(("GRYPE_DB_CACHE_DIR".to_string(), cache_dir.path().to_string_lossy().to_string()));
+            "grype"
+        }
+    };
+
+    let report = exec_scanner(scanner, binary, &scanner_args(scanner, &image_name), &envs, should_abort)?;
+    to_vulnerabilities(scanner, &report)
+}
+
+fn exec_kubectl(args: &[&str], kubeconfig_path: &Path) -> Result<(), CommandError> {
+    let kubeconfig_path = kubeconfig_path.to_string_lossy();
+    let mut cmd = QoveryCommand::new("kubectl", args, &[(KUBECONFIG, kubeconfig_path.as_ref())]);
+    cmd.exec_with_output(&mut |line| info!("{}", line), &mut |line| warn!("{}", line))
+}
+
+/// Registry credentials are stored in a short-lived Secret, so they never appear in the command line nor in the pod spec
+fn create_credentials_secret(
+    scanner: VulnerabilityScanner,
+    secret_name: &str,
+    namespace: &str,
+    credentials: &[(String, String)],
+    kubeconfig_path: &Path,
+) -> Result<(), VulnerabilityScannerError> {
+    let execution_error = |raw_error: std::io::Error| VulnerabilityScannerError::ExecutionError { scanner, raw_error };
+    let mut env_file = NamedTempFile::with_prefix("vulnerability-scan-").map_err(execution_error)?;
+    for (key, value) in credentials {
+        writeln!(env_file, "{key}={value}").map_err(execution_error)?;
+    }
+
+    let env_file_arg = format!("--from-env-file={}", env_file.path().to_string_lossy());
+    exec_kubectl(
+        &[
+            "create",
+            "secret",
+            "generic",
+            secret_name,
+            "--namespace",
+            namespace,
+            &env_file_arg,
+        ],
+        kubeconfig_path,
+    )
+    .map_err(|err| match err {
+        CommandError::ExitStatusError(exit_status) => {
+            VulnerabilityScannerError::ExitStatusError { scanner, exit_status }
+        }
+        err => execution_error(std::io::Error::new(std::io::ErrorKind::Other, format!("{err:?}"))),
+    })
+}
+
+/// Scan a remote image from a short-lived pod of the cluster, for engines running without the scanner binary.
+/// The pod and its credentials are deleted once the report has been read, even when the scan is aborted.
+pub fn scan_image_in_kube_pod(
+    scanner: VulnerabilityScanner,
+    image: &ContainerImage,
+    credentials: Option<(&str, &str)>,
+    kubeconfig_path: &Path,
+    namespace: &str,
+    tls_verify: bool,
+    should_abort: &CommandKiller,
+) -> Result<Vec<Vulnerability>, VulnerabilityScannerError> {
+    let image_name = image.image_name();
+    info!(
+        "{} scanning image {} for vulnerabilities in namespace {}",
+        scanner, image_name, namespace
+    );
+
+    let pod_name = format!("vulnerability-scan-{}", &Uuid::new_v4().to_string()[..8]);
+    let pod_image = match scanner {
+        VulnerabilityScanner::Trivy => TRIVY_POD_IMAGE,
+        VulnerabilityScanner::Grype => GRYPE_POD_IMAGE,
+    };
+    let _cleanup = scopeguard::guard((), |_| {
+        for kind in ["pod", "secret"] {
+            if let Err(err) = exec_kubectl(
+                &[
+                    "delete",
+                    kind,
+                    &pod_name,
+                    "--namespace",
+                    namespace,
+                    "--ignore-not-found",
+                    "--wait=false",
+                ],
+                kubeconfig_path,
+            ) {
+                warn!("Cannot delete vulnerability scan {} {}: {:?}", kind, pod_name, err);
+            }
+        }
+    });
+
+    let mut args = vec![
+        "run".to_string(),
+        pod_name.to_string(),
+        "--namespace".to_string(),
+        namespace.to_string(),
+        "--image".to_string(),
+        pod_image.to_string(),
+        "--restart=Never".to_string(),
+        "--rm".to_string(),
+        "--attach".to_string(),
+        "--quiet".to_string(),
+    ];
+    args.extend(
+        insecure_envs(scanner, image, tls_verify)
+            .iter()
+            .map(|(k, v)| format!("--env={}={}", k, v)),
+    );
+    if let Some((username, password)) = credentials {
+        // the secret has the pod name, so both are cleaned up together
+        create_credentials_secret(
+            scanner,
+            &pod_name,
+            namespace,
+            &credentials_envs(scanner, image, username, password),
+            kubeconfig_path,
+        )?;
+        let overrides = json!({
+            "spec": {
+                "containers": [{
+                    "name": pod_name,
+                    "envFrom": [{"secretRef": {"name": pod_name}}],
+                }]
+            }
+        });
+        args.push("--override-type=strategic".to_string());
+        args.push(format!("--overrides={overrides}"));
+    }
+    args.push("--".to_string());
+    args.extend(scanner_args(scanner, &image_name));
+
+    let envs = vec![(KUBECONFIG.to_string(), kubeconfig_path.to_string_lossy().to_string())];
+    let report = exec_scanner(scanner, "kubectl", &args, &envs, should_abort)?;
+    to_vulnerabilities(scanner, &report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trivy_report() {
+        let report = r#"{
+          "SchemaVersion": 2,
+          "ArtifactName": "registry.example.com/app:v1",
+          "Results": [
+            {
+              "Target": "registry.example.com/app:v1 (debian 12.5)",
+              "Vulnerabilities": [
+                {"VulnerabilityID": "CVE-2024-0001", "PkgName": "openssl", "InstalledVersion": "3.0.11", "FixedVersion": "3.0.13", "Severity": "CRITICAL"},
+                {"VulnerabilityID": "CVE-2024-0002", "PkgName": "zlib", "InstalledVersion": "1.2.13", "FixedVersion": "", "Severity": "LOW"}
+              ]
+            },
+            {"Target": "app/package-lock.json"}
+          ]
+        }"#;
+
+        let vulnerabilities = parse_report(VulnerabilityScanner::Trivy, report).unwrap();
+        assert_eq!(
+            vulnerabilities,
+            vec![
+                Vulnerability {
+                    id: "CVE-2024-0001".to_string(),
+                    package_name: "openssl".to_string(),
+                    installed_version: "3.0.11".to_string(),
+                    fixed_version: Some("3.0.13".to_string()),
+                    severity: VulnerabilitySeverity::Critical,
+                },
+                Vulnerability {
+                    id: "CVE-2024-0002".to_string(),
+                    package_name: "zlib".to_string(),
+                    installed_version: "1.2.13".to_string(),
+                    fixed_version: None,
+                    severity: VulnerabilitySeverity::Low,
+                },
+            ]
+        );
+
+        // Clean images have no results at all
+        assert!(parse_report(VulnerabilityScanner::Trivy, r#"{"SchemaVersion": 2}"#)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_grype_report() {
+        let report = r#"{
+          "matches": [
+            {
+              "vulnerability": {"id": "GHSA-xxxx", "severity": "High", "fix": {"versions": ["2.0.1"], "state": "fixed"}},
+              "artifact": {"name": "lodash", "version": "2.0.0", "type": "npm"}
+            },
+            {
+              "vulnerability": {"id": "CVE-2024-0003", "severity": "Negligible", "fix": {"versions": [], "state": "not-fixed"}},
+              "artifact": {"name": "libc6", "version": "2.36", "type": "deb"}
+            }
+          ],
+          "source": {"type": "image"}
+        }"#;
+
+        let vulnerabilities = parse_report(VulnerabilityScanner::Grype, report).unwrap();
+        assert_eq!(vulnerabilities.len(), 2);
+        assert_eq!(vulnerabilities[0].id, "GHSA-xxxx");
+        assert_eq!(vulnerabilities[0].severity, VulnerabilitySeverity::High);
+        assert_eq!(vulnerabilities[0].fixed_version, Some("2.0.1".to_string()));
+        assert_eq!(vulnerabilities[1].severity, VulnerabilitySeverity::Low);
+        assert_eq!(vulnerabilities[1].fixed_version, None);
+    }
+}
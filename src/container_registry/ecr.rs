@@ -2,6 +2,7 @@
 
 use base64::engine::general_purpose;
 use base64::Engine;
+use chrono::DateTime;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::time::Duration;
@@ -18,6 +19,7 @@ use rusoto_sts::{GetCallerIdentityRequest, Sts, StsClient};
 
 use crate::build_platform::Image;
 use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::retention::RegistryImage;
 use crate::container_registry::{
     take_last_x_chars_and_remove_leading_dash_char, ContainerRegistry, ContainerRegistryInfo, Kind, Repository,
     RepositoryInfo,
@@ -528,6 +530,56 @@ impl ContainerRegistry for ECR {
     fn image_exists(&self, image: &Image) -> bool {
         self.get_image(image).is_some()
     }
+
+    fn list_images(&self, repository_name: &str) -> Result<Vec<RegistryImage>, ContainerRegistryError> {
+        let to_error = |raw_error_message: String| ContainerRegistryError::CannotGetRepository {
+            registry_name: self.name.clone(),
+            repository_name: repository_name.to_string(),
+            raw_error_message,
+        };
+
+        // describe_images returns the tags, digest and push date of up to 1000 images per call
+        let mut images = vec![];
+        let mut next_token = None;
+        loop {
+            let request = DescribeImagesRequest {
+                repository_name: repository_name.to_string(),
+                max_results: Some(1000),
+                next_token,
+                ..Default::default()
+            };
+            let response = match block_on_with_timeout(self.ecr_client().describe_images(request)) {
+                Ok(Ok(response)) => response,
+                Ok(Err(err)) => return Err(to_error(err.to_string())),
+                Err(err) => return Err(to_error(err.to_string())),
+            };
+
+            for image in response.image_details.unwrap_or_default() {
+                let Some(digest) = image.image_digest else {
+                    continue;
+                };
+                let created_at = image
+                    .image_pushed_at
+                    .and_then(|pushed_at| DateTime::from_timestamp(pushed_at as i64, 0));
+                images.extend(
+                    image
+                        .image_tags
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|tag| RegistryImage {
+                            tag,
+                            digest: digest.clone(),
+                            created_at,
+                        }),
+                );
+            }
+
+            next_token = response.next_token;
+            if next_token.is_none() {
+                return Ok(images);
+            }
+        }
+    }
 }
 
 pub struct ECRCredentials {
@@ -41,6 +41,19 @@ pub enum ContainerRegistryError {
         image_name: String,
         raw_error_message: String,
     },
+    #[error("Cannot resolve digest of image `{image_name:?}` in registry `{registry_name:?}`: {raw_error_message:?}.")]
+    CannotResolveImageDigest {
+        registry_name: String,
+        image_name: String,
+        raw_error_message: String,
+    },
+    #[error("Cannot promote image `{source_image:?}` to `{image_name:?}` in registry `{registry_name:?}`: {raw_error_message:?}.")]
+    CannotPromoteImage {
+        registry_name: String,
+        source_image: String,
+        image_name: String,
+        raw_error_message: String,
+    },
     #[error("Image `{image_name:?}` doesn't exist in repository `{repository_name:?}` in registry `{registry_name:?}` error.")]
     ImageDoesntExistInRegistry {
         registry_name: String,
@@ -3,6 +3,8 @@ use base64::Engine;
 
 use crate::build_platform::Image;
 use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::oci_registry::OciDistributionClient;
+use crate::container_registry::retention::RegistryImage;
 use crate::container_registry::{
     take_last_x_chars_and_remove_leading_dash_char, ContainerRegistry, ContainerRegistryInfo, Kind, Repository,
     RepositoryInfo,
@@ -25,6 +27,8 @@ pub struct GenericCr {
     skip_tls_verification: bool,
     _repository_name: String,
     skopeo: Skopeo,
+    // to list images in batch through the registry API, instead of spawning a skopeo process per tag
+    oci_client: OciDistributionClient,
     cr_info: ContainerRegistryInfo,
     // Only used for the demo mode, which does not support delete operations on its registry.
     // And skopeo does not return the same error with ARM version. On AMD64 it works fine.
@@ -62,6 +66,12 @@ impl GenericCr {
             ));
         }
 
+        let oci_client =
+            OciDistributionClient::new(url.clone(), credentials.clone(), skip_tls_verification).map_err(|err| {
+                ContainerRegistryError::CannotInstantiateClient {
+                    raw_error_message: err.to_string(),
+                }
+            })?;
         let skopeo = Skopeo::new(credentials).map_err(|err| ContainerRegistryError::CannotInstantiateClient {
             raw_error_message: err.to_string(),
         })?;
@@ -124,6 +134,7 @@ impl GenericCr {
             url,
             _repository_name: repository_name,
             skopeo,
+            oci_client,
             cr_info: container_registry_info,
             support_delete,
         };
@@ -263,4 +274,14 @@ impl ContainerRegistry for GenericCr {
 
         tags.contains(&image.tag)
     }
+
+    fn list_images(&self, repository_name: &str) -> Result<Vec<RegistryImage>, ContainerRegistryError> {
+        self.oci_client
+            .list_images(repository_name)
+            .map_err(|err| ContainerRegistryError::CannotGetRepository {
+                registry_name: self.name.clone(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: err.to_string(),
+            })
+    }
 }
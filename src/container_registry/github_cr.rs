@@ -3,8 +3,10 @@ use crate::build_platform::Image;
 use crate::cmd::docker::ContainerImage;
 use crate::container_registry::errors::ContainerRegistryError;
 use crate::container_registry::generic_cr::GenericCr;
+use crate::container_registry::retention::RegistryImage;
 use crate::container_registry::{ContainerRegistry, ContainerRegistryInfo, Kind, Repository, RepositoryInfo};
 use crate::io_models::context::Context;
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
 use reqwest::Error;
@@ -39,6 +41,24 @@ struct GithubUserResponse {
     login: String,
 }
 
+#[derive(Default, Deserialize)]
+struct ImageVersion {
+    id: u64,
+    name: String, // the digest, start with sha256:
+    created_at: Option<DateTime<Utc>>,
+    metadata: ImageMetadata,
+}
+#[derive(Default, Deserialize)]
+struct ImageMetadata {
+    container: ImageContainer,
+}
+#[derive(Default, Deserialize)]
+struct ImageContainer {
+    tags: Vec<String>,
+}
+
+const VERSIONS_PAGE_SIZE: usize = 100;
+
 impl GithubCr {
     pub fn new(
         context: Context,
@@ -108,6 +128,42 @@ impl GithubCr {
 
         Ok(cr)
     }
+
+    // https://docs.github.com/en/rest/packages/packages?apiVersion=2022-11-28#list-package-versions-for-a-package-owned-by-an-organization
+    fn list_versions(&self, package_name: &str) -> reqwest::Result<Vec<ImageVersion>> {
+        let api_url = match &self.registry_type {
+            RegistryType::User(_) => {
+                format!("https://api.github.com/user/packages/container/{}/versions", package_name)
+            }
+            RegistryType::Organization(org) => format!(
+                "https://api.github.com/orgs/{}/packages/container/{}/versions",
+                org, package_name
+            ),
+        };
+
+        let mut versions = vec![];
+        for page in 1.. {
+            let page_versions: Vec<ImageVersion> = match self
+                .http_client
+                .get(&api_url)
+                .query(&[("per_page", VERSIONS_PAGE_SIZE), ("page", page)])
+                .send()
+                .and_then(|res| res.error_for_status())
+            {
+                Ok(res) => res.json().unwrap_or_default(),
+                Err(err) if matches!(err.status(), Some(reqwest::StatusCode::NOT_FOUND)) => vec![],
+                Err(err) => return Err(err),
+            };
+
+            let is_last_page = page_versions.len() < VERSIONS_PAGE_SIZE;
+            versions.extend(page_versions);
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(versions)
+    }
 }
 
 impl ContainerRegistry for GithubCr {
@@ -185,44 +241,6 @@ impl ContainerRegistry for GithubCr {
             raw_error_message,
         };
 
-        #[derive(Default, Deserialize)]
-        struct ImageVersion {
-            id: u64,
-            name: String, // the digest, start with sha256:
-            metadata: ImageMetadata,
-        }
-        #[derive(Default, Deserialize)]
-        struct ImageMetadata {
-            container: ImageContainer,
-        }
-        #[derive(Default, Deserialize)]
-        struct ImageContainer {
-            tags: Vec<String>,
-        }
-
-        fn list_versions(this: &GithubCr, repository_name: &str) -> reqwest::Result<Vec<ImageVersion>> {
-            let api_url = match &this.registry_type {
-                RegistryType::User(_) => {
-                    format!("https://api.github.com/user/packages/container/{}/versions", repository_name)
-                }
-                RegistryType::Organization(org) => format!(
-                    "https://api.github.com/orgs/{}/packages/container/{}/versions",
-                    org, repository_name
-                ),
-            };
-
-            match this
-                .http_client
-                .get(api_url)
-                .send()
-                .and_then(|res| res.error_for_status())
-            {
-                Ok(res) => Ok(res.json().unwrap_or_default()),
-                Err(err) if matches!(err.status(), Some(reqwest::StatusCode::NOT_FOUND)) => Ok(vec![]),
-                Err(err) => Err(err),
-            }
-        }
-
         fn delete_version(this: &GithubCr, repository_name: &str, version_id: u64) -> reqwest::Result<()> {
             // https://docs.github.com/en/rest/packages/packages?apiVersion=2022-11-28#delete-package-version-for-an-organization
             // https://docs.github.com/en/rest/packages/packages?apiVersion=2022-11-28#delete-a-package-version-for-the-authenticated-user
@@ -251,7 +269,9 @@ impl ContainerRegistry for GithubCr {
 
         // list all versions/digest for this image to get the version id
         // Github has its own version/id system for layers, they don't use the sha256 digest for that.
-        let versions = list_versions(self, image.name_without_repository()).map_err(|e| to_error(e.to_string()))?;
+        let versions = self
+            .list_versions(image.name_without_repository())
+            .map_err(|e| to_error(e.to_string()))?;
 
         // Github forbid to delete the last tag of an image, in this case you must delete the repository itself.
         let tags = versions
@@ -286,4 +306,34 @@ impl ContainerRegistry for GithubCr {
     fn image_exists(&self, image: &Image) -> bool {
         self.generic_cr.image_exists(image)
     }
+    fn list_images(&self, repository_name: &str) -> Result<Vec<RegistryImage>, ContainerRegistryError> {
+        let package_name = repository_name
+            .split_once('/')
+            .map(|(_, name)| name)
+            .unwrap_or(repository_name);
+        let versions = self
+            .list_versions(package_name)
+            .map_err(|err| ContainerRegistryError::CannotGetRepository {
+                registry_name: self.name().to_string(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: err.to_string(),
+            })?;
+
+        // untagged versions are the platform manifests of multi-arch images, deleted along with their image
+        Ok(versions
+            .into_iter()
+            .flat_map(|version| {
+                version
+                    .metadata
+                    .container
+                    .tags
+                    .into_iter()
+                    .map(move |tag| RegistryImage {
+                        tag,
+                        digest: version.name.clone(),
+                        created_at: version.created_at,
+                    })
+            })
+            .collect())
+    }
 }
@@ -4,18 +4,30 @@ use url::Url;
 use uuid::Uuid;
 
 use crate::build_platform::Image;
+use crate::cmd::command::CommandKiller;
+use crate::cmd::docker::ContainerImage;
+use crate::cmd::skopeo::Skopeo;
 use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::retention::{select_expired_images, ImagesInUse, RegistryImage, RetentionSweepReport};
 use crate::errors::EngineError;
 use crate::events::{EventDetails, Stage, Transmitter};
+use crate::io_models::container::Registry;
 use crate::io_models::context::Context;
 use crate::io_models::QoveryIdentifier;
+use crate::models::abort::Abort;
+use chrono::Utc;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 pub mod ecr;
 pub mod errors;
 pub mod generic_cr;
 pub mod github_cr;
 pub mod google_artifact_registry;
+pub mod oci_registry;
+pub mod retention;
 pub mod scaleway_container_registry;
+pub mod vulnerability_scan;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Repository {
@@ -33,6 +45,27 @@ pub struct DockerImage {
     pub tag: String,
 }
 
+/// An image already built and pushed into another registry (i.e: the one of a staging cluster).
+/// It is referenced by digest, so the exact same image is deployed without being rebuilt.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ImagePromotion {
+    pub registry: Registry,
+    pub image_name: String,
+    // i.e: sha256:d35dfc2fe3ef66bcc085ca00d3152b482e6cafb23cdda1864154caf3b19094ba
+    pub digest: String,
+}
+
+impl ImagePromotion {
+    pub fn source_image(&self) -> ContainerImage {
+        ContainerImage::new_for_digest(self.registry.url().clone(), self.image_name.clone(), self.digest.clone())
+    }
+
+    // The tag is derived from the digest, so the same image keeps the same tag in every cluster it is promoted to
+    pub fn image_tag(&self) -> String {
+        self.digest.replace(':', "-")
+    }
+}
+
 pub struct RegistryTags {
     pub environment_id: String,
     pub project_id: String,
@@ -69,6 +102,96 @@ pub trait ContainerRegistry: Send + Sync {
     // Check on the registry if a specific image already exists
     fn image_exists(&self, image: &Image) -> bool;
 
+    // Copy an image from another registry into this one, by digest and with all its architectures
+    fn promote_image(
+        &self,
+        promotion: &ImagePromotion,
+        image: &Image,
+        abort: &dyn Abort,
+    ) -> Result<(), ContainerRegistryError> {
+        let source_image = promotion.source_image();
+        let to_error = |raw_error_message: String| ContainerRegistryError::CannotPromoteImage {
+            registry_name: self.name().to_string(),
+            source_image: source_image.image_name(),
+            image_name: image.name_with_tag(),
+            raw_error_message,
+        };
+
+        let source_url = promotion
+            .registry
+            .get_url_with_credentials()
+            .map_err(|err| to_error(err.to_string()))?;
+        let source_credentials = source_url
+            .password()
+            .map(|password| (source_url.username().to_string(), password.to_string()));
+        let skopeo = Skopeo::new(source_credentials).map_err(|err| to_error(err.to_string()))?;
+
+        let destination_image =
+            ContainerImage::new(self.registry_info().endpoint.clone(), image.name(), vec![image.tag.clone()]);
+        skopeo
+            .copy(
+                &source_image,
+                source_url.scheme() != "http",
+                &destination_image,
+                &self.context().docker.config_path().join("config.json"),
+                !self.registry_info().insecure_registry,
+                &CommandKiller::from(Duration::from_secs(30 * 60), abort),
+            )
+            .map_err(|err| to_error(err.to_string()))
+    }
+
+    // List the tags of a repository, with the digest they point to and the creation date of the image.
+    // Registries list them in batch through their own API, the ones without such API rely on their cleanup policies
+    fn list_images(&self, repository_name: &str) -> Result<Vec<RegistryImage>, ContainerRegistryError> {
+        Err(ContainerRegistryError::CannotGetRepository {
+            registry_name: self.name().to_string(),
+            repository_name: repository_name.to_string(),
+            raw_error_message: format!(
+                "listing images is not supported by {:?} registries, use the cleanup policies of the registry instead",
+                self.kind()
+            ),
+        })
+    }
+
+    // Delete the images of the repository older than the retention, except the ones used by the cluster workloads
+    fn sweep_expired_images(
+        &self,
+        repository_name: &str,
+        retention: Duration,
+        images_in_use: &ImagesInUse,
+        dry_run: bool,
+    ) -> Result<RetentionSweepReport, ContainerRegistryError> {
+        let endpoint = &self.registry_info().endpoint;
+        let (expired, kept_in_use) =
+            select_expired_images(self.list_images(repository_name)?, retention, Utc::now(), |image| {
+                images_in_use.contains(endpoint, repository_name, image)
+            });
+
+        if !dry_run {
+            // Deleting a tag deletes its manifest, and so all the other tags pointing to the same digest
+            let mut deleted_digests = HashSet::new();
+            for image in expired
+                .iter()
+                .filter(|image| deleted_digests.insert(image.digest.clone()))
+            {
+                self.delete_image(&Image {
+                    name: repository_name.to_string(),
+                    tag: image.tag.clone(),
+                    registry_url: endpoint.clone(),
+                    repository_name: repository_name.to_string(),
+                    ..Default::default()
+                })?;
+            }
+        }
+
+        Ok(RetentionSweepReport {
+            repository_name: repository_name.to_string(),
+            dry_run,
+            expired,
+            kept_in_use,
+        })
+    }
+
     fn get_event_details(&self, stage: Stage) -> EventDetails {
         let context = self.context();
         let ev = EventDetails::new(
@@ -143,6 +266,7 @@ pub enum Kind {
     GcpArtifactRegistry,
     GenericCr,
     GithubCr,
+    OciRegistry,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -165,7 +289,32 @@ fn take_last_x_chars(input: &str, max_length: usize) -> String {
 
 #[cfg(test)]
 mod test {
-    use crate::container_registry::take_last_x_chars_and_remove_leading_dash_char;
+    use crate::container_registry::{take_last_x_chars_and_remove_leading_dash_char, ImagePromotion};
+    use crate::io_models::container::Registry;
+    use url::Url;
+    use uuid::Uuid;
+
+    #[test]
+    fn image_promotion_source_is_referenced_by_digest() {
+        let promotion = ImagePromotion {
+            registry: Registry::GenericCr {
+                long_id: Uuid::new_v4(),
+                url: Url::parse("https://registry.staging.example.com").unwrap(),
+                credentials: None,
+            },
+            image_name: "qovery/app".to_string(),
+            digest: "sha256:d35dfc2fe3ef66bcc085ca00d3152b482e6cafb23cdda1864154caf3b19094ba".to_string(),
+        };
+
+        assert_eq!(
+            promotion.source_image().image_name(),
+            "registry.staging.example.com/qovery/app@sha256:d35dfc2fe3ef66bcc085ca00d3152b482e6cafb23cdda1864154caf3b19094ba"
+        );
+        assert_eq!(
+            promotion.image_tag(),
+            "sha256-d35dfc2fe3ef66bcc085ca00d3152b482e6cafb23cdda1864154caf3b19094ba"
+        );
+    }
 
     #[test]
     fn when_string_is_starting_by_dash_remove_it() {
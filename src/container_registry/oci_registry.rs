@@ -0,0 +1,807 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::{HeaderMap, ACCEPT, LINK, RETRY_AFTER, WWW_AUTHENTICATE};
+use reqwest::StatusCode;
+use serde_derive::Deserialize;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
+
+use super::RegistryTags;
+use crate::build_platform::Image;
+use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::retention::RegistryImage;
+use crate::container_registry::{
+    take_last_x_chars_and_remove_leading_dash_char, ContainerRegistry, ContainerRegistryInfo, Kind, Repository,
+    RepositoryInfo,
+};
+use crate::io_models::context::Context;
+
+const MANIFEST_MEDIA_TYPES: [&str; 4] = [
+    "application/vnd.oci.image.index.v1+json",
+    "application/vnd.oci.image.manifest.v1+json",
+    "application/vnd.docker.distribution.manifest.list.v2+json",
+    "application/vnd.docker.distribution.manifest.v2+json",
+];
+const DOCKER_CONTENT_DIGEST: &str = "Docker-Content-Digest";
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+const TAGS_PAGE_SIZE: u32 = 1000;
+
+#[derive(Error, Debug)]
+pub enum OciRegistryError {
+    #[error("Request to the registry failed: {raw_error_message}")]
+    RequestError { raw_error_message: String },
+    #[error("Registry answered with unexpected status {status}: {body}")]
+    UnexpectedStatus { status: StatusCode, body: String },
+    #[error("Registry is still rate limiting requests after {retries} retries")]
+    TooManyRequests { retries: u32 },
+    #[error("Cannot authenticate against the registry: {raw_error_message}")]
+    AuthenticationError { raw_error_message: String },
+    #[error("Invalid manifest `{reference}`: {raw_error_message}")]
+    InvalidManifest {
+        reference: String,
+        raw_error_message: String,
+    },
+}
+
+impl From<reqwest::Error> for OciRegistryError {
+    fn from(err: reqwest::Error) -> Self {
+        // reqwest errors contain the url, which may carry credentials
+        OciRegistryError::RequestError {
+            raw_error_message: err.without_url().to_string(),
+        }
+    }
+}
+
+// Permissions requested to the token server, the challenge of the registry tells us the exact scope
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Access {
+    Pull,
+    Delete,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum AuthChallenge {
+    Basic,
+    Bearer {
+        realm: Url,
+        service: Option<String>,
+        scope: Option<String>,
+    },
+}
+
+impl AuthChallenge {
+    // i.e: Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:qovery/engine:pull"
+    fn parse(header: &str) -> Option<AuthChallenge> {
+        let (scheme, params) = header.trim().split_once(' ').unwrap_or((header.trim(), ""));
+        if scheme.eq_ignore_ascii_case("basic") {
+            return Some(AuthChallenge::Basic);
+        }
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            return None;
+        }
+
+        let mut params = parse_auth_params(params);
+        Some(AuthChallenge::Bearer {
+            realm: Url::parse(&params.remove("realm")?).ok()?,
+            service: params.remove("service"),
+            scope: params.remove("scope"),
+        })
+    }
+}
+
+// Parse comma separated key="value" pairs, values may contain commas (i.e: scope="repository:x:pull,push")
+fn parse_auth_params(params: &str) -> HashMap<String, String> {
+    let mut ret = HashMap::new();
+    let mut rest = params.trim();
+
+    while let Some((key, value)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_lowercase();
+        let value = value.trim_start();
+        let (value, remaining) = match value.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, remaining)) => (value, remaining),
+                None => (quoted, ""),
+            },
+            None => value.split_once(',').unwrap_or((value, "")),
+        };
+        ret.insert(key, value.to_string());
+        rest = remaining.trim_start().trim_start_matches(',');
+    }
+
+    ret
+}
+
+// Retry-After is either a number of seconds or an HTTP date
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+fn retry_delay(headers: &HeaderMap, attempt: u32) -> Duration {
+    let retry_after = headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, Utc::now()));
+
+    // No hint from the registry, fallback on an exponential backoff
+    retry_after
+        .unwrap_or_else(|| Duration::from_secs(2_u64.pow(attempt)))
+        .min(MAX_RETRY_AFTER)
+}
+
+// i.e: </v2/qovery/engine/tags/list?last=v1.0&n=1000>; rel="next"
+fn next_page_url(base: &Url, headers: &HeaderMap) -> Option<Url> {
+    let link = headers.get(LINK)?.to_str().ok()?;
+    link.split(',')
+        .find(|link| link.contains("rel=\"next\""))
+        .and_then(|link| link.split_once('<'))
+        .and_then(|(_, link)| link.split_once('>'))
+        .and_then(|(url, _)| base.join(url).ok())
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct ManifestDescriptor {
+    digest: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    config: Option<ManifestDescriptor>,
+    // only present for image index/manifest list
+    manifests: Option<Vec<ManifestDescriptor>>,
+}
+
+#[derive(Deserialize)]
+struct ImageConfig {
+    created: Option<DateTime<Utc>>,
+}
+
+/// Client of the OCI distribution API (https://github.com/opencontainers/distribution-spec)
+/// Handle basic and bearer token authentication, and back off when the registry rate limits us
+pub struct OciDistributionClient {
+    url: Url,
+    credentials: Option<(String, String)>,
+    http_client: reqwest::blocking::Client,
+    // bearer tokens already obtained, by repository and access
+    tokens: Mutex<HashMap<(String, Access), String>>,
+    use_basic_auth: AtomicBool,
+}
+
+impl OciDistributionClient {
+    pub fn new(
+        url: Url,
+        credentials: Option<(String, String)>,
+        skip_tls_verification: bool,
+    ) -> Result<Self, OciRegistryError> {
+        let http_client = reqwest::blocking::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(60))
+            .danger_accept_invalid_certs(skip_tls_verification)
+            .user_agent("qovery-engine")
+            .build()?;
+
+        Ok(Self {
+            url,
+            credentials,
+            http_client,
+            tokens: Mutex::new(HashMap::new()),
+            use_basic_auth: AtomicBool::new(false),
+        })
+    }
+
+    fn api_url(&self, path: &str) -> Result<Url, OciRegistryError> {
+        self.url
+            .join(&format!("v2/{}", path))
+            .map_err(|err| OciRegistryError::RequestError {
+                raw_error_message: format!("invalid registry url: {}", err),
+            })
+    }
+
+    fn authenticate(&self, request: RequestBuilder, repository: &str, access: Access) -> RequestBuilder {
+        if let Some(token) = self.tokens.lock().unwrap().get(&(repository.to_string(), access)) {
+            return request.bearer_auth(token);
+        }
+
+        match &self.credentials {
+            Some((user, password)) if self.use_basic_auth.load(Ordering::Relaxed) => {
+                request.basic_auth(user, Some(password))
+            }
+            _ => request,
+        }
+    }
+
+    fn fetch_token(&self, challenge: &AuthChallenge, repository: &str, access: Access) -> Result<(), OciRegistryError> {
+        let AuthChallenge::Bearer { realm, service, scope } = challenge else {
+            self.use_basic_auth.store(true, Ordering::Relaxed);
+            return Ok(());
+        };
+
+        let scope = scope.clone().unwrap_or_else(|| match access {
+            Access::Pull => format!("repository:{}:pull", repository),
+            Access::Delete => format!("repository:{}:pull,delete", repository),
+        });
+        let mut request = self.http_client.get(realm.clone()).query(&[("scope", scope)]);
+        if let Some(service) = service {
+            request = request.query(&[("service", service)]);
+        }
+        if let Some((user, password)) = &self.credentials {
+            request = request.basic_auth(user, Some(password));
+        }
+
+        let response = request.send()?;
+        if !response.status().is_success() {
+            return Err(OciRegistryError::AuthenticationError {
+                raw_error_message: format!("token server answered with status {}", response.status()),
+            });
+        }
+
+        let token: TokenResponse = response.json()?;
+        let token = token
+            .token
+            .or(token.access_token)
+            .ok_or_else(|| OciRegistryError::AuthenticationError {
+                raw_error_message: "token server did not return any token".to_string(),
+            })?;
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert((repository.to_string(), access), token);
+
+        Ok(())
+    }
+
+    // Send the request, answering the authentication challenge and retrying while the registry rate limits us
+    fn send(
+        &self,
+        repository: &str,
+        access: Access,
+        request: impl Fn() -> RequestBuilder,
+    ) -> Result<Response, OciRegistryError> {
+        let mut authenticated = false;
+        let mut attempt = 0;
+
+        loop {
+            let response = self.authenticate(request(), repository, access).send()?;
+
+            match response.status() {
+                StatusCode::UNAUTHORIZED if !authenticated => {
+                    let challenge = response
+                        .headers()
+                        .get(WWW_AUTHENTICATE)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(AuthChallenge::parse)
+                        .ok_or_else(|| OciRegistryError::AuthenticationError {
+                            raw_error_message: "missing or invalid authentication challenge".to_string(),
+                        })?;
+                    self.fetch_token(&challenge, repository, access)?;
+                    authenticated = true;
+                }
+                StatusCode::TOO_MANY_REQUESTS if attempt < MAX_RATE_LIMIT_RETRIES => {
+                    let delay = retry_delay(response.headers(), attempt);
+                    warn!(
+                        "Registry {} is rate limiting us, retrying in {}s",
+                        self.url.host_str().unwrap_or_default(),
+                        delay.as_secs()
+                    );
+                    thread::sleep(delay);
+                    attempt += 1;
+                }
+                StatusCode::TOO_MANY_REQUESTS => return Err(OciRegistryError::TooManyRequests { retries: attempt }),
+                _ => return Ok(response),
+            }
+        }
+    }
+
+    fn unexpected_status(response: Response) -> OciRegistryError {
+        let status = response.status();
+        OciRegistryError::UnexpectedStatus {
+            status,
+            body: response.text().unwrap_or_default(),
+        }
+    }
+
+    /// List all the tags of a repository, an unknown repository has no tags
+    pub fn list_tags(&self, repository: &str) -> Result<Vec<String>, OciRegistryError> {
+        let mut tags = vec![];
+        let mut url = self.api_url(&format!("{}/tags/list?n={}", repository, TAGS_PAGE_SIZE))?;
+
+        loop {
+            let response = self.send(repository, Access::Pull, || self.http_client.get(url.clone()))?;
+            match response.status() {
+                StatusCode::NOT_FOUND => return Ok(tags),
+                status if status.is_success() => {}
+                _ => return Err(Self::unexpected_status(response)),
+            }
+
+            let next_url = next_page_url(&url, response.headers());
+            let page: TagsResponse = response.json()?;
+            tags.extend(page.tags.unwrap_or_default());
+
+            match next_url {
+                Some(next_url) => url = next_url,
+                None => return Ok(tags),
+            }
+        }
+    }
+
+    /// Digest of the manifest pointed by the reference (tag or digest), None if it does not exist
+    pub fn manifest_digest(&self, repository: &str, reference: &str) -> Result<Option<String>, OciRegistryError> {
+        let url = self.api_url(&format!("{}/manifests/{}", repository, reference))?;
+        let response = self.send(repository, Access::Pull, || {
+            self.http_client
+                .head(url.clone())
+                .header(ACCEPT, MANIFEST_MEDIA_TYPES.join(","))
+        })?;
+        match response.status() {
+            StatusCode::NOT_FOUND => return Ok(None),
+            status if status.is_success() => {}
+            _ => return Err(Self::unexpected_status(response)),
+        }
+
+        if let Some(digest) = response
+            .headers()
+            .get(DOCKER_CONTENT_DIGEST)
+            .and_then(|value| value.to_str().ok())
+        {
+            return Ok(Some(digest.to_string()));
+        }
+
+        // The header is optional in the spec, compute the digest from the manifest content
+        Ok(self
+            .get_manifest(repository, reference)?
+            .map(|manifest| format!("sha256:{:x}", Sha256::digest(manifest))))
+    }
+
+    fn get_manifest(&self, repository: &str, reference: &str) -> Result<Option<Vec<u8>>, OciRegistryError> {
+        let url = self.api_url(&format!("{}/manifests/{}", repository, reference))?;
+        let response = self.send(repository, Access::Pull, || {
+            self.http_client
+                .get(url.clone())
+                .header(ACCEPT, MANIFEST_MEDIA_TYPES.join(","))
+        })?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(response.bytes()?.to_vec())),
+            _ => Err(Self::unexpected_status(response)),
+        }
+    }
+
+    /// Creation date of the image, read from its config. For multi-arch images, the first platform is used
+    pub fn image_created_at(
+        &self,
+        repository: &str,
+        reference: &str,
+    ) -> Result<Option<DateTime<Utc>>, OciRegistryError> {
+        let to_error = |raw_error_message: String| OciRegistryError::InvalidManifest {
+            reference: format!("{}:{}", repository, reference),
+            raw_error_message,
+        };
+
+        let Some(manifest) = self.get_manifest(repository, reference)? else {
+            return Ok(None);
+        };
+        let mut manifest: Manifest = serde_json::from_slice(&manifest).map_err(|err| to_error(err.to_string()))?;
+
+        if let Some(platform_manifest) = manifest.manifests.as_ref().and_then(|manifests| manifests.first()) {
+            let Some(platform_manifest) = self.get_manifest(repository, &platform_manifest.digest)? else {
+                return Ok(None);
+            };
+            manifest = serde_json::from_slice(&platform_manifest).map_err(|err| to_error(err.to_string()))?;
+        }
+
+        let config_digest = manifest
+            .config
+            .ok_or_else(|| to_error("manifest has no config".to_string()))?
+            .digest;
+        let url = self.api_url(&format!("{}/blobs/{}", repository, config_digest))?;
+        let response = self.send(repository, Access::Pull, || self.http_client.get(url.clone()))?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(response.json::<ImageConfig>()?.created),
+            _ => Err(Self::unexpected_status(response)),
+        }
+    }
+
+    /// List the tags of the repository with their digest and creation date.
+    /// The creation date is only fetched once per digest, as tags often point to the same image
+    pub fn list_images(&self, repository: &str) -> Result<Vec<RegistryImage>, OciRegistryError> {
+        let mut created_at_by_digest: HashMap<String, Option<DateTime<Utc>>> = HashMap::new();
+        let mut images = vec![];
+        for tag in self.list_tags(repository)? {
+            // the tag may have been deleted in the meantime
+            let Some(digest) = self.manifest_digest(repository, &tag)? else {
+                continue;
+            };
+            let created_at = match created_at_by_digest.get(&digest) {
+                Some(created_at) => *created_at,
+                None => {
+                    let created_at = self.image_created_at(repository, &digest)?;
+                    created_at_by_digest.insert(digest.clone(), created_at);
+                    created_at
+                }
+            };
+            images.push(RegistryImage {
+                tag,
+                digest,
+                created_at,
+            });
+        }
+
+        Ok(images)
+    }
+
+    /// Delete the manifest, and so all the tags pointing to this digest
+    pub fn delete_manifest(&self, repository: &str, digest: &str) -> Result<(), OciRegistryError> {
+        let url = self.api_url(&format!("{}/manifests/{}", repository, digest))?;
+        let response = self.send(repository, Access::Delete, || self.http_client.delete(url.clone()))?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(()),
+            status if status.is_success() => Ok(()),
+            _ => Err(Self::unexpected_status(response)),
+        }
+    }
+}
+
+/// Any registry implementing the OCI distribution API (Docker Hub, Harbor, registry:2, ...)
+/// Unlike GenericCr, it does not shell out to skopeo to list and delete images
+pub struct OciRegistry {
+    context: Context,
+    long_id: Uuid,
+    name: String,
+    url: Url,
+    client: OciDistributionClient,
+    cr_info: ContainerRegistryInfo,
+}
+
+impl OciRegistry {
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        name: &str,
+        url: Url,
+        skip_tls_verification: bool,
+        repository_name: String,
+        credentials: Option<(String, String)>,
+    ) -> Result<Self, ContainerRegistryError> {
+        let mut registry_docker_json_config = None;
+        if let Some((user, pass)) = &credentials {
+            let mut registry_url = url.clone();
+            let _ = registry_url.set_username(user);
+            let _ = registry_url.set_password(Some(pass));
+
+            context
+                .docker
+                .login(&registry_url)
+                .map_err(|_err| ContainerRegistryError::InvalidCredentials)?;
+
+            registry_docker_json_config = Some(Self::get_docker_json_config_raw(&url, user, pass));
+        }
+
+        let client = OciDistributionClient::new(url.clone(), credentials, skip_tls_verification).map_err(|err| {
+            ContainerRegistryError::CannotInstantiateClient {
+                raw_error_message: err.to_string(),
+            }
+        })?;
+
+        const MAX_REGISTRY_NAME_LENGTH: usize = 90;
+        let shared_name = {
+            let repository = repository_name.clone();
+            move |image_build_context: &super::ImageBuildContext| {
+                let git_repo_truncated: String = take_last_x_chars_and_remove_leading_dash_char(
+                    image_build_context.git_repo_url_sanitized.as_str(),
+                    MAX_REGISTRY_NAME_LENGTH,
+                );
+                format!(
+                    "{}/{}-{}",
+                    repository,
+                    image_build_context.cluster_id.short(),
+                    git_repo_truncated
+                )
+            }
+        };
+        let container_registry_info = ContainerRegistryInfo {
+            endpoint: url.clone(),
+            registry_name: name.to_string(),
+            registry_docker_json_config,
+            insecure_registry: skip_tls_verification || url.scheme() == "http",
+            get_shared_image_name: Box::new(shared_name.clone()),
+            get_image_name: Box::new({
+                let repository = repository_name.clone();
+                move |name| format!("{}/{}", repository, name)
+            }),
+            get_shared_repository_name: Box::new(shared_name),
+            get_repository_name: Box::new({
+                let repository = repository_name.clone();
+                move |name| format!("{}/{}", repository, name)
+            }),
+        };
+
+        Ok(Self {
+            context,
+            long_id,
+            name: name.to_string(),
+            url,
+            client,
+            cr_info: container_registry_info,
+        })
+    }
+
+    pub fn client(&self) -> &OciDistributionClient {
+        &self.client
+    }
+
+    fn get_docker_json_config_raw(url: &Url, login: &str, secret_token: &str) -> String {
+        let host = match url.port_or_known_default() {
+            None | Some(443) => url.host_str().unwrap_or_default().to_string(),
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+        };
+        general_purpose::STANDARD.encode(
+            format!(
+                r#"{{"auths":{{"{}":{{"auth":"{}"}}}}}}"#,
+                host,
+                general_purpose::STANDARD.encode(format!("{login}:{secret_token}").as_bytes())
+            )
+            .as_bytes(),
+        )
+    }
+
+    fn repository(&self, repository_name: &str) -> Repository {
+        Repository {
+            registry_id: repository_name.to_string(),
+            name: repository_name.to_string(),
+            uri: Some(
+                self.url
+                    .join(repository_name)
+                    .map(|u| u.to_string())
+                    .unwrap_or_default(),
+            ),
+            ttl: None,
+            labels: None,
+        }
+    }
+}
+
+impl ContainerRegistry for OciRegistry {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::OciRegistry
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn registry_info(&self) -> &ContainerRegistryInfo {
+        &self.cr_info
+    }
+
+    fn create_repository(
+        &self,
+        name: &str,
+        _image_retention_time_in_seconds: u32,
+        _registry_tags: RegistryTags,
+    ) -> Result<(Repository, RepositoryInfo), ContainerRegistryError> {
+        // Repositories are created on the first push. Expired images are deleted by the retention sweeper, which keeps
+        // the ones still used by the cluster
+        let created = match self.client.list_tags(name) {
+            Ok(tags) => tags.is_empty(),
+            Err(err) => {
+                return Err(ContainerRegistryError::CannotCreateRepository {
+                    registry_name: self.name.clone(),
+                    repository_name: name.to_string(),
+                    raw_error_message: err.to_string(),
+                })
+            }
+        };
+
+        Ok((self.repository(name), RepositoryInfo { created }))
+    }
+
+    fn get_repository(&self, repository_name: &str) -> Result<Repository, ContainerRegistryError> {
+        Ok(self.repository(repository_name))
+    }
+
+    fn delete_repository(&self, repository_name: &str) -> Result<(), ContainerRegistryError> {
+        let to_error = |err: OciRegistryError| ContainerRegistryError::CannotDeleteRepository {
+            registry_name: self.name.clone(),
+            repository_name: repository_name.to_string(),
+            raw_error_message: err.to_string(),
+        };
+
+        let mut deleted_digests = HashSet::new();
+        for tag in self.client.list_tags(repository_name).map_err(to_error)? {
+            let Some(digest) = self.client.manifest_digest(repository_name, &tag).map_err(to_error)? else {
+                continue;
+            };
+            if deleted_digests.insert(digest.clone()) {
+                self.client
+                    .delete_manifest(repository_name, &digest)
+                    .map_err(to_error)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn delete_image(&self, image: &Image) -> Result<(), ContainerRegistryError> {
+        let to_error = |err: OciRegistryError| ContainerRegistryError::CannotDeleteImage {
+            registry_name: self.name.clone(),
+            repository_name: image.repository_name().to_string(),
+            image_name: image.name().to_string(),
+            raw_error_message: err.to_string(),
+        };
+
+        let Some(digest) = self.client.manifest_digest(&image.name, &image.tag).map_err(to_error)? else {
+            return Ok(());
+        };
+
+        self.client.delete_manifest(&image.name, &digest).map_err(to_error)
+    }
+
+    fn image_exists(&self, image: &Image) -> bool {
+        matches!(self.client.manifest_digest(&image.name, &image.tag), Ok(Some(_)))
+    }
+
+    fn list_images(&self, repository_name: &str) -> Result<Vec<RegistryImage>, ContainerRegistryError> {
+        self.client
+            .list_images(repository_name)
+            .map_err(|err| ContainerRegistryError::CannotGetRepository {
+                registry_name: self.name.clone(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: err.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_parse_auth_challenge() {
+        let challenge = AuthChallenge::parse(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:qovery/engine:pull,push""#,
+        );
+        assert_eq!(
+            challenge,
+            Some(AuthChallenge::Bearer {
+                realm: Url::parse("https://auth.docker.io/token").unwrap(),
+                service: Some("registry.docker.io".to_string()),
+                scope: Some("repository:qovery/engine:pull,push".to_string()),
+            })
+        );
+
+        let challenge = AuthChallenge::parse(r#"Bearer realm="https://ghcr.io/token""#);
+        assert_eq!(
+            challenge,
+            Some(AuthChallenge::Bearer {
+                realm: Url::parse("https://ghcr.io/token").unwrap(),
+                service: None,
+                scope: None,
+            })
+        );
+
+        assert_eq!(
+            AuthChallenge::parse(r#"Basic realm="Registry Realm""#),
+            Some(AuthChallenge::Basic)
+        );
+        assert_eq!(AuthChallenge::parse(r#"Bearer service="registry.docker.io""#), None);
+        assert_eq!(AuthChallenge::parse("Negotiate"), None);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_delay(&headers, 0), Duration::from_secs(1));
+        assert_eq!(retry_delay(&headers, 3), Duration::from_secs(8));
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        assert_eq!(retry_delay(&headers, 0), MAX_RETRY_AFTER);
+    }
+
+    #[test]
+    fn test_next_page_url() {
+        let base = Url::parse("https://registry.example.com/v2/qovery/engine/tags/list?n=1000").unwrap();
+        let mut headers = HeaderMap::new();
+        assert_eq!(next_page_url(&base, &headers), None);
+
+        headers.insert(
+            LINK,
+            HeaderValue::from_static(r#"</v2/qovery/engine/tags/list?last=v1.0&n=1000>; rel="next""#),
+        );
+        assert_eq!(
+            next_page_url(&base, &headers),
+            Some(Url::parse("https://registry.example.com/v2/qovery/engine/tags/list?last=v1.0&n=1000").unwrap())
+        );
+    }
+
+    // start a local registry with deletion enabled to run this test
+    // docker run --rm -ti -p 5000:5000 -e REGISTRY_STORAGE_DELETE_ENABLED=true --name registry registry:2
+    #[cfg(feature = "test-local-docker")]
+    #[test]
+    fn test_oci_client_with_local_registry() {
+        use crate::cmd::command::CommandKiller;
+        use crate::cmd::docker::{ContainerImage, Docker};
+
+        let registry_url = Url::parse("http://localhost:5000").unwrap();
+        let docker = Docker::new_with_local_builder(None).unwrap();
+        let image_source = ContainerImage::new(
+            Url::parse("https://public.ecr.aws").unwrap(),
+            "r3m4q3r9/pub-mirror-debian".to_string(),
+            vec!["11.6-ci".to_string()],
+        );
+        let image_dest =
+            ContainerImage::new(registry_url.clone(), "oci/debian".to_string(), vec!["mirror".to_string()]);
+        docker
+            .mirror(
+                &image_source,
+                &image_dest,
+                &mut |msg| println!("{msg}"),
+                &mut |msg| eprintln!("{msg}"),
+                &CommandKiller::never(),
+            )
+            .unwrap();
+
+        let client = OciDistributionClient::new(registry_url, None, false).unwrap();
+        assert_eq!(client.list_tags("oci/debian").unwrap(), vec!["mirror".to_string()]);
+        assert!(client.list_tags("oci/does-not-exist").unwrap().is_empty());
+        assert!(client.image_created_at("oci/debian", "mirror").unwrap().is_some());
+
+        let digest = client.manifest_digest("oci/debian", "mirror").unwrap().unwrap();
+        assert!(digest.starts_with("sha256:"));
+        let images = client.list_images("oci/debian").unwrap();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].digest, digest);
+        assert!(images[0].created_at.is_some());
+
+        client.delete_manifest("oci/debian", &digest).unwrap();
+        assert_eq!(client.manifest_digest("oci/debian", "mirror").unwrap(), None);
+    }
+}
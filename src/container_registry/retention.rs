@@ -0,0 +1,325 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{ConfigMap, Pod, PodSpec};
+use kube::api::{ListParams, ObjectMeta, Patch, PatchParams, PostParams};
+use kube::Api;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+// Date of the last sweep of each repository, so a repository is swept at most once per interval whatever the number of deployments
+const SWEEPS_CONFIG_MAP_NAME: &str = "registry-retention-sweeper";
+const SWEEPS_CONFIG_MAP_NAMESPACE: &str = "qovery";
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionSweeperMode {
+    #[default]
+    Disabled,
+    // expired images are only reported, nothing is deleted
+    DryRun,
+    Enabled,
+}
+
+/// A tag of a repository, with the digest it points to
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RegistryImage {
+    pub tag: String,
+    pub digest: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RetentionSweepReport {
+    pub repository_name: String,
+    pub dry_run: bool,
+    // images older than the retention, deleted unless in dry run
+    pub expired: Vec<RegistryImage>,
+    // expired images kept because a workload of the cluster still uses them
+    pub kept_in_use: Vec<RegistryImage>,
+}
+
+impl Display for RetentionSweepReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let expired_tags = self.expired.iter().map(|img| img.tag.as_str()).collect::<Vec<_>>();
+        let kept_tags = self.kept_in_use.iter().map(|img| img.tag.as_str()).collect::<Vec<_>>();
+        write!(
+            f,
+            "repository {}: {} {} expired image(s) {:?}, kept {} image(s) still in use {:?}",
+            self.repository_name,
+            if self.dry_run { "would delete" } else { "deleted" },
+            expired_tags.len(),
+            expired_tags,
+            kept_tags.len(),
+            kept_tags,
+        )
+    }
+}
+
+/// Images used by the workloads of the cluster, they must never be deleted from the registry.
+/// Images are referenced as `host/name:tag` or `host/name@digest`.
+#[derive(Debug, Clone, Default)]
+pub struct ImagesInUse {
+    images: HashSet<String>,
+}
+
+impl ImagesInUse {
+    pub fn new(images: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            images: images.into_iter().collect(),
+        }
+    }
+
+    pub fn contains(&self, registry_url: &Url, repository_name: &str, image: &RegistryImage) -> bool {
+        let host = match registry_url.port() {
+            Some(port) => format!("{}:{}", registry_url.host_str().unwrap_or_default(), port),
+            None => registry_url.host_str().unwrap_or_default().to_string(),
+        };
+
+        self.images
+            .contains(&format!("{}/{}:{}", host, repository_name, image.tag))
+            || self
+                .images
+                .contains(&format!("{}/{}@{}", host, repository_name, image.digest))
+    }
+}
+
+/// Select the images older than the retention.
+/// As a registry deletes a manifest with all the tags pointing to it, an expired image sharing its digest with an image
+/// in use, or with an image not yet expired, is kept.
+pub fn select_expired_images(
+    images: Vec<RegistryImage>,
+    retention: Duration,
+    now: DateTime<Utc>,
+    is_in_use: impl Fn(&RegistryImage) -> bool,
+) -> (Vec<RegistryImage>, Vec<RegistryImage>) {
+    let Ok(retention) = chrono::Duration::from_std(retention) else {
+        return (vec![], vec![]);
+    };
+    let expiration_date = now - retention;
+    // Images without creation date (i.e: built with reproducible builds) are never considered as expired
+    let is_expired =
+        |image: &RegistryImage| matches!(image.created_at, Some(created_at) if created_at < expiration_date);
+
+    let in_use_digests: HashSet<&str> = images
+        .iter()
+        .filter(|image| is_in_use(image))
+        .map(|image| image.digest.as_str())
+        .collect();
+    let not_expired_digests: HashSet<&str> = images
+        .iter()
+        .filter(|image| !is_expired(image))
+        .map(|image| image.digest.as_str())
+        .collect();
+
+    let mut expired = vec![];
+    let mut kept_in_use = vec![];
+    for image in images.iter().filter(|image| is_expired(image)) {
+        if in_use_digests.contains(image.digest.as_str()) {
+            kept_in_use.push(image.clone());
+        } else if !not_expired_digests.contains(image.digest.as_str()) {
+            expired.push(image.clone());
+        }
+    }
+
+    (expired, kept_in_use)
+}
+
+// Repository names contain `/`, which is not allowed in config map keys
+fn sweep_key(repository_name: &str) -> String {
+    format!("{:x}", Sha256::digest(repository_name.as_bytes()))[..16].to_string()
+}
+
+/// Select the repositories not swept since `interval`, according to the last sweeps recorded in the cluster
+pub fn repositories_to_sweep(
+    repositories: impl IntoIterator<Item = String>,
+    last_sweeps: &BTreeMap<String, String>,
+    interval: Duration,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let Ok(interval) = chrono::Duration::from_std(interval) else {
+        return vec![];
+    };
+
+    repositories
+        .into_iter()
+        .filter(|repository| {
+            match last_sweeps
+                .get(&sweep_key(repository))
+                .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            {
+                Some(last_sweep) => last_sweep.with_timezone(&Utc) + interval <= now,
+                None => true,
+            }
+        })
+        .collect()
+}
+
+/// Last sweeps of the repositories, recorded in the cluster by `record_sweeps`
+pub async fn last_sweeps(client: kube::Client) -> Result<BTreeMap<String, String>, kube::Error> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(client, SWEEPS_CONFIG_MAP_NAMESPACE);
+    Ok(config_maps
+        .get_opt(SWEEPS_CONFIG_MAP_NAME)
+        .await?
+        .and_then(|config_map| config_map.data)
+        .unwrap_or_default())
+}
+
+/// Record the repositories as swept at `now`, keeping the last sweeps of the other repositories
+pub async fn record_sweeps(
+    client: kube::Client,
+    repositories: &[String],
+    now: DateTime<Utc>,
+) -> Result<(), kube::Error> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(client, SWEEPS_CONFIG_MAP_NAMESPACE);
+    let data: BTreeMap<String, String> = repositories
+        .iter()
+        .map(|repository| (sweep_key(repository), now.to_rfc3339()))
+        .collect();
+
+    if config_maps.get_opt(SWEEPS_CONFIG_MAP_NAME).await?.is_none() {
+        let config_map = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(SWEEPS_CONFIG_MAP_NAME.to_string()),
+                ..Default::default()
+            },
+            data: Some(data),
+            ..Default::default()
+        };
+        return config_maps
+            .create(&PostParams::default(), &config_map)
+            .await
+            .map(|_| ());
+    }
+
+    config_maps
+        .patch(
+            SWEEPS_CONFIG_MAP_NAME,
+            &PatchParams::default(),
+            &Patch::Merge(json!({ "data": data })),
+        )
+        .await
+        .map(|_| ())
+}
+
+fn pod_spec_images(spec: Option<&PodSpec>) -> impl Iterator<Item = String> + '_ {
+    spec.into_iter().flat_map(|spec| {
+        spec.containers
+            .iter()
+            .chain(spec.init_containers.iter().flatten())
+            .filter_map(|container| container.image.clone())
+    })
+}
+
+/// List the images of every workload of the cluster, even the paused ones (0 replicas) as they can be resumed
+pub async fn list_images_in_use(client: kube::Client) -> Result<ImagesInUse, kube::Error> {
+    let params = ListParams::default();
+    let mut images: Vec<String> = vec![];
+
+    for pod in Api::<Pod>::all(client.clone()).list(&params).await?.items {
+        images.extend(pod_spec_images(pod.spec.as_ref()));
+    }
+    for deployment in Api::<Deployment>::all(client.clone()).list(&params).await?.items {
+        images.extend(pod_spec_images(
+            deployment.spec.as_ref().and_then(|spec| spec.template.spec.as_ref()),
+        ));
+    }
+    for statefulset in Api::<StatefulSet>::all(client.clone()).list(&params).await?.items {
+        images.extend(pod_spec_images(
+            statefulset.spec.as_ref().and_then(|spec| spec.template.spec.as_ref()),
+        ));
+    }
+    for daemonset in Api::<DaemonSet>::all(client.clone()).list(&params).await?.items {
+        images.extend(pod_spec_images(
+            daemonset.spec.as_ref().and_then(|spec| spec.template.spec.as_ref()),
+        ));
+    }
+    for job in Api::<Job>::all(client.clone()).list(&params).await?.items {
+        images.extend(pod_spec_images(job.spec.as_ref().and_then(|spec| spec.template.spec.as_ref())));
+    }
+    for cronjob in Api::<CronJob>::all(client).list(&params).await?.items {
+        images.extend(pod_spec_images(cronjob.spec.as_ref().and_then(|spec| {
+            spec.job_template
+                .spec
+                .as_ref()
+                .and_then(|job| job.template.spec.as_ref())
+        })));
+    }
+
+    Ok(ImagesInUse::new(images))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(tag: &str, digest: &str, age_in_days: Option<i64>, now: DateTime<Utc>) -> RegistryImage {
+        RegistryImage {
+            tag: tag.to_string(),
+            digest: digest.to_string(),
+            created_at: age_in_days.map(|days| now - chrono::Duration::days(days)),
+        }
+    }
+
+    #[test]
+    fn test_select_expired_images() {
+        let now = Utc::now();
+        let images = vec![
+            image("old", "sha256:1", Some(40), now),
+            image("old-in-use", "sha256:2", Some(40), now),
+            image("recent", "sha256:3", Some(1), now),
+            image("old-same-digest-as-recent", "sha256:3", Some(40), now),
+            image("old-same-digest-as-in-use", "sha256:2", Some(50), now),
+            image("no-creation-date", "sha256:4", None, now),
+        ];
+        let in_use = |img: &RegistryImage| img.tag == "old-in-use";
+
+        let (expired, kept_in_use) = select_expired_images(images, Duration::from_secs(30 * 24 * 3600), now, in_use);
+
+        assert_eq!(expired.iter().map(|img| img.tag.as_str()).collect::<Vec<_>>(), vec!["old"]);
+        assert_eq!(
+            kept_in_use.iter().map(|img| img.tag.as_str()).collect::<Vec<_>>(),
+            vec!["old-in-use", "old-same-digest-as-in-use"]
+        );
+    }
+
+    #[test]
+    fn test_repositories_to_sweep() {
+        let now = Utc::now();
+        let last_sweeps = BTreeMap::from([
+            (sweep_key("qovery/recent"), (now - chrono::Duration::hours(1)).to_rfc3339()),
+            (sweep_key("qovery/old"), (now - chrono::Duration::hours(25)).to_rfc3339()),
+            (sweep_key("qovery/invalid"), "not a date".to_string()),
+        ]);
+        let repositories = ["qovery/recent", "qovery/old", "qovery/invalid", "qovery/never"].map(String::from);
+
+        assert_eq!(
+            repositories_to_sweep(repositories, &last_sweeps, Duration::from_secs(24 * 3600), now),
+            vec!["qovery/old", "qovery/invalid", "qovery/never"]
+        );
+        assert_ne!(sweep_key("qovery/app"), sweep_key("qovery_app"));
+    }
+
+    #[test]
+    fn test_images_in_use() {
+        let images_in_use = ImagesInUse::new(vec![
+            "registry.example.com/qovery/app:v1".to_string(),
+            "localhost:5000/qovery/app@sha256:2".to_string(),
+        ]);
+        let registry_url = Url::parse("https://registry.example.com").unwrap();
+        let now = Utc::now();
+
+        assert!(images_in_use.contains(&registry_url, "qovery/app", &image("v1", "sha256:1", None, now)));
+        assert!(!images_in_use.contains(&registry_url, "qovery/app", &image("v2", "sha256:1", None, now)));
+        assert!(!images_in_use.contains(&registry_url, "qovery/other", &image("v1", "sha256:1", None, now)));
+
+        let registry_url = Url::parse("http://localhost:5000").unwrap();
+        assert!(images_in_use.contains(&registry_url, "qovery/app", &image("v3", "sha256:2", None, now)));
+    }
+}
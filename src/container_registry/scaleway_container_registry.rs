@@ -4,6 +4,8 @@ use self::scaleway_api_rs::models::scaleway_registry_v1_namespace::Status;
 use crate::build_platform::Image;
 use crate::cmd::docker;
 use crate::container_registry::errors::{ContainerRegistryError, RepositoryNamingRule};
+use crate::container_registry::oci_registry::OciDistributionClient;
+use crate::container_registry::retention::RegistryImage;
 use crate::container_registry::{
     take_last_x_chars_and_remove_leading_dash_char, ContainerRegistry, ContainerRegistryInfo, Kind, Repository,
     RepositoryInfo,
@@ -447,6 +449,22 @@ impl ContainerRegistry for ScalewayCR {
 
         image_exists.is_ok()
     }
+
+    fn list_images(&self, repository_name: &str) -> Result<Vec<RegistryImage>, ContainerRegistryError> {
+        let to_error = |raw_error_message: String| ContainerRegistryError::CannotGetRepository {
+            registry_name: self.name.to_string(),
+            repository_name: repository_name.to_string(),
+            raw_error_message,
+        };
+
+        // Scaleway API only gives the digest of the tags, the registry API also gives the creation date of the images
+        let mut url = self.registry_info.endpoint.clone();
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+        OciDistributionClient::new(url, Some(("nologin".to_string(), self.secret_token.clone())), false)
+            .and_then(|client| client.list_images(repository_name))
+            .map_err(|err| to_error(err.to_string()))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::cloud_provider::kubernetes::Kubernetes;
+use crate::cmd::command::CommandKiller;
+use crate::cmd::docker::{ContainerImage, Docker};
+use crate::cmd::vulnerability_scanner::{
+    scan_image_in_kube_pod, scan_image_locally, Vulnerability, VulnerabilityScanner, VulnerabilityScannerError,
+    VulnerabilitySeverity,
+};
+use crate::events::{EngineMsg, EngineMsgPayload};
+use crate::metrics_registry::{MetricsRegistry, StepLabel, StepName, StepStatus};
+use crate::models::abort::Abort;
+
+const VULNERABILITY_SCAN_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+const SCANNER_POD_NAMESPACE: &str = "qovery";
+// Only the first blocking vulnerabilities are listed in the deployment logs, the summary has them all
+const MAX_LOGGED_VULNERABILITIES: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VulnerabilityScanRuntime {
+    // scanner binary installed next to the engine
+    #[default]
+    LocalBinary,
+    // short-lived pod in the cluster, running the official image of the scanner
+    KubePod,
+}
+
+/// Cluster wide configuration of the scanner, the threshold blocking the deployment is set per service
+#[derive(Debug, Clone)]
+pub struct VulnerabilityScanConfig {
+    pub scanner: VulnerabilityScanner,
+    pub runtime: VulnerabilityScanRuntime,
+    pub docker_config_path: PathBuf,
+    pub kubeconfig_path: PathBuf,
+}
+
+impl VulnerabilityScanConfig {
+    pub fn from_cluster(kubernetes: &dyn Kubernetes, docker: &Docker) -> Option<Self> {
+        let advanced_settings = kubernetes.advanced_settings();
+        advanced_settings
+            .registry_vulnerability_scan_scanner
+            .map(|scanner| VulnerabilityScanConfig {
+                scanner,
+                runtime: advanced_settings.registry_vulnerability_scan_runtime,
+                docker_config_path: docker.config_path().to_path_buf(),
+                kubeconfig_path: kubernetes.kubeconfig_local_file_path(),
+            })
+    }
+}
+
+/// Scan summary of an image, published as an engine message for dashboards
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct VulnerabilityScanSummary {
+    pub service_id: Uuid,
+    pub image_name: String,
+    pub scanner: VulnerabilityScanner,
+    pub vulnerabilities_count: BTreeMap<VulnerabilitySeverity, usize>,
+    pub fail_on_severity: Option<VulnerabilitySeverity>,
+    // vulnerabilities at or above fail_on_severity, most severe first
+    pub blocking_vulnerabilities: Vec<Vulnerability>,
+}
+
+impl VulnerabilityScanSummary {
+    pub fn new(
+        service_id: Uuid,
+        image_name: String,
+        scanner: VulnerabilityScanner,
+        vulnerabilities: Vec<Vulnerability>,
+        fail_on_severity: Option<VulnerabilitySeverity>,
+    ) -> Self {
+        let mut vulnerabilities_count = BTreeMap::new();
+        for vulnerability in &vulnerabilities {
+            *vulnerabilities_count.entry(vulnerability.severity).or_insert(0) += 1;
+        }
+
+        let mut blocking_vulnerabilities = match fail_on_severity {
+            Some(threshold) => vulnerabilities
+                .into_iter()
+                .filter(|vulnerability| vulnerability.severity >= threshold)
+                .collect(),
+            None => vec![],
+        };
+        blocking_vulnerabilities.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.id.cmp(&b.id)));
+
+        VulnerabilityScanSummary {
+            service_id,
+            image_name,
+            scanner,
+            vulnerabilities_count,
+            fail_on_severity,
+            blocking_vulnerabilities,
+        }
+    }
+
+    pub fn is_blocking(&self) -> bool {
+        !self.blocking_vulnerabilities.is_empty()
+    }
+
+    /// Lines to be displayed in the deployment logs
+    pub fn log_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("🛡️ {}", self)];
+        for vulnerability in self.blocking_vulnerabilities.iter().take(MAX_LOGGED_VULNERABILITIES) {
+            lines.push(format!(
+                "  {} {} in {} {}{}",
+                vulnerability.severity,
+                vulnerability.id,
+                vulnerability.package_name,
+                vulnerability.installed_version,
+                vulnerability
+                    .fixed_version
+                    .as_ref()
+                    .map(|version| format!(", fixed in {version}"))
+                    .unwrap_or_default(),
+            ));
+        }
+        if self.blocking_vulnerabilities.len() > MAX_LOGGED_VULNERABILITIES {
+            lines.push(format!(
+                "  ... and {} more",
+                self.blocking_vulnerabilities.len() - MAX_LOGGED_VULNERABILITIES
+            ));
+        }
+        lines
+    }
+}
+
+impl Display for VulnerabilityScanSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let total: usize = self.vulnerabilities_count.values().sum();
+        let counts = self
+            .vulnerabilities_count
+            .iter()
+            .rev()
+            .map(|(severity, count)| format!("{severity}: {count}"))
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "{} found {} vulnerabilities in image {}",
+            self.scanner, total, self.image_name
+        )?;
+        if !counts.is_empty() {
+            write!(f, " ({})", counts.join(", "))?;
+        }
+        if let Some(threshold) = self.fail_on_severity {
+            write!(
+                f,
+                ", {} of them at or above the {} threshold",
+                self.blocking_vulnerabilities.len(),
+                threshold
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Scan an image pushed in a registry and publish its summary.
+/// `credentials` are only needed when the scanner runs in a pod, the local binary uses the engine docker config.
+pub fn scan_image(
+    config: &VulnerabilityScanConfig,
+    service_id: Uuid,
+    image: &ContainerImage,
+    credentials: Option<(&str, &str)>,
+    tls_verify: bool,
+    fail_on_severity: Option<VulnerabilitySeverity>,
+    metrics_registry: &dyn MetricsRegistry,
+    abort: &dyn Abort,
+) -> Result<VulnerabilityScanSummary, VulnerabilityScannerError> {
+    let record = metrics_registry.start_record(service_id, StepLabel::Service, StepName::VulnerabilityScan);
+    let cmd_killer = CommandKiller::from(VULNERABILITY_SCAN_TIMEOUT, abort);
+    let scan_result = match config.runtime {
+        VulnerabilityScanRuntime::LocalBinary => {
+            scan_image_locally(config.scanner, image, &config.docker_config_path, tls_verify, &cmd_killer)
+        }
+        VulnerabilityScanRuntime::KubePod => scan_image_in_kube_pod(
+            config.scanner,
+            image,
+            credentials,
+            &config.kubeconfig_path,
+            SCANNER_POD_NAMESPACE,
+            tls_verify,
+            &cmd_killer,
+        ),
+    };
+
+    let vulnerabilities = match scan_result {
+        Ok(vulnerabilities) => vulnerabilities,
+        Err(err) => {
+            record.stop(if err.is_aborted() {
+                StepStatus::Cancel
+            } else {
+                StepStatus::Error
+            });
+            return Err(err);
+        }
+    };
+
+    let summary = VulnerabilityScanSummary::new(
+        service_id,
+        image.image_name(),
+        config.scanner,
+        vulnerabilities,
+        fail_on_severity,
+    );
+    record.stop(if summary.is_blocking() {
+        StepStatus::Error
+    } else {
+        StepStatus::Success
+    });
+    metrics_registry.publish(EngineMsg::new(EngineMsgPayload::VulnerabilityScan(summary.clone())));
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vulnerability(id: &str, severity: VulnerabilitySeverity) -> Vulnerability {
+        Vulnerability {
+            id: id.to_string(),
+            package_name: "openssl".to_string(),
+            installed_version: "3.0.11".to_string(),
+            fixed_version: None,
+            severity,
+        }
+    }
+
+    #[test]
+    fn test_vulnerability_scan_summary_threshold() {
+        let vulnerabilities = vec![
+            vulnerability("CVE-1", VulnerabilitySeverity::Low),
+            vulnerability("CVE-2", VulnerabilitySeverity::High),
+            vulnerability("CVE-3", VulnerabilitySeverity::Critical),
+            vulnerability("CVE-4", VulnerabilitySeverity::High),
+        ];
+        let summary = |threshold| {
+            VulnerabilityScanSummary::new(
+                Uuid::nil(),
+                "registry/app:v1".to_string(),
+                VulnerabilityScanner::Trivy,
+                vulnerabilities.clone(),
+                threshold,
+            )
+        };
+
+        let report_only = summary(None);
+        assert!(!report_only.is_blocking());
+        assert_eq!(report_only.vulnerabilities_count.get(&VulnerabilitySeverity::High), Some(&2));
+        assert_eq!(
+            report_only.to_string(),
+            "Trivy found 4 vulnerabilities in image registry/app:v1 (CRITICAL: 1, HIGH: 2, LOW: 1)"
+        );
+
+        let fail_on_high = summary(Some(VulnerabilitySeverity::High));
+        assert!(fail_on_high.is_blocking());
+        assert_eq!(
+            fail_on_high
+                .blocking_vulnerabilities
+                .iter()
+                .map(|v| v.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["CVE-3", "CVE-2", "CVE-4"]
+        );
+
+        let no_vulnerabilities = VulnerabilityScanSummary::new(
+            Uuid::nil(),
+            "registry/app:v1".to_string(),
+            VulnerabilityScanner::Grype,
+            vec![],
+            Some(VulnerabilitySeverity::Critical),
+        );
+        assert!(!no_vulnerabilities.is_blocking());
+    }
+
+    #[test]
+    fn test_vulnerability_severity_deserialization() {
+        let severity: VulnerabilitySeverity = serde_json::from_str("\"CRITICAL\"").unwrap();
+        assert_eq!(severity, VulnerabilitySeverity::Critical);
+        assert!(VulnerabilitySeverity::Critical > VulnerabilitySeverity::High);
+        assert!(VulnerabilitySeverity::Low > VulnerabilitySeverity::Unknown);
+    }
+}
@@ -0,0 +1,451 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::command::{AbortReason, CommandKiller};
+use crate::deployment_action::DeploymentAction;
+use crate::errors::EngineError;
+use crate::events::EventDetails;
+use crate::models::router::RouterError;
+use crate::runtime::block_on;
+use kube::api::{ApiResource, DynamicObject, GroupVersionKind, ListParams};
+use kube::Api;
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::thread;
+use std::time::Duration;
+
+const CERT_MANAGER_GROUP: &str = "cert-manager.io";
+const CERT_MANAGER_ACME_GROUP: &str = "acme.cert-manager.io";
+const DEFAULT_CHECK_FREQUENCY: Duration = Duration::from_secs(30);
+const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(60 * 5);
+
+/// Watches the cert-manager Certificate (and its CertificateRequest/Order/Challenge) generated for router custom domains
+pub struct CheckCertificateForDomains<'a> {
+    pub certificate_name: String,
+    pub domains: Vec<String>,
+    pub strict: bool,
+    pub event_details: EventDetails,
+    pub log: Box<dyn Fn(String) + 'a + Send + Sync>,
+    pub warn: Box<dyn Fn(String) + 'a + Send + Sync>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CertificateFailureReason {
+    CaaRecord,
+    RateLimited,
+    Http01Unreachable,
+    Other,
+}
+
+impl Display for CertificateFailureReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CertificateFailureReason::CaaRecord => "CAA record forbids Let's Encrypt to issue a certificate",
+            CertificateFailureReason::RateLimited => "Let's Encrypt rate limit reached",
+            CertificateFailureReason::Http01Unreachable => "HTTP-01 challenge cannot be reached",
+            CertificateFailureReason::Other => "certificate issuance failed",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CertificateIssuanceFailure {
+    pub domain: Option<String>,
+    pub reason: CertificateFailureReason,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum CertificateIssuanceStatus {
+    Issued,
+    // issuance is still in progress, with the last error reported by cert-manager if any
+    Pending(Option<CertificateIssuanceFailure>),
+    // cert-manager gave up, it will only retry with a backoff
+    Failed(CertificateIssuanceFailure),
+}
+
+fn certificate_failure_reason(challenge_type: Option<&str>, message: &str) -> CertificateFailureReason {
+    let message = message.to_lowercase();
+    let is_http01 = challenge_type == Some("HTTP-01") || message.contains("http-01") || message.contains("self check");
+
+    if message.contains("caa") {
+        CertificateFailureReason::CaaRecord
+    } else if message.contains("ratelimited")
+        || message.contains("rate limit")
+        || message.contains("too many certificates")
+        || message.contains("too many failed authorizations")
+    {
+        CertificateFailureReason::RateLimited
+    } else if is_http01
+        && [
+            "acme:error:connection",
+            "acme:error:unauthorized",
+            "self check",
+            "timeout during connect",
+            "connection refused",
+            "no such host",
+            "wrong status code",
+        ]
+        .iter()
+        .any(|marker| message.contains(marker))
+    {
+        CertificateFailureReason::Http01Unreachable
+    } else {
+        CertificateFailureReason::Other
+    }
+}
+
+fn str_at<'a>(object: &'a DynamicObject, pointer: &str) -> Option<&'a str> {
+    object.data.pointer(pointer).and_then(Value::as_str)
+}
+
+fn condition<'a>(object: &'a DynamicObject, condition_type: &str) -> Option<&'a Value> {
+    object
+        .data
+        .pointer("/status/conditions")
+        .and_then(Value::as_array)?
+        .iter()
+        .find(|condition| condition.get("type").and_then(Value::as_str) == Some(condition_type))
+}
+
+fn condition_field<'a>(condition: &'a Value, field: &str) -> &'a str {
+    condition.get(field).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn owned_by<'a>(objects: &'a [DynamicObject], owner: &'a DynamicObject) -> impl Iterator<Item = &'a DynamicObject> {
+    objects.iter().filter(move |object| {
+        object
+            .metadata
+            .owner_references
+            .iter()
+            .flatten()
+            .any(|reference| Some(&reference.uid) == owner.metadata.uid.as_ref())
+    })
+}
+
+fn is_final_state(object: &DynamicObject) -> bool {
+    matches!(str_at(object, "/status/state"), Some("invalid") | Some("errored"))
+}
+
+fn certificate_issuance_status(
+    certificate: Option<&DynamicObject>,
+    certificate_requests: &[DynamicObject],
+    orders: &[DynamicObject],
+    challenges: &[DynamicObject],
+) -> CertificateIssuanceStatus {
+    let certificate = match certificate {
+        Some(certificate) => certificate,
+        None => return CertificateIssuanceStatus::Pending(None),
+    };
+    if condition(certificate, "Ready").map(|ready| condition_field(ready, "status")) == Some("True") {
+        return CertificateIssuanceStatus::Issued;
+    }
+
+    let failure = |domain: Option<&str>, challenge_type: Option<&str>, message: &str| CertificateIssuanceFailure {
+        domain: domain.map(|domain| domain.to_string()),
+        reason: certificate_failure_reason(challenge_type, message),
+        message: message.to_string(),
+    };
+    let mut last_error = condition(certificate, "Issuing")
+        .filter(|issuing| condition_field(issuing, "status") == "False")
+        .map(|issuing| failure(None, None, condition_field(issuing, "message")));
+
+    // previous requests are kept by cert-manager, only the last one is relevant
+    let certificate_request = owned_by(certificate_requests, certificate)
+        .max_by_key(|certificate_request| certificate_request.metadata.creation_timestamp.clone());
+    if let Some(certificate_request) = certificate_request {
+        if let Some(ready) = condition(certificate_request, "Ready")
+            .filter(|ready| matches!(condition_field(ready, "reason"), "Failed" | "Denied"))
+        {
+            return CertificateIssuanceStatus::Failed(failure(None, None, condition_field(ready, "message")));
+        }
+
+        for order in owned_by(orders, certificate_request) {
+            for challenge in owned_by(challenges, order) {
+                let reason = str_at(challenge, "/status/reason").unwrap_or_default();
+                if reason.is_empty() {
+                    continue;
+                }
+
+                let challenge_failure =
+                    failure(str_at(challenge, "/spec/dnsName"), str_at(challenge, "/spec/type"), reason);
+                if is_final_state(challenge) {
+                    return CertificateIssuanceStatus::Failed(challenge_failure);
+                }
+                last_error = Some(challenge_failure);
+            }
+
+            if is_final_state(order) {
+                return CertificateIssuanceStatus::Failed(failure(
+                    None,
+                    None,
+                    str_at(order, "/status/reason").unwrap_or("order failed"),
+                ));
+            }
+        }
+    }
+
+    CertificateIssuanceStatus::Pending(last_error)
+}
+
+fn fetch_certificate_issuance_status(
+    client: &kube::Client,
+    namespace: &str,
+    certificate_name: &str,
+) -> Result<CertificateIssuanceStatus, kube::Error> {
+    let api = |group: &str, kind: &str| -> Api<DynamicObject> {
+        let resource = ApiResource::from_gvk(&GroupVersionKind::gvk(group, "v1", kind));
+        Api::namespaced_with(client.clone(), namespace, &resource)
+    };
+
+    block_on(async {
+        let certificate = api(CERT_MANAGER_GROUP, "Certificate").get_opt(certificate_name).await?;
+        let certificate_requests = api(CERT_MANAGER_GROUP, "CertificateRequest")
+            .list(&ListParams::default())
+            .await?;
+        let orders = api(CERT_MANAGER_ACME_GROUP, "Order")
+            .list(&ListParams::default())
+            .await?;
+        let challenges = api(CERT_MANAGER_ACME_GROUP, "Challenge")
+            .list(&ListParams::default())
+            .await?;
+
+        Ok(certificate_issuance_status(
+            certificate.as_ref(),
+            &certificate_requests.items,
+            &orders.items,
+            &challenges.items,
+        ))
+    })
+}
+
+impl<'a> CheckCertificateForDomains<'a> {
+    fn certificate_issuance_status(&self, target: &DeploymentTarget) -> Result<CertificateIssuanceStatus, kube::Error> {
+        fetch_certificate_issuance_status(&target.kube, target.environment.namespace(), &self.certificate_name)
+    }
+
+    // Returns the last status when the timeout is reached, and the abort reason when the deployment is cancelled
+    fn await_certificate_issuance(
+        &self,
+        target: &DeploymentTarget,
+        should_abort: &CommandKiller,
+    ) -> Result<CertificateIssuanceStatus, AbortReason> {
+        let mut last_status = CertificateIssuanceStatus::Pending(None);
+        loop {
+            match self.certificate_issuance_status(target) {
+                Ok(status @ CertificateIssuanceStatus::Issued) | Ok(status @ CertificateIssuanceStatus::Failed(_)) => {
+                    return Ok(status)
+                }
+                Ok(status) => last_status = status,
+                Err(err) => (self.log)(format!("Cannot get certificate status, retrying: {err}")),
+            }
+
+            match should_abort.should_abort() {
+                Some(AbortReason::Timeout(_)) => return Ok(last_status),
+                Some(reason) => return Err(reason),
+                None => {}
+            }
+            (self.log)(format!(
+                "🔒 Waiting certificate to be issued for domains {}...",
+                self.domains.join(", ")
+            ));
+            thread::sleep(DEFAULT_CHECK_FREQUENCY);
+        }
+    }
+}
+
+impl<'a> DeploymentAction for CheckCertificateForDomains<'a> {
+    fn on_create(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        if self.domains.is_empty() {
+            return Ok(());
+        }
+
+        // Outside of strict mode, the deployment does not wait for the certificate, cert-manager keeps issuing it in background
+        let status = if self.strict {
+            (self.log)(format!(
+                "🔒 Checking certificate issuance for domains {}. Please wait, it can take some time...",
+                self.domains.join(", ")
+            ));
+            let should_abort = CommandKiller::from(DEFAULT_CHECK_TIMEOUT, target.abort);
+            self.await_certificate_issuance(target, &should_abort)
+                .map_err(|_| Box::new(EngineError::new_task_cancellation_requested(self.event_details.clone())))?
+        } else {
+            match self.certificate_issuance_status(target) {
+                Ok(status) => status,
+                Err(err) => {
+                    (self.log)(format!("Cannot get certificate status: {err}"));
+                    return Ok(());
+                }
+            }
+        };
+
+        let failure = match status {
+            CertificateIssuanceStatus::Issued => {
+                (self.log)(format!("✨ Certificate issued for domains {}", self.domains.join(", ")));
+                return Ok(());
+            }
+            CertificateIssuanceStatus::Pending(None) if !self.strict => {
+                (self.log)(format!(
+                    "🔒 Certificate for domains {} is being issued, it can take some time due to domain propagation",
+                    self.domains.join(", ")
+                ));
+                return Ok(());
+            }
+            CertificateIssuanceStatus::Failed(failure) | CertificateIssuanceStatus::Pending(Some(failure)) => failure,
+            CertificateIssuanceStatus::Pending(None) => CertificateIssuanceFailure {
+                domain: None,
+                reason: CertificateFailureReason::Other,
+                message: "certificate is still not issued, it can be due to a too long domain propagation".to_string(),
+            },
+        };
+
+        let domain = failure.domain.unwrap_or_else(|| self.domains.join(", "));
+        (self.warn)(format!(
+            "💥 Certificate for domain {} has not been issued. Reason: {}. Details: {}",
+            domain, failure.reason, failure.message
+        ));
+        if self.strict {
+            return Err(Box::new(EngineError::new_router_error(
+                self.event_details.clone(),
+                RouterError::CertificateNotIssued {
+                    domain,
+                    reason: format!("{}: {}", failure.reason, failure.message),
+                },
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn on_pause(&self, _target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        Ok(())
+    }
+
+    fn on_delete(&self, _target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        Ok(())
+    }
+
+    fn on_restart(&self, _target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(kind: &str, uid: &str, owner_uid: Option<&str>, creation: &str, data: Value) -> DynamicObject {
+        let mut object = json!({
+            "apiVersion": "v1",
+            "kind": kind,
+            "metadata": {
+                "name": uid,
+                "uid": uid,
+                "creationTimestamp": creation,
+            },
+        });
+        if let Some(owner_uid) = owner_uid {
+            object["metadata"]["ownerReferences"] =
+                json!([{"apiVersion": "v1", "kind": "Owner", "name": owner_uid, "uid": owner_uid}]);
+        }
+        object
+            .as_object_mut()
+            .unwrap()
+            .extend(data.as_object().unwrap().clone());
+
+        serde_json::from_value(object).unwrap()
+    }
+
+    #[test]
+    fn test_certificate_failure_reason() {
+        assert_eq!(
+            certificate_failure_reason(
+                Some("HTTP-01"),
+                "Error accepting authorization: acme: authorization error for app.example.com: 403 urn:ietf:params:acme:error:caa: CAA record for app.example.com prevents issuance"
+            ),
+            CertificateFailureReason::CaaRecord
+        );
+        assert_eq!(
+            certificate_failure_reason(
+                None,
+                "Failed to create Order: 429 urn:ietf:params:acme:error:rateLimited: Error creating new order :: too many certificates already issued"
+            ),
+            CertificateFailureReason::RateLimited
+        );
+        assert_eq!(
+            certificate_failure_reason(
+                Some("HTTP-01"),
+                "Waiting for HTTP-01 challenge propagation: failed to perform self check GET request 'http://app.example.com/.well-known/acme-challenge/token': connection refused"
+            ),
+            CertificateFailureReason::Http01Unreachable
+        );
+        assert_eq!(
+            certificate_failure_reason(Some("DNS-01"), "Error presenting challenge: connection refused"),
+            CertificateFailureReason::Other
+        );
+    }
+
+    #[test]
+    fn test_certificate_issuance_status() {
+        let certificate = |ready: &str| {
+            object(
+                "Certificate",
+                "cert",
+                None,
+                "2024-01-01T00:00:00Z",
+                json!({"status": {"conditions": [{"type": "Ready", "status": ready}]}}),
+            )
+        };
+        let old_request = object(
+            "CertificateRequest",
+            "old-request",
+            Some("cert"),
+            "2024-01-01T00:00:00Z",
+            json!({"status": {"conditions": [{"type": "Ready", "status": "False", "reason": "Failed", "message": "429 urn:ietf:params:acme:error:rateLimited"}]}}),
+        );
+        let request = object("CertificateRequest", "request", Some("cert"), "2024-01-02T00:00:00Z", json!({}));
+        let order = object(
+            "Order",
+            "order",
+            Some("request"),
+            "2024-01-02T00:00:00Z",
+            json!({"status": {"state": "pending"}}),
+        );
+        let challenge = |state: &str| {
+            object(
+                "Challenge",
+                "challenge",
+                Some("order"),
+                "2024-01-02T00:00:00Z",
+                json!({
+                    "spec": {"dnsName": "app.example.com", "type": "HTTP-01"},
+                    "status": {"state": state, "reason": "Waiting for HTTP-01 challenge propagation: failed to perform self check GET request"},
+                }),
+            )
+        };
+        let requests = vec![old_request, request];
+        let orders = vec![order];
+        let http01_failure = CertificateIssuanceFailure {
+            domain: Some("app.example.com".to_string()),
+            reason: CertificateFailureReason::Http01Unreachable,
+            message: "Waiting for HTTP-01 challenge propagation: failed to perform self check GET request".to_string(),
+        };
+
+        assert_eq!(
+            certificate_issuance_status(None, &[], &[], &[]),
+            CertificateIssuanceStatus::Pending(None)
+        );
+        assert_eq!(
+            certificate_issuance_status(Some(&certificate("True")), &requests, &orders, &[challenge("invalid")]),
+            CertificateIssuanceStatus::Issued
+        );
+        // the old failed request is ignored
+        assert_eq!(
+            certificate_issuance_status(Some(&certificate("False")), &requests, &orders, &[challenge("pending")]),
+            CertificateIssuanceStatus::Pending(Some(http01_failure.clone()))
+        );
+        assert_eq!(
+            certificate_issuance_status(Some(&certificate("False")), &requests, &orders, &[challenge("invalid")]),
+            CertificateIssuanceStatus::Failed(http01_failure)
+        );
+    }
+}
@@ -1,16 +1,20 @@
+use crate::cloud_provider::io::ClusterAdvancedSettings;
 use crate::cloud_provider::models::CustomDomain;
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::command::CommandKiller;
 use crate::deployment_action::DeploymentAction;
 use crate::errors::EngineError;
 use crate::models::abort::Abort;
-use std::net::IpAddr;
+use serde_derive::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
 use std::thread;
 use std::time::Duration;
-use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_client::client::{Client, SyncClient};
+use trust_dns_client::rr::DNSClass;
+use trust_dns_client::udp::UdpClientConnection;
+use trust_dns_resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
 use trust_dns_resolver::error::ResolveError;
-use trust_dns_resolver::lookup_ip::LookupIp;
-use trust_dns_resolver::proto::rr::{RData, RecordType};
+use trust_dns_resolver::proto::rr::{RData, Record, RecordType};
 use trust_dns_resolver::{Name, Resolver};
 
 pub struct CheckDnsForDomains<'a> {
@@ -19,9 +23,111 @@ pub struct CheckDnsForDomains<'a> {
     pub log: Box<dyn Fn(String) + 'a + Send + Sync>,
 }
 
-const DEFAULT_CHECK_FREQUENCY: Duration = Duration::from_secs(30);
+const DNS_PORT: u16 = 53;
+const AUTHORITATIVE_QUERY_TIMEOUT: Duration = Duration::from_secs(10);
 
-fn dns_resolvers() -> Vec<Resolver> {
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsCheckMode {
+    /// The domain is resolved as soon as one of the resolvers answers
+    #[default]
+    AnyResolver,
+    /// The domain is resolved when all nameservers of its zone answer the same records, no cache involved
+    Authoritative,
+    /// The domain is resolved when at least `dns.check.consistency_min_resolvers` resolvers agree
+    Consistency,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DnsCheckConfig {
+    // resolvers used to look up domains, public and system resolvers when empty
+    pub resolvers: Vec<IpAddr>,
+    pub mode: DnsCheckMode,
+    pub consistency_min_resolvers: usize,
+    pub timeout: Duration,
+    pub frequency: Duration,
+}
+
+impl From<&ClusterAdvancedSettings> for DnsCheckConfig {
+    fn from(advanced_settings: &ClusterAdvancedSettings) -> Self {
+        DnsCheckConfig {
+            resolvers: advanced_settings.dns_check_resolvers.clone(),
+            mode: advanced_settings.dns_check_mode,
+            consistency_min_resolvers: advanced_settings.dns_check_consistency_min_resolvers.max(1) as usize,
+            timeout: Duration::from_secs(advanced_settings.dns_check_timeout_sec as u64),
+            frequency: Duration::from_secs(advanced_settings.dns_check_frequency_sec.max(1) as u64),
+        }
+    }
+}
+
+impl Default for DnsCheckConfig {
+    fn default() -> Self {
+        DnsCheckConfig::from(&ClusterAdvancedSettings::default())
+    }
+}
+
+/// Nameserver used to check a domain propagation
+enum Nameserver {
+    Recursive(Resolver),
+    // queried directly, so answers are not altered by any cache or CNAME chasing
+    Authoritative(SocketAddr),
+}
+
+impl Nameserver {
+    fn query(&self, domain: &str, record_type: RecordType) -> Result<Vec<RData>, ResolveError> {
+        match self {
+            Nameserver::Recursive(resolver) => Ok(resolver
+                .lookup(domain, record_type)?
+                .into_iter()
+                .collect::<Vec<RData>>()),
+            Nameserver::Authoritative(nameserver) => {
+                let connection = UdpClientConnection::with_timeout(*nameserver, AUTHORITATIVE_QUERY_TIMEOUT)
+                    .map_err(|e| ResolveError::from(e.to_string()))?;
+                let name = Name::from_ascii(domain).map_err(|e| ResolveError::from(e.to_string()))?;
+                let response = SyncClient::new(connection)
+                    .query(&name, DNSClass::IN, record_type)
+                    .map_err(|e| ResolveError::from(e.to_string()))?;
+
+                Ok(response.answers().iter().filter_map(Record::data).cloned().collect())
+            }
+        }
+    }
+
+    fn lookup_ip(&self, domain: &str) -> Result<Vec<String>, ResolveError> {
+        let mut answers: Vec<String> = match self {
+            Nameserver::Recursive(resolver) => resolver.lookup_ip(domain)?.iter().map(|ip| ip.to_string()).collect(),
+            // an authoritative nameserver doesn't follow CNAME pointing outside of its zone, the CNAME is enough
+            Nameserver::Authoritative(_) => self
+                .query(domain, RecordType::A)?
+                .into_iter()
+                .filter_map(|rdata| match rdata {
+                    RData::A(ip) => Some(ip.to_string()),
+                    RData::CNAME(cname) => Some(cname.0.to_utf8()),
+                    _ => None,
+                })
+                .collect(),
+        };
+        if answers.is_empty() {
+            return Err(ResolveError::from("no Ip address available for this domain"));
+        }
+        answers.sort();
+
+        Ok(answers)
+    }
+
+    fn lookup_cname(&self, domain: &str) -> Result<Name, ResolveError> {
+        self.query(domain, RecordType::CNAME)?
+            .into_iter()
+            .filter_map(|rdata| match rdata {
+                RData::CNAME(cname) => Some(cname.0),
+                _ => None,
+            })
+            .next()
+            .ok_or_else(|| ResolveError::from("no CNAME record available for this domain"))
+    }
+}
+
+fn dns_resolvers(resolvers: &[IpAddr]) -> Vec<Resolver> {
     let mut resolver_options = ResolverOpts::default();
 
     //  We want to avoid cache and using host file of the host, as some provider force caching
@@ -30,6 +136,17 @@ fn dns_resolvers() -> Vec<Resolver> {
     resolver_options.use_hosts_file = true;
     //resolver_options.ip_strategy = LookupIpStrategy::Ipv4Only;
 
+    if !resolvers.is_empty() {
+        return resolvers
+            .iter()
+            .map(|ip| {
+                let nameservers = NameServerConfigGroup::from_ips_clear(&[*ip], DNS_PORT, true);
+                Resolver::new(ResolverConfig::from_parts(None, vec![], nameservers), resolver_options)
+                    .expect("Invalid custom DNS resolver configuration")
+            })
+            .collect();
+    }
+
     vec![
         Resolver::new(ResolverConfig::google(), resolver_options).expect("Invalid google DNS resolver configuration"),
         Resolver::new(ResolverConfig::cloudflare(), resolver_options)
@@ -39,29 +156,108 @@ fn dns_resolvers() -> Vec<Resolver> {
     ]
 }
 
-fn await_resolve<R>(
-    with_resolver: &impl Fn(&Resolver) -> Result<R, ResolveError>,
-    check_frequency: Duration,
+/// Returns the addresses of the nameservers of the closest zone owning the domain
+fn authoritative_nameservers(domain: &str, resolvers: &[Resolver]) -> Result<Vec<SocketAddr>, ResolveError> {
+    let labels: Vec<&str> = domain.trim_end_matches('.').split('.').collect();
+    for resolver in resolvers {
+        for ix in 0..labels.len().saturating_sub(1) {
+            let zone = labels[ix..].join(".");
+            let nameservers: Vec<Name> = match resolver.lookup(zone.as_str(), RecordType::NS) {
+                Ok(lookup) => lookup
+                    .into_iter()
+                    .filter_map(|rdata| match rdata {
+                        RData::NS(ns) => Some(ns.0),
+                        _ => None,
+                    })
+                    .collect(),
+                Err(_) => continue,
+            };
+            if nameservers.is_empty() {
+                continue;
+            }
+
+            let addresses: Vec<SocketAddr> = nameservers
+                .iter()
+                .filter_map(|nameserver| resolver.lookup_ip(nameserver.clone()).ok())
+                .flat_map(|ips| ips.iter().next())
+                .map(|ip| SocketAddr::new(ip, DNS_PORT))
+                .collect();
+            if !addresses.is_empty() {
+                return Ok(addresses);
+            }
+        }
+    }
+
+    Err(ResolveError::from("cannot find authoritative nameservers for this domain"))
+}
+
+fn nameservers(domain: &str, config: &DnsCheckConfig, log: &impl Fn(String)) -> Vec<Nameserver> {
+    let resolvers = dns_resolvers(&config.resolvers);
+    if config.mode != DnsCheckMode::Authoritative {
+        return resolvers.into_iter().map(Nameserver::Recursive).collect();
+    }
+
+    match authoritative_nameservers(domain, &resolvers) {
+        Ok(nameservers) => nameservers.into_iter().map(Nameserver::Authoritative).collect(),
+        Err(err) => {
+            (log)(format!(
+                "Cannot find authoritative nameservers of domain {domain}, falling back to resolvers: {err}"
+            ));
+            resolvers.into_iter().map(Nameserver::Recursive).collect()
+        }
+    }
+}
+
+/// Returns the answer given by at least `min_agreeing` nameservers, if any
+fn agreed_answer<R: PartialEq + Clone>(answers: &[Result<R, ResolveError>], min_agreeing: usize) -> Option<R> {
+    answers
+        .iter()
+        .flatten()
+        .find(|answer| answers.iter().flatten().filter(|other| other == answer).count() >= min_agreeing)
+        .cloned()
+}
+
+fn await_resolve<R: PartialEq + Clone>(
+    nameservers: &[Nameserver],
+    with_nameserver: &impl Fn(&Nameserver) -> Result<R, ResolveError>,
+    config: &DnsCheckConfig,
     should_abort: &CommandKiller,
 ) -> Result<R, ResolveError> {
-    let resolvers = dns_resolvers();
+    if nameservers.is_empty() {
+        return Err(ResolveError::from("no nameserver available to check the domain"));
+    }
 
     let mut ix: usize = 0;
-    let mut next_resolver = || {
-        let resolver = &resolvers[ix % resolvers.len()];
+    let mut next_nameserver = || {
+        let nameserver = &nameservers[ix % nameservers.len()];
         ix += 1;
-        resolver
+        nameserver
     };
 
     loop {
-        match with_resolver(next_resolver()) {
-            Ok(ip) => break Ok(ip),
+        let resolved = match config.mode {
+            DnsCheckMode::AnyResolver => with_nameserver(next_nameserver()),
+            DnsCheckMode::Authoritative | DnsCheckMode::Consistency => {
+                // every authoritative nameserver must be up to date, for consistency N of M resolvers must agree
+                let min_agreeing = match config.mode {
+                    DnsCheckMode::Consistency => config.consistency_min_resolvers.min(nameservers.len()),
+                    _ => nameservers.len(),
+                };
+                let answers: Vec<Result<R, ResolveError>> = nameservers.iter().map(with_nameserver).collect();
+                agreed_answer(&answers, min_agreeing).ok_or_else(|| {
+                    ResolveError::from(format!("less than {min_agreeing} nameservers agree on the domain records"))
+                })
+            }
+        };
+
+        match resolved {
+            Ok(answer) => break Ok(answer),
             Err(err) => {
                 if should_abort.should_abort().is_some() {
                     break Err(err);
                 }
 
-                thread::sleep(check_frequency)
+                thread::sleep(config.frequency)
             }
         }
     }
@@ -69,43 +265,33 @@ fn await_resolve<R>(
 
 fn await_domain_resolve_cname<'a>(
     domain_to_check: impl Fn() -> &'a str,
-    check_frequency: Duration,
+    nameservers: &[Nameserver],
+    config: &DnsCheckConfig,
     should_abort: CommandKiller,
 ) -> Result<Name, ResolveError> {
     await_resolve(
-        &|resolver| {
-            resolver
-                .lookup(domain_to_check(), RecordType::CNAME)
-                .into_iter()
-                .flat_map(|lookup| lookup.into_iter())
-                .filter_map(|rdata| {
-                    if let RData::CNAME(cname) = rdata {
-                        Some(cname.0)
-                    } else {
-                        None
-                    }
-                })
-                .next()
-                .ok_or_else(|| ResolveError::from("no CNAME record available for this domain"))
-        },
-        check_frequency,
+        nameservers,
+        &|nameserver| nameserver.lookup_cname(domain_to_check()),
+        config,
         &should_abort,
     )
 }
 
 fn await_domain_resolve_ip<'a>(
     domain_to_check: impl Fn() -> &'a str,
-    check_frequency: Duration,
+    nameservers: &[Nameserver],
+    config: &DnsCheckConfig,
     should_abort: CommandKiller,
-) -> Result<LookupIp, ResolveError> {
+) -> Result<Vec<String>, ResolveError> {
     await_resolve(
-        &|resolver| resolver.lookup_ip(domain_to_check()),
-        check_frequency,
+        nameservers,
+        &|nameserver| nameserver.lookup_ip(domain_to_check()),
+        config,
         &should_abort,
     )
 }
 
-fn check_domain_resolve_ip(domain: &str, log: &impl Fn(String), abort: &dyn Abort) {
+fn check_domain_resolve_ip(domain: &str, config: &DnsCheckConfig, log: &impl Fn(String), abort: &dyn Abort) {
     // We use send_success because if on_check is called it means the DB is already correctly deployed
     (log)(format!(
         "🌍 Checking DNS Ip resolution for domain {domain}. Please wait, it can take some time..."
@@ -116,15 +302,16 @@ fn check_domain_resolve_ip(domain: &str, log: &impl Fn(String), abort: &dyn Abor
         domain
     };
 
-    let should_abort = CommandKiller::from(Duration::from_secs(60 * 5), abort);
-    let does_resolve = await_domain_resolve_ip(get_domain, DEFAULT_CHECK_FREQUENCY, should_abort);
+    let nameservers = nameservers(domain, config, log);
+    let should_abort = CommandKiller::from(config.timeout, abort);
+    let does_resolve = await_domain_resolve_ip(get_domain, &nameservers, config, should_abort);
 
     match does_resolve {
         Ok(ip) => {
             (log)(format!(
                 "✨ Domain {} resolved to ip {}",
                 domain,
-                ip.iter().next().unwrap_or_else(|| IpAddr::from([0_u8, 0, 0, 0]))
+                ip.first().map(|ip| ip.as_str()).unwrap_or("0.0.0.0")
             ));
         }
         Err(_) => {
@@ -138,7 +325,12 @@ fn check_domain_resolve_ip(domain: &str, log: &impl Fn(String), abort: &dyn Abor
     }
 }
 
-fn check_domain_resolve_cname(custom_domain: &CustomDomain, log: &impl Fn(String), abort_status: &dyn Abort) {
+fn check_domain_resolve_cname(
+    custom_domain: &CustomDomain,
+    config: &DnsCheckConfig,
+    log: &impl Fn(String),
+    abort_status: &dyn Abort,
+) {
     // We use send_success because if on_check is called it means the DB is already correctly deployed
     (log)(format!(
         "🌍 Checking DNS CNAME resolution for domain {}. Please wait, it can take some time...",
@@ -153,8 +345,9 @@ fn check_domain_resolve_cname(custom_domain: &CustomDomain, log: &impl Fn(String
         custom_domain.domain.as_str()
     };
 
-    let should_abort = CommandKiller::from(Duration::from_secs(60 * 5), abort_status);
-    let does_resolve = await_domain_resolve_cname(get_domain, DEFAULT_CHECK_FREQUENCY, should_abort);
+    let nameservers = nameservers(&custom_domain.domain, config, log);
+    let should_abort = CommandKiller::from(config.timeout, abort_status);
+    let does_resolve = await_domain_resolve_cname(get_domain, &nameservers, config, should_abort);
 
     match does_resolve {
         Ok(cname) => {
@@ -176,12 +369,14 @@ fn check_domain_resolve_cname(custom_domain: &CustomDomain, log: &impl Fn(String
 
 impl<'a> DeploymentAction for CheckDnsForDomains<'a> {
     fn on_create(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
+        let config = DnsCheckConfig::from(target.kubernetes.advanced_settings());
+
         for domain in &self.resolve_to_ip {
-            check_domain_resolve_ip(domain, &self.log, target.abort);
+            check_domain_resolve_ip(domain, &config, &self.log, target.abort);
         }
 
         for domain in &self.resolve_to_cname {
-            check_domain_resolve_cname(domain, &self.log, target.abort);
+            check_domain_resolve_cname(domain, &config, &self.log, target.abort);
         }
 
         Ok(())
@@ -206,12 +401,89 @@ mod tests {
 
     #[test]
     pub fn test_cname_resolution() {
+        let config = DnsCheckConfig {
+            frequency: Duration::from_secs(10),
+            ..Default::default()
+        };
+        let cname = await_domain_resolve_cname(
+            || "ci-test-no-delete.qovery.io",
+            &nameservers("ci-test-no-delete.qovery.io", &config, &|_| {}),
+            &config,
+            CommandKiller::from_timeout(Duration::from_secs(30)),
+        );
+
+        assert_eq!(cname.unwrap().to_utf8(), String::from("qovery.io."));
+    }
+
+    #[test]
+    pub fn test_authoritative_cname_resolution() {
+        let config = DnsCheckConfig {
+            mode: DnsCheckMode::Authoritative,
+            frequency: Duration::from_secs(10),
+            ..Default::default()
+        };
+        let nameservers = nameservers("ci-test-no-delete.qovery.io", &config, &|_| {});
+        assert!(nameservers
+            .iter()
+            .all(|nameserver| matches!(nameserver, Nameserver::Authoritative(_))));
+
         let cname = await_domain_resolve_cname(
             || "ci-test-no-delete.qovery.io",
-            Duration::from_secs(10),
+            &nameservers,
+            &config,
             CommandKiller::from_timeout(Duration::from_secs(30)),
         );
 
         assert_eq!(cname.unwrap().to_utf8(), String::from("qovery.io."));
     }
+
+    #[test]
+    pub fn test_agreed_answer() {
+        let answers: Vec<Result<&str, ResolveError>> = vec![
+            Ok("1.1.1.1"),
+            Err(ResolveError::from("timeout")),
+            Ok("2.2.2.2"),
+            Ok("1.1.1.1"),
+        ];
+
+        assert_eq!(agreed_answer(&answers, 1), Some("1.1.1.1"));
+        assert_eq!(agreed_answer(&answers, 2), Some("1.1.1.1"));
+        assert_eq!(agreed_answer(&answers, 3), None);
+        assert_eq!(agreed_answer::<&str>(&[], 1), None);
+    }
+
+    #[test]
+    pub fn test_dns_check_config_from_advanced_settings() {
+        let advanced_settings: ClusterAdvancedSettings = serde_json::from_str(
+            r#"{
+                "dns.check.resolvers": ["10.0.0.2", "10.0.0.3"],
+                "dns.check.mode": "consistency",
+                "dns.check.consistency_min_resolvers": 2,
+                "dns.check.timeout_sec": 60,
+                "dns.check.frequency_sec": 5
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            DnsCheckConfig::from(&advanced_settings),
+            DnsCheckConfig {
+                resolvers: vec!["10.0.0.2".parse().unwrap(), "10.0.0.3".parse().unwrap()],
+                mode: DnsCheckMode::Consistency,
+                consistency_min_resolvers: 2,
+                timeout: Duration::from_secs(60),
+                frequency: Duration::from_secs(5),
+            }
+        );
+        assert_eq!(
+            DnsCheckConfig::default(),
+            DnsCheckConfig {
+                resolvers: vec![],
+                mode: DnsCheckMode::AnyResolver,
+                consistency_min_resolvers: 2,
+                timeout: Duration::from_secs(60 * 5),
+                frequency: Duration::from_secs(30),
+            }
+        );
+    }
 }
@@ -17,7 +17,8 @@ use k8s_openapi::api::core::v1::PersistentVolumeClaim;
 use crate::cloud_provider::utilities::update_pvcs;
 use crate::deployment_action::restart_service::RestartServiceAction;
 use crate::deployment_action::utils::{
-    delete_cached_image, delete_nlb_or_alb_service, get_last_deployed_image, mirror_image_if_necessary, KubeObjectKind,
+    delete_cached_image, delete_nlb_or_alb_service, get_last_deployed_image, mirror_image_if_necessary,
+    pin_image_digest, scan_image_vulnerabilities_if_necessary, verify_image_signature_if_necessary, KubeObjectKind,
 };
 use crate::deployment_report::logger::{EnvProgressLogger, EnvSuccessLogger};
 use std::path::PathBuf;
@@ -32,13 +33,26 @@ where
         let metrics_registry = target.metrics_registry.clone();
         struct TaskContext {
             last_deployed_image: Option<String>,
+            image_digest: Option<String>,
         }
 
-        // We first mirror the image if needed
+        // We first verify the image signature and mirror the image if needed
         let pre_task = |logger: &EnvProgressLogger| -> Result<TaskContext, Box<EngineError>> {
+            let image_digest =
+                verify_image_signature_if_necessary(&self.source, target, logger, event_details.clone())?;
+            scan_image_vulnerabilities_if_necessary(
+                self.long_id(),
+                &self.source,
+                image_digest.as_deref(),
+                self.advanced_settings().security_vulnerability_scan_fail_on_severity,
+                target,
+                logger,
+                event_details.clone(),
+            )?;
             mirror_image_if_necessary(
                 self.long_id(),
                 &self.source,
+                image_digest.as_deref(),
                 target,
                 logger,
                 event_details.clone(),
@@ -58,6 +72,7 @@ where
 
             Ok(TaskContext {
                 last_deployed_image: last_image,
+                image_digest,
             })
         };
 
@@ -102,9 +117,11 @@ where
                 ..Default::default()
             };
 
+            let mut tera_context = self.to_tera_context(target)?;
+            pin_image_digest(&mut tera_context, state.image_digest.as_deref());
             let helm = HelmDeployment::new(
                 event_details.clone(),
-                self.to_tera_context(target)?,
+                tera_context,
                 PathBuf::from(self.helm_chart_dir()),
                 None,
                 chart,
@@ -201,9 +218,11 @@ where
                 action: HelmAction::Destroy,
                 ..Default::default()
             };
+            let mut tera_context = self.to_tera_context(target)?;
+            pin_image_digest(&mut tera_context, state.image_digest.as_deref());
             let helm = HelmDeployment::new(
                 event_details.clone(),
-                self.to_tera_context(target)?,
+                tera_context,
                 PathBuf::from(self.helm_chart_dir().as_str()),
                 None,
                 chart,
@@ -9,12 +9,16 @@ use crate::deployment_action::check_dns::CheckDnsForDomains;
 use crate::deployment_action::deploy_helm::HelmDeployment;
 use crate::deployment_action::deploy_terraform::TerraformDeployment;
 use crate::deployment_action::pause_service::PauseServiceAction;
+use crate::deployment_action::rotate_database_credentials::{
+    is_dual_password_supported, update_dependent_services_secrets, DatabaseCredentialsRotation, RotationPhase,
+};
 use crate::deployment_action::DeploymentAction;
 use crate::deployment_report::database::reporter::DatabaseDeploymentReporter;
 use crate::deployment_report::{execute_long_deployment, DeploymentTaskImpl};
 use crate::errors::{CommandError, EngineError, Tag};
 use crate::events::{EnvironmentStep, EventDetails, Stage};
-use crate::kubers_utils::{kube_delete_all_from_selector, KubeDeleteMode};
+use crate::io_models::database::DatabaseOptions;
+use crate::kubers_utils::{kube_delete_all_from_selector, kube_get_resources_by_selector, KubeDeleteMode};
 use crate::models::database::{
     get_database_with_invalid_storage_size, Container, Database, DatabaseError, DatabaseService, DatabaseType, Managed,
 };
@@ -22,10 +26,12 @@ use crate::models::types::{CloudProvider, ToTeraContext, VersionsNumber};
 use crate::runtime::block_on;
 use crate::services::aws::models::QoveryAwsSdkConfigManagedDatabase;
 use aws_types::SdkConfig;
+use k8s_openapi::api::apps::v1::StatefulSet;
 use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use retry::delay::Fixed;
 use semver::Version;
 use serde::Deserialize;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 
 use crate::cloud_provider::utilities::{are_pvcs_bound, update_pvcs};
 use crate::deployment_action::restart_service::RestartServiceAction;
@@ -40,6 +46,7 @@ use aws_sdk_rds::operation::describe_db_instances::{DescribeDBInstancesError, De
 use std::path::PathBuf;
 use std::thread;
 use std::time::{Duration, Instant};
+use uuid::Uuid;
 
 use super::utils::delete_nlb_or_alb_service;
 
@@ -702,8 +709,173 @@ where
     }
 }
 
+/// Rotates the credentials inside the running database, before the chart is upgraded with the new ones.
+/// Returns `None` when the database is not running yet, as it is then created with the new credentials.
+fn start_container_database_credentials_rotation<
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    previous_password: &str,
+) -> Result<Option<DatabaseCredentialsRotation>, Box<EngineError>> {
+    let rotation_failed = |err: CommandError| {
+        Box::new(EngineError::new_database_credentials_rotation_failed(
+            event_details.clone(),
+            db.name(),
+            err,
+        ))
+    };
+    let namespace = target.environment.namespace();
+    let statefulsets = block_on(kube_get_resources_by_selector::<StatefulSet>(
+        &target.kube,
+        namespace,
+        &db.kube_label_selector(),
+    ))
+    .map_err(rotation_failed)?;
+    if statefulsets.items.is_empty() {
+        logger.info("🔑 Database is not running yet, it is going to be created with the new credentials".to_string());
+        return Ok(None);
+    }
+
+    let version = db.get_version(event_details.clone())?.matched_version();
+    let rotation = DatabaseCredentialsRotation {
+        database_id: *db.long_id(),
+        db_type: T::db_type(),
+        namespace: namespace.to_string(),
+        host: format!("{}.{}.svc.cluster.local", db.fqdn_id, namespace),
+        port: db.private_port,
+        login: db.options.login.to_string(),
+        database_name: db.name.to_string(),
+        image: db.container_image(&version),
+        is_dual_password_supported: is_dual_password_supported(T::db_type(), &version),
+    };
+    let new_password = db.options.password.as_str();
+
+    logger.info("🔑 Rotating credentials inside the database".to_string());
+    rotation
+        .run(&target.kube, RotationPhase::Rotate, previous_password, new_password)
+        .map_err(rotation_failed)?;
+
+    // new credentials not saved by Qovery would be lost at the next deployment, so the database is rolled back
+    if let Err(retry::Error { error, .. }) = retry::retry(Fixed::from_millis(5 * 1000).take(3), || {
+        target.kubernetes.context().qovery_api.update_database_credentials(
+            db.long_id(),
+            &db.options.login,
+            new_password,
+        )
+    }) {
+        logger.warning("Cannot save the new credentials, rolling back to the previous ones".to_string());
+        rotation
+            .run(&target.kube, RotationPhase::Rotate, new_password, previous_password)
+            .map_err(rotation_failed)?;
+        return Err(rotation_failed(CommandError::new(
+            "Cannot save the new credentials of the database".to_string(),
+            Some(error.to_string()),
+            None,
+        )));
+    }
+
+    Ok(Some(rotation))
+}
+
+/// Once the chart uses the new credentials, updates and restarts the services of the namespace still using the
+/// previous ones, then schedules the removal of the previous password when the database supports dual passwords,
+/// leaving clients outside of the namespace some time to switch.
+/// The database is already usable with the new credentials, so failures are only reported as warnings.
+fn finish_container_database_credentials_rotation<
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    logger: &EnvProgressLogger,
+    event_details: &EventDetails,
+    rotation: &DatabaseCredentialsRotation,
+    previous_password: &str,
+) {
+    let new_password = db.options.password.as_str();
+    // services deployed along the database already got the new credentials
+    let environment = target.environment;
+    let deployed_service_ids: HashSet<Uuid> = std::iter::empty()
+        .chain(environment.applications.iter().map(|x| *x.as_service().long_id()))
+        .chain(environment.containers.iter().map(|x| *x.as_service().long_id()))
+        .chain(environment.databases.iter().map(|x| *x.as_service().long_id()))
+        .chain(environment.jobs.iter().map(|x| *x.as_service().long_id()))
+        .chain(environment.helm_charts.iter().map(|x| *x.as_service().long_id()))
+        .collect();
+
+    let dependent_service_ids = match block_on(update_dependent_services_secrets(
+        &target.kube,
+        environment.namespace(),
+        &rotation.password_variables(),
+        previous_password,
+        new_password,
+        &deployed_service_ids,
+    )) {
+        Ok(service_ids) => service_ids,
+        Err(err) => {
+            logger.warning(format!(
+                "Cannot update the services using the database credentials, they must be redeployed: {err}"
+            ));
+            return;
+        }
+    };
+
+    let mut are_dependent_services_restarted = true;
+    for service_id in &dependent_service_ids {
+        logger.info(format!(
+            "🔄 Restarting service {service_id} to use the new database credentials"
+        ));
+        for is_statefulset in [false, true] {
+            let restart_service = RestartServiceAction::new(
+                format!("qovery.com/service-id={service_id}"),
+                is_statefulset,
+                event_details.clone(),
+            );
+            if let Err(err) = restart_service.on_restart(target) {
+                logger.warning(format!("Cannot restart service {service_id}: {}", err.user_log_message()));
+                are_dependent_services_restarted = false;
+            }
+        }
+    }
+
+    if !rotation.is_dual_password_supported {
+        return;
+    }
+    if !are_dependent_services_restarted {
+        logger.warning("Previous database password is kept, as some services may still use it".to_string());
+        return;
+    }
+    let delay = Duration::from_secs(
+        target
+            .kubernetes
+            .advanced_settings()
+            .database_credentials_rotation_discard_previous_password_delay_sec as u64,
+    );
+    match rotation.schedule(
+        &target.kube,
+        RotationPhase::DiscardPrevious,
+        previous_password,
+        new_password,
+        delay,
+    ) {
+        Ok(_) => logger.info(format!(
+            "🔑 Previous database password is going to be removed in {}s",
+            delay.as_secs()
+        )),
+        Err(err) => logger.warning(format!(
+            "Cannot schedule the removal of the previous password from the database: {}",
+            err.message_safe()
+        )),
+    }
+}
+
 // For Container database
-impl<C: CloudProvider, T: DatabaseType<C, Container>> DeploymentAction for Database<C, Container, T>
+impl<C: CloudProvider, T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>> DeploymentAction
+    for Database<C, Container, T>
 where
     Database<C, Container, T>: ToTeraContext,
 {
@@ -733,6 +905,17 @@ where
                     .send_warning(e.to_string()),
             }
 
+            let credentials_rotation = match &self.options.previous_password {
+                Some(previous_password) => start_container_database_credentials_rotation(
+                    self,
+                    target,
+                    logger,
+                    &event_details,
+                    previous_password,
+                )?,
+                None => None,
+            };
+
             let chart = ChartInfo {
                 name: self.helm_release_name(),
                 path: self.workspace_directory().to_string(),
@@ -785,6 +968,18 @@ where
                 };
             };
 
+            if let (Some(rotation), Some(previous_password)) = (&credentials_rotation, &self.options.previous_password)
+            {
+                finish_container_database_credentials_rotation(
+                    self,
+                    target,
+                    logger,
+                    &event_details,
+                    rotation,
+                    previous_password,
+                );
+            }
+
             Ok(())
         };
 
@@ -801,9 +801,9 @@ mod tests {
     #[test]
     fn test_replace_qovery_env_variables() {
         let envs = hashmap! {
-            "TOTO".to_string() => VariableInfo { value: "toto_var".to_string(), is_secret: false},
-            "LABEL_NAME".to_string() => VariableInfo {value: "toto_label".to_string(), is_secret: false},
-            "NGNIX_TAG".to_string() => VariableInfo {value: "42".to_string(), is_secret: false}
+            "TOTO".to_string() => VariableInfo { value: "toto_var".to_string(), is_secret: false, external_secret: None},
+            "LABEL_NAME".to_string() => VariableInfo {value: "toto_label".to_string(), is_secret: false, external_secret: None},
+            "NGNIX_TAG".to_string() => VariableInfo {value: "42".to_string(), is_secret: false, external_secret: None}
         };
 
         let ret = replace_qovery_env_variable(Cow::Borrowed("    tag: \"qovery.env.NGNIX_TAG\""), &envs);
@@ -912,8 +912,8 @@ controller:
         let env_id = Uuid::parse_str("11111111-1111-1111-1111-111111111111").unwrap();
         let project_id = Uuid::parse_str("22222222-2222-2222-2222-222222222222").unwrap();
         let envs = hashmap! {
-            "NGINX_TAG".to_string() => VariableInfo { value: "42".to_string(), is_secret: false},
-            "LABEL_NAME".to_string() => VariableInfo {value: "toto_label".to_string(), is_secret: false}
+            "NGINX_TAG".to_string() => VariableInfo { value: "42".to_string(), is_secret: false, external_secret: None},
+            "LABEL_NAME".to_string() => VariableInfo {value: "toto_label".to_string(), is_secret: false, external_secret: None}
         };
         let mut output: Vec<u8> = vec![];
 
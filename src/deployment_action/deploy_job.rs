@@ -5,7 +5,10 @@ use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::kubectl::{kubectl_exec_delete_job, kubectl_get_job_pod_output};
 use crate::cmd::structs::KubernetesPodStatusPhase;
 use crate::deployment_action::deploy_helm::HelmDeployment;
-use crate::deployment_action::utils::{get_last_deployed_image, mirror_image_if_necessary, KubeObjectKind};
+use crate::deployment_action::utils::{
+    get_last_deployed_image, mirror_image_if_necessary, pin_image_digest, scan_image_vulnerabilities_if_necessary,
+    verify_image_signature_if_necessary, KubeObjectKind,
+};
 use crate::deployment_action::DeploymentAction;
 use crate::deployment_report::job::reporter::JobDeploymentReporter;
 use crate::deployment_report::logger::{EnvProgressLogger, EnvSuccessLogger};
@@ -141,6 +144,7 @@ where
 
 struct TaskContext {
     last_deployed_image: Option<String>,
+    image_digest: Option<String>,
 }
 
 fn run_job<'a, T: CloudProvider>(
@@ -157,20 +161,33 @@ where
 {
     let metrics_registry = target.metrics_registry.clone();
     let pre_run = move |logger: &EnvProgressLogger| -> Result<TaskContext, Box<EngineError>> {
-        match &job.image_source {
+        let image_digest = match &job.image_source {
             // If image come from a registry, we mirror it to the cluster registry in order to avoid losing access to it due to creds expiration
             ImageSource::Registry { source } => {
+                let image_digest = verify_image_signature_if_necessary(source, target, logger, event_details.clone())?;
+                scan_image_vulnerabilities_if_necessary(
+                    job.long_id(),
+                    source,
+                    image_digest.as_deref(),
+                    job.advanced_settings().security_vulnerability_scan_fail_on_severity,
+                    target,
+                    logger,
+                    event_details.clone(),
+                )?;
                 mirror_image_if_necessary(
                     job.long_id(),
                     source,
+                    image_digest.as_deref(),
                     target,
                     logger,
                     event_details.clone(),
                     metrics_registry.clone(),
                 )?;
+                image_digest
             }
-            ImageSource::Build { .. } => {}
-        }
+            // Built images are already pinned to the digest built or verified by the environment task
+            ImageSource::Build { .. } => None,
+        };
 
         let last_image = block_on(get_last_deployed_image(
             target.kube.clone(),
@@ -185,6 +202,7 @@ where
 
         Ok(TaskContext {
             last_deployed_image: last_image,
+            image_digest,
         })
     };
 
@@ -199,9 +217,11 @@ where
             ..Default::default()
         };
 
+        let mut tera_context = job.to_tera_context(target)?;
+        pin_image_digest(&mut tera_context, state.image_digest.as_deref());
         let helm = HelmDeployment::new(
             event_details.clone(),
-            job.to_tera_context(target)?,
+            tera_context,
             PathBuf::from(job.helm_chart_dir()),
             None,
             chart,
@@ -503,6 +523,7 @@ where
 
         Ok(TaskContext {
             last_deployed_image: last_image,
+            image_digest: None,
         })
     };
 
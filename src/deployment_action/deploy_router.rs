@@ -2,6 +2,7 @@ use crate::cloud_provider::helm::{ChartInfo, HelmAction, HelmChartNamespaces};
 use crate::cloud_provider::models::CustomDomain;
 use crate::cloud_provider::service::{Action, Service};
 use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::check_certificate::CheckCertificateForDomains;
 use crate::deployment_action::check_dns::CheckDnsForDomains;
 use crate::deployment_action::deploy_helm::HelmDeployment;
 use crate::deployment_action::DeploymentAction;
@@ -13,6 +14,10 @@ use crate::models::router::Router;
 use crate::models::types::{CloudProvider, ToTeraContext};
 
 use crate::deployment_report::logger::{EnvProgressLogger, EnvSuccessLogger};
+use crate::dns_provider::errors::DnsProviderError;
+use crate::dns_provider::{DnsRecord, DnsRecordType};
+use std::collections::HashSet;
+use std::iter;
 use std::path::PathBuf;
 
 impl<T: CloudProvider> DeploymentAction for Router<T>
@@ -56,6 +61,24 @@ where
             };
             let _ = domain_checker.on_create(target);
 
+            // check certificates cert-manager has to generate for custom domains
+            let cluster_domain = target.dns_provider.domain().to_string();
+            let certificate_checker = CheckCertificateForDomains {
+                // cert-manager names the certificate after the ingress tls secret
+                certificate_name: format!("router-tls-{}", self.id),
+                domains: self
+                    .custom_domains
+                    .iter()
+                    .filter(|it| it.generate_certificate && (it.is_wildcard() || !it.domain.ends_with(&cluster_domain)))
+                    .map(|it| it.domain.to_string())
+                    .collect(),
+                strict: self.advanced_settings.certificate_strict_check,
+                event_details: event_details.clone(),
+                log: Box::new(move |msg| logger.info(msg)),
+                warn: Box::new(move |msg| logger.warning(msg)),
+            };
+            certificate_checker.on_create(target)?;
+
             Ok(())
         };
 
@@ -81,7 +104,7 @@ where
     fn on_delete(&self, target: &DeploymentTarget) -> Result<(), Box<EngineError>> {
         execute_long_deployment(
             RouterDeploymentReporter::new(self, target, Action::Delete),
-            |_logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
+            |logger: &EnvProgressLogger| -> Result<(), Box<EngineError>> {
                 let chart = ChartInfo {
                     name: self.helm_release_name(),
                     namespace: HelmChartNamespaces::Custom,
@@ -97,8 +120,31 @@ where
                     chart,
                 );
 
-                helm.on_delete(target)
+                helm.on_delete(target)?;
                 // FIXME: Delete also certificates
+
+                // external-dns may miss the ingress deletion, so records it created for the router are cleaned up here.
+                // It is best effort, leftovers don't prevent the router from being deleted.
+                let leftovers = target.dns_provider.list_records(None).map(|records| {
+                    router_leftover_dns_records(
+                        &records,
+                        &self.cluster_zone_domains(target.environment),
+                        target.kubernetes.short_id(),
+                    )
+                });
+                match leftovers {
+                    Ok(leftovers) => {
+                        for (name, record_type) in leftovers {
+                            if let Err(err) = target.dns_provider.delete_record(&name, record_type) {
+                                logger.warning(format!("Cannot delete DNS record {record_type} {name}: {err}"));
+                            }
+                        }
+                    }
+                    Err(DnsProviderError::RecordsManagementNotSupported) => {}
+                    Err(err) => logger.warning(format!("Cannot list DNS records to clean up: {err}")),
+                }
+
+                Ok(())
             },
         )
     }
@@ -110,3 +156,92 @@ where
         )
     }
 }
+
+/// Returns the records created by external-dns for the router domains, only the ones owned by the cluster.
+/// External-dns registry TXT records are named `qvy-<cluster id>-[<record type>-]<record name>`.
+fn router_leftover_dns_records(
+    records: &[DnsRecord],
+    router_domains: &[String],
+    cluster_id: &str,
+) -> Vec<(String, DnsRecordType)> {
+    let registry_prefix = format!("qvy-{cluster_id}-");
+    let owner = format!("external-dns/owner={cluster_id}");
+    let registry_record_domain = |name: &str| -> Option<String> {
+        let name = name.strip_prefix(&registry_prefix)?;
+        iter::once(name)
+            .chain(
+                ["a-", "aaaa-", "cname-"]
+                    .iter()
+                    .filter_map(|prefix| name.strip_prefix(prefix)),
+            )
+            .find(|name| router_domains.iter().any(|domain| domain == name))
+            .map(|name| name.to_string())
+    };
+
+    let mut owned_domains = HashSet::new();
+    let mut leftovers = vec![];
+    for record in records
+        .iter()
+        .filter(|record| record.record_type == DnsRecordType::Txt && record.value.contains(&owner))
+    {
+        if let Some(domain) = registry_record_domain(&record.name) {
+            owned_domains.insert(domain);
+            leftovers.push((record.name.to_string(), DnsRecordType::Txt));
+        }
+    }
+    for record in records {
+        if record.record_type != DnsRecordType::Txt && owned_domains.contains(&record.name) {
+            leftovers.push((record.name.to_string(), record.record_type));
+        }
+    }
+    leftovers.sort();
+    leftovers.dedup();
+
+    leftovers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_router_leftover_dns_records() {
+        let record = |name: &str, record_type: DnsRecordType, value: &str| DnsRecord {
+            name: name.to_string(),
+            record_type,
+            value: value.to_string(),
+            ttl: 300,
+        };
+        let owned = "\"heritage=external-dns,external-dns/owner=z1234,external-dns/resource=ingress/ns/router\"";
+        let records = vec![
+            record("app.z1234.qovery.io", DnsRecordType::Cname, "lb.z1234.qovery.io"),
+            record("p80-app.z1234.qovery.io", DnsRecordType::Cname, "lb.z1234.qovery.io"),
+            record("qvy-z1234-app.z1234.qovery.io", DnsRecordType::Txt, owned),
+            record("qvy-z1234-cname-p80-app.z1234.qovery.io", DnsRecordType::Txt, owned),
+            // not owned by the cluster
+            record("p443-app.z1234.qovery.io", DnsRecordType::A, "10.0.0.1"),
+            record(
+                "qvy-z1234-p443-app.z1234.qovery.io",
+                DnsRecordType::Txt,
+                "\"heritage=external-dns,external-dns/owner=z5678\"",
+            ),
+            // another router
+            record("api.z1234.qovery.io", DnsRecordType::Cname, "lb.z1234.qovery.io"),
+            record("qvy-z1234-api.z1234.qovery.io", DnsRecordType::Txt, owned),
+        ];
+
+        assert_eq!(
+            router_leftover_dns_records(
+                &records,
+                &["app.z1234.qovery.io".to_string(), "p80-app.z1234.qovery.io".to_string()],
+                "z1234"
+            ),
+            vec![
+                ("app.z1234.qovery.io".to_string(), DnsRecordType::Cname),
+                ("p80-app.z1234.qovery.io".to_string(), DnsRecordType::Cname),
+                ("qvy-z1234-app.z1234.qovery.io".to_string(), DnsRecordType::Txt),
+                ("qvy-z1234-cname-p80-app.z1234.qovery.io".to_string(), DnsRecordType::Txt),
+            ]
+        );
+    }
+}
@@ -2,7 +2,8 @@ use crate::cloud_provider::service::Action;
 use crate::cloud_provider::DeploymentTarget;
 use crate::errors::EngineError;
 
-mod check_dns;
+mod check_certificate;
+pub mod check_dns;
 mod deploy_application;
 mod deploy_container;
 mod deploy_database;
@@ -15,6 +16,7 @@ mod deploy_router;
 mod deploy_terraform;
 mod pause_service;
 mod restart_service;
+pub mod rotate_database_credentials;
 #[cfg(test)]
 mod test_utils;
 mod utils;
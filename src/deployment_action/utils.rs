@@ -3,7 +3,11 @@ use crate::cloud_provider::io::RegistryMirroringMode;
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::command::CommandKiller;
 use crate::cmd::docker::ContainerImage;
+use crate::cmd::oras::Oras;
+use crate::cmd::vulnerability_scanner::VulnerabilitySeverity;
 use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::vulnerability_scan;
+use crate::container_registry::vulnerability_scan::VulnerabilityScanConfig;
 use crate::container_registry::RegistryTags;
 use crate::deployment_report::logger::{EnvProgressLogger, EnvSuccessLogger};
 use crate::errors::{CommandError, EngineError};
@@ -23,10 +27,12 @@ use kube::api::ListParams;
 use kube::Api;
 use retry::delay::{Fibonacci, Fixed};
 use retry::OperationResult;
+use serde_json::Value;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
+use tera::Context as TeraContext;
 use uuid::Uuid;
 
 // specific to AWS
@@ -122,9 +128,10 @@ pub fn delete_cached_image(
     }
 
     // Delete previous image from cache to cleanup resources
+    // The deployed image can be pinned to a digest, i.e: registry/image:tag@sha256:...
     if let Some(last_image_tag) = last_image
         .as_ref()
-        .and_then(|img| img.split(':').last().map(str::to_string))
+        .and_then(|img| img.split('@').next()?.split(':').last().map(str::to_string))
     {
         if is_service_deletion || last_image_tag != current_image_tag {
             logger.send_success(format!("🪓 Deleting previous cached image {last_image_tag}"));
@@ -156,9 +163,12 @@ pub fn delete_cached_image(
     Ok(())
 }
 
+/// Mirror the image into the cluster registry. When the source image signature has been verified, its
+/// `source_digest` is mirrored instead of its tag, as the tag may have been moved since the verification.
 pub fn mirror_image_if_necessary(
     service_id: &Uuid,
     source: &RegistryImageSource,
+    source_digest: Option<&str>,
     target: &DeploymentTarget,
     logger: &EnvProgressLogger,
     event_details: EventDetails,
@@ -173,9 +183,14 @@ pub fn mirror_image_if_necessary(
             &target.kubernetes.advanced_settings().registry_mirroring_mode,
             target.container_registry.registry_info(),
         );
-    let dest_image = ContainerImage::new(cluster_container_registry, image_name, vec![image_tag]);
+    let dest_image = ContainerImage::new(cluster_container_registry.clone(), image_name.clone(), vec![image_tag]);
+    // The deployment is pinned to the digest, so it is the digest that must exist, whatever the tag points to
+    let existing_image = match source_digest {
+        Some(digest) => ContainerImage::new_for_digest(cluster_container_registry, image_name, digest.to_string()),
+        None => dest_image.clone(),
+    };
 
-    if image_already_exist(&dest_image, target) {
+    if image_already_exist(&existing_image, target) {
         let skip_image_mirroring_message = if must_mirror_image {
             format!(
                 "🎯 Skipping image mirroring: image {} already exists in the registry",
@@ -191,6 +206,7 @@ pub fn mirror_image_if_necessary(
         let result = mirror_image(
             service_id,
             source,
+            source_digest,
             &dest_image,
             target,
             logger,
@@ -214,16 +230,12 @@ fn image_already_exist(dest_image: &ContainerImage, target: &DeploymentTarget) -
     matches!(target.docker.does_image_exist_remotely(dest_image), Ok(true))
 }
 
-fn mirror_image(
-    service_id: &Uuid,
+fn login_to_source_registry(
     source: &RegistryImageSource,
-    dest_image: &ContainerImage,
     target: &DeploymentTarget,
     logger: &EnvProgressLogger,
     event_details: EventDetails,
-    tags: RegistryTags,
 ) -> Result<(), Box<EngineError>> {
-    // We need to login to the registry to get access to the image
     let url = source.registry.get_url_with_credentials().map_err(|_| {
         logger.warning("⚠️Cannot get the registry credentials".to_string());
         EngineError::new_error_cannot_get_registry_credentials(event_details.clone())
@@ -254,6 +266,161 @@ fn mirror_image(
         }
     }
 
+    Ok(())
+}
+
+/// When an image signing key is configured, images coming from a registry must be signed with it.
+/// Unsigned or wrongly signed images are refused before being mirrored/deployed.
+/// The tag is resolved once and its digest is verified, the returned digest must then be the one mirrored/deployed.
+pub fn verify_image_signature_if_necessary(
+    source: &RegistryImageSource,
+    target: &DeploymentTarget,
+    logger: &EnvProgressLogger,
+    event_details: EventDetails,
+) -> Result<Option<String>, Box<EngineError>> {
+    let Some(image_signer) = target.image_signer else {
+        return Ok(None);
+    };
+
+    login_to_source_registry(source, target, logger, event_details.clone())?;
+
+    let source_image = ContainerImage::new(
+        source.registry.url().clone(),
+        source.image.to_string(),
+        vec![source.tag.to_string()],
+    );
+    let image_name = source_image.image_name();
+    let digest = Oras::new(target.docker.config_path())
+        .resolve_digest(&source_image, true)
+        .map_err(|err| {
+            EngineError::new_image_signature_verification_failed(
+                event_details.clone(),
+                image_name.clone(),
+                CommandError::new("Cannot resolve image digest".to_string(), Some(err.to_string()), None),
+            )
+        })?;
+    let source_image =
+        ContainerImage::new_for_digest(source.registry.url().clone(), source.image.to_string(), digest.clone());
+    logger.info(format!("🔏 Verifying signature of image {image_name} ({digest})"));
+
+    match image_signer.verify(
+        &source_image,
+        target.docker.config_path(),
+        true,
+        &CommandKiller::from(Duration::from_secs(60 * 5), target.abort),
+    ) {
+        Ok(_) => {
+            logger.info(format!("🔏 Image {image_name} signature is valid"));
+            Ok(Some(digest))
+        }
+        Err(err) if err.is_aborted() => Err(Box::new(EngineError::new_task_cancellation_requested(event_details))),
+        Err(err) => {
+            let err = EngineError::new_image_signature_verification_failed(
+                event_details,
+                image_name,
+                CommandError::new("Image signature verification failed".to_string(), Some(err.to_string()), None),
+            );
+            Err(Box::new(err))
+        }
+    }
+}
+
+/// Scan the third-party image of a service before it is deployed, and block the deployment when it has
+/// vulnerabilities at or above the severity threshold of the service.
+pub fn scan_image_vulnerabilities_if_necessary(
+    service_id: &Uuid,
+    source: &RegistryImageSource,
+    source_digest: Option<&str>,
+    fail_on_severity: Option<VulnerabilitySeverity>,
+    target: &DeploymentTarget,
+    logger: &EnvProgressLogger,
+    event_details: EventDetails,
+) -> Result<(), Box<EngineError>> {
+    let Some(scan_config) = VulnerabilityScanConfig::from_cluster(target.kubernetes, target.docker) else {
+        return Ok(());
+    };
+
+    login_to_source_registry(source, target, logger, event_details.clone())?;
+    let registry_url = source.registry.get_url_with_credentials().map_err(|_| {
+        logger.warning("⚠️Cannot get the registry credentials".to_string());
+        EngineError::new_error_cannot_get_registry_credentials(event_details.clone())
+    })?;
+    let credentials = registry_url
+        .password()
+        .map(|password| (registry_url.username(), password));
+
+    // The digest verified before, if any, is the one to deploy
+    let source_image = match source_digest {
+        Some(digest) => {
+            ContainerImage::new_for_digest(source.registry.url().clone(), source.image.to_string(), digest.to_string())
+        }
+        None => ContainerImage::new(
+            source.registry.url().clone(),
+            source.image.to_string(),
+            vec![source.tag.to_string()],
+        ),
+    };
+    let image_name = source_image.image_name();
+    logger.info(format!(
+        "🛡️ Scanning image {} for vulnerabilities with {}",
+        image_name, scan_config.scanner
+    ));
+
+    let summary = match vulnerability_scan::scan_image(
+        &scan_config,
+        *service_id,
+        &source_image,
+        credentials,
+        true,
+        fail_on_severity,
+        target.metrics_registry.as_ref(),
+        target.abort,
+    ) {
+        Ok(summary) => summary,
+        Err(err) if err.is_aborted() => {
+            return Err(Box::new(EngineError::new_task_cancellation_requested(event_details)))
+        }
+        // Without a threshold, the scan is only informative and must not block the deployment
+        Err(err) if fail_on_severity.is_none() => {
+            logger.warning(format!("⚠️ Cannot scan image {image_name} for vulnerabilities: {err}"));
+            return Ok(());
+        }
+        Err(err) => {
+            return Err(Box::new(EngineError::new_vulnerability_scan_failed(
+                event_details,
+                image_name,
+                CommandError::new("Cannot scan image".to_string(), Some(err.to_string()), None),
+            )))
+        }
+    };
+
+    for line in summary.log_lines() {
+        logger.info(line);
+    }
+    if summary.is_blocking() {
+        return Err(Box::new(EngineError::new_vulnerability_scan_failed(
+            event_details,
+            image_name,
+            CommandError::new_from_safe_message(summary.to_string()),
+        )));
+    }
+
+    Ok(())
+}
+
+fn mirror_image(
+    service_id: &Uuid,
+    source: &RegistryImageSource,
+    source_digest: Option<&str>,
+    dest_image: &ContainerImage,
+    target: &DeploymentTarget,
+    logger: &EnvProgressLogger,
+    event_details: EventDetails,
+    tags: RegistryTags,
+) -> Result<(), Box<EngineError>> {
+    // We need to login to the registry to get access to the image
+    login_to_source_registry(source, target, logger, event_details.clone())?;
+
     // Once we are logged to the registry, we mirror the user image into our cluster private registry
     // This is required only to avoid to manage rotating credentials
     logger.info("🪞 Mirroring image to private cluster registry to ensure reproducibility".to_string());
@@ -271,11 +438,16 @@ fn mirror_image(
         )
         .map_err(|err| EngineError::new_container_registry_error(event_details.clone(), err))?;
 
-    let source_image = ContainerImage::new(
-        source.registry.url().clone(),
-        source.image.to_string(),
-        vec![source.tag.to_string()],
-    );
+    let source_image = match source_digest {
+        Some(digest) => {
+            ContainerImage::new_for_digest(source.registry.url().clone(), source.image.to_string(), digest.to_string())
+        }
+        None => ContainerImage::new(
+            source.registry.url().clone(),
+            source.image.to_string(),
+            vec![source.tag.to_string()],
+        ),
+    };
 
     let should_abort_waiting_thread = AtomicBool::new(false);
     let current_span = tracing::Span::current();
@@ -342,6 +514,22 @@ fn mirror_image(
     result
 }
 
+/// Pin the image of the service to the digest verified before its deployment, i.e: registry/image:tag@sha256:...
+pub fn pin_image_digest(tera_context: &mut TeraContext, digest: Option<&str>) {
+    let Some(digest) = digest else {
+        return;
+    };
+    let Some(mut service) = tera_context.get("service").cloned() else {
+        return;
+    };
+    let Some(image_full) = service.get("image_full").and_then(|image_full| image_full.as_str()) else {
+        return;
+    };
+
+    service["image_full"] = Value::String(format!("{image_full}@{digest}"));
+    tera_context.insert("service", &service);
+}
+
 pub enum KubeObjectKind {
     Deployment,
     Statefulset,
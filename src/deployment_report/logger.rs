@@ -20,16 +20,27 @@ pub struct EnvLogger {
 
 impl EnvLogger {
     pub fn new(service: &(impl Service + ?Sized), step: EnvironmentStep, logger: Arc<Box<dyn Logger>>) -> Self {
+        Self::new_for_event_details(service.get_event_details(Stage::Environment(step.clone())), step, logger)
+    }
+
+    // For tasks working on services without their domain model (i.e: image promotion)
+    pub fn new_for_event_details(
+        event_details: EventDetails,
+        step: EnvironmentStep,
+        logger: Arc<Box<dyn Logger>>,
+    ) -> Self {
         let (progress_step, success_step) = match step {
             EnvironmentStep::Deploy => (EnvironmentStep::Deploy, EnvironmentStep::Deployed),
             EnvironmentStep::Pause => (EnvironmentStep::Pause, EnvironmentStep::Paused),
             EnvironmentStep::Delete => (EnvironmentStep::Delete, EnvironmentStep::Deleted),
             EnvironmentStep::Build => (EnvironmentStep::Build, EnvironmentStep::Built),
+            EnvironmentStep::Promote => (EnvironmentStep::Promote, EnvironmentStep::Promoted),
             EnvironmentStep::Restart => (EnvironmentStep::Restart, EnvironmentStep::Restarted),
             _ => panic!("Invalid environment step for logger"),
         };
-        let event_details_progress = service.get_event_details(Stage::Environment(progress_step));
-        let event_details_success = service.get_event_details(Stage::Environment(success_step));
+        let event_details_progress =
+            EventDetails::clone_changing_stage(event_details.clone(), Stage::Environment(progress_step));
+        let event_details_success = EventDetails::clone_changing_stage(event_details, Stage::Environment(success_step));
 
         EnvLogger {
             logger,
@@ -1,6 +1,7 @@
 use itertools::Itertools;
 use regex::Regex;
 use std::borrow::Cow;
+use std::sync::{Arc, RwLock};
 
 pub trait ObfuscationService: Send + Sync {
     fn obfuscate_secrets(&self, text: String) -> String;
@@ -8,20 +9,32 @@ pub trait ObfuscationService: Send + Sync {
     fn clone_dyn(&self) -> Box<dyn ObfuscationService>;
 
     fn with_secrets(&self, secrets: Vec<String>) -> Box<dyn ObfuscationService>;
+
+    // Secrets only known once the task is running (i.e: fetched from a secret store), they are obfuscated by
+    // this service and all its clones
+    fn register_secrets(&self, secrets: Vec<String>);
 }
 
 pub struct StdObfuscationService {
+    // shared with the clones, so they all obfuscate the secrets registered afterward
+    state: Arc<RwLock<ObfuscationState>>,
+}
+
+struct ObfuscationState {
+    secrets: Vec<String>,
     regex: Option<Regex>,
 }
 
 impl StdObfuscationService {
     pub fn new(secrets: Vec<String>) -> Self {
-        let regex = Self::create_regex(secrets);
+        let regex = Self::create_regex(&secrets);
 
-        StdObfuscationService { regex }
+        StdObfuscationService {
+            state: Arc::new(RwLock::new(ObfuscationState { secrets, regex })),
+        }
     }
 
-    fn create_regex(secrets: Vec<String>) -> Option<Regex> {
+    fn create_regex(secrets: &[String]) -> Option<Regex> {
         if secrets.is_empty() {
             return None;
         }
@@ -50,7 +63,8 @@ impl Default for StdObfuscationService {
 
 impl ObfuscationService for StdObfuscationService {
     fn obfuscate_secrets(&self, text: String) -> String {
-        if let Some(regex) = &self.regex {
+        let state = self.state.read().unwrap_or_else(|err| err.into_inner());
+        if let Some(regex) = &state.regex {
             if let Cow::Owned(obfuscated) = regex.replace_all(&text, "xxx") {
                 return obfuscated;
             }
@@ -60,13 +74,18 @@ impl ObfuscationService for StdObfuscationService {
 
     fn clone_dyn(&self) -> Box<dyn ObfuscationService> {
         Box::new(StdObfuscationService {
-            regex: self.regex.clone(),
+            state: self.state.clone(),
         })
     }
 
     fn with_secrets(&self, secrets: Vec<String>) -> Box<dyn ObfuscationService> {
-        let regex = Self::create_regex(secrets);
-        Box::new(StdObfuscationService { regex })
+        Box::new(StdObfuscationService::new(secrets))
+    }
+
+    fn register_secrets(&self, secrets: Vec<String>) {
+        let mut state = self.state.write().unwrap_or_else(|err| err.into_inner());
+        state.secrets.extend(secrets);
+        state.regex = Self::create_regex(&state.secrets);
     }
 }
 
@@ -121,6 +140,18 @@ mod tests {
         assert_eq!(obfuscation_service.obfuscate_secrets(log.clone()), log);
     }
 
+    #[test]
+    fn test_registered_secrets_are_obfuscated_by_clones() {
+        let log = "a log with my password: 1234-abcd".to_string();
+        let obfuscation_service = StdObfuscationService::new(vec!["with".to_string()]);
+        let clone = obfuscation_service.clone_dyn();
+        let other = obfuscation_service.with_secrets(vec![]);
+
+        obfuscation_service.register_secrets(vec!["1234-abcd".to_string()]);
+        assert_eq!(clone.obfuscate_secrets(log.clone()), "a log xxx my password: xxx".to_string());
+        assert_eq!(other.obfuscate_secrets(log.clone()), log);
+    }
+
     #[test]
     fn test_service_creation_from_gcp_credential() {
         let secret = "{
@@ -138,7 +169,7 @@ mod tests {
 }";
         let obfuscation_service = StdObfuscationService::new(vec![secret.to_string()]);
 
-        assert!(obfuscation_service.regex.is_some());
+        assert!(obfuscation_service.state.read().unwrap().regex.is_some());
         assert_eq!(obfuscation_service.obfuscate_secrets(secret.to_string()), "xxx");
     }
 }
@@ -1,12 +1,23 @@
+use reqwest::blocking::RequestBuilder;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::net::Ipv4Addr;
+use std::str::FromStr;
+use std::time::Duration;
 use tera::Context as TeraContext;
 use uuid::Uuid;
 
 use crate::dns_provider::errors::DnsProviderError;
-use crate::dns_provider::{DnsProvider, DnsProviderConfiguration, Kind};
+use crate::dns_provider::{DnsProvider, DnsProviderConfiguration, DnsRecord, DnsRecordType, Kind};
 use crate::io_models::context::Context;
 use crate::models::domain::Domain;
 
+const CLOUDFLARE_API_URL: &str = "https://api.cloudflare.com/client/v4";
+const CLOUDFLARE_RECORDS_PER_PAGE: u32 = 100;
+// Cloudflare uses a ttl of 1 for `automatic`
+const CLOUDFLARE_AUTOMATIC_TTL: u32 = 1;
+
 #[derive(Clone, Debug)]
 pub struct CloudflareDnsConfig {
     pub cloudflare_email: String,
@@ -46,6 +57,133 @@ impl Cloudflare {
     }
 }
 
+// doc: https://developers.cloudflare.com/api/operations/dns-records-for-a-zone-list-dns-records
+#[derive(Deserialize)]
+struct CloudflareResponse<T> {
+    success: bool,
+    #[serde(default)]
+    errors: Vec<CloudflareResponseError>,
+    result: Option<T>,
+    result_info: Option<CloudflareResultInfo>,
+}
+
+#[derive(Deserialize)]
+struct CloudflareResponseError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct CloudflareResultInfo {
+    total_pages: u32,
+}
+
+#[derive(Deserialize)]
+struct CloudflareZone {
+    id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CloudflareRecord {
+    #[serde(skip_serializing)]
+    id: String,
+    #[serde(rename = "type")]
+    record_type: String,
+    name: String,
+    content: String,
+    ttl: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxied: Option<bool>,
+}
+
+impl CloudflareRecord {
+    /// Returns None for record types not managed by the engine (MX, SRV, ...)
+    fn to_dns_record(&self) -> Option<DnsRecord> {
+        Some(DnsRecord {
+            name: self.name.to_string(),
+            record_type: DnsRecordType::from_str(&self.record_type).ok()?,
+            value: self.content.to_string(),
+            ttl: self.ttl,
+        })
+    }
+}
+
+impl Cloudflare {
+    fn cannot_manage_record(record_name: &str, raw_error_message: String) -> DnsProviderError {
+        DnsProviderError::CannotManageRecord {
+            record_name: record_name.to_string(),
+            raw_error_message,
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder, String> {
+        let http_client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("qovery-engine")
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        Ok(http_client
+            .request(method, format!("{}{}", CLOUDFLARE_API_URL, path))
+            .bearer_auth(&self.cloudflare_api_token))
+    }
+
+    fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<(Option<T>, u32), String> {
+        let response = request.send().map_err(|e| e.to_string())?;
+        let status = response.status();
+        let response: CloudflareResponse<T> = response
+            .json()
+            .map_err(|e| format!("{}: cannot parse Cloudflare response: {}", status, e))?;
+        if !response.success {
+            return Err(format!(
+                "{}: {}",
+                status,
+                response
+                    .errors
+                    .iter()
+                    .map(|e| format!("{} ({})", e.message, e.code))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        Ok((response.result, response.result_info.map(|info| info.total_pages).unwrap_or(1)))
+    }
+
+    fn zone_id(&self) -> Result<String, String> {
+        let zone_name = self.domain.root_domain().to_string();
+        let request = self
+            .request(Method::GET, "/zones")?
+            .query(&[("name", zone_name.as_str())]);
+        let (zones, _) = Cloudflare::send::<Vec<CloudflareZone>>(request)?;
+
+        zones
+            .and_then(|zones| zones.into_iter().next())
+            .map(|zone| zone.id)
+            .ok_or_else(|| format!("zone `{}` not found", zone_name))
+    }
+
+    fn records(&self, zone_id: &str, name: Option<&str>) -> Result<Vec<CloudflareRecord>, String> {
+        let mut records = vec![];
+        let mut page = 1;
+        loop {
+            let mut request = self
+                .request(Method::GET, &format!("/zones/{}/dns_records", zone_id))?
+                .query(&[("page", page), ("per_page", CLOUDFLARE_RECORDS_PER_PAGE)]);
+            if let Some(name) = name {
+                request = request.query(&[("name", name)]);
+            }
+            let (page_records, total_pages) = Cloudflare::send::<Vec<CloudflareRecord>>(request)?;
+            records.extend(page_records.unwrap_or_default());
+
+            if page >= total_pages {
+                return Ok(records);
+            }
+            page += 1;
+        }
+    }
+}
+
 impl DnsProvider for Cloudflare {
     fn context(&self) -> &Context {
         &self.context
@@ -97,4 +235,63 @@ impl DnsProvider for Cloudflare {
             Ok(())
         }
     }
+
+    fn list_records(&self, name: Option<&str>) -> Result<Vec<DnsRecord>, DnsProviderError> {
+        let records = self
+            .zone_id()
+            .and_then(|zone_id| self.records(&zone_id, name))
+            .map_err(|e| Cloudflare::cannot_manage_record(name.unwrap_or("*"), e))?;
+
+        Ok(records.iter().filter_map(CloudflareRecord::to_dns_record).collect())
+    }
+
+    fn upsert_record(&self, record: &DnsRecord) -> Result<(), DnsProviderError> {
+        let upsert = || -> Result<(), String> {
+            let zone_id = self.zone_id()?;
+            let existing_record = self
+                .records(&zone_id, Some(&record.name))?
+                .into_iter()
+                .find(|existing_record| existing_record.record_type == record.record_type.to_string());
+            let cloudflare_record = CloudflareRecord {
+                id: "".to_string(),
+                record_type: record.record_type.to_string(),
+                name: record.name.to_string(),
+                content: record.value.to_string(),
+                ttl: record.ttl.max(CLOUDFLARE_AUTOMATIC_TTL),
+                // only records pointing to an address can be proxied
+                proxied: match record.record_type {
+                    DnsRecordType::Txt => None,
+                    _ => Some(self.cloudflare_proxied),
+                },
+            };
+
+            let request = match existing_record {
+                Some(existing_record) => {
+                    self.request(Method::PUT, &format!("/zones/{}/dns_records/{}", zone_id, existing_record.id))?
+                }
+                None => self.request(Method::POST, &format!("/zones/{}/dns_records", zone_id))?,
+            };
+            Cloudflare::send::<CloudflareRecord>(request.json(&cloudflare_record)).map(|_| ())
+        };
+
+        upsert().map_err(|e| Cloudflare::cannot_manage_record(&record.name, e))
+    }
+
+    fn delete_record(&self, name: &str, record_type: DnsRecordType) -> Result<(), DnsProviderError> {
+        let delete = || -> Result<(), String> {
+            let zone_id = self.zone_id()?;
+            for record in self
+                .records(&zone_id, Some(name))?
+                .into_iter()
+                .filter(|record| record.record_type == record_type.to_string())
+            {
+                let request = self.request(Method::DELETE, &format!("/zones/{}/dns_records/{}", zone_id, record.id))?;
+                Cloudflare::send::<serde_json::Value>(request)?;
+            }
+
+            Ok(())
+        };
+
+        delete().map_err(|e| Cloudflare::cannot_manage_record(name, e))
+    }
 }
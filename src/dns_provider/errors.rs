@@ -8,6 +8,18 @@ pub enum DnsProviderError {
     InvalidCredentials,
     #[error("Invalid API url error.")]
     InvalidApiUrl,
+    #[error("Invalid hosted zone `{hosted_zone_id}` error: {raw_error_message}")]
+    InvalidHostedZone {
+        hosted_zone_id: String,
+        raw_error_message: String,
+    },
+    #[error("Cannot manage record `{record_name}` error: {raw_error_message}")]
+    CannotManageRecord {
+        record_name: String,
+        raw_error_message: String,
+    },
+    #[error("Records management is not supported by this DNS provider.")]
+    RecordsManagementNotSupported,
 }
 
 impl DnsProviderError {
@@ -17,6 +29,25 @@ impl DnsProviderError {
                 EngineError::new_error_on_dns_provider_invalid_credentials(event_details)
             }
             DnsProviderError::InvalidApiUrl => EngineError::new_error_on_dns_provider_invalid_api_url(event_details),
+            DnsProviderError::InvalidHostedZone {
+                hosted_zone_id,
+                raw_error_message,
+            } => EngineError::new_error_on_dns_provider_invalid_hosted_zone(
+                event_details,
+                hosted_zone_id,
+                raw_error_message,
+            ),
+            DnsProviderError::CannotManageRecord {
+                record_name,
+                raw_error_message,
+            } => EngineError::new_error_on_dns_provider_cannot_manage_record(
+                event_details,
+                record_name,
+                raw_error_message,
+            ),
+            DnsProviderError::RecordsManagementNotSupported => {
+                EngineError::new_error_on_dns_provider_records_management_not_supported(event_details)
+            }
         }
     }
 }
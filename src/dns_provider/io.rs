@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 pub enum Kind {
     Cloudflare,
     QoveryDns,
+    Route53,
+    Rfc2136,
 }
 
 impl From<dns_provider::Kind> for Kind {
@@ -13,6 +15,8 @@ impl From<dns_provider::Kind> for Kind {
         match kind {
             dns_provider::Kind::Cloudflare => Kind::Cloudflare,
             dns_provider::Kind::QoveryDns => Kind::QoveryDns,
+            dns_provider::Kind::Route53 => Kind::Route53,
+            dns_provider::Kind::Rfc2136 => Kind::Rfc2136,
         }
     }
 }
@@ -1,8 +1,12 @@
+use std::fmt::{Display, Formatter};
 use std::net::Ipv4Addr;
+use std::str::FromStr;
 
 use crate::dns_provider::cloudflare::CloudflareDnsConfig;
 use crate::dns_provider::errors::DnsProviderError;
 use crate::dns_provider::qoverydns::QoveryDnsConfig;
+use crate::dns_provider::rfc2136::Rfc2136DnsConfig;
+use crate::dns_provider::route53::Route53DnsConfig;
 use crate::events::{EventDetails, InfrastructureStep, Stage, Transmitter};
 use tera::Context as TeraContext;
 use uuid::Uuid;
@@ -15,17 +19,23 @@ pub mod cloudflare;
 pub mod errors;
 pub mod io;
 pub mod qoverydns;
+pub mod rfc2136;
+pub mod route53;
 
 #[derive(Clone, Debug)]
 pub enum Kind {
     Cloudflare,
     QoveryDns,
+    Route53,
+    Rfc2136,
 }
 
 #[derive(Clone, Debug)]
 pub enum DnsProviderConfiguration {
     Cloudflare(CloudflareDnsConfig),
     QoveryDns(QoveryDnsConfig),
+    Route53(Route53DnsConfig),
+    Rfc2136(Rfc2136DnsConfig),
 }
 
 impl DnsProviderConfiguration {
@@ -33,11 +43,64 @@ impl DnsProviderConfiguration {
         match self {
             DnsProviderConfiguration::Cloudflare(_) => "cloudflare",
             DnsProviderConfiguration::QoveryDns(_) => "pdns",
+            // external-dns names its Route53 provider `aws`
+            DnsProviderConfiguration::Route53(_) => "aws",
+            DnsProviderConfiguration::Rfc2136(_) => "rfc2136",
         }
         .to_string()
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DnsRecordType {
+    A,
+    Aaaa,
+    Cname,
+    Txt,
+}
+
+impl Display for DnsRecordType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DnsRecordType::A => "A",
+            DnsRecordType::Aaaa => "AAAA",
+            DnsRecordType::Cname => "CNAME",
+            DnsRecordType::Txt => "TXT",
+        })
+    }
+}
+
+impl FromStr for DnsRecordType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "A" => Ok(DnsRecordType::A),
+            "AAAA" => Ok(DnsRecordType::Aaaa),
+            "CNAME" => Ok(DnsRecordType::Cname),
+            "TXT" => Ok(DnsRecordType::Txt),
+            _ => Err(format!("unsupported DNS record type `{}`", s)),
+        }
+    }
+}
+
+/// A single value of a DNS record set, names are fully qualified without the trailing dot
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DnsRecord {
+    pub name: String,
+    pub record_type: DnsRecordType,
+    pub value: String,
+    pub ttl: u32,
+}
+
+/// Whether a domain can be managed from a DNS zone, i.e: the zone itself or one of its sub domains
+pub(crate) fn is_domain_in_zone(domain: &str, zone_name: &str) -> bool {
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    let zone_name = zone_name.trim_end_matches('.').to_lowercase();
+
+    domain == zone_name || domain.ends_with(&format!(".{}", zone_name))
+}
+
 pub trait DnsProvider: Send + Sync {
     fn context(&self) -> &Context;
     fn provider_name(&self) -> &str;
@@ -49,6 +112,18 @@ pub trait DnsProvider: Send + Sync {
     fn domain(&self) -> &Domain;
     fn resolvers(&self) -> Vec<Ipv4Addr>;
     fn is_valid(&self) -> Result<(), DnsProviderError>;
+    /// Lists the records of the provider domain, only the ones named `name` when set
+    fn list_records(&self, _name: Option<&str>) -> Result<Vec<DnsRecord>, DnsProviderError> {
+        Err(DnsProviderError::RecordsManagementNotSupported)
+    }
+    /// Creates the record, replacing the value and ttl of an existing record with the same name and type
+    fn upsert_record(&self, _record: &DnsRecord) -> Result<(), DnsProviderError> {
+        Err(DnsProviderError::RecordsManagementNotSupported)
+    }
+    /// Deletes the records with the given name and type, it is not an error if there is none
+    fn delete_record(&self, _name: &str, _record_type: DnsRecordType) -> Result<(), DnsProviderError> {
+        Err(DnsProviderError::RecordsManagementNotSupported)
+    }
     fn event_details(&self) -> EventDetails {
         EventDetails::new(
             None,
@@ -60,3 +135,18 @@ pub trait DnsProvider: Send + Sync {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_domain_in_zone() {
+        assert!(is_domain_in_zone("example.com", "example.com."));
+        assert!(is_domain_in_zone("z1234.Example.com", "example.com."));
+        assert!(is_domain_in_zone("a.b.example.com.", "b.example.com"));
+        assert!(!is_domain_in_zone("example.com", "b.example.com."));
+        assert!(!is_domain_in_zone("notexample.com", "example.com."));
+        assert!(!is_domain_in_zone("example.org", "example.com."));
+    }
+}
@@ -1,11 +1,16 @@
+use reqwest::blocking::RequestBuilder;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
 use std::net::Ipv4Addr;
+use std::str::FromStr;
+use std::time::Duration;
 use tera::Context as TeraContext;
 use url::Url;
 use uuid::Uuid;
 
 use crate::dns_provider::errors::DnsProviderError;
 use crate::dns_provider::Kind;
-use crate::dns_provider::{DnsProvider, DnsProviderConfiguration};
+use crate::dns_provider::{is_domain_in_zone, DnsProvider, DnsProviderConfiguration, DnsRecord, DnsRecordType};
 use crate::io_models::context::Context;
 use crate::models::domain::Domain;
 
@@ -60,6 +65,142 @@ impl QoveryDns {
     }
 }
 
+// Qovery DNS exposes the PowerDNS API, doc: https://doc.powerdns.com/authoritative/http-api/zone.html
+#[derive(Deserialize)]
+struct PdnsZone {
+    id: String,
+    name: String,
+    #[serde(default)]
+    rrsets: Vec<PdnsRrset>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PdnsRrset {
+    name: String,
+    #[serde(rename = "type")]
+    record_type: String,
+    #[serde(default)]
+    ttl: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changetype: Option<String>,
+    #[serde(default)]
+    records: Vec<PdnsRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PdnsRecord {
+    content: String,
+    disabled: bool,
+}
+
+#[derive(Serialize)]
+struct PdnsRrsetsPatch {
+    rrsets: Vec<PdnsRrset>,
+}
+
+/// PowerDNS expects fully qualified names, with the trailing dot
+fn pdns_fqdn(name: &str) -> String {
+    format!("{}.", name.trim_end_matches('.'))
+}
+
+fn from_pdns_content(record_type: DnsRecordType, content: &str) -> String {
+    match record_type {
+        DnsRecordType::Txt => content.trim_matches('"').to_string(),
+        DnsRecordType::Cname => content.trim_end_matches('.').to_string(),
+        DnsRecordType::A | DnsRecordType::Aaaa => content.to_string(),
+    }
+}
+
+fn to_pdns_content(record_type: DnsRecordType, value: &str) -> String {
+    match record_type {
+        DnsRecordType::Txt => format!("\"{}\"", value.trim_matches('"')),
+        DnsRecordType::Cname => pdns_fqdn(value),
+        DnsRecordType::A | DnsRecordType::Aaaa => value.to_string(),
+    }
+}
+
+fn from_pdns_rrsets(rrsets: &[PdnsRrset]) -> Vec<DnsRecord> {
+    rrsets
+        .iter()
+        // other record types (SOA, NS, MX, ...) are not managed
+        .filter_map(|rrset| Some((rrset, DnsRecordType::from_str(&rrset.record_type).ok()?)))
+        .flat_map(|(rrset, record_type)| {
+            rrset
+                .records
+                .iter()
+                .filter(|record| !record.disabled)
+                .map(move |record| DnsRecord {
+                    name: rrset.name.trim_end_matches('.').to_string(),
+                    record_type,
+                    value: from_pdns_content(record_type, &record.content),
+                    ttl: rrset.ttl,
+                })
+        })
+        .collect()
+}
+
+impl QoveryDns {
+    fn cannot_manage_record(record_name: &str, raw_error_message: String) -> DnsProviderError {
+        DnsProviderError::CannotManageRecord {
+            record_name: record_name.to_string(),
+            raw_error_message,
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder, String> {
+        let http_client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("qovery-engine")
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        Ok(http_client
+            .request(
+                method,
+                format!(
+                    "{}:{}/api/v1/servers/localhost{}",
+                    self.dns_config.api_url_scheme_and_domain, self.dns_config.api_url_port, path
+                ),
+            )
+            .header("X-API-Key", &self.dns_config.api_key))
+    }
+
+    fn send(request: RequestBuilder) -> Result<reqwest::blocking::Response, String> {
+        let response = request.send().map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        let status = response.status();
+        Err(format!("{}: {}", status, response.text().unwrap_or_default()))
+    }
+
+    /// Returns the most specific zone containing the name
+    fn zone(&self, name: &str) -> Result<PdnsZone, String> {
+        let zones: Vec<PdnsZone> = QoveryDns::send(self.request(Method::GET, "/zones")?)?
+            .json()
+            .map_err(|e| e.to_string())?;
+        let zone = zones
+            .into_iter()
+            .filter(|zone| is_domain_in_zone(name, &zone.name))
+            .max_by_key(|zone| zone.name.len())
+            .ok_or_else(|| format!("no zone found for `{}`", name))?;
+
+        QoveryDns::send(self.request(Method::GET, &format!("/zones/{}", zone.id))?)?
+            .json()
+            .map_err(|e| e.to_string())
+    }
+
+    fn patch_rrset(&self, name: &str, rrset: PdnsRrset) -> Result<(), String> {
+        let zone = self.zone(name)?;
+        let request = self
+            .request(Method::PATCH, &format!("/zones/{}", zone.id))?
+            .json(&PdnsRrsetsPatch { rrsets: vec![rrset] });
+
+        QoveryDns::send(request).map(|_| ())
+    }
+}
+
 impl DnsProvider for QoveryDns {
     fn context(&self) -> &Context {
         &self.context
@@ -112,4 +253,98 @@ impl DnsProvider for QoveryDns {
 
         Ok(())
     }
+
+    fn list_records(&self, name: Option<&str>) -> Result<Vec<DnsRecord>, DnsProviderError> {
+        let zone = self
+            .zone(name.unwrap_or(&self.domain.to_string()))
+            .map_err(|e| QoveryDns::cannot_manage_record(name.unwrap_or("*"), e))?;
+
+        Ok(from_pdns_rrsets(&zone.rrsets)
+            .into_iter()
+            .filter(|record| {
+                name.map(|name| name.trim_end_matches('.') == record.name)
+                    .unwrap_or(true)
+            })
+            .collect())
+    }
+
+    fn upsert_record(&self, record: &DnsRecord) -> Result<(), DnsProviderError> {
+        let rrset = PdnsRrset {
+            name: pdns_fqdn(&record.name),
+            record_type: record.record_type.to_string(),
+            ttl: record.ttl,
+            changetype: Some("REPLACE".to_string()),
+            records: vec![PdnsRecord {
+                content: to_pdns_content(record.record_type, &record.value),
+                disabled: false,
+            }],
+        };
+
+        self.patch_rrset(&record.name, rrset)
+            .map_err(|e| QoveryDns::cannot_manage_record(&record.name, e))
+    }
+
+    fn delete_record(&self, name: &str, record_type: DnsRecordType) -> Result<(), DnsProviderError> {
+        let rrset = PdnsRrset {
+            name: pdns_fqdn(name),
+            record_type: record_type.to_string(),
+            ttl: 0,
+            changetype: Some("DELETE".to_string()),
+            records: vec![],
+        };
+
+        self.patch_rrset(name, rrset)
+            .map_err(|e| QoveryDns::cannot_manage_record(name, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_pdns_rrsets() {
+        let zone: PdnsZone = serde_json::from_str(
+            r#"{
+                "id": "qovery.io.",
+                "name": "qovery.io.",
+                "rrsets": [
+                    {"name": "qovery.io.", "type": "SOA", "ttl": 3600, "records": [{"content": "ns1.qovery.io. admin.qovery.io. 1 10800 3600 604800 3600", "disabled": false}]},
+                    {"name": "app.z1234.qovery.io.", "type": "CNAME", "ttl": 300, "records": [{"content": "lb.z1234.qovery.io.", "disabled": false}]},
+                    {"name": "qvy-z1234-app.z1234.qovery.io.", "type": "TXT", "ttl": 300, "records": [{"content": "\"heritage=external-dns\"", "disabled": false}]},
+                    {"name": "z1234.qovery.io.", "type": "A", "ttl": 60, "records": [{"content": "10.0.0.1", "disabled": false}, {"content": "10.0.0.2", "disabled": true}]}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            from_pdns_rrsets(&zone.rrsets),
+            vec![
+                DnsRecord {
+                    name: "app.z1234.qovery.io".to_string(),
+                    record_type: DnsRecordType::Cname,
+                    value: "lb.z1234.qovery.io".to_string(),
+                    ttl: 300,
+                },
+                DnsRecord {
+                    name: "qvy-z1234-app.z1234.qovery.io".to_string(),
+                    record_type: DnsRecordType::Txt,
+                    value: "heritage=external-dns".to_string(),
+                    ttl: 300,
+                },
+                DnsRecord {
+                    name: "z1234.qovery.io".to_string(),
+                    record_type: DnsRecordType::A,
+                    value: "10.0.0.1".to_string(),
+                    ttl: 60,
+                },
+            ]
+        );
+        assert_eq!(
+            to_pdns_content(DnsRecordType::Txt, "heritage=external-dns"),
+            "\"heritage=external-dns\""
+        );
+        assert_eq!(to_pdns_content(DnsRecordType::Cname, "lb.qovery.io"), "lb.qovery.io.");
+    }
 }
@@ -0,0 +1,304 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use derivative::Derivative;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::str::FromStr;
+use std::time::Duration;
+use tera::Context as TeraContext;
+use trust_dns_client::client::{Client, SyncClient};
+use trust_dns_client::op::ResponseCode;
+use trust_dns_client::rr::dnssec::rdata::tsig::TsigAlgorithm;
+use trust_dns_client::rr::dnssec::tsig::TSigner;
+use trust_dns_client::rr::rdata::TXT;
+use trust_dns_client::rr::{DNSClass, Name, RData, Record, RecordType};
+use trust_dns_client::udp::UdpClientConnection;
+use uuid::Uuid;
+
+use crate::dns_provider::errors::DnsProviderError;
+use crate::dns_provider::{is_domain_in_zone, DnsProvider, DnsProviderConfiguration, Kind};
+use crate::io_models::context::Context;
+use crate::models::domain::Domain;
+
+const RFC2136_TIMEOUT: Duration = Duration::from_secs(10);
+// allowed clock skew between the engine and the DNS server when checking TSIG signatures
+const TSIG_FUDGE_IN_SECONDS: u16 = 300;
+// record created then deleted by `is_valid` to make sure the key is allowed to update the zone
+const VALIDATION_RECORD_PREFIX: &str = "_qovery-rfc2136-check";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rfc2136TsigAlgorithm {
+    HmacSha256,
+    HmacSha384,
+    HmacSha512,
+}
+
+impl Rfc2136TsigAlgorithm {
+    /// Algorithm name as expected by external-dns
+    pub fn to_external_dns_format(&self) -> &str {
+        match self {
+            Rfc2136TsigAlgorithm::HmacSha256 => "hmac-sha256",
+            Rfc2136TsigAlgorithm::HmacSha384 => "hmac-sha384",
+            Rfc2136TsigAlgorithm::HmacSha512 => "hmac-sha512",
+        }
+    }
+
+    /// Algorithm name as expected by cert-manager
+    pub fn to_cert_manager_format(&self) -> &str {
+        match self {
+            Rfc2136TsigAlgorithm::HmacSha256 => "HMACSHA256",
+            Rfc2136TsigAlgorithm::HmacSha384 => "HMACSHA384",
+            Rfc2136TsigAlgorithm::HmacSha512 => "HMACSHA512",
+        }
+    }
+
+    fn to_tsig_algorithm(self) -> TsigAlgorithm {
+        match self {
+            Rfc2136TsigAlgorithm::HmacSha256 => TsigAlgorithm::HmacSha256,
+            Rfc2136TsigAlgorithm::HmacSha384 => TsigAlgorithm::HmacSha384,
+            Rfc2136TsigAlgorithm::HmacSha512 => TsigAlgorithm::HmacSha512,
+        }
+    }
+}
+
+impl FromStr for Rfc2136TsigAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "").as_str() {
+            "hmacsha256" => Ok(Rfc2136TsigAlgorithm::HmacSha256),
+            "hmacsha384" => Ok(Rfc2136TsigAlgorithm::HmacSha384),
+            "hmacsha512" => Ok(Rfc2136TsigAlgorithm::HmacSha512),
+            _ => Err(format!("unsupported TSIG algorithm `{}`", s)),
+        }
+    }
+}
+
+#[derive(Clone, Derivative)]
+#[derivative(Debug)]
+pub struct Rfc2136DnsConfig {
+    pub nameserver_host: String,
+    pub nameserver_port: u16,
+    pub zone: String,
+    pub tsig_key_name: String,
+    #[derivative(Debug = "ignore")]
+    pub tsig_secret: String,
+    pub tsig_algorithm: Rfc2136TsigAlgorithm,
+}
+
+impl Rfc2136DnsConfig {
+    /// Nameserver as `host:port`
+    pub fn nameserver(&self) -> String {
+        match self.nameserver_host.parse::<Ipv6Addr>() {
+            Ok(_) => format!("[{}]:{}", self.nameserver_host, self.nameserver_port),
+            Err(_) => format!("{}:{}", self.nameserver_host, self.nameserver_port),
+        }
+    }
+}
+
+pub struct Rfc2136 {
+    context: Context,
+    long_id: Uuid,
+    name: String,
+    domain: Domain,
+    dns_config: Rfc2136DnsConfig,
+}
+
+impl Rfc2136 {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        name: &str,
+        domain: Domain,
+        nameserver_host: &str,
+        nameserver_port: u16,
+        zone: &str,
+        tsig_key_name: &str,
+        tsig_secret: &str,
+        tsig_algorithm: Rfc2136TsigAlgorithm,
+    ) -> Self {
+        Rfc2136 {
+            context,
+            long_id,
+            name: name.to_string(),
+            domain,
+            dns_config: Rfc2136DnsConfig {
+                nameserver_host: nameserver_host.to_string(),
+                nameserver_port,
+                zone: zone.trim_end_matches('.').to_string(),
+                tsig_key_name: tsig_key_name.to_string(),
+                tsig_secret: tsig_secret.to_string(),
+                tsig_algorithm,
+            },
+        }
+    }
+
+    fn invalid_zone(&self, raw_error_message: String) -> DnsProviderError {
+        DnsProviderError::InvalidHostedZone {
+            hosted_zone_id: self.dns_config.zone.to_string(),
+            raw_error_message,
+        }
+    }
+
+    fn fqdn(&self, name: &str) -> Result<Name, DnsProviderError> {
+        Name::from_ascii(format!("{}.", name.trim_end_matches('.')))
+            .map_err(|e| self.invalid_zone(format!("invalid DNS name `{}`: {}", name, e)))
+    }
+
+    fn client(&self) -> Result<SyncClient<UdpClientConnection>, DnsProviderError> {
+        let nameserver: SocketAddr = (self.dns_config.nameserver_host.as_str(), self.dns_config.nameserver_port)
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .ok_or(DnsProviderError::InvalidApiUrl)?;
+        let connection = UdpClientConnection::with_timeout(nameserver, RFC2136_TIMEOUT)
+            .map_err(|e| self.invalid_zone(format!("cannot connect to nameserver `{}`: {}", nameserver, e)))?;
+
+        let tsig_secret = general_purpose::STANDARD
+            .decode(self.dns_config.tsig_secret.as_bytes())
+            .map_err(|_| DnsProviderError::InvalidCredentials)?;
+        let signer = TSigner::new(
+            tsig_secret,
+            self.dns_config.tsig_algorithm.to_tsig_algorithm(),
+            self.fqdn(&self.dns_config.tsig_key_name)?,
+            TSIG_FUDGE_IN_SECONDS,
+        )
+        .map_err(|_| DnsProviderError::InvalidCredentials)?;
+
+        Ok(SyncClient::with_tsigner(connection, signer))
+    }
+
+    /// Checks the zone is served by the nameserver, and the TSIG key is allowed to update it
+    fn check_zone_updates(&self) -> Result<(), DnsProviderError> {
+        let client = self.client()?;
+        let zone = self.fqdn(&self.dns_config.zone)?;
+
+        let soa = client
+            .query(&zone, DNSClass::IN, RecordType::SOA)
+            .map_err(|e| self.invalid_zone(format!("cannot query SOA: {}", e)))?;
+        if let ResponseCode::NotAuth | ResponseCode::Refused = soa.response_code() {
+            return Err(DnsProviderError::InvalidCredentials);
+        }
+        if soa.response_code() != ResponseCode::NoError
+            || !soa
+                .answers()
+                .iter()
+                .any(|record| record.record_type() == RecordType::SOA)
+        {
+            return Err(self.invalid_zone(format!(
+                "nameserver is not authoritative for the zone, SOA query answered {}",
+                soa.response_code()
+            )));
+        }
+
+        let record = Record::from_rdata(
+            self.fqdn(&format!("{}.{}", VALIDATION_RECORD_PREFIX, self.domain))?,
+            60,
+            RData::TXT(TXT::new(vec![self.long_id.to_string()])),
+        );
+        let check_update_response = |response_code: ResponseCode, operation: &str| match response_code {
+            ResponseCode::NoError => Ok(()),
+            ResponseCode::NotAuth | ResponseCode::Refused => Err(DnsProviderError::InvalidCredentials),
+            response_code => Err(self.invalid_zone(format!("cannot {} test record: {}", operation, response_code))),
+        };
+
+        // a leftover record from a previous check is not an issue, it is deleted right after
+        let created = client
+            .append(record.clone(), zone.clone(), false)
+            .map_err(|e| self.invalid_zone(format!("cannot create test record: {}", e)))?;
+        check_update_response(created.response_code(), "create")?;
+
+        let deleted = client
+            .delete_rrset(record, zone)
+            .map_err(|e| self.invalid_zone(format!("cannot delete test record: {}", e)))?;
+        check_update_response(deleted.response_code(), "delete")
+    }
+}
+
+impl DnsProvider for Rfc2136 {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn provider_name(&self) -> &str {
+        "rfc2136"
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Rfc2136
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn insert_into_teracontext<'a>(&self, context: &'a mut TeraContext) -> &'a mut TeraContext {
+        context.insert("external_dns_provider", &self.provider_name());
+        context.insert("rfc2136_nameserver_host", &self.dns_config.nameserver_host);
+        context.insert("rfc2136_nameserver_port", &self.dns_config.nameserver_port);
+        context.insert("rfc2136_zone", &self.dns_config.zone);
+        context.insert("rfc2136_tsig_key_name", &self.dns_config.tsig_key_name);
+        context.insert("rfc2136_tsig_secret", &self.dns_config.tsig_secret);
+        context.insert(
+            "rfc2136_tsig_algorithm",
+            self.dns_config.tsig_algorithm.to_external_dns_format(),
+        );
+        context
+    }
+
+    fn provider_configuration(&self) -> DnsProviderConfiguration {
+        DnsProviderConfiguration::Rfc2136(self.dns_config.clone())
+    }
+
+    fn domain(&self) -> &Domain {
+        &self.domain
+    }
+
+    fn resolvers(&self) -> Vec<Ipv4Addr> {
+        // the zone is most likely private, so its own nameserver is used when it is an IPv4
+        match self.dns_config.nameserver_host.parse::<Ipv4Addr>() {
+            Ok(nameserver) => vec![nameserver],
+            Err(_) => vec![Ipv4Addr::new(8, 8, 8, 8), Ipv4Addr::new(8, 8, 4, 4)],
+        }
+    }
+
+    fn is_valid(&self) -> Result<(), DnsProviderError> {
+        if self.dns_config.tsig_key_name.is_empty() || self.dns_config.tsig_secret.is_empty() {
+            return Err(DnsProviderError::InvalidCredentials);
+        }
+        if self.dns_config.nameserver_host.is_empty() {
+            return Err(DnsProviderError::InvalidApiUrl);
+        }
+        if !is_domain_in_zone(&self.domain.to_string(), &self.dns_config.zone) {
+            return Err(self.invalid_zone(format!(
+                "domain `{}` is not part of zone `{}`",
+                self.domain, self.dns_config.zone
+            )));
+        }
+
+        self.check_zone_updates()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tsig_algorithm_from_str() {
+        assert_eq!(
+            Rfc2136TsigAlgorithm::from_str("hmac-sha256"),
+            Ok(Rfc2136TsigAlgorithm::HmacSha256)
+        );
+        assert_eq!(
+            Rfc2136TsigAlgorithm::from_str("HMACSHA512"),
+            Ok(Rfc2136TsigAlgorithm::HmacSha512)
+        );
+        assert!(Rfc2136TsigAlgorithm::from_str("hmac-md5").is_err());
+        assert_eq!(Rfc2136TsigAlgorithm::HmacSha384.to_cert_manager_format(), "HMACSHA384");
+    }
+}
@@ -0,0 +1,174 @@
+use derivative::Derivative;
+use rusoto_core::{Client, HttpClient, Region as RusotoRegion, RusotoError};
+use rusoto_credential::StaticProvider;
+use rusoto_route53::{GetHostedZoneRequest, Route53 as _, Route53Client};
+use std::net::Ipv4Addr;
+use tera::Context as TeraContext;
+use url::Url;
+use uuid::Uuid;
+
+use crate::dns_provider::errors::DnsProviderError;
+use crate::dns_provider::{is_domain_in_zone, DnsProvider, DnsProviderConfiguration, Kind};
+use crate::io_models::context::Context;
+use crate::models::domain::Domain;
+use crate::runtime::block_on;
+
+const ROUTE53_API_ENDPOINT: &str = "https://route53.amazonaws.com";
+const ROUTE53_SIGNING_REGION: &str = "us-east-1";
+
+#[derive(Clone, Derivative)]
+#[derivative(Debug)]
+pub struct Route53DnsConfig {
+    pub access_key_id: String,
+    #[derivative(Debug = "ignore")]
+    pub secret_access_key: String,
+    pub region: String,
+    pub hosted_zone_id: String,
+}
+
+pub struct Route53 {
+    context: Context,
+    long_id: Uuid,
+    name: String,
+    domain: Domain,
+    dns_config: Route53DnsConfig,
+    api_endpoint: Url,
+}
+
+impl Route53 {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        name: &str,
+        domain: Domain,
+        access_key_id: &str,
+        secret_access_key: &str,
+        region: &str,
+        hosted_zone_id: &str,
+    ) -> Self {
+        Route53 {
+            context,
+            long_id,
+            name: name.to_string(),
+            domain,
+            dns_config: Route53DnsConfig {
+                access_key_id: access_key_id.to_string(),
+                secret_access_key: secret_access_key.to_string(),
+                region: region.to_string(),
+                // AWS returns ids as `/hostedzone/<id>`, only the id is expected by external-dns and cert-manager
+                hosted_zone_id: hosted_zone_id.trim_start_matches("/hostedzone/").to_string(),
+            },
+            api_endpoint: Url::parse(ROUTE53_API_ENDPOINT).expect("valid Route53 API endpoint"),
+        }
+    }
+
+    /// Overrides Route53 API endpoint, i.e: to target a local mock such as moto
+    pub fn with_api_endpoint(mut self, api_endpoint: Url) -> Self {
+        self.api_endpoint = api_endpoint;
+        self
+    }
+
+    fn get_route53_client(&self) -> Route53Client {
+        let credentials = StaticProvider::new(
+            self.dns_config.access_key_id.to_string(),
+            self.dns_config.secret_access_key.to_string(),
+            None,
+            None,
+        );
+        let client = Client::new_with(credentials, HttpClient::new().unwrap());
+
+        // Route53 is a global service, its requests are always signed for us-east-1
+        Route53Client::new_with_client(
+            client,
+            RusotoRegion::Custom {
+                name: ROUTE53_SIGNING_REGION.to_string(),
+                endpoint: self.api_endpoint.as_str().trim_end_matches('/').to_string(),
+            },
+        )
+    }
+
+    /// Returns the name of the configured hosted zone, without its trailing dot
+    fn get_hosted_zone_name(&self) -> Result<String, DnsProviderError> {
+        match block_on(self.get_route53_client().get_hosted_zone(GetHostedZoneRequest {
+            id: self.dns_config.hosted_zone_id.to_string(),
+        })) {
+            Ok(res) => Ok(res.hosted_zone.name.trim_end_matches('.').to_string()),
+            Err(RusotoError::Credentials(_)) => Err(DnsProviderError::InvalidCredentials),
+            Err(RusotoError::Unknown(res)) if [401, 403].contains(&res.status.as_u16()) => {
+                Err(DnsProviderError::InvalidCredentials)
+            }
+            Err(e) => Err(DnsProviderError::InvalidHostedZone {
+                hosted_zone_id: self.dns_config.hosted_zone_id.to_string(),
+                raw_error_message: e.to_string(),
+            }),
+        }
+    }
+}
+
+impl DnsProvider for Route53 {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn provider_name(&self) -> &str {
+        // name of the provider in external-dns
+        "aws"
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Route53
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn insert_into_teracontext<'a>(&self, context: &'a mut TeraContext) -> &'a mut TeraContext {
+        context.insert("external_dns_provider", &self.provider_name());
+        context.insert("route53_access_key_id", &self.dns_config.access_key_id);
+        context.insert("route53_secret_access_key", &self.dns_config.secret_access_key);
+        context.insert("route53_region", &self.dns_config.region);
+        context.insert("route53_hosted_zone_id", &self.dns_config.hosted_zone_id);
+        context
+    }
+
+    fn provider_configuration(&self) -> DnsProviderConfiguration {
+        DnsProviderConfiguration::Route53(self.dns_config.clone())
+    }
+
+    fn domain(&self) -> &Domain {
+        &self.domain
+    }
+
+    fn resolvers(&self) -> Vec<Ipv4Addr> {
+        vec![Ipv4Addr::new(8, 8, 8, 8), Ipv4Addr::new(8, 8, 4, 4)]
+    }
+
+    fn is_valid(&self) -> Result<(), DnsProviderError> {
+        if self.dns_config.access_key_id.is_empty() || self.dns_config.secret_access_key.is_empty() {
+            return Err(DnsProviderError::InvalidCredentials);
+        }
+        if self.dns_config.hosted_zone_id.is_empty() {
+            return Err(DnsProviderError::InvalidHostedZone {
+                hosted_zone_id: "".to_string(),
+                raw_error_message: "hosted zone id is not set".to_string(),
+            });
+        }
+
+        // credentials should give access to the hosted zone, and the zone should own the cluster domain
+        let zone_name = self.get_hosted_zone_name()?;
+        if !is_domain_in_zone(&self.domain.to_string(), &zone_name) {
+            return Err(DnsProviderError::InvalidHostedZone {
+                hosted_zone_id: self.dns_config.hosted_zone_id.to_string(),
+                raw_error_message: format!("domain `{}` is not part of hosted zone `{}`", self.domain, zone_name),
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ use thiserror::Error;
 use crate::build_platform::BuildPlatform;
 use crate::cloud_provider::kubernetes::Kubernetes;
 use crate::cloud_provider::CloudProvider;
+use crate::cmd::cosign::Cosign;
 use crate::container_registry::ContainerRegistry;
 use crate::dns_provider::DnsProvider;
 use crate::errors::EngineError;
@@ -45,6 +46,7 @@ pub struct InfrastructureContext {
     dns_provider: Box<dyn DnsProvider>,
     kubernetes: Box<dyn Kubernetes>,
     metrics_registry: Box<dyn MetricsRegistry>,
+    image_signer: Option<Cosign>,
     is_infra_deployment: bool,
     kube_client: Mutex<Option<QubeClient>>,
 }
@@ -58,6 +60,7 @@ impl InfrastructureContext {
         dns_provider: Box<dyn DnsProvider>,
         kubernetes: Box<dyn Kubernetes>,
         metrics_registry: Box<dyn MetricsRegistry>,
+        image_signer: Option<Cosign>,
         is_infra_deployment: bool,
     ) -> InfrastructureContext {
         InfrastructureContext {
@@ -68,11 +71,16 @@ impl InfrastructureContext {
             dns_provider,
             kubernetes,
             metrics_registry,
+            image_signer,
             is_infra_deployment,
             kube_client: Mutex::new(None),
         }
     }
 
+    pub fn image_signer(&self) -> Option<&Cosign> {
+        self.image_signer.as_ref()
+    }
+
     pub fn kubernetes(&self) -> &dyn Kubernetes {
         self.kubernetes.as_ref()
     }
@@ -0,0 +1,286 @@
+use super::Task;
+use crate::cmd::docker::Docker;
+use crate::engine::InfrastructureContext;
+use crate::engine_task::environment_task::EnvironmentTask;
+use crate::engine_task::qovery_api::QoveryApi;
+use crate::errors::{EngineError, ErrorMessageVerbosity};
+use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, Stage};
+use crate::io_models::context::Context;
+use crate::io_models::engine_request::DatabaseCredentialsRotationEngineRequest;
+use crate::log_file_writer::LogFileWriter;
+use crate::logger::Logger;
+use crate::metrics_registry::MetricsRegistry;
+use crate::models::abort::{Abort, AbortStatus, AtomicAbortStatus};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, RwLock};
+use std::{env, fs};
+use tokio::sync::broadcast;
+
+/// Give new credentials to container databases, without redeploying their environment.
+/// Only the databases are upgraded, the services using them are updated and restarted in place by the rotation.
+pub struct DatabaseCredentialsRotationTask {
+    workspace_root_dir: String,
+    lib_root_dir: String,
+    docker: Arc<Docker>,
+    request: DatabaseCredentialsRotationEngineRequest,
+    cancel_requested: Arc<AtomicAbortStatus>,
+    logger: Box<dyn Logger>,
+    metrics_registry: Box<dyn MetricsRegistry>,
+    qovery_api: Arc<dyn QoveryApi>,
+    span: tracing::Span,
+    is_terminated: (RwLock<Option<broadcast::Sender<()>>>, broadcast::Receiver<()>),
+    log_file_writer: Option<LogFileWriter>,
+}
+
+impl DatabaseCredentialsRotationTask {
+    pub fn new(
+        request: DatabaseCredentialsRotationEngineRequest,
+        workspace_root_dir: String,
+        lib_root_dir: String,
+        docker: Arc<Docker>,
+        logger: Box<dyn Logger>,
+        metrics_registry: Box<dyn MetricsRegistry>,
+        qovery_api: Box<dyn QoveryApi>,
+        log_file_writer: Option<LogFileWriter>,
+    ) -> Self {
+        let span = info_span!("database_credentials_rotation_task", execution_id = request.id);
+
+        let secrets = Self::get_secrets(&request);
+        DatabaseCredentialsRotationTask {
+            workspace_root_dir,
+            lib_root_dir,
+            docker,
+            request,
+            cancel_requested: Arc::new(AtomicAbortStatus::new(AbortStatus::None)),
+            logger: logger.with_secrets(secrets),
+            metrics_registry,
+            qovery_api: Arc::from(qovery_api),
+            span,
+            is_terminated: {
+                let (tx, rx) = broadcast::channel(1);
+                (RwLock::new(Some(tx)), rx)
+            },
+            log_file_writer,
+        }
+    }
+
+    fn info_context(&self) -> Context {
+        Context::new(
+            self.request.organization_long_id,
+            self.request.kubernetes.long_id,
+            self.request.id.to_string(),
+            self.workspace_root_dir.to_string(),
+            self.lib_root_dir.to_string(),
+            self.request.test_cluster,
+            self.request.features.clone(),
+            self.request.metadata.clone(),
+            self.docker.clone(),
+            self.qovery_api.clone(),
+            self.request.event_details(),
+        )
+    }
+
+    fn get_event_details(&self, step: EnvironmentStep) -> EventDetails {
+        EventDetails::clone_changing_stage(self.request.event_details(), Stage::Environment(step))
+    }
+
+    fn get_secrets(request: &DatabaseCredentialsRotationEngineRequest) -> Vec<String> {
+        let databases_secrets = request
+            .target_environment
+            .databases
+            .iter()
+            .map(|x| &x.password)
+            .filter(|password| !password.is_empty());
+
+        let cloud_provider_secrets = request
+            .cloud_provider
+            .options
+            .gcp_credentials
+            .as_ref()
+            .map(|x| &x.private_key)
+            .into_iter()
+            .chain(request.cloud_provider.options.secret_access_key.iter())
+            .chain(request.cloud_provider.options.password.iter())
+            .chain(request.cloud_provider.options.scaleway_secret_key.iter())
+            .chain(request.cloud_provider.options.spaces_secret_key.iter());
+
+        databases_secrets.chain(cloud_provider_secrets).cloned().collect()
+    }
+
+    fn rotate_credentials(&self, infra_ctx: &InfrastructureContext, abort: &dyn Abort) -> Result<(), Box<EngineError>> {
+        let event_details = self.get_event_details(EnvironmentStep::RotateCredentials);
+        let (target_environment, new_passwords) =
+            self.request.target_environment.to_environment_request(&self.request.id);
+        if new_passwords.is_empty() {
+            self.logger.log(EngineEvent::Info(
+                event_details,
+                EventMessage::new("No container database to rotate".to_string(), None),
+            ));
+            return Ok(());
+        }
+
+        // Registered before anything uses them, the logger being shared with the infrastructure context
+        let rotated_databases_count = new_passwords.len();
+        self.logger.register_secrets(new_passwords);
+        self.logger.log(EngineEvent::Info(
+            event_details.clone(),
+            EventMessage::new(
+                format!("🔑 New credentials generated for {rotated_databases_count} database(s) to rotate"),
+                None,
+            ),
+        ));
+
+        let environment = target_environment
+            .to_environment_domain(
+                infra_ctx.context(),
+                infra_ctx.cloud_provider(),
+                infra_ctx.container_registry(),
+                infra_ctx.kubernetes(),
+            )
+            .map_err(|err| {
+                Box::new(EngineError::new_invalid_engine_payload(
+                    event_details.clone(),
+                    err.to_string().as_str(),
+                    None,
+                ))
+            })?;
+
+        let env_logger = |msg: String| {
+            self.logger
+                .log(EngineEvent::Info(event_details.clone(), EventMessage::new(msg, None)));
+        };
+        EnvironmentTask::deploy_environment(environment, infra_ctx, env_logger, abort)
+    }
+}
+
+impl Task for DatabaseCredentialsRotationTask {
+    fn id(&self) -> &str {
+        self.request.id.as_str()
+    }
+
+    fn run(&self) {
+        if self.request.is_self_managed() {
+            super::enable_log_file_writer(&self.info_context(), &self.log_file_writer);
+        }
+
+        let _span = self.span.enter();
+        info!("database credentials rotation task {} started", self.id());
+
+        self.logger.log(EngineEvent::Info(
+            self.get_event_details(EnvironmentStep::Start),
+            EventMessage::new("🚀 Qovery Engine starts to rotate the database credentials".to_string(), None),
+        ));
+        let guard = scopeguard::guard((), |_| {
+            self.logger.log(EngineEvent::Info(
+                self.get_event_details(EnvironmentStep::Terminated),
+                EventMessage::new(
+                    "Qovery Engine has terminated the database credentials rotation".to_string(),
+                    None,
+                ),
+            ));
+            let Some(is_terminated_tx) = self.is_terminated.0.write().unwrap().take() else {
+                return;
+            };
+            let _ = is_terminated_tx.send(());
+        });
+
+        let infra_ctx = match self.request.engine(
+            &self.info_context(),
+            self.request.event_details(),
+            self.logger.clone(),
+            self.metrics_registry.clone(),
+            false,
+        ) {
+            Ok(infra_ctx) => infra_ctx,
+            Err(err) => {
+                self.logger.log(EngineEvent::Error(*err, None));
+                return;
+            }
+        };
+
+        match self.rotate_credentials(&infra_ctx, self.cancel_checker().as_ref()) {
+            Ok(()) => self.logger.log(EngineEvent::Info(
+                self.get_event_details(EnvironmentStep::CredentialsRotated),
+                EventMessage::new("✅ Database credentials are rotated".to_string(), None),
+            )),
+            Err(err) if err.tag().is_cancel() => self.logger.log(EngineEvent::Info(
+                self.get_event_details(EnvironmentStep::Cancelled),
+                EventMessage::new(
+                    "🚫 Database credentials rotation has been canceled at user request 🚫".to_string(),
+                    None,
+                ),
+            )),
+            Err(err) => self.logger.log(EngineEvent::Info(
+                self.get_event_details(EnvironmentStep::CredentialsRotatedError),
+                EventMessage::new(
+                    "💣 Database credentials rotation failed. Look at your databases status to know which one made it fail"
+                        .to_string(),
+                    Some(err.message(ErrorMessageVerbosity::FullDetailsWithoutEnvVars)),
+                ),
+            )),
+        };
+
+        // Uploading to S3 can take a lot of time, and might hit the core timeout
+        // So we early drop the guard to notify core that the task is done
+        drop(guard);
+        super::disable_log_file_writer(&self.log_file_writer);
+
+        // only store if not running on a workstation
+        if env::var("DEPLOY_FROM_FILE_KIND").is_err() {
+            match crate::fs::create_workspace_archive(
+                infra_ctx.context().workspace_root_dir(),
+                infra_ctx.context().execution_id(),
+            ) {
+                Ok(file) => match super::upload_s3_file(
+                    self.request.archive.as_ref(),
+                    infra_ctx.kubernetes().object_storage(),
+                    &file,
+                ) {
+                    Ok(_) => {
+                        let _ = fs::remove_file(file).map_err(|err| error!("Cannot remove file {}", err));
+                    }
+                    Err(e) => error!("Error while uploading archive {}", e),
+                },
+                Err(err) => error!("{}", err),
+            };
+        };
+
+        info!("database credentials rotation task {} finished", self.id());
+    }
+
+    fn cancel(&self, force_requested: bool) -> bool {
+        if self.is_terminated() {
+            info!("Skipping cancel action as the task is already terminated.");
+            return false;
+        }
+
+        self.cancel_requested.store(
+            match force_requested {
+                true => AbortStatus::UserForceRequested,
+                false => AbortStatus::Requested,
+            },
+            Ordering::Relaxed,
+        );
+        self.logger.log(EngineEvent::Info(
+            self.get_event_details(EnvironmentStep::Cancel),
+            EventMessage::new(
+                "🚫 Cancel received, database credentials rotation is going to stop.".to_string(),
+                None,
+            ),
+        ));
+        true
+    }
+
+    fn cancel_checker(&self) -> Box<dyn Abort> {
+        let cancel_requested = self.cancel_requested.clone();
+        Box::new(move || cancel_requested.load(Ordering::Relaxed))
+    }
+
+    fn is_terminated(&self) -> bool {
+        self.is_terminated.0.read().map(|tx| tx.is_none()).unwrap_or(true)
+    }
+
+    fn await_terminated(&self) -> broadcast::Receiver<()> {
+        self.is_terminated.1.resubscribe()
+    }
+}
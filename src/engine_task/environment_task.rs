@@ -1,29 +1,43 @@
 use super::Task;
 use crate::build_platform;
-use crate::build_platform::{BuildError, BuildPlatform};
+use crate::build_platform::{BuildError, BuildOutcome, BuildPlatform, Image};
 use crate::cloud_provider::environment::Environment;
 use crate::cloud_provider::service;
 use crate::cloud_provider::service::Service;
-use crate::cmd::docker::Docker;
+use crate::cmd::command::CommandKiller;
+use crate::cmd::cosign::Cosign;
+use crate::cmd::docker::{ContainerImage, Docker};
+use crate::cmd::oras::Oras;
+use crate::cmd::vulnerability_scanner::VulnerabilitySeverity;
 use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::retention::{
+    last_sweeps, list_images_in_use, record_sweeps, repositories_to_sweep, RetentionSweeperMode,
+};
+use crate::container_registry::vulnerability_scan;
+use crate::container_registry::vulnerability_scan::VulnerabilityScanConfig;
 use crate::container_registry::{to_engine_error, ContainerRegistry, RegistryTags};
 use crate::deployment_action::deploy_environment::EnvironmentDeployment;
 use crate::deployment_report::logger::EnvLogger;
 use crate::engine::InfrastructureContext;
 use crate::engine_task::qovery_api::QoveryApi;
-use crate::errors::{EngineError, ErrorMessageVerbosity};
+use crate::errors::{CommandError, EngineError, ErrorMessageVerbosity};
 use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, Stage};
 use crate::io_models::context::Context;
 use crate::io_models::engine_request::EnvironmentEngineRequest;
+use crate::io_models::environment::EnvironmentRequest;
+use crate::io_models::job::JobSource;
 use crate::io_models::Action;
 use crate::log_file_writer::LogFileWriter;
 use crate::logger::Logger;
 use crate::metrics_registry::{MetricsRegistry, StepLabel, StepName, StepRecordHandle, StepStatus};
 use crate::models::abort::{Abort, AbortStatus, AtomicAbortStatus};
+use crate::runtime::block_on;
+use crate::secret_manager::external_secret::{has_external_secrets, resolve_external_secrets};
 use base64::Engine;
+use chrono::Utc;
 use itertools::Itertools;
 use std::cmp::{max, min};
-use std::collections::{HashSet, VecDeque};
+use std::collections::{BTreeSet, HashSet, VecDeque};
 use std::num::NonZeroUsize;
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, RwLock};
@@ -140,13 +154,12 @@ impl EnvironmentTask {
         let metrics_registry: Arc<dyn MetricsRegistry> = Arc::from(infra_ctx.metrics_registry().clone_dyn());
         let services = services
             .into_iter()
-            .filter(|srv| {
-                if let Some(build) = srv.build() {
+            .filter(|srv| match srv.build() {
+                Some(build) => {
                     build_needs_buildpacks = build_needs_buildpacks || build.use_buildpacks();
                     true
-                } else {
-                    false
                 }
+                None => false,
             })
             .collect::<Vec<_>>();
 
@@ -183,6 +196,9 @@ impl EnvironmentTask {
         let resource_ttl = infra_ctx.kubernetes().advanced_settings().resource_ttl();
         let cr_registry = infra_ctx.container_registry();
         let build_platform = infra_ctx.build_platform();
+        let image_signer = infra_ctx.image_signer().filter(|signer| signer.can_sign());
+        let docker = infra_ctx.context().docker.as_ref();
+        let vulnerability_scan = VulnerabilityScanConfig::from_cluster(infra_ctx.kubernetes(), docker);
 
         services.iter().for_each(|service| {
             metrics_registry.start_record(*service.long_id(), StepLabel::Service, StepName::BuildQueueing);
@@ -198,6 +214,9 @@ impl EnvironmentTask {
                         option,
                         cr_registry,
                         build_platform,
+                        image_signer,
+                        vulnerability_scan.as_ref(),
+                        docker,
                         img_retention_time_sec,
                         RegistryTags {
                             environment_id: environment_id.to_string(),
@@ -227,6 +246,9 @@ impl EnvironmentTask {
         option: &DeploymentOption,
         cr_registry: &dyn ContainerRegistry,
         build_platform: &dyn BuildPlatform,
+        image_signer: Option<&Cosign>,
+        vulnerability_scan: Option<&VulnerabilityScanConfig>,
+        docker: &Docker,
         image_retention_time_sec: u32,
         registry_tags: RegistryTags,
         cr_to_engine_error: impl Fn(ContainerRegistryError) -> EngineError,
@@ -239,10 +261,43 @@ impl EnvironmentTask {
             Some(build) => build,
             None => return Ok(()), // this case should not happen as we filter on buildable services
         };
+
+        // For monorepos, the image tag can depend only on the sources of the service and not on the commit
+        if let Err(err) = build.resolve_source_tree_id(&logger) {
+            let msg = format!("❌ Application {} sources version cannot be computed: {}", &service.name(), err);
+            let event_details = service.get_event_details(Stage::Environment(EnvironmentStep::BuiltError));
+            let build_result = build_platform::to_engine_error(event_details, err, msg);
+            logger.send_error(build_result.clone());
+            return Err(Box::new(build_result));
+        }
         let image_name = build.image.full_image_name_with_tag();
 
+        // Promoted images are copied by the image promotion task, they are never built by a deployment
+        if build.image_promotion.is_some() && !cr_registry.image_exists(&build.image) {
+            let err = BuildError::InvalidConfig {
+                application: service.name().to_string(),
+                raw_error_message: format!(
+                    "Container image {image_name} has not been promoted to this cluster, the image promotion must succeed before deploying"
+                ),
+            };
+            let msg = format!("❌ Container image {image_name} is not promoted: {err}");
+            let event_details = service.get_event_details(Stage::Environment(EnvironmentStep::BuiltError));
+            let build_result = build_platform::to_engine_error(event_details, err, msg);
+            logger.send_error(build_result.clone());
+            return Err(Box::new(build_result));
+        }
+
         // If image already exists in the registry, skip the build
-        if !option.force_build && cr_registry.image_exists(&build.image) {
+        if (!option.force_build || build.image_promotion.is_some()) && cr_registry.image_exists(&build.image) {
+            Self::use_existing_image(
+                service,
+                image_signer,
+                vulnerability_scan,
+                docker,
+                &logger,
+                metrics_registry.as_ref(),
+                abort,
+            )?;
             let msg = format!("✅ Container image {image_name} already exists and ready to use");
             logger.send_success(msg);
             return Ok(());
@@ -274,7 +329,57 @@ impl EnvironmentTask {
         // Ok now everything is setup, we can try to build the app
         let build_result = build_platform.build(build, &logger, metrics_registry.clone(), abort);
         match build_result {
-            Ok(_) => {
+            // The image with the tag computed by the build platform may already exist
+            Ok(BuildOutcome::AlreadyExists) => {
+                Self::use_existing_image(
+                    service,
+                    image_signer,
+                    vulnerability_scan,
+                    docker,
+                    &logger,
+                    metrics_registry.as_ref(),
+                    abort,
+                )?;
+                let msg = format!("✅ Container image {} already exists and ready to use", &image_name);
+                logger.send_success(msg);
+                Ok(())
+            }
+            Ok(BuildOutcome::Built) => {
+                // The digest is resolved once, the image is then scanned, signed and deployed by this digest
+                let event_details = service.get_event_details(Stage::Environment(EnvironmentStep::BuiltError));
+                let digest = match Self::resolve_image_digest(&build.image, docker, event_details) {
+                    Ok(digest) => digest,
+                    Err(err) => {
+                        logger.send_error(*err.clone());
+                        return Err(err);
+                    }
+                };
+                build.image.digest = Some(digest.clone());
+                let built_image = build.image.clone();
+                let fail_on_severity = build.vulnerability_scan_fail_on_severity;
+
+                // Vulnerable images must be rejected before being signed
+                Self::check_image_vulnerabilities(
+                    service,
+                    &built_image,
+                    fail_on_severity,
+                    vulnerability_scan,
+                    &logger,
+                    EnvironmentStep::BuiltError,
+                    metrics_registry.as_ref(),
+                    abort,
+                )?;
+
+                if let Some(image_signer) = image_signer {
+                    let event_details = service.get_event_details(Stage::Environment(EnvironmentStep::BuiltError));
+                    if let Err(err) =
+                        Self::sign_image(&built_image, &digest, image_signer, docker, &logger, abort, event_details)
+                    {
+                        logger.send_error(*err.clone());
+                        return Err(err);
+                    }
+                }
+
                 let msg = format!("✅ Container image {} is built and ready to use", &image_name);
                 logger.send_success(msg);
                 Ok(())
@@ -290,7 +395,7 @@ impl EnvironmentTask {
                 logger.send_error(build_result.clone());
                 Err(Box::new(build_result))
             }
-            Err(err @ BuildError::DockerError { .. }) => {
+            Err(err @ (BuildError::DockerError { .. } | BuildError::BuildKitError { .. })) => {
                 let msg = format!(
                     "❌ Container image {} failed to be build: Look at the build logs to understand the error",
                     &image_name
@@ -319,6 +424,238 @@ impl EnvironmentTask {
         }
     }
 
+    /// Images are pushed before being scanned, and the scan verdict is not stored: images already in the registry
+    /// (skipped builds, promotions) are scanned again, so an image rejected once is never deployed by a later run.
+    fn check_image_vulnerabilities(
+        service: &dyn Service,
+        image: &Image,
+        fail_on_severity: Option<VulnerabilitySeverity>,
+        vulnerability_scan: Option<&VulnerabilityScanConfig>,
+        logger: &EnvLogger,
+        error_step: EnvironmentStep,
+        metrics_registry: &dyn MetricsRegistry,
+        abort: &dyn Abort,
+    ) -> Result<(), Box<EngineError>> {
+        let Some(scan_config) = vulnerability_scan else {
+            return Ok(());
+        };
+
+        Self::scan_image_vulnerabilities(image, fail_on_severity, scan_config, logger, metrics_registry, abort).map_err(
+            |err| {
+                let msg = format!(
+                    "❌ Container image {} did not pass the vulnerability scan",
+                    image.full_image_name_with_tag()
+                );
+                let event_details = service.get_event_details(Stage::Environment(error_step));
+                let engine_error = build_platform::to_engine_error(event_details, err, msg);
+                logger.send_error(engine_error.clone());
+                Box::new(engine_error)
+            },
+        )
+    }
+
+    /// Scan the pushed image and log its summary. When the scanner cannot run, the deployment is only blocked
+    /// if the service has a severity threshold, as we cannot tell if the image is safe.
+    pub(super) fn scan_image_vulnerabilities(
+        image: &Image,
+        fail_on_severity: Option<VulnerabilitySeverity>,
+        scan_config: &VulnerabilityScanConfig,
+        logger: &EnvLogger,
+        metrics_registry: &dyn MetricsRegistry,
+        abort: &dyn Abort,
+    ) -> Result<(), BuildError> {
+        // Scan the digest to deploy, if known, as the tag can be moved in the meantime
+        let container_image = match &image.digest {
+            Some(digest) => ContainerImage::new_for_digest(image.registry_url.clone(), image.name(), digest.clone()),
+            None => ContainerImage::new(image.registry_url.clone(), image.name(), vec![image.tag.clone()]),
+        };
+        let image_name = container_image.image_name();
+        let credentials = image
+            .registry_url
+            .password()
+            .map(|password| (image.registry_url.username(), password));
+        logger.send_progress(format!(
+            "🛡️ Scanning container image {} for vulnerabilities with {}",
+            image_name, scan_config.scanner
+        ));
+
+        let summary = match vulnerability_scan::scan_image(
+            scan_config,
+            image.service_long_id,
+            &container_image,
+            credentials,
+            !image.registry_insecure,
+            fail_on_severity,
+            metrics_registry,
+            abort,
+        ) {
+            Ok(summary) => summary,
+            Err(err) if err.is_aborted() => {
+                return Err(BuildError::Aborted {
+                    application: image.service_id.clone(),
+                })
+            }
+            Err(err) if fail_on_severity.is_some() => {
+                return Err(BuildError::VulnerabilityScanFailed {
+                    application: image.service_id.clone(),
+                    image_name,
+                    raw_error_message: format!("cannot scan image: {err}"),
+                })
+            }
+            Err(err) => {
+                logger.send_warning(format!(
+                    "⚠️ Cannot scan container image {image_name} for vulnerabilities: {err}"
+                ));
+                return Ok(());
+            }
+        };
+
+        for line in summary.log_lines() {
+            logger.send_progress(line);
+        }
+        if summary.is_blocking() {
+            return Err(BuildError::VulnerabilityScanFailed {
+                application: image.service_id.clone(),
+                image_name,
+                raw_error_message: summary.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the digest of the image currently pushed under its tag
+    fn resolve_image_digest(
+        image: &Image,
+        docker: &Docker,
+        event_details: EventDetails,
+    ) -> Result<String, Box<EngineError>> {
+        let container_image = ContainerImage::new(image.registry_url.clone(), image.name(), vec![image.tag.clone()]);
+        Oras::new(docker.config_path())
+            .resolve_digest(&container_image, !image.registry_insecure)
+            .map_err(|err| {
+                Box::new(EngineError::new_container_registry_error(
+                    event_details,
+                    ContainerRegistryError::CannotResolveImageDigest {
+                        registry_name: image.registry_name.clone(),
+                        image_name: image.full_image_name_with_tag(),
+                        raw_error_message: err.to_string(),
+                    },
+                ))
+            })
+    }
+
+    /// An image already in the registry has not been built by this deployment, so it is never signed: its signature
+    /// must be valid for it to be deployed. It is then pinned to the verified digest and scanned.
+    fn use_existing_image(
+        service: &mut dyn Service,
+        image_signer: Option<&Cosign>,
+        vulnerability_scan: Option<&VulnerabilityScanConfig>,
+        docker: &Docker,
+        logger: &EnvLogger,
+        metrics_registry: &dyn MetricsRegistry,
+        abort: &dyn Abort,
+    ) -> Result<(), Box<EngineError>> {
+        let event_details = service.get_event_details(Stage::Environment(EnvironmentStep::BuiltError));
+        let Some(build) = service.build_mut() else {
+            return Ok(());
+        };
+
+        let digest = match Self::verify_existing_image(&build.image, image_signer, docker, logger, abort, event_details)
+        {
+            Ok(digest) => digest,
+            Err(err) => {
+                logger.send_error(*err.clone());
+                return Err(err);
+            }
+        };
+        build.image.digest = Some(digest);
+        let existing_image = build.image.clone();
+        let fail_on_severity = build.vulnerability_scan_fail_on_severity;
+
+        Self::check_image_vulnerabilities(
+            service,
+            &existing_image,
+            fail_on_severity,
+            vulnerability_scan,
+            logger,
+            EnvironmentStep::BuiltError,
+            metrics_registry,
+            abort,
+        )
+    }
+
+    /// Resolve the digest of an image already in the registry, and verify its signature when signing is enabled.
+    /// The returned digest is the one to deploy, as the tag can be moved after the verification.
+    fn verify_existing_image(
+        image: &Image,
+        image_signer: Option<&Cosign>,
+        docker: &Docker,
+        logger: &EnvLogger,
+        abort: &dyn Abort,
+        event_details: EventDetails,
+    ) -> Result<String, Box<EngineError>> {
+        let digest = Self::resolve_image_digest(image, docker, event_details.clone())?;
+        let Some(image_signer) = image_signer else {
+            return Ok(digest);
+        };
+
+        let image_name = image.full_image_name_with_tag();
+        let container_image = ContainerImage::new_for_digest(image.registry_url.clone(), image.name(), digest.clone());
+        match image_signer.verify(
+            &container_image,
+            docker.config_path(),
+            !image.registry_insecure,
+            &CommandKiller::from(Duration::from_secs(5 * 60), abort),
+        ) {
+            Ok(_) => {
+                logger.send_progress(format!("🔏 Container image {image_name} signature is valid ({digest})"));
+                Ok(digest)
+            }
+            Err(err) if err.is_aborted() => Err(Box::new(EngineError::new_task_cancellation_requested(event_details))),
+            Err(err) => Err(Box::new(EngineError::new_image_signature_verification_failed(
+                event_details,
+                image_name,
+                CommandError::new("Image signature verification failed".to_string(), Some(err.to_string()), None),
+            ))),
+        }
+    }
+
+    /// Sign the image `digest`, which must have been built or verified by this deployment. Never sign a tag, as
+    /// what it points to can change between its resolution and the signature.
+    pub(super) fn sign_image(
+        image: &Image,
+        digest: &str,
+        image_signer: &Cosign,
+        docker: &Docker,
+        logger: &EnvLogger,
+        abort: &dyn Abort,
+        event_details: EventDetails,
+    ) -> Result<(), Box<EngineError>> {
+        let image_name = image.full_image_name_with_tag();
+        let container_image = ContainerImage::new(image.registry_url.clone(), image.name(), vec![image.tag.clone()]);
+        logger.send_progress(format!("🔏 Signing container image {image_name}"));
+
+        match image_signer.sign(
+            &container_image,
+            digest,
+            docker.config_path(),
+            !image.registry_insecure,
+            &CommandKiller::from(Duration::from_secs(10 * 60), abort),
+        ) {
+            Ok(_) => {
+                logger.send_progress(format!("🔏 Container image {image_name} is signed ({digest})"));
+                Ok(())
+            }
+            Err(err) if err.is_aborted() => Err(Box::new(EngineError::new_task_cancellation_requested(event_details))),
+            Err(err) => Err(Box::new(EngineError::new_image_signing_error(
+                event_details,
+                image_name,
+                CommandError::new("Cannot sign image".to_string(), Some(err.to_string()), None),
+            ))),
+        }
+    }
+
     pub fn deploy_environment(
         mut environment: Environment,
         infra_ctx: &InfrastructureContext,
@@ -372,6 +709,9 @@ impl EnvironmentTask {
 
         let deployment_err = match run_deploy() {
             Ok(_) => {
+                if environment.action == service::Action::Create {
+                    Self::sweep_expired_images(&environment, infra_ctx);
+                }
                 return Ok(());
             } // return early if no error
             Err(err) => err,
@@ -399,6 +739,109 @@ impl EnvironmentTask {
         Err(deployment_err)
     }
 
+    // Delete the expired images of the services of the environment, for registries not enforcing the retention themselves.
+    // It is best effort, a registry that cannot be cleaned must not fail the deployment
+    fn sweep_expired_images(environment: &Environment, infra_ctx: &InfrastructureContext) {
+        let advanced_settings = infra_ctx.kubernetes().advanced_settings();
+        let dry_run = match advanced_settings.registry_retention_sweeper_mode {
+            RetentionSweeperMode::Disabled => return,
+            RetentionSweeperMode::DryRun => true,
+            RetentionSweeperMode::Enabled => false,
+        };
+        let retention = Duration::from_secs(advanced_settings.registry_image_retention_time_sec as u64);
+        let log = |msg: String| {
+            infra_ctx.kubernetes().logger().log(EngineEvent::Info(
+                environment.event_details().clone(),
+                EventMessage::new_from_safe(msg),
+            ))
+        };
+
+        let kube_client = match infra_ctx.mk_kube_client() {
+            Ok(kube_client) => kube_client.client().clone(),
+            Err(err) => {
+                warn!("Cannot connect to the cluster, skipping registry retention: {}", err);
+                return;
+            }
+        };
+
+        let repositories: BTreeSet<String> = environment
+            .applications
+            .iter()
+            .filter_map(|app| app.build())
+            .chain(environment.jobs.iter().filter_map(|job| job.build()))
+            .map(|build| build.image.name())
+            .collect();
+        let interval = Duration::from_secs(advanced_settings.registry_retention_sweeper_interval_sec as u64);
+        let now = Utc::now();
+        let repositories = match block_on(last_sweeps(kube_client.clone())) {
+            Ok(last_sweeps) => repositories_to_sweep(repositories, &last_sweeps, interval, now),
+            Err(err) => {
+                warn!(
+                    "Cannot get the last registry retention sweeps, skipping registry retention: {}",
+                    err
+                );
+                return;
+            }
+        };
+        if repositories.is_empty() {
+            return;
+        }
+        // recorded first, so concurrent deployments do not sweep the same repositories
+        if let Err(err) = block_on(record_sweeps(kube_client.clone(), &repositories, now)) {
+            warn!(
+                "Cannot record the registry retention sweeps, skipping registry retention: {}",
+                err
+            );
+            return;
+        }
+
+        let images_in_use = match block_on(list_images_in_use(kube_client)) {
+            Ok(images_in_use) => images_in_use,
+            Err(err) => {
+                warn!("Cannot list images used by the cluster, skipping registry retention: {}", err);
+                return;
+            }
+        };
+
+        for repository in repositories {
+            match infra_ctx
+                .container_registry()
+                .sweep_expired_images(&repository, retention, &images_in_use, dry_run)
+            {
+                Ok(report) if report.expired.is_empty() => {}
+                Ok(report) => log(format!("🧹 Registry retention, {}", report)),
+                Err(err) => warn!("Cannot apply registry retention on {}: {}", repository, err),
+            }
+        }
+    }
+
+    /// Fetches the external secrets referenced by environment variables. As their values must never appear in logs,
+    /// they are registered to the logger shared with the infrastructure context before being used.
+    fn prepare_secrets(
+        &self,
+        target_environment: &mut EnvironmentRequest,
+        infra_context: &InfrastructureContext,
+        event_details: &EventDetails,
+    ) -> Result<(), Box<EngineError>> {
+        if has_external_secrets(target_environment) {
+            let kube_client = infra_context.mk_kube_client()?;
+            let resolved_secrets = resolve_external_secrets(target_environment, kube_client.client(), event_details)?;
+            if !resolved_secrets.is_empty() {
+                let resolved_secrets_count = resolved_secrets.len();
+                self.logger.register_secrets(resolved_secrets);
+                self.logger.log(EngineEvent::Info(
+                    event_details.clone(),
+                    EventMessage::new(
+                        format!("🔐 {resolved_secrets_count} external secret(s) fetched from their secret store"),
+                        None,
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     fn get_secrets(request: &EnvironmentEngineRequest) -> Vec<String> {
         let mut secrets = vec![];
         let services_secrets = request
@@ -439,6 +882,18 @@ impl EnvironmentTask {
         });
         secrets.extend(service_secrets);
 
+        let source_archive_secrets = request
+            .target_environment
+            .applications
+            .iter()
+            .filter_map(|x| x.source_archive.as_ref())
+            .chain(request.target_environment.jobs.iter().filter_map(|x| match &x.source {
+                JobSource::Archive { source_archive, .. } => Some(source_archive),
+                _ => None,
+            }))
+            .flat_map(|x| x.secrets());
+        secrets.extend(source_archive_secrets);
+
         let cloud_provider_secrets = request
             .cloud_provider
             .options
@@ -453,6 +908,14 @@ impl EnvironmentTask {
             .cloned();
 
         secrets.extend(cloud_provider_secrets);
+        secrets.extend(request.build_platform.options.buildkit_tls_client_key.iter().cloned());
+
+        let image_signing_secrets = request
+            .image_signing
+            .iter()
+            .flat_map(|x| x.private_key.iter().chain(x.private_key_password.iter()))
+            .cloned();
+        secrets.extend(image_signing_secrets);
         secrets
     }
 
@@ -516,7 +979,12 @@ impl Task for EnvironmentTask {
             .to_service_action()
             .to_environment_step();
         let event_details = self.get_event_details(env_step);
-        let environment = match self.request.target_environment.to_environment_domain(
+        let mut target_environment = self.request.target_environment.clone();
+        if let Err(err) = self.prepare_secrets(&mut target_environment, &infra_context, &event_details) {
+            self.logger.log(EngineEvent::Error(*err, None));
+            return;
+        }
+        let environment = match target_environment.to_environment_domain(
             infra_context.context(),
             infra_context.cloud_provider(),
             infra_context.container_registry(),
@@ -634,7 +1102,11 @@ impl Task for EnvironmentTask {
                 infra_context.context().workspace_root_dir(),
                 infra_context.context().execution_id(),
             ) {
-                Ok(file) => match super::upload_s3_file(self.request.archive.as_ref(), &file) {
+                Ok(file) => match super::upload_s3_file(
+                    self.request.archive.as_ref(),
+                    infra_context.kubernetes().object_storage(),
+                    &file,
+                ) {
                     Ok(_) => {
                         let _ = fs::remove_file(file).map_err(|err| error!("Cannot remove file {}", err));
                     }
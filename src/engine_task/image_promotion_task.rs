@@ -0,0 +1,430 @@
+use super::Task;
+use crate::build_platform;
+use crate::build_platform::Image;
+use crate::cmd::command::CommandKiller;
+use crate::cmd::cosign::Cosign;
+use crate::cmd::docker::Docker;
+use crate::container_registry::vulnerability_scan::VulnerabilityScanConfig;
+use crate::container_registry::{to_engine_error, ImagePromotion, RegistryTags};
+use crate::deployment_report::logger::EnvLogger;
+use crate::engine::InfrastructureContext;
+use crate::engine_task::environment_task::EnvironmentTask;
+use crate::engine_task::qovery_api::QoveryApi;
+use crate::errors::{CommandError, EngineError, ErrorMessageVerbosity};
+use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, Stage, Transmitter};
+use crate::io_models::context::Context;
+use crate::io_models::engine_request::ImagePromotionEngineRequest;
+use crate::io_models::image_promotion::ApplicationImagePromotion;
+use crate::io_models::QoveryIdentifier;
+use crate::log_file_writer::LogFileWriter;
+use crate::logger::Logger;
+use crate::metrics_registry::{MetricsRegistry, StepLabel, StepName, StepStatus};
+use crate::models::abort::{Abort, AbortStatus, AtomicAbortStatus};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use std::{env, fs};
+use tokio::sync::broadcast;
+
+/// Copy images already built for another cluster into the cluster registry, without deploying anything.
+/// The promoted applications are then deployed by an environment task, which finds their image instead of building it.
+pub struct ImagePromotionTask {
+    workspace_root_dir: String,
+    lib_root_dir: String,
+    docker: Arc<Docker>,
+    request: ImagePromotionEngineRequest,
+    cancel_requested: Arc<AtomicAbortStatus>,
+    logger: Box<dyn Logger>,
+    metrics_registry: Box<dyn MetricsRegistry>,
+    qovery_api: Arc<dyn QoveryApi>,
+    span: tracing::Span,
+    is_terminated: (RwLock<Option<broadcast::Sender<()>>>, broadcast::Receiver<()>),
+    log_file_writer: Option<LogFileWriter>,
+}
+
+impl ImagePromotionTask {
+    pub fn new(
+        request: ImagePromotionEngineRequest,
+        workspace_root_dir: String,
+        lib_root_dir: String,
+        docker: Arc<Docker>,
+        logger: Box<dyn Logger>,
+        metrics_registry: Box<dyn MetricsRegistry>,
+        qovery_api: Box<dyn QoveryApi>,
+        log_file_writer: Option<LogFileWriter>,
+    ) -> Self {
+        let span = info_span!("image_promotion_task", execution_id = request.id);
+
+        let secrets = Self::get_secrets(&request);
+        ImagePromotionTask {
+            workspace_root_dir,
+            lib_root_dir,
+            docker,
+            request,
+            cancel_requested: Arc::new(AtomicAbortStatus::new(AbortStatus::None)),
+            logger: logger.with_secrets(secrets),
+            metrics_registry,
+            qovery_api: Arc::from(qovery_api),
+            span,
+            is_terminated: {
+                let (tx, rx) = broadcast::channel(1);
+                (RwLock::new(Some(tx)), rx)
+            },
+            log_file_writer,
+        }
+    }
+
+    fn info_context(&self) -> Context {
+        Context::new(
+            self.request.organization_long_id,
+            self.request.kubernetes.long_id,
+            self.request.id.to_string(),
+            self.workspace_root_dir.to_string(),
+            self.lib_root_dir.to_string(),
+            self.request.test_cluster,
+            self.request.features.clone(),
+            self.request.metadata.clone(),
+            self.docker.clone(),
+            self.qovery_api.clone(),
+            self.request.event_details(),
+        )
+    }
+
+    fn get_event_details(&self, step: EnvironmentStep) -> EventDetails {
+        EventDetails::clone_changing_stage(self.request.event_details(), Stage::Environment(step))
+    }
+
+    fn get_application_event_details(&self, application: &ApplicationImagePromotion) -> EventDetails {
+        EventDetails::clone_changing_transmitter(
+            self.request.event_details(),
+            Transmitter::Application(application.long_id, application.name.clone()),
+        )
+    }
+
+    fn get_secrets(request: &ImagePromotionEngineRequest) -> Vec<String> {
+        let source_registry_secrets = request
+            .target_environment
+            .applications
+            .iter()
+            .filter_map(|x| x.image_promotion.registry.get_url_with_credentials().ok())
+            .filter_map(|url| url.password().map(|password| password.to_string()));
+
+        let cloud_provider_secrets = request
+            .cloud_provider
+            .options
+            .gcp_credentials
+            .as_ref()
+            .map(|x| &x.private_key)
+            .into_iter()
+            .chain(request.cloud_provider.options.secret_access_key.iter())
+            .chain(request.cloud_provider.options.password.iter())
+            .chain(request.cloud_provider.options.scaleway_secret_key.iter())
+            .chain(request.cloud_provider.options.spaces_secret_key.iter())
+            .cloned();
+
+        let image_signing_secrets = request
+            .image_signing
+            .iter()
+            .flat_map(|x| x.private_key.iter().chain(x.private_key_password.iter()))
+            .cloned();
+
+        source_registry_secrets
+            .chain(cloud_provider_secrets)
+            .chain(image_signing_secrets)
+            .collect()
+    }
+
+    fn promote_images(&self, infra_ctx: &InfrastructureContext, abort: &dyn Abort) -> Result<(), Box<EngineError>> {
+        let logger = Arc::new(self.logger.clone());
+        let registry_info = infra_ctx.container_registry().registry_info();
+        let cluster_id = QoveryIdentifier::new(self.request.kubernetes.long_id);
+
+        for application in &self.request.target_environment.applications {
+            if abort.status().should_cancel() {
+                return Err(Box::new(EngineError::new_task_cancellation_requested(
+                    self.get_event_details(EnvironmentStep::Cancelled),
+                )));
+            }
+
+            let event_details = self.get_application_event_details(application);
+            let image = application.to_image(registry_info, &cluster_id);
+            let env_logger =
+                EnvLogger::new_for_event_details(event_details.clone(), EnvironmentStep::Promote, logger.clone());
+            if let Err(err) = self.promote_image(application, &image, infra_ctx, &env_logger, event_details, abort) {
+                env_logger.send_error(*err.clone());
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The image is copied by digest, so only the source digest needs to be verified before signing the copy
+    fn promote_image(
+        &self,
+        application: &ApplicationImagePromotion,
+        image: &Image,
+        infra_ctx: &InfrastructureContext,
+        logger: &EnvLogger,
+        event_details: EventDetails,
+        abort: &dyn Abort,
+    ) -> Result<(), Box<EngineError>> {
+        let image_promotion = &application.image_promotion;
+        let error_event_details = || {
+            EventDetails::clone_changing_stage(
+                event_details.clone(),
+                Stage::Environment(EnvironmentStep::PromotedError),
+            )
+        };
+        let metrics_registry = infra_ctx.metrics_registry();
+        let advanced_settings = infra_ctx.kubernetes().advanced_settings();
+        let cr_registry = infra_ctx.container_registry();
+        let image_signer = infra_ctx.image_signer().filter(|signer| signer.can_sign());
+        let docker = infra_ctx.context().docker.as_ref();
+        let image_name = image.full_image_name_with_tag();
+
+        // Verified before being copied, an image with an invalid signature never reaches the cluster registry
+        if let Some(image_signer) = image_signer {
+            Self::verify_promotion_source(image_promotion, image_signer, docker, logger, abort, error_event_details())?;
+        }
+
+        if cr_registry.image_exists(image) {
+            logger.send_progress(format!("🎯 Container image {image_name} is already promoted"));
+        } else {
+            cr_registry
+                .create_repository(
+                    image.repository_name(),
+                    advanced_settings.registry_image_retention_time_sec,
+                    RegistryTags {
+                        environment_id: self.request.target_environment.long_id.to_string(),
+                        project_id: self.request.target_environment.project_long_id.to_string(),
+                        resource_ttl: advanced_settings.resource_ttl(),
+                    },
+                )
+                .map_err(|err| Box::new(to_engine_error(error_event_details(), err)))?;
+
+            logger.send_progress(format!(
+                "🚚 Promoting container image {} to {}",
+                image_promotion.source_image().image_name(),
+                &image_name
+            ));
+            let promote_record =
+                metrics_registry.start_record(image.service_long_id, StepLabel::Service, StepName::PromoteImage);
+            if let Err(err) = cr_registry.promote_image(image_promotion, image, abort) {
+                promote_record.stop(StepStatus::Error);
+                return Err(Box::new(if abort.status().should_cancel() {
+                    EngineError::new_task_cancellation_requested(EventDetails::clone_changing_stage(
+                        event_details.clone(),
+                        Stage::Environment(EnvironmentStep::Cancelled),
+                    ))
+                } else {
+                    to_engine_error(error_event_details(), err)
+                }));
+            }
+            promote_record.stop(StepStatus::Success);
+        }
+
+        // The tag can be moved, so what is scanned and signed is the promoted digest
+        let promoted_image = Image {
+            digest: Some(image_promotion.digest.clone()),
+            ..image.clone()
+        };
+        if let Some(scan_config) = VulnerabilityScanConfig::from_cluster(infra_ctx.kubernetes(), docker) {
+            EnvironmentTask::scan_image_vulnerabilities(
+                &promoted_image,
+                application.vulnerability_scan_fail_on_severity,
+                &scan_config,
+                logger,
+                metrics_registry,
+                abort,
+            )
+            .map_err(|err| {
+                let msg = format!("❌ Container image {image_name} did not pass the vulnerability scan");
+                Box::new(build_platform::to_engine_error(error_event_details(), err, msg))
+            })?;
+        }
+
+        if let Some(image_signer) = image_signer {
+            EnvironmentTask::sign_image(
+                &promoted_image,
+                &image_promotion.digest,
+                image_signer,
+                docker,
+                logger,
+                abort,
+                error_event_details(),
+            )?;
+        }
+
+        logger.send_success(format!("✅ Container image {image_name} is promoted and ready to be deployed"));
+        Ok(())
+    }
+
+    /// Verify the signature of the promoted digest in its source registry
+    fn verify_promotion_source(
+        image_promotion: &ImagePromotion,
+        image_signer: &Cosign,
+        docker: &Docker,
+        logger: &EnvLogger,
+        abort: &dyn Abort,
+        event_details: EventDetails,
+    ) -> Result<(), Box<EngineError>> {
+        let source_image = image_promotion.source_image();
+        let image_name = source_image.image_name();
+        let verification_failed = |raw_error_message: String| {
+            Box::new(EngineError::new_image_signature_verification_failed(
+                event_details.clone(),
+                image_name.clone(),
+                CommandError::new("Image signature verification failed".to_string(), Some(raw_error_message), None),
+            ))
+        };
+
+        // cosign reads the source registry credentials from the docker config
+        let source_url = image_promotion
+            .registry
+            .get_url_with_credentials()
+            .map_err(|err| verification_failed(err.to_string()))?;
+        if source_url.password().is_some() {
+            docker
+                .login(&source_url)
+                .map_err(|err| verification_failed(err.to_string()))?;
+        }
+
+        match image_signer.verify(
+            &source_image,
+            docker.config_path(),
+            true,
+            &CommandKiller::from(Duration::from_secs(5 * 60), abort),
+        ) {
+            Ok(_) => {
+                logger.send_progress(format!("🔏 Container image {image_name} signature is valid"));
+                Ok(())
+            }
+            Err(err) if err.is_aborted() => Err(Box::new(EngineError::new_task_cancellation_requested(event_details))),
+            Err(err) => Err(verification_failed(err.to_string())),
+        }
+    }
+}
+
+impl Task for ImagePromotionTask {
+    fn id(&self) -> &str {
+        self.request.id.as_str()
+    }
+
+    fn run(&self) {
+        if self.request.is_self_managed() {
+            super::enable_log_file_writer(&self.info_context(), &self.log_file_writer);
+        }
+
+        let _span = self.span.enter();
+        info!("image promotion task {} started", self.id());
+
+        self.logger.log(EngineEvent::Info(
+            self.get_event_details(EnvironmentStep::Start),
+            EventMessage::new("🚀 Qovery Engine starts to promote the images".to_string(), None),
+        ));
+        let guard = scopeguard::guard((), |_| {
+            self.logger.log(EngineEvent::Info(
+                self.get_event_details(EnvironmentStep::Terminated),
+                EventMessage::new("Qovery Engine has terminated the image promotion".to_string(), None),
+            ));
+            let Some(is_terminated_tx) = self.is_terminated.0.write().unwrap().take() else {
+                return;
+            };
+            let _ = is_terminated_tx.send(());
+        });
+
+        let infra_ctx = match self.request.engine(
+            &self.info_context(),
+            self.request.event_details(),
+            self.logger.clone(),
+            self.metrics_registry.clone(),
+            false,
+        ) {
+            Ok(infra_ctx) => infra_ctx,
+            Err(err) => {
+                self.logger.log(EngineEvent::Error(*err, None));
+                return;
+            }
+        };
+
+        match self.promote_images(&infra_ctx, self.cancel_checker().as_ref()) {
+            Ok(()) => self.logger.log(EngineEvent::Info(
+                self.get_event_details(EnvironmentStep::Promoted),
+                EventMessage::new("✅ Images are promoted and ready to be deployed".to_string(), None),
+            )),
+            Err(err) if err.tag().is_cancel() => self.logger.log(EngineEvent::Info(
+                self.get_event_details(EnvironmentStep::Cancelled),
+                EventMessage::new("🚫 Image promotion has been canceled at user request 🚫".to_string(), None),
+            )),
+            Err(err) => self.logger.log(EngineEvent::Info(
+                self.get_event_details(EnvironmentStep::PromotedError),
+                EventMessage::new(
+                    "💣 Image promotion failed. Look at your services status to know which one made it fail"
+                        .to_string(),
+                    Some(err.message(ErrorMessageVerbosity::FullDetailsWithoutEnvVars)),
+                ),
+            )),
+        };
+
+        // Uploading to S3 can take a lot of time, and might hit the core timeout
+        // So we early drop the guard to notify core that the task is done
+        drop(guard);
+        super::disable_log_file_writer(&self.log_file_writer);
+
+        // only store if not running on a workstation
+        if env::var("DEPLOY_FROM_FILE_KIND").is_err() {
+            match crate::fs::create_workspace_archive(
+                infra_ctx.context().workspace_root_dir(),
+                infra_ctx.context().execution_id(),
+            ) {
+                Ok(file) => match super::upload_s3_file(
+                    self.request.archive.as_ref(),
+                    infra_ctx.kubernetes().object_storage(),
+                    &file,
+                ) {
+                    Ok(_) => {
+                        let _ = fs::remove_file(file).map_err(|err| error!("Cannot remove file {}", err));
+                    }
+                    Err(e) => error!("Error while uploading archive {}", e),
+                },
+                Err(err) => error!("{}", err),
+            };
+        };
+
+        info!("image promotion task {} finished", self.id());
+    }
+
+    fn cancel(&self, force_requested: bool) -> bool {
+        if self.is_terminated() {
+            info!("Skipping cancel action as the task is already terminated.");
+            return false;
+        }
+
+        self.cancel_requested.store(
+            match force_requested {
+                true => AbortStatus::UserForceRequested,
+                false => AbortStatus::Requested,
+            },
+            Ordering::Relaxed,
+        );
+        self.logger.log(EngineEvent::Info(
+            self.get_event_details(EnvironmentStep::Cancel),
+            EventMessage::new("🚫 Cancel received, image promotion is going to stop.".to_string(), None),
+        ));
+        true
+    }
+
+    fn cancel_checker(&self) -> Box<dyn Abort> {
+        let cancel_requested = self.cancel_requested.clone();
+        Box::new(move || cancel_requested.load(Ordering::Relaxed))
+    }
+
+    fn is_terminated(&self) -> bool {
+        self.is_terminated.0.read().map(|tx| tx.is_none()).unwrap_or(true)
+    }
+
+    fn await_terminated(&self) -> broadcast::Receiver<()> {
+        self.is_terminated.1.resubscribe()
+    }
+}
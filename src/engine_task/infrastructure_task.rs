@@ -208,7 +208,11 @@ impl Task for InfrastructureTask {
                 infra_ctx.context().workspace_root_dir(),
                 infra_ctx.context().execution_id(),
             ) {
-                Ok(file) => match super::upload_s3_file(self.request.archive.as_ref(), &file) {
+                Ok(file) => match super::upload_s3_file(
+                    self.request.archive.as_ref(),
+                    infra_ctx.kubernetes().object_storage(),
+                    &file,
+                ) {
                     Ok(_) => {
                         let _ = fs::remove_file(file).map_err(|err| error!("Cannot delete file {}", err));
                     }
@@ -3,12 +3,15 @@ use crate::io_models::context::Context;
 use crate::io_models::engine_request::Archive;
 use crate::log_file_writer::LogFileWriter;
 use crate::models::abort::Abort;
+use crate::object_storage::ObjectStorage;
 use reqwest::header::CONTENT_TYPE;
 use std::path::Path;
 use std::time::Duration;
 use tokio::sync::broadcast;
 
+pub mod database_credentials_rotation_task;
 pub mod environment_task;
+pub mod image_promotion_task;
 pub mod infrastructure_task;
 pub mod qovery_api;
 
@@ -21,7 +24,11 @@ pub trait Task: Send + Sync {
     fn await_terminated(&self) -> broadcast::Receiver<()>;
 }
 
-fn upload_s3_file(archive: Option<&Archive>, file_path: &Path) -> Result<(), anyhow::Error> {
+fn upload_s3_file(
+    archive: Option<&Archive>,
+    object_storage: Option<&dyn ObjectStorage>,
+    file_path: &Path,
+) -> Result<(), anyhow::Error> {
     let archive = match archive {
         Some(archive) => archive,
         None => {
@@ -30,6 +37,20 @@ fn upload_s3_file(archive: Option<&Archive>, file_path: &Path) -> Result<(), any
         }
     };
 
+    if let (Some(location), Some(object_storage)) = (&archive.bucket_location, object_storage) {
+        info!(
+            "Sending file {} to bucket {}/{} of {}",
+            file_path.to_str().unwrap_or_default(),
+            location.bucket_name,
+            location.object_key,
+            object_storage.name_with_id()
+        );
+
+        let mut file = std::fs::File::open(file_path)?;
+        object_storage.write_object(&location.bucket_name, &location.object_key, &mut file, None)?;
+        return Ok(());
+    }
+
     info!(
         "Sending file {} to bucket {}://{}{}",
         file_path.to_str().unwrap_or_default(),
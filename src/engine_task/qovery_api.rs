@@ -16,6 +16,7 @@ pub trait QoveryApi: Send + Sync {
     fn git_token(&self, service_type: ServiceType, service_id: &Uuid) -> anyhow::Result<GitCredentials>;
 
     fn update_cluster_credentials(&self, kubeconfig: String) -> anyhow::Result<()>;
+    fn update_database_credentials(&self, database_id: &Uuid, login: &str, password: &str) -> anyhow::Result<()>;
 }
 
 pub struct FakeQoveryApi {}
@@ -32,6 +33,10 @@ impl QoveryApi for FakeQoveryApi {
     fn update_cluster_credentials(&self, _kubeconfig: String) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn update_database_credentials(&self, _database_id: &Uuid, _login: &str, _password: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 pub struct StaticQoveryApi {
@@ -54,4 +59,8 @@ impl QoveryApi for StaticQoveryApi {
     fn update_cluster_credentials(&self, _kubeconfig: String) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn update_database_credentials(&self, _database_id: &Uuid, _login: &str, _password: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
@@ -95,6 +95,8 @@ pub enum Tag {
     ContainerRegistryCannotLinkRegistryToCluster,
     ContainerRegistryCannotSetRepositoryLifecycleError,
     ContainerRegistryCannotSetRepositoryTags,
+    ContainerRegistryCannotResolveImageDigest,
+    ContainerRegistryCannotPromoteImage,
     ContainerRegistryImageDoesntExist,
     ContainerRegistryImageUnreachableAfterPush,
     ContainerRegistryInvalidCredentials,
@@ -109,6 +111,9 @@ pub enum Tag {
     DnsProviderInformationError,
     DnsProviderInvalidApiUrl,
     DnsProviderInvalidCredentials,
+    DnsProviderInvalidHostedZone,
+    DnsProviderCannotManageRecord,
+    DnsProviderRecordsManagementNotSupported,
     DoNotRespectCloudProviderBestPractices,
     DockerError,
     DockerPullImageError,
@@ -188,6 +193,10 @@ pub enum Tag {
     ObjectStorageCannotDeleteFileIntoBucket,
     ObjectStorageCannotEmptyBucket,
     ObjectStorageCannotGetObjectFile,
+    ObjectStorageCannotListObjects,
+    ObjectStorageCannotCopyFile,
+    ObjectStorageCannotPresignUrl,
+    ObjectStorageObjectLockNotActivated,
     ObjectStorageCannotPutFileIntoBucket,
     ObjectStorageCannotTagBucket,
     ObjectStorageInvalidBucketName,
@@ -241,14 +250,21 @@ pub enum Tag {
     VaultSecretCouldNotBeCreatedOrUpdated,
     VaultSecretCouldNotBeDeleted,
     VaultSecretCouldNotBeRetrieved,
+    SecretBackendInvalidConfiguration,
+    ExternalSecretCannotBeResolved,
+    DatabaseCredentialsRotationFailed,
     VersionNumberParsingError,
     RouterInvalidConfiguration,
     RouterBasicAuthEnvVarCannotDecodeBase64Error,
     RouterBasicAuthEnvVarNotFound,
+    RouterCertificateNotIssued,
     ServiceInstantiationError,
     CannotGetRegistryCredentials,
     K8sCannotDeleteService,
     K8sGetWebHookConfigurationError,
+    ImageSigningError,
+    ImageSignatureVerificationFailed,
+    VulnerabilityScanFailed,
 }
 
 impl From<errors::Tag> for Tag {
@@ -382,6 +398,8 @@ impl From<errors::Tag> for Tag {
             }
             errors::Tag::ContainerRegistryCannotDeleteRegistry => Tag::ContainerRegistryCannotDeleteRegistry,
             errors::Tag::ContainerRegistryCannotSetRepositoryTags => Tag::ContainerRegistryCannotSetRepositoryTags,
+            errors::Tag::ContainerRegistryCannotResolveImageDigest => Tag::ContainerRegistryCannotResolveImageDigest,
+            errors::Tag::ContainerRegistryCannotPromoteImage => Tag::ContainerRegistryCannotPromoteImage,
             errors::Tag::ContainerRegistryUnknownError => Tag::ContainerRegistryUnknownError,
             errors::Tag::ContainerRegistryRepositoryNameInvalid => Tag::ContainerRegistryRepositoryNameInvalid,
             errors::Tag::BuilderDockerCannotListImages => Tag::BuilderDockerCannotListImages,
@@ -406,6 +424,9 @@ impl From<errors::Tag> for Tag {
             errors::Tag::VaultConnectionError => Tag::VaultConnectionError,
             errors::Tag::VaultSecretCouldNotBeRetrieved => Tag::VaultSecretCouldNotBeRetrieved,
             errors::Tag::VaultSecretCouldNotBeCreatedOrUpdated => Tag::VaultSecretCouldNotBeCreatedOrUpdated,
+            errors::Tag::SecretBackendInvalidConfiguration => Tag::SecretBackendInvalidConfiguration,
+            errors::Tag::ExternalSecretCannotBeResolved => Tag::ExternalSecretCannotBeResolved,
+            errors::Tag::DatabaseCredentialsRotationFailed => Tag::DatabaseCredentialsRotationFailed,
             errors::Tag::JsonDeserializationError => Tag::JsonDeserializationError,
             errors::Tag::ClusterSecretsManipulationError => Tag::ClusterSecretsManipulationError,
             errors::Tag::VaultSecretCouldNotBeDeleted => Tag::VaultSecretCouldNotBeDeleted,
@@ -420,6 +441,9 @@ impl From<errors::Tag> for Tag {
             errors::Tag::CloudProviderInformationError => Tag::CloudProviderInformationError,
             errors::Tag::DnsProviderInvalidCredentials => Tag::DnsProviderInvalidCredentials,
             errors::Tag::DnsProviderInvalidApiUrl => Tag::DnsProviderInvalidApiUrl,
+            errors::Tag::DnsProviderInvalidHostedZone => Tag::DnsProviderInvalidHostedZone,
+            errors::Tag::DnsProviderCannotManageRecord => Tag::DnsProviderCannotManageRecord,
+            errors::Tag::DnsProviderRecordsManagementNotSupported => Tag::DnsProviderRecordsManagementNotSupported,
             errors::Tag::K8sErrorCopySecret => Tag::K8sErrorCopySecret,
             errors::Tag::K8sCannotReachToApi => Tag::K8sCannotReachToApi,
             errors::Tag::TerraformUnknownError => Tag::TerraformUnknownError,
@@ -453,6 +477,10 @@ impl From<errors::Tag> for Tag {
             errors::Tag::ObjectStorageCannotGetBucket => Tag::ObjectStorageCannotGetBucket,
             errors::Tag::ObjectStorageQuotaExceeded => Tag::ObjectStorageQuotaExceeded,
             errors::Tag::ObjectStorageCannotGetObjectFile => Tag::ObjectStorageCannotGetObjectFile,
+            errors::Tag::ObjectStorageCannotListObjects => Tag::ObjectStorageCannotListObjects,
+            errors::Tag::ObjectStorageCannotCopyFile => Tag::ObjectStorageCannotCopyFile,
+            errors::Tag::ObjectStorageCannotPresignUrl => Tag::ObjectStorageCannotPresignUrl,
+            errors::Tag::ObjectStorageObjectLockNotActivated => Tag::ObjectStorageObjectLockNotActivated,
             errors::Tag::CloudProviderGetLoadBalancer => Tag::CloudProviderGetLoadBalancer,
             errors::Tag::CloudProviderGetLoadBalancerTags => Tag::CloudProviderGetLoadBalancerTags,
             errors::Tag::K8sCannotDeletePvc => Tag::K8sCannotDeletePvc,
@@ -497,6 +525,7 @@ impl From<errors::Tag> for Tag {
                 Tag::RouterBasicAuthEnvVarCannotDecodeBase64Error
             }
             errors::Tag::RouterBasicAuthEnvVarNotFound => Tag::RouterBasicAuthEnvVarNotFound,
+            errors::Tag::RouterCertificateNotIssued => Tag::RouterCertificateNotIssued,
             errors::Tag::CannotFetchScalewayPrivateNetworks => Tag::CannotFetchScalewayPrivateNetworks,
             errors::Tag::CannotWriteToFile => Tag::CannotWriteToFile,
             errors::Tag::CannotCreateHelmAdmissionControllerConfigMap => {
@@ -508,6 +537,9 @@ impl From<errors::Tag> for Tag {
             errors::Tag::ServiceInstantiationError => Tag::ServiceInstantiationError,
             errors::Tag::CannotGetRegistryCredentials => Tag::CannotGetRegistryCredentials,
             errors::Tag::CannotCreateAwsServiceLinkedRoleForSpotInstance => Tag::ServiceInstantiationError,
+            errors::Tag::ImageSigningError => Tag::ImageSigningError,
+            errors::Tag::ImageSignatureVerificationFailed => Tag::ImageSignatureVerificationFailed,
+            errors::Tag::VulnerabilityScanFailed => Tag::VulnerabilityScanFailed,
         }
     }
 }
@@ -314,6 +314,9 @@ impl From<ObjectStorageError> for CommandError {
                 Some(raw_error_message),
                 None,
             ),
+            ObjectStorageError::ObjectLockNotActivated { bucket_name } => CommandError::new_from_safe_message(
+                format!("Object storage error, object lock can only be activated at bucket creation, bucket `{bucket_name}` has been created without it"),
+            ),
             ObjectStorageError::CannotUploadFile {
                 bucket_name,
                 object_name: file_name,
@@ -323,6 +326,32 @@ impl From<ObjectStorageError> for CommandError {
                 Some(raw_error_message),
                 None,
             ),
+            ObjectStorageError::CannotListObjects {
+                bucket_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Object storage error, cannot list objects of bucket: `{bucket_name}`"),
+                Some(raw_error_message),
+                None,
+            ),
+            ObjectStorageError::CannotCopyFile {
+                bucket_name,
+                object_name: file_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Object storage error, cannot copy file `{file_name}` into bucket: `{bucket_name}`"),
+                Some(raw_error_message),
+                None,
+            ),
+            ObjectStorageError::CannotPresignUrl {
+                bucket_name,
+                object_name: file_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Object storage error, cannot presign url for file `{file_name}` in bucket: `{bucket_name}`"),
+                Some(raw_error_message),
+                None,
+            ),
         }
     }
 }
@@ -428,6 +457,27 @@ impl From<ContainerRegistryError> for CommandError {
                 Some(raw_error_message),
                 None,
             ),
+            ContainerRegistryError::CannotResolveImageDigest {
+                registry_name,
+                image_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Container registry error, cannot resolve digest of image `{image_name}` in registry: `{registry_name}`"),
+                Some(raw_error_message),
+                None,
+            ),
+            ContainerRegistryError::CannotPromoteImage {
+                registry_name,
+                source_image,
+                image_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!(
+                    "Container registry error, cannot promote image `{source_image}` to `{image_name}` in registry: `{registry_name}`"
+                ),
+                Some(raw_error_message),
+                None,
+            ),
             ContainerRegistryError::ImageDoesntExistInRegistry {
                 registry_name,
                 repository_name,
@@ -577,11 +627,41 @@ impl From<BuildError> for CommandError {
                 Some(raw_error.to_string()),
                 None,
             ),
+            BuildError::BuildKitError { application, raw_error } => CommandError::new(
+                format!("Build error, cannot build application `{application}` due to a remote BuildKit error"),
+                Some(raw_error.to_string()),
+                None,
+            ),
             BuildError::BuildpackError { application, raw_error } => CommandError::new(
                 format!("Build error, cannot build application `{application}` due to a Buildpack error"),
                 Some(raw_error.to_string()),
                 None,
             ),
+            BuildError::AttestationError {
+                application,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Build error, cannot generate attestations of application `{application}`"),
+                Some(raw_error_message),
+                None,
+            ),
+            BuildError::SourceArchiveError {
+                application,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Build error, cannot get the source archive of application `{application}`"),
+                Some(raw_error_message),
+                None,
+            ),
+            BuildError::VulnerabilityScanFailed {
+                application,
+                image_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Build error, image `{image_name}` of application `{application}` did not pass the vulnerability scan"),
+                Some(raw_error_message),
+                None,
+            ),
             BuildError::CannotGetCredentials { .. } => {
                 CommandError::new("Build error, cannot get registry credentials".to_string(), None, None)
             }
@@ -662,6 +742,12 @@ impl From<RouterError> for CommandError {
                 Some(router_error.to_string()),
                 None,
             ),
+
+            RouterError::CertificateNotIssued { domain, .. } => CommandError::new(
+                format!("Router error: certificate for domain `{domain}` has not been issued"),
+                Some(router_error.to_string()),
+                None,
+            ),
         }
     }
 }
@@ -987,6 +1073,10 @@ pub enum Tag {
     ContainerRegistryInvalidRegistryUrl,
     /// ContainerRegistryCannotDeleteImage: represents an error while trying to delete an image.
     ContainerRegistryCannotDeleteImage,
+    /// ContainerRegistryCannotResolveImageDigest: represents an error while trying to get the digest an image tag points to.
+    ContainerRegistryCannotResolveImageDigest,
+    /// ContainerRegistryCannotPromoteImage: represents an error on container registry where it cannot copy an image from another registry.
+    ContainerRegistryCannotPromoteImage,
     /// ContainerRegistryImageDoesntExist: represents an error, image doesn't exist in the registry.
     ContainerRegistryImageDoesntExist,
     /// ContainerRegistryImageUnreachableAfterPush: represents an error when image has been pushed but is unreachable.
@@ -1029,6 +1119,12 @@ pub enum Tag {
     VaultSecretCouldNotBeCreatedOrUpdated,
     /// VaultSecretCouldNotBeDeleted, represent a vault secret deletion error
     VaultSecretCouldNotBeDeleted,
+    /// SecretBackendInvalidConfiguration: represents an error while instantiating the configured secret backend
+    SecretBackendInvalidConfiguration,
+    /// ExternalSecretCannotBeResolved: represents an error while fetching a secret referenced by an environment variable
+    ExternalSecretCannotBeResolved,
+    /// DatabaseCredentialsRotationFailed: represents an error while rotating the credentials of a container database
+    DatabaseCredentialsRotationFailed,
     /// JsonDeserializationError: represent a deserialization issue
     JsonDeserializationError,
     /// ClusterSecretsManipulationError: represent an error while trying to manipulate ClusterSecrets
@@ -1039,6 +1135,12 @@ pub enum Tag {
     DnsProviderInvalidCredentials,
     /// DnsProviderInvalidApiUrl: represent an error on invalid DNS provider api url.
     DnsProviderInvalidApiUrl,
+    /// DnsProviderInvalidHostedZone: represent an error on a DNS provider hosted zone not found or not owning the domain.
+    DnsProviderInvalidHostedZone,
+    /// DnsProviderCannotManageRecord: represent an error while creating, listing or deleting a DNS provider record.
+    DnsProviderCannotManageRecord,
+    /// DnsProviderRecordsManagementNotSupported: represent an error when the DNS provider can't manage records.
+    DnsProviderRecordsManagementNotSupported,
     /// ObjectStorageCannotInstantiateClient: represents an error while trying to instantiate object storage client.
     ObjectStorageCannotInstantiateClient,
     /// ObjectStorageCannotCreateBucket: represents an error while trying to create a new object storage bucket.
@@ -1065,6 +1167,14 @@ pub enum Tag {
     ObjectStorageCannotTagBucket,
     /// ObjectStorageCannotGetObjectFile: represents an error while trying to get a file from object storage bucket.
     ObjectStorageCannotGetObjectFile,
+    /// ObjectStorageCannotListObjects: represents an error while trying to list objects of an object storage bucket.
+    ObjectStorageCannotListObjects,
+    /// ObjectStorageCannotCopyFile: represents an error while trying to copy a file into an object storage bucket.
+    ObjectStorageCannotCopyFile,
+    /// ObjectStorageCannotPresignUrl: represents an error while trying to generate a presigned url for an object storage file.
+    ObjectStorageCannotPresignUrl,
+    /// ObjectStorageObjectLockNotActivated: represents an error while trying to set object lock on a bucket created without it.
+    ObjectStorageObjectLockNotActivated,
     /// JobFailure: represents an error while indicating that the job failed to terminate properly
     JobFailure,
     /// CannotParseString: represents an error while trying to parse a string
@@ -1105,6 +1215,8 @@ pub enum Tag {
     RouterBasicAuthEnvVarCannotDecodeBase64Error,
     /// RouterBasicAuthEnvVarNotFound: represents an error with a router not able to find value of basic auth env variable
     RouterBasicAuthEnvVarNotFound,
+    /// RouterCertificateNotIssued: represents an error with a router whose custom domain certificate has not been issued by cert-manager
+    RouterCertificateNotIssued,
     /// CannotFetchScalewayPrivateNetworks: (only during migration VPC) We need to fetch the private networks to identify already existing clusters with no private network
     CannotFetchScalewayPrivateNetworks,
     /// K8sCannotGetNodes: represents an error where we are not able to get nodes.
@@ -1125,6 +1237,12 @@ pub enum Tag {
     CannotGetRegistryCredentials,
     /// CannotCreateAwsServiceLinkedRoleForSpotInstance: represents an error while trying to create an AWS Service Linked Role
     CannotCreateAwsServiceLinkedRoleForSpotInstance,
+    /// ImageSigningError: represents an error while trying to sign an image
+    ImageSigningError,
+    /// ImageSignatureVerificationFailed: represents an image without a valid signature for the configured key
+    ImageSignatureVerificationFailed,
+    /// VulnerabilityScanFailed: represents an image with vulnerabilities above the severity allowed for the service
+    VulnerabilityScanFailed,
 }
 
 impl Tag {
@@ -3193,6 +3311,22 @@ impl EngineError {
                 None,
                 None,
             ),
+            ContainerRegistryError::CannotResolveImageDigest { ref image_name, ref registry_name, .. } => EngineError::new(
+                event_details,
+                Tag::ContainerRegistryCannotResolveImageDigest,
+                format!("Container registry: cannot resolve digest of image `{image_name}` in registry `{registry_name}`. Due to {}", error),
+                Some(error.into()),
+                None,
+                None,
+            ),
+            ContainerRegistryError::CannotPromoteImage { ref source_image, ref image_name, ref registry_name, .. } => EngineError::new(
+                event_details,
+                Tag::ContainerRegistryCannotPromoteImage,
+                format!("Container registry: cannot promote image `{source_image}` to `{image_name}` in registry `{registry_name}`. Due to {}", error),
+                Some(error.into()),
+                None,
+                None,
+            ),
             ContainerRegistryError::ImageDoesntExistInRegistry { ref image_name, ref registry_name, ref repository_name, .. } => EngineError::new(
                 event_details,
                 Tag::ContainerRegistryImageDoesntExist,
@@ -4603,6 +4737,14 @@ impl EngineError {
                 None,
                 None,
             ),
+            ObjectStorageError::ObjectLockNotActivated { ref bucket_name } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageObjectLockNotActivated,
+                format!("Error, object lock can only be activated at creation of object storage bucket `{bucket_name}`, delete and recreate the bucket to activate it."),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
             ObjectStorageError::CannotActivateBucketVersioning { ref bucket_name, .. } => EngineError::new(
                 event_details,
                 Tag::ObjectStorageCannotActivateBucketVersioning,
@@ -4647,6 +4789,38 @@ impl EngineError {
                 None,
                 None,
             ),
+            ObjectStorageError::CannotListObjects { ref bucket_name, .. } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageCannotListObjects,
+                format!("Error, cannot list objects of object storage bucket `{bucket_name}`.",),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
+            ObjectStorageError::CannotCopyFile {
+                ref bucket_name,
+                object_name: ref file_name,
+                ..
+            } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageCannotCopyFile,
+                format!("Error, cannot copy file `{file_name}` into object storage bucket `{bucket_name}`.",),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
+            ObjectStorageError::CannotPresignUrl {
+                ref bucket_name,
+                object_name: ref file_name,
+                ..
+            } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageCannotPresignUrl,
+                format!("Error, cannot presign url for file `{file_name}` in object storage bucket `{bucket_name}`.",),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
         }
     }
 
@@ -4669,6 +4843,79 @@ impl EngineError {
         )
     }
 
+    /// Creates new error when the configured secret backend can't be instantiated
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `raw_error`: Raw error message.
+    pub fn new_secret_backend_invalid_configuration(
+        event_details: EventDetails,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message_safe = "Secret backend configuration is invalid".to_string();
+
+        EngineError::new(
+            event_details,
+            Tag::SecretBackendInvalidConfiguration,
+            message_safe,
+            Some(raw_error),
+            None,
+            Some("Check `SECRET_BACKEND` and its related environment variables".to_string()),
+        )
+    }
+
+    /// Creates new error when the external secret referenced by an environment variable can't be resolved
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `variable_name`: Environment variable referencing the external secret.
+    /// * `raw_error`: Raw error message.
+    pub fn new_external_secret_cannot_be_resolved(
+        event_details: EventDetails,
+        variable_name: &str,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message_safe = format!("Cannot resolve the external secret of environment variable `{variable_name}`");
+
+        EngineError::new(
+            event_details,
+            Tag::ExternalSecretCannotBeResolved,
+            message_safe,
+            Some(raw_error),
+            None,
+            Some("Check the secret reference: Kubernetes Secrets must be in the environment namespace, other secret stores are only reachable through External Secrets Operator and its ClusterSecretStores".to_string()),
+        )
+    }
+
+    /// Creates new error when the credentials of a container database can't be rotated
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `database_name`: Database name.
+    /// * `raw_error`: Raw error message.
+    pub fn new_database_credentials_rotation_failed(
+        event_details: EventDetails,
+        database_name: &str,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message_safe = format!("Cannot rotate the credentials of database `{database_name}`");
+
+        EngineError::new(
+            event_details,
+            Tag::DatabaseCredentialsRotationFailed,
+            message_safe,
+            Some(raw_error),
+            None,
+            Some(
+                "The database still accepts its previous credentials, the rotation can be retried by redeploying it"
+                    .to_string(),
+            ),
+        )
+    }
+
     /// Creates new error when Vault secret couldn't be retrieved
     ///
     /// Arguments:
@@ -4861,6 +5108,70 @@ impl EngineError {
         )
     }
 
+    /// Creates new error when client DNS provider hosted zone can't be found or doesn't own the domain
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `hosted_zone_id`: DNS provider hosted zone id.
+    /// * `raw_error_message`: Raw error message.
+    pub fn new_error_on_dns_provider_invalid_hosted_zone(
+        event_details: EventDetails,
+        hosted_zone_id: &str,
+        raw_error_message: &str,
+    ) -> EngineError {
+        let message_safe = format!("Invalid DNS provider hosted zone `{hosted_zone_id}`");
+
+        EngineError::new(
+            event_details,
+            Tag::DnsProviderInvalidHostedZone,
+            message_safe.to_string(),
+            Some(CommandError::new(message_safe, Some(raw_error_message.to_string()), None)),
+            None,
+            Some("Check your DNS provider hosted zone exists, is accessible with your credentials and owns the cluster domain".to_string()),
+        )
+    }
+
+    /// Creates new error when a DNS provider record can't be listed, created or deleted
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `record_name`: DNS record name.
+    /// * `raw_error_message`: Raw error message.
+    pub fn new_error_on_dns_provider_cannot_manage_record(
+        event_details: EventDetails,
+        record_name: &str,
+        raw_error_message: &str,
+    ) -> EngineError {
+        let message_safe = format!("Cannot manage DNS provider record `{record_name}`");
+
+        EngineError::new(
+            event_details,
+            Tag::DnsProviderCannotManageRecord,
+            message_safe.to_string(),
+            Some(CommandError::new(message_safe, Some(raw_error_message.to_string()), None)),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error when the DNS provider doesn't support records management
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    pub fn new_error_on_dns_provider_records_management_not_supported(event_details: EventDetails) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::DnsProviderRecordsManagementNotSupported,
+            "DNS provider records management is not supported".to_string(),
+            None,
+            None,
+            None,
+        )
+    }
+
     /// Creates new error to match Cloud Provider best practices
     ///
     /// Arguments:
@@ -5092,6 +5403,14 @@ impl EngineError {
                 Some(Url::parse("https://hub.qovery.com/docs/using-qovery/configuration/advanced-settings/#networkingressbasic_auth_env_var").expect("Error while trying to parse error link helper for `Tag::RouterBasicAuthEnvVarNotFound`, URL is not valid.")),
                 Some("Make sure the environment variable set in `network.ingress.basic_auth_env_var` is set".to_string()),
             ),
+            RouterError::CertificateNotIssued { domain, reason } => EngineError::new(
+                event_details,
+                Tag::RouterCertificateNotIssued,
+                format!("Error, certificate for domain `{domain}` has not been issued: {reason}"),
+                Some(router_error.into()),
+                None,
+                Some(format!("Make sure domain `{domain}` points to your cluster and its CAA records allow Let's Encrypt. You can disable `network.ingress.certificate_strict_check` to not fail the deployment.")),
+            ),
         }
     }
 
@@ -5242,6 +5561,72 @@ impl EngineError {
             None,
         )
     }
+
+    /// Creates new error when an image cannot be signed
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `image_name`: Image which failed to be signed.
+    /// * `error`: Raw error message.
+    pub fn new_image_signing_error(
+        event_details: EventDetails,
+        image_name: String,
+        error: CommandError,
+    ) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::ImageSigningError,
+            format!("Cannot sign image `{image_name}`"),
+            Some(error),
+            None,
+            Some("Make sure the image signing key pair and its password are valid.".to_string()),
+        )
+    }
+
+    /// Creates new error when an image is not signed, or not signed with the expected key
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `image_name`: Image which failed the signature verification.
+    /// * `error`: Raw error message.
+    pub fn new_image_signature_verification_failed(
+        event_details: EventDetails,
+        image_name: String,
+        error: CommandError,
+    ) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::ImageSignatureVerificationFailed,
+            format!("Image `{image_name}` is not signed or its signature does not match the expected signing key"),
+            Some(error),
+            None,
+            Some("Sign the image with the organization cosign key before deploying it.".to_string()),
+        )
+    }
+
+    /// Creates new error when an image has vulnerabilities at or above the severity threshold of the service
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `image_name`: Image which failed the vulnerability scan.
+    /// * `error`: Raw error message.
+    pub fn new_vulnerability_scan_failed(
+        event_details: EventDetails,
+        image_name: String,
+        error: CommandError,
+    ) -> EngineError {
+        EngineError::new(
+            event_details,
+            Tag::VulnerabilityScanFailed,
+            format!("Image `{image_name}` has vulnerabilities at or above the severity allowed for the service"),
+            Some(error),
+            None,
+            Some("Update the vulnerable packages of the image, or raise the `vulnerability_scan.fail_on_severity` advanced setting of the service.".to_string()),
+        )
+    }
 }
 impl Display for EngineError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -209,6 +209,12 @@ pub enum EnvironmentStep {
     Restart,
     Restarted,
     RestartedError,
+    Promote,
+    Promoted,
+    PromotedError,
+    RotateCredentials,
+    CredentialsRotated,
+    CredentialsRotatedError,
 }
 
 impl From<events::EnvironmentStep> for EnvironmentStep {
@@ -243,6 +249,12 @@ impl From<events::EnvironmentStep> for EnvironmentStep {
             events::EnvironmentStep::DatabaseOutput => EnvironmentStep::DatabaseOutput,
             events::EnvironmentStep::Recap => EnvironmentStep::Recap,
             events::EnvironmentStep::GlobalError => EnvironmentStep::GlobalError,
+            events::EnvironmentStep::Promote => EnvironmentStep::Promote,
+            events::EnvironmentStep::Promoted => EnvironmentStep::Promoted,
+            events::EnvironmentStep::PromotedError => EnvironmentStep::PromotedError,
+            events::EnvironmentStep::RotateCredentials => EnvironmentStep::RotateCredentials,
+            events::EnvironmentStep::CredentialsRotated => EnvironmentStep::CredentialsRotated,
+            events::EnvironmentStep::CredentialsRotatedError => EnvironmentStep::CredentialsRotatedError,
         }
     }
 }
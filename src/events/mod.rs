@@ -8,6 +8,7 @@ extern crate derivative;
 extern crate url;
 
 use crate::cloud_provider::Kind;
+use crate::container_registry::vulnerability_scan::VulnerabilityScanSummary;
 use crate::errors::{CommandError, EngineError, ErrorMessageVerbosity};
 use crate::io_models::QoveryIdentifier;
 use crate::metrics_registry::StepRecord;
@@ -18,6 +19,7 @@ use uuid::Uuid;
 #[derive(Debug, Clone)]
 pub enum EngineMsgPayload {
     Metrics(StepRecord),
+    VulnerabilityScan(VulnerabilityScanSummary),
 }
 
 #[derive(Debug, Clone)]
@@ -448,6 +450,18 @@ pub enum EnvironmentStep {
     DeletedError,
     /// Recap: Display the error(s) recap of the whole service deployment
     Recap,
+    /// Promote: copy an image already built for another cluster into the cluster registry
+    Promote,
+    /// Promoted: image has been promoted
+    Promoted,
+    /// PromotedError: Terminal error on promoting an image
+    PromotedError,
+    /// RotateCredentials: generate new credentials for databases and apply them in place
+    RotateCredentials,
+    /// CredentialsRotated: database credentials have been rotated
+    CredentialsRotated,
+    /// CredentialsRotatedError: Terminal error on rotating database credentials
+    CredentialsRotatedError,
     /// Restart: Restart service pods
     Restart,
     /// Restarted: Service pods have been restarted
@@ -468,6 +482,8 @@ impl EnvironmentStep {
         matches!(
             self,
             EnvironmentStep::BuiltError
+                | EnvironmentStep::PromotedError
+                | EnvironmentStep::CredentialsRotatedError
                 | EnvironmentStep::Cancelled
                 | EnvironmentStep::DeployedError
                 | EnvironmentStep::PausedError
@@ -501,6 +517,12 @@ impl Display for EnvironmentStep {
                 EnvironmentStep::Cancelled => "cancelled",
                 EnvironmentStep::Terminated => "terminated",
                 EnvironmentStep::BuiltError => "built-error",
+                EnvironmentStep::Promote => "promote",
+                EnvironmentStep::Promoted => "promoted",
+                EnvironmentStep::PromotedError => "promoted-error",
+                EnvironmentStep::RotateCredentials => "rotate-credentials",
+                EnvironmentStep::CredentialsRotated => "credentials-rotated",
+                EnvironmentStep::CredentialsRotatedError => "credentials-rotated-error",
                 EnvironmentStep::DeployedError => "deployed-error",
                 EnvironmentStep::PausedError => "paused-error",
                 EnvironmentStep::DeletedError => "deleted-error",
@@ -666,6 +688,12 @@ impl EventDetails {
             },
             Stage::Environment(step) => match step {
                 EnvironmentStep::Build | EnvironmentStep::Built => Stage::Environment(EnvironmentStep::BuiltError),
+                EnvironmentStep::Promote | EnvironmentStep::Promoted => {
+                    Stage::Environment(EnvironmentStep::PromotedError)
+                }
+                EnvironmentStep::RotateCredentials | EnvironmentStep::CredentialsRotated => {
+                    Stage::Environment(EnvironmentStep::CredentialsRotatedError)
+                }
                 EnvironmentStep::Deploy | EnvironmentStep::Deployed => {
                     Stage::Environment(EnvironmentStep::DeployedError)
                 }
@@ -684,6 +712,8 @@ impl EventDetails {
                 EnvironmentStep::Start
                 | EnvironmentStep::Terminated
                 | EnvironmentStep::BuiltError
+                | EnvironmentStep::PromotedError
+                | EnvironmentStep::CredentialsRotatedError
                 | EnvironmentStep::Cancel
                 | EnvironmentStep::Cancelled
                 | EnvironmentStep::DeployedError
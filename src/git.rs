@@ -1,13 +1,13 @@
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use std::time::Duration;
 
 use crate::build_platform::{BuildError, GitCmd};
 use git2::build::CheckoutBuilder;
-use git2::ErrorCode::Auth;
+use git2::ErrorCode::{Auth, NotFound};
 use git2::ResetType::Hard;
 use git2::{
-    opts, AutotagOption, CertificateCheckStatus, Cred, CredentialType, Error, FetchOptions, Object, RemoteCallbacks,
-    Repository, SubmoduleUpdateOptions,
+    opts, AutotagOption, CertificateCheckStatus, Cred, CredentialType, Error, FetchOptions, Object, Oid,
+    RemoteCallbacks, Repository, SubmoduleUpdateOptions,
 };
 use tracing::field::debug;
 use url::Url;
@@ -89,6 +89,61 @@ where
     Ok(())
 }
 
+/// Return the git object id of each path at the given commit: the tree id for a directory, the blob id for a file.
+/// Ids only change when the content under the path changes, whatever the commit.
+/// An empty path (or `.`) designates the root of the repository, and paths that do not exist are returned as `None`.
+pub fn tree_entry_ids_at_commit<P>(
+    repository_path: P,
+    commit_id: &str,
+    paths: &[&Path],
+) -> Result<Vec<Option<Oid>>, Error>
+where
+    P: AsRef<Path>,
+{
+    let repo = Repository::open(repository_path)?;
+    let tree = repo.revparse_single(commit_id)?.peel_to_commit()?.tree()?;
+
+    paths
+        .iter()
+        .map(|path| {
+            let path = path
+                .components()
+                .filter(|c| matches!(c, Component::Normal(_)))
+                .collect::<PathBuf>();
+            if path.as_os_str().is_empty() {
+                return Ok(Some(tree.id()));
+            }
+
+            match tree.get_path(&path) {
+                Ok(entry) => Ok(Some(entry.id())),
+                Err(err) if err.code() == NotFound => Ok(None),
+                Err(err) => Err(err),
+            }
+        })
+        .collect()
+}
+
+/// Fetch the objects of the commit only, without checking out its files nor updating its submodules.
+/// It is enough to read the git ids of the commit tree with `tree_entry_ids_at_commit`.
+pub fn fetch_commit<P>(
+    repository_url: &Url,
+    commit_id: &str,
+    into_dir: P,
+    get_credentials: &impl Fn(&str) -> Vec<(CredentialType, Cred)>,
+) -> Result<(), BuildError>
+where
+    P: AsRef<Path>,
+{
+    fetch(repository_url, into_dir, get_credentials, commit_id).map_err(|error| BuildError::GitError {
+        application: "".to_string(),
+        git_cmd: GitCmd::Fetch,
+        context: format!("url: {}/ commit id: {}", repository_url, commit_id),
+        raw_error: error,
+    })?;
+
+    Ok(())
+}
+
 // Credentials callback is called endlessly until the server return Auth Ok (or a definitive error)
 // If auth is denied, it up to us to return a new credential to try different auth method
 // or an error to specify that we have exhausted everything we are able to provide
@@ -223,7 +278,7 @@ fn remote_fetch(
 
 #[cfg(test)]
 mod tests {
-    use crate::git::{checkout, clone_at_commit, fetch};
+    use crate::git::{checkout, clone_at_commit, fetch, tree_entry_ids_at_commit};
     use base64::engine::general_purpose;
     use base64::Engine;
     use git2::{Cred, CredentialType, Repository};
@@ -395,4 +450,61 @@ mod tests {
         assert!(repo.is_ok());
         assert_eq!(repo.unwrap().head().unwrap().target().unwrap().to_string(), commit_id);
     }
+
+    fn commit_files(repo: &Repository, files: &[(&str, &str)]) -> String {
+        let workdir = repo.workdir().unwrap().to_path_buf();
+        let mut index = repo.index().unwrap();
+        for (path, content) in files {
+            let file_path = workdir.join(path);
+            std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            std::fs::write(&file_path, content).unwrap();
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@qovery.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        repo.commit(Some("HEAD"), &signature, &signature, "test", &tree, &parents)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_tree_entry_ids_at_commit() {
+        let repo_dir = DirectoryForTests::new_with_random_suffix("/tmp/engine_test_tree_ids".to_string());
+        let repo = Repository::init(repo_dir.path()).unwrap();
+        let first_commit = commit_files(
+            &repo,
+            &[
+                ("backend/main.rs", "v1"),
+                ("frontend/index.js", "v1"),
+                ("Dockerfile", "FROM scratch"),
+            ],
+        );
+        let second_commit = commit_files(&repo, &[("frontend/index.js", "v2")]);
+
+        let paths = [
+            Path::new("/backend"),
+            Path::new("./frontend"),
+            Path::new("Dockerfile"),
+            Path::new("missing"),
+            Path::new(""),
+        ];
+        let first = tree_entry_ids_at_commit(repo_dir.path(), &first_commit, &paths).unwrap();
+        let second = tree_entry_ids_at_commit(repo_dir.path(), &second_commit, &paths).unwrap();
+
+        // untouched paths keep the same id across commits
+        assert!(first[0].is_some());
+        assert_eq!(first[0], second[0]);
+        assert_eq!(first[2], second[2]);
+
+        // modified paths get a new id
+        assert_ne!(first[1], second[1]);
+        assert_ne!(first[4], second[4]);
+
+        assert_eq!(first[3], None);
+        assert_eq!(second[3], None);
+    }
 }
@@ -26,7 +26,7 @@ use crate::infrastructure_action::eks::{AwsEksQoveryTerraformOutput, AWS_EKS_DEF
 use crate::infrastructure_action::InfraLogger;
 use crate::runtime::block_on;
 use crate::secret_manager;
-use crate::secret_manager::vault::QVaultClient;
+use crate::secret_manager::secret_backend_from_env;
 use crate::services::kube_client::SelectK8sResourceBy;
 use crate::utilities::envs_to_string;
 use std::collections::HashSet;
@@ -169,11 +169,11 @@ pub fn delete_eks_cluster(
 
     logger.info("Kubernetes cluster successfully deleted");
 
-    // delete info on vault
-    if let Ok(vault_conn) = QVaultClient::new(event_details) {
+    // delete info from the secret backend
+    if let Ok(secret_backend) = secret_backend_from_env(event_details) {
         let mount = secret_manager::vault::get_vault_mount_name(kubernetes.context().is_test_cluster());
         // ignore on failure
-        let _ = vault_conn.delete_secret(mount.as_str(), kubernetes.short_id());
+        let _ = secret_backend.delete(mount.as_str(), kubernetes.short_id());
     };
 
     Ok(())
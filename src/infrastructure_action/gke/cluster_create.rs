@@ -105,7 +105,7 @@ fn create_object_storage(
     for bucket_name in &[&cluster.logs_bucket_name()] {
         let existing_bucket = cluster
             .object_storage
-            .create_bucket(bucket_name, cluster.advanced_settings.resource_ttl(), true)
+            .create_bucket(bucket_name, cluster.advanced_settings.resource_ttl(), true, None)
             .map_err(|e| Box::new(EngineError::new_object_storage_error(event_details.clone(), e)))?;
 
         logger.info(format!("Object storage bucket {} already exists", &bucket_name));
@@ -116,7 +116,7 @@ fn create_object_storage(
             continue;
         }
 
-        if let Err(err) = cluster.object_storage.update_bucket(bucket_name, true) {
+        if let Err(err) = cluster.object_storage.update_bucket(bucket_name, true, None) {
             let error = EngineError::new_object_storage_error(event_details.clone(), err);
             return Err(Box::new(error));
         }
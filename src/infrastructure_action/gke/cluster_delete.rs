@@ -11,7 +11,7 @@ use crate::infrastructure_action::gke::GkeQoveryTerraformOutput;
 use crate::infrastructure_action::{InfraLogger, ToInfraTeraContext};
 use crate::object_storage::ObjectStorage;
 use crate::secret_manager;
-use crate::secret_manager::vault::QVaultClient;
+use crate::secret_manager::secret_backend_from_env;
 use crate::utilities::envs_to_string;
 use std::collections::HashSet;
 
@@ -53,7 +53,7 @@ pub(super) fn delete_gke_cluster(
     logger.info(format!("Deleting Kubernetes cluster {}/{}", cluster.name(), cluster.short_id()));
     tf_resources.delete(&[], &logger)?;
 
-    // delete info on vault
+    // delete info from the secret backend
     let _ = delete_vault_data(cluster, event_details.clone(), &logger);
 
     delete_object_storage(cluster, &logger)?;
@@ -81,14 +81,14 @@ fn delete_vault_data(
     event_details: EventDetails,
     logger: &impl InfraLogger,
 ) -> Result<(), Box<EngineError>> {
-    let vault_conn = QVaultClient::new(event_details.clone());
-    if let Ok(vault_conn) = vault_conn {
+    let secret_backend = secret_backend_from_env(event_details.clone());
+    if let Ok(secret_backend) = secret_backend {
         let mount = secret_manager::vault::get_vault_mount_name(cluster.context().is_test_cluster());
 
         // ignore on failure
-        if let Err(e) = vault_conn.delete_secret(mount.as_str(), cluster.long_id().to_string().as_str()) {
+        if let Err(e) = secret_backend.delete(mount.as_str(), cluster.long_id().to_string().as_str()) {
             logger.warn(EventMessage::new(
-                "Cannot delete cluster config from Vault".to_string(),
+                "Cannot delete cluster config from the secret backend".to_string(),
                 Some(e.to_string()),
             ));
         }
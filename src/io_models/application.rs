@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 use std::str;
 use std::sync::Arc;
 use std::time::Duration;
@@ -11,14 +12,22 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 use uuid::Uuid;
 
-use crate::build_platform::{Build, GitRepository, Image, SshKey};
+use crate::build_platform::attestation::SbomFormat;
+use crate::build_platform::dockerfile_generator::GENERATED_DOCKERFILE_NAME;
+use crate::build_platform::dockerfile_linter::{DockerfileLintPolicy, LintLevel, LintRule};
+use crate::build_platform::source_archive::SourceArchive;
+use crate::build_platform::{
+    Build, BuildEnvironmentVariable, CacheSettings, GitRepository, Image, ImageTagStrategy, SshKey,
+};
 use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
 use crate::cloud_provider::models::{
     CpuArchitecture, EnvironmentVariable, KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit, StorageClass,
 };
 use crate::cloud_provider::service::ServiceType;
 use crate::cloud_provider::{CloudProvider, Kind as CPKind};
-use crate::container_registry::ContainerRegistryInfo;
+use crate::cmd::docker::CacheMode;
+use crate::cmd::vulnerability_scanner::VulnerabilitySeverity;
+use crate::container_registry::{ContainerRegistryInfo, ImagePromotion};
 use crate::engine_task::qovery_api::QoveryApi;
 use crate::io_models::annotations_group::AnnotationsGroup;
 use crate::io_models::container::{ContainerAdvancedSettings, Registry};
@@ -38,6 +47,7 @@ use crate::models::gcp::GcpAppExtraSettings;
 use crate::models::scaleway::ScwAppExtraSettings;
 use crate::models::selfmanaged::OnPremiseAppExtraSettings;
 use crate::models::types::{AWSEc2, OnPremise, AWS, GCP, SCW};
+use crate::secret_manager::external_secret::external_secrets_operator_remote_ref;
 use crate::utilities::to_short_id;
 
 use super::{PodAntiAffinity, UpdateStrategy};
@@ -81,6 +91,10 @@ pub fn to_environment_variable(env_vars: BTreeMap<String, VariableInfo>) -> Vec<
             key: k,
             value: variable_infos.value,
             is_secret: variable_infos.is_secret,
+            external_secret: variable_infos
+                .external_secret
+                .as_ref()
+                .and_then(external_secrets_operator_remote_ref),
         })
         .collect()
 }
@@ -102,6 +116,9 @@ pub struct ApplicationAdvancedSettings {
     pub security_read_only_root_filesystem: bool,
     #[serde(alias = "security.automount_service_account_token")]
     pub security_automount_service_account_token: bool,
+    // deployment is blocked when the image has a vulnerability at or above this severity
+    #[serde(alias = "security.vulnerability_scan.fail_on_severity")]
+    pub security_vulnerability_scan_fail_on_severity: Option<VulnerabilitySeverity>,
 
     // Deployment
     #[serde(alias = "deployment.termination_grace_period_seconds")]
@@ -128,6 +145,29 @@ pub struct ApplicationAdvancedSettings {
     pub build_cpu_max_in_milli: u32,
     #[serde(alias = "build.ram_max_in_gib")]
     pub build_ram_max_in_gib: u32,
+    #[serde(alias = "build.attestation.enabled")]
+    pub build_attestation_enabled: bool,
+    #[serde(alias = "build.attestation.sbom_format")]
+    pub build_attestation_sbom_format: SbomFormat,
+    // monorepo: tag images from the git tree of the root path instead of the commit, to skip builds of untouched services
+    #[serde(alias = "build.tag_from_git_tree")]
+    pub build_tag_from_git_tree: bool,
+    // extra paths (i.e: shared libraries) that must trigger a new build when modified
+    #[serde(alias = "build.watch_paths")]
+    pub build_watch_paths: Vec<String>,
+    #[serde(alias = "build.cache.mode")]
+    pub build_cache_mode: CacheMode,
+    // when disabled, the registry cache is only read and never updated by the build
+    #[serde(alias = "build.cache.export_enabled")]
+    pub build_cache_export_enabled: bool,
+    // branch whose cache is used as a fallback when the branch being built has no cache yet
+    #[serde(alias = "build.cache.default_branch")]
+    pub build_cache_default_branch: String,
+    #[serde(alias = "build.dockerfile_lint.level")]
+    pub build_dockerfile_lint_level: LintLevel,
+    // with the error level, only findings of those rules fail the build (all rules when empty)
+    #[serde(alias = "build.dockerfile_lint.enforced_rules")]
+    pub build_dockerfile_lint_enforced_rules: BTreeSet<LintRule>,
 
     // Ingress
     #[serde(alias = "network.ingress.proxy_body_size_mb")]
@@ -170,6 +210,8 @@ pub struct ApplicationAdvancedSettings {
     pub network_ingress_denylist_source_range: String,
     #[serde(alias = "network.ingress.basic_auth_env_var")]
     pub network_ingress_basic_auth_env_var: String,
+    #[serde(alias = "network.ingress.certificate_strict_check")]
+    pub network_ingress_certificate_strict_check: bool,
 
     #[serde(alias = "network.ingress.grpc_send_timeout_seconds")]
     pub network_ingress_grpc_send_timeout_seconds: u32,
@@ -189,6 +231,7 @@ impl Default for ApplicationAdvancedSettings {
             security_service_account_name: "".to_string(),
             security_read_only_root_filesystem: false,
             security_automount_service_account_token: false,
+            security_vulnerability_scan_fail_on_severity: None,
             deployment_termination_grace_period_seconds: 60,
             deployment_update_strategy_type: UpdateStrategy::RollingUpdate,
             deployment_update_strategy_rolling_update_max_unavailable_percent: 25,
@@ -200,6 +243,15 @@ impl Default for ApplicationAdvancedSettings {
             build_timeout_max_sec: 30 * 60,
             build_cpu_max_in_milli: 4000,
             build_ram_max_in_gib: 8,
+            build_attestation_enabled: false,
+            build_attestation_sbom_format: SbomFormat::default(),
+            build_tag_from_git_tree: false,
+            build_watch_paths: vec![],
+            build_cache_mode: CacheMode::Max,
+            build_cache_export_enabled: true,
+            build_cache_default_branch: "main".to_string(),
+            build_dockerfile_lint_level: LintLevel::Warn,
+            build_dockerfile_lint_enforced_rules: BTreeSet::new(),
             network_ingress_proxy_body_size_mb: 100,
             network_ingress_cors_enable: false,
             network_ingress_sticky_session_enable: false,
@@ -220,6 +272,7 @@ impl Default for ApplicationAdvancedSettings {
             network_ingress_whitelist_source_range: "0.0.0.0/0".to_string(),
             network_ingress_denylist_source_range: "".to_string(),
             network_ingress_basic_auth_env_var: "".to_string(),
+            network_ingress_certificate_strict_check: false,
             network_ingress_grpc_send_timeout_seconds: 60,
             network_ingress_grpc_read_timeout_seconds: 60,
             hpa_cpu_average_utilization_percent: 60,
@@ -234,6 +287,7 @@ impl ApplicationAdvancedSettings {
             security_service_account_name: self.security_service_account_name.clone(),
             security_read_only_root_filesystem: self.security_read_only_root_filesystem,
             security_automount_service_account_token: self.security_automount_service_account_token,
+            security_vulnerability_scan_fail_on_severity: self.security_vulnerability_scan_fail_on_severity,
             deployment_termination_grace_period_seconds: self.deployment_termination_grace_period_seconds,
             deployment_update_strategy_type: self.deployment_update_strategy_type,
             deployment_update_strategy_rolling_update_max_unavailable_percent: self
@@ -264,6 +318,7 @@ impl ApplicationAdvancedSettings {
             network_ingress_whitelist_source_range: self.network_ingress_whitelist_source_range.clone(),
             network_ingress_denylist_source_range: self.network_ingress_denylist_source_range.clone(),
             network_ingress_basic_auth_env_var: self.network_ingress_basic_auth_env_var.clone(),
+            network_ingress_certificate_strict_check: self.network_ingress_certificate_strict_check,
             network_ingress_grpc_send_timeout_seconds: self.network_ingress_grpc_send_timeout_seconds,
             network_ingress_grpc_read_timeout_seconds: self.network_ingress_grpc_read_timeout_seconds,
             hpa_cpu_average_utilization_percent: self.hpa_cpu_average_utilization_percent,
@@ -286,6 +341,16 @@ pub struct Application {
     pub command_args: Vec<String>,
     pub entrypoint: Option<String>,
     pub buildpack_language: Option<String>,
+    // Without a dockerfile, generate one from the detected language instead of using buildpacks
+    #[serde(default)] // Default is false
+    pub zero_config_build: bool,
+    // Sources are downloaded from this archive instead of being cloned from git_url
+    #[serde(default)]
+    pub source_archive: Option<SourceArchive>,
+    // Image already built for another cluster, copied into the cluster registry by an image promotion request.
+    // The application is never built, its deployment fails until the image has been promoted
+    #[serde(default)]
+    pub image_promotion: Option<ImagePromotion>,
     #[serde(default = "default_root_path_value")]
     pub root_path: String,
     pub public_domain: String,
@@ -318,6 +383,38 @@ pub struct Application {
     pub shared_image_feature_enabled: bool,
 }
 
+// Image of an application in the cluster registry, the image promotion must copy the image where the deployment
+// looks for it
+pub(crate) fn to_application_image(
+    long_id: Uuid,
+    name: &str,
+    git_url: &str,
+    commit_id: &str,
+    shared_image_feature_enabled: bool,
+    cr_info: &ContainerRegistryInfo,
+    cluster_id: &QoveryIdentifier,
+) -> Image {
+    Image {
+        service_id: to_short_id(&long_id),
+        service_long_id: long_id,
+        service_name: name.to_string(),
+        name: match shared_image_feature_enabled {
+            true => cr_info.get_shared_image_name(cluster_id, sanitized_git_url(git_url)),
+            false => cr_info.get_image_name(name),
+        },
+        tag: "".to_string(), // It needs to be computed after creation
+        commit_id: commit_id.to_string(),
+        registry_name: cr_info.registry_name.clone(),
+        registry_url: cr_info.endpoint.clone(),
+        registry_insecure: cr_info.insecure_registry,
+        registry_docker_json_config: cr_info.registry_docker_json_config.clone(),
+        repository_name: cr_info.get_repository_name(name),
+        shared_repository_name: cr_info.get_shared_repository_name(cluster_id, sanitized_git_url(git_url)),
+        shared_image_feature_enabled,
+        digest: None, // It is resolved once the image is pushed
+    }
+}
+
 fn default_root_path_value() -> String {
     "/".to_string()
 }
@@ -519,25 +616,15 @@ impl Application {
     }
 
     fn to_image(&self, cr_info: &ContainerRegistryInfo, cluster_id: &QoveryIdentifier) -> Image {
-        Image {
-            service_id: to_short_id(&self.long_id),
-            service_long_id: self.long_id,
-            service_name: self.name.clone(),
-            name: match self.shared_image_feature_enabled {
-                true => cr_info.get_shared_image_name(cluster_id, sanitized_git_url(self.git_url.as_str())),
-                false => cr_info.get_image_name(&self.name),
-            },
-            tag: "".to_string(), // It needs to be computed after creation
-            commit_id: self.commit_id.clone(),
-            registry_name: cr_info.registry_name.clone(),
-            registry_url: cr_info.endpoint.clone(),
-            registry_insecure: cr_info.insecure_registry,
-            registry_docker_json_config: cr_info.registry_docker_json_config.clone(),
-            repository_name: cr_info.get_repository_name(&self.name),
-            shared_repository_name: cr_info
-                .get_shared_repository_name(cluster_id, sanitized_git_url(self.git_url.as_str())),
-            shared_image_feature_enabled: self.shared_image_feature_enabled,
-        }
+        to_application_image(
+            self.long_id,
+            &self.name,
+            &self.git_url,
+            &self.commit_id,
+            self.shared_image_feature_enabled,
+            cr_info,
+            cluster_id,
+        )
     }
 
     pub fn to_build(
@@ -552,26 +639,39 @@ impl Application {
 
         // Convert our root path to an relative path to be able to append them correctly
         let (root_path, dockerfile_path) = normalize_root_and_dockerfile_path(&self.root_path, &self.dockerfile_path);
+        let generate_dockerfile = self.zero_config_build && dockerfile_path.is_none();
+        let dockerfile_path = match generate_dockerfile {
+            true => Some(root_path.join(GENERATED_DOCKERFILE_NAME)),
+            false => dockerfile_path,
+        };
 
-        //FIXME: Return a result the function
-        let url = Url::parse(&self.git_url).unwrap_or_else(|_| Url::parse("https://invalid-git-url.com").unwrap());
+        // Image tag is computed from the archive checksum, as there is no commit for archives
+        let (url, commit_id) = match &self.source_archive {
+            Some(source_archive) => (source_archive.url(), source_archive.sha256.clone()),
+            //FIXME: Return a result the function
+            None => (
+                Url::parse(&self.git_url).unwrap_or_else(|_| Url::parse("https://invalid-git-url.com").unwrap()),
+                self.commit_id.clone(),
+            ),
+        };
 
         let mut disable_build_cache = false;
         let mut build = Build {
             git_repository: GitRepository {
                 url,
-                get_credentials: if self.git_credentials.is_none() {
+                get_credentials: if self.git_credentials.is_none() || self.source_archive.is_some() {
                     None
                 } else {
                     let id = self.long_id;
                     Some(Box::new(move || fetch_git_token(&*qovery_api, ServiceType::Application, &id)))
                 },
                 ssh_keys,
-                commit_id: self.commit_id.clone(),
+                commit_id,
                 dockerfile_path,
                 dockerfile_content: None,
                 root_path,
                 buildpack_language: self.buildpack_language.clone(),
+                generate_dockerfile,
             },
             image: self.to_image(registry_url, cluster_id),
             environment_variables: self
@@ -590,7 +690,13 @@ impl Application {
                         return None;
                     }
 
-                    Some((k.clone(), v))
+                    Some((
+                        k.clone(),
+                        BuildEnvironmentVariable {
+                            value: v,
+                            is_secret: variable_infos.is_secret,
+                        },
+                    ))
                 })
                 .collect::<BTreeMap<_, _>>(),
             disable_cache: disable_build_cache,
@@ -599,9 +705,47 @@ impl Application {
             max_cpu_in_milli: self.advanced_settings.build_cpu_max_in_milli,
             max_ram_in_gib: self.advanced_settings.build_ram_max_in_gib,
             registries: self.container_registries.clone(),
+            sbom_format: self
+                .advanced_settings
+                .build_attestation_enabled
+                .then_some(self.advanced_settings.build_attestation_sbom_format),
+            tag_strategy: if self.advanced_settings.build_tag_from_git_tree
+                && self.source_archive.is_none()
+                && self.image_promotion.is_none()
+            {
+                ImageTagStrategy::GitTree {
+                    watch_paths: self
+                        .advanced_settings
+                        .build_watch_paths
+                        .iter()
+                        .map(PathBuf::from)
+                        .collect(),
+                }
+            } else {
+                ImageTagStrategy::CommitId
+            },
+            source_tree_id: None,
+            cache_settings: CacheSettings {
+                branch: Some(self.branch.clone()).filter(|branch| !branch.is_empty()),
+                default_branch: Some(self.advanced_settings.build_cache_default_branch.clone())
+                    .filter(|branch| !branch.is_empty()),
+                mode: self.advanced_settings.build_cache_mode,
+                export: self.advanced_settings.build_cache_export_enabled,
+            },
+            dockerfile_lint_policy: DockerfileLintPolicy {
+                level: self.advanced_settings.build_dockerfile_lint_level,
+                enforced_rules: self.advanced_settings.build_dockerfile_lint_enforced_rules.clone(),
+            },
+            source_archive: self.source_archive.clone(),
+            image_promotion: self.image_promotion.clone(),
+            vulnerability_scan_fail_on_severity: self.advanced_settings.security_vulnerability_scan_fail_on_severity,
         };
 
         build.compute_image_tag();
+        // A promoted image is not built, it is identified by its digest only
+        if let Some(image_promotion) = &self.image_promotion {
+            build.image.tag = image_promotion.image_tag();
+        }
         build
     }
 }
@@ -2,6 +2,7 @@ use super::{PodAntiAffinity, UpdateStrategy};
 use crate::cloud_provider::kubernetes::{Kind as KubernetesKind, Kubernetes};
 use crate::cloud_provider::models::{KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit};
 use crate::cloud_provider::{CloudProvider, Kind as CPKind};
+use crate::cmd::vulnerability_scanner::VulnerabilitySeverity;
 use crate::container_registry::ecr::ECR;
 use crate::container_registry::errors::ContainerRegistryError;
 use crate::container_registry::ContainerRegistry;
@@ -215,6 +216,9 @@ pub struct ContainerAdvancedSettings {
     pub security_read_only_root_filesystem: bool,
     #[serde(alias = "security.automount_service_account_token")]
     pub security_automount_service_account_token: bool,
+    // deployment is blocked when the image has a vulnerability at or above this severity
+    #[serde(alias = "security.vulnerability_scan.fail_on_severity")]
+    pub security_vulnerability_scan_fail_on_severity: Option<VulnerabilitySeverity>,
 
     // Deployment
     #[serde(alias = "deployment.termination_grace_period_seconds")]
@@ -275,6 +279,8 @@ pub struct ContainerAdvancedSettings {
     pub network_ingress_denylist_source_range: String,
     #[serde(alias = "network.ingress.basic_auth_env_var")]
     pub network_ingress_basic_auth_env_var: String,
+    #[serde(alias = "network.ingress.certificate_strict_check")]
+    pub network_ingress_certificate_strict_check: bool,
 
     #[serde(alias = "network.ingress.grpc_send_timeout_seconds")]
     pub network_ingress_grpc_send_timeout_seconds: u32,
@@ -294,6 +300,7 @@ impl Default for ContainerAdvancedSettings {
             security_service_account_name: "".to_string(),
             security_read_only_root_filesystem: false,
             security_automount_service_account_token: false,
+            security_vulnerability_scan_fail_on_severity: None,
             deployment_termination_grace_period_seconds: 60,
             deployment_update_strategy_type: UpdateStrategy::RollingUpdate,
             deployment_update_strategy_rolling_update_max_unavailable_percent: 25,
@@ -322,6 +329,7 @@ impl Default for ContainerAdvancedSettings {
             network_ingress_whitelist_source_range: "0.0.0.0/0".to_string(),
             network_ingress_denylist_source_range: "".to_string(),
             network_ingress_basic_auth_env_var: "".to_string(),
+            network_ingress_certificate_strict_check: false,
             network_ingress_grpc_send_timeout_seconds: 60,
             network_ingress_grpc_read_timeout_seconds: 60,
             hpa_cpu_average_utilization_percent: 60,
@@ -61,6 +61,9 @@ pub struct Database {
     pub annotations_group_ids: BTreeSet<Uuid>,
     #[serde(default)]
     pub labels_group_ids: BTreeSet<Uuid>,
+    /// Set by the engine while rotating credentials: the password still in use in the database, `password` being the new one
+    #[serde(skip)]
+    pub previous_password: Option<String>,
 }
 
 impl Database {
@@ -75,6 +78,7 @@ impl Database {
             mode: self.mode.clone(),
             login: self.username.clone(),
             password: self.password.clone(),
+            previous_password: self.previous_password.clone(),
             host: self.fqdn.clone(),
             port: self.port,
             disk_size_in_gib: self.disk_size_in_gib,
@@ -1046,6 +1050,8 @@ impl DatabaseKind {
 pub struct DatabaseOptions {
     pub login: String,
     pub password: String,
+    /// Password to replace in the running database when rotating credentials
+    pub previous_password: Option<String>,
     pub host: String,
     pub port: u16,
     pub mode: DatabaseMode,
@@ -0,0 +1,95 @@
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::io_models::annotations_group::AnnotationsGroup;
+use crate::io_models::database::{Database, DatabaseMode};
+use crate::io_models::environment::EnvironmentRequest;
+use crate::io_models::labels_group::LabelsGroup;
+use crate::io_models::Action;
+
+/// Container databases to give new credentials, without redeploying their environment.
+/// The services of the environment using them are updated and restarted in the cluster by the rotation.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DatabaseCredentialsRotationRequest {
+    // environment of the databases
+    pub long_id: Uuid,
+    pub name: String,
+    pub kube_name: String,
+    pub project_long_id: Uuid,
+    pub organization_long_id: Uuid,
+    pub databases: Vec<Database>,
+    #[serde(default)]
+    pub annotations_groups: BTreeMap<Uuid, AnnotationsGroup>,
+    #[serde(default)]
+    pub labels_groups: BTreeMap<Uuid, LabelsGroup>,
+}
+
+fn generate_database_password() -> String {
+    // alphanumeric only, so it can be used as is in connection URIs and rotation queries
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+impl DatabaseCredentialsRotationRequest {
+    /// Environment upgrading only the databases to rotate, each one with new credentials.
+    /// Returns the new passwords along with it, so they can be obfuscated in logs.
+    pub fn to_environment_request(&self, execution_id: &str) -> (EnvironmentRequest, Vec<String>) {
+        let mut new_passwords = vec![];
+        let databases = self
+            .databases
+            .iter()
+            .filter(|database| database.mode == DatabaseMode::CONTAINER && !database.password.is_empty())
+            .map(|database| {
+                let new_password = generate_database_password();
+                new_passwords.push(new_password.clone());
+                Database {
+                    action: Action::Create,
+                    password: new_password,
+                    previous_password: Some(database.password.clone()),
+                    ..database.clone()
+                }
+            })
+            .collect();
+
+        let environment = EnvironmentRequest {
+            execution_id: execution_id.to_string(),
+            long_id: self.long_id,
+            name: self.name.clone(),
+            kube_name: self.kube_name.clone(),
+            project_long_id: self.project_long_id,
+            organization_long_id: self.organization_long_id,
+            action: Action::Create,
+            max_parallel_build: 1,
+            max_parallel_deploy: 1,
+            applications: vec![],
+            containers: vec![],
+            jobs: vec![],
+            routers: vec![],
+            databases,
+            helms: vec![],
+            annotations_groups: self.annotations_groups.clone(),
+            labels_groups: self.labels_groups.clone(),
+        };
+
+        (environment, new_passwords)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_database_password() {
+        let password = generate_database_password();
+        assert_eq!(password.len(), 32);
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(password, generate_database_password());
+    }
+}
@@ -3,6 +3,8 @@ use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
 use crate::build_platform::local_docker::LocalDocker;
+use crate::build_platform::remote_buildkit::RemoteBuildkit;
+use crate::build_platform::BuildError;
 use crate::cloud_provider::aws::kubernetes::{ec2::EC2, eks::EKS};
 use crate::cloud_provider::aws::regions::AwsRegion;
 use crate::cloud_provider::aws::AWS;
@@ -15,20 +17,27 @@ use crate::cloud_provider::models::NodeGroups;
 use crate::cloud_provider::scaleway::kubernetes::Kapsule;
 use crate::cloud_provider::scaleway::Scaleway;
 use crate::cloud_provider::self_managed::SelfManaged;
+use crate::cmd::buildctl::BuildKitTls;
+use crate::cmd::cosign::{Cosign, CosignKeys};
 use crate::container_registry::ecr::ECR;
 use crate::container_registry::generic_cr::GenericCr;
 use crate::container_registry::github_cr::{GithubCr, RegistryType};
 use crate::container_registry::google_artifact_registry::GoogleArtifactRegistry;
+use crate::container_registry::oci_registry::OciRegistry;
 use crate::container_registry::scaleway_container_registry::ScalewayCR;
 use crate::dns_provider::cloudflare::Cloudflare;
 use crate::dns_provider::io::Kind;
 use crate::dns_provider::qoverydns::QoveryDns;
+use crate::dns_provider::rfc2136::{Rfc2136, Rfc2136TsigAlgorithm};
+use crate::dns_provider::route53::Route53;
 use crate::engine::InfrastructureContext;
 use crate::errors::{CommandError, EngineError as IoEngineError, EngineError};
-use crate::events::{EventDetails, InfrastructureStep, Stage, Transmitter};
+use crate::events::{EnvironmentStep, EventDetails, InfrastructureStep, Stage, Transmitter};
 use crate::fs::workspace_directory;
 use crate::io_models::context::{Context, Features, Metadata};
+use crate::io_models::database_credentials_rotation::DatabaseCredentialsRotationRequest;
 use crate::io_models::environment::EnvironmentRequest;
+use crate::io_models::image_promotion::ImagePromotionRequest;
 use crate::io_models::{Action, QoveryIdentifier};
 use crate::logger::Logger;
 use crate::metrics_registry::MetricsRegistry;
@@ -51,6 +60,8 @@ use uuid::Uuid;
 
 pub type EnvironmentEngineRequest = EngineRequest<EnvironmentRequest>;
 pub type InfrastructureEngineRequest = EngineRequest<Option<()>>;
+pub type ImagePromotionEngineRequest = EngineRequest<ImagePromotionRequest>;
+pub type DatabaseCredentialsRotationEngineRequest = EngineRequest<DatabaseCredentialsRotationRequest>;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct EngineRequest<T> {
@@ -70,6 +81,9 @@ pub struct EngineRequest<T> {
     pub target_environment: T,
     pub metadata: Option<Metadata>,
     pub archive: Option<Archive>,
+    // cosign key pair used to sign built images, and to verify images deployed from a registry
+    #[serde(default)]
+    pub image_signing: Option<ImageSigning>,
 }
 
 impl<T> EngineRequest<T> {
@@ -83,7 +97,11 @@ impl<T> EngineRequest<T> {
     ) -> Result<InfrastructureContext, Box<EngineError>> {
         let build_platform = self
             .build_platform
-            .to_engine_build_platform(context, metrics_registry.clone_dyn());
+            .to_engine_build_platform(context, metrics_registry.clone_dyn())
+            .map_err(|err| {
+                let msg = format!("Invalid build platform {}: {}", self.build_platform.name, err);
+                Box::new(build_platform::to_engine_error(event_details.clone(), err, msg))
+            })?;
         let cloud_provider = self
             .cloud_provider
             .to_engine_cloud_provider(context.clone(), &self.kubernetes.region, self.kubernetes.kind)
@@ -129,6 +147,17 @@ impl<T> EngineRequest<T> {
                 )
             })?;
 
+        let image_signer = match &self.image_signing {
+            None => None,
+            Some(image_signing) => Some(Cosign::new(image_signing.to_cosign_keys()).map_err(|err| {
+                IoEngineError::new_invalid_engine_payload(
+                    event_details.clone(),
+                    "Invalid image signing information",
+                    Some(CommandError::new_from_safe_message(err.to_string())),
+                )
+            })?),
+        };
+
         let cluster_jwt_token: String = self
             .kubernetes
             .options
@@ -170,6 +199,7 @@ impl<T> EngineRequest<T> {
             dns_provider,
             kubernetes,
             metrics_registry,
+            image_signer,
             is_infra_deployment,
         ))
     }
@@ -224,6 +254,55 @@ impl EnvironmentEngineRequest {
     }
 }
 
+impl ImagePromotionEngineRequest {
+    pub fn event_details(&self) -> EventDetails {
+        let kubernetes = &self.kubernetes;
+        EventDetails::new(
+            Some(self.cloud_provider.kind.clone()),
+            QoveryIdentifier::new(self.organization_long_id),
+            QoveryIdentifier::new(kubernetes.long_id),
+            self.id.to_string(),
+            Stage::Environment(EnvironmentStep::Promote),
+            Transmitter::Environment(self.target_environment.long_id, self.target_environment.name.clone()),
+        )
+    }
+}
+
+impl DatabaseCredentialsRotationEngineRequest {
+    pub fn event_details(&self) -> EventDetails {
+        let kubernetes = &self.kubernetes;
+        EventDetails::new(
+            Some(self.cloud_provider.kind.clone()),
+            QoveryIdentifier::new(self.organization_long_id),
+            QoveryIdentifier::new(kubernetes.long_id),
+            self.id.to_string(),
+            Stage::Environment(EnvironmentStep::RotateCredentials),
+            Transmitter::Environment(self.target_environment.long_id, self.target_environment.name.clone()),
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Derivative)]
+#[derivative(Debug)]
+pub struct ImageSigning {
+    // cosign private key, in PEM format. Only required to sign images
+    #[derivative(Debug = "ignore")]
+    pub private_key: Option<String>,
+    #[derivative(Debug = "ignore")]
+    pub private_key_password: Option<String>,
+    pub public_key: String,
+}
+
+impl ImageSigning {
+    pub fn to_cosign_keys(&self) -> CosignKeys {
+        CosignKeys {
+            private_key: self.private_key.clone(),
+            private_key_password: self.private_key_password.clone(),
+            public_key: self.public_key.clone(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct BuildPlatform {
     pub kind: build_platform::Kind,
@@ -238,13 +317,57 @@ impl BuildPlatform {
         &self,
         context: &Context,
         metrics_registry: Box<dyn MetricsRegistry>,
-    ) -> Box<dyn build_platform::BuildPlatform> {
-        Box::new(match self.kind {
-            build_platform::Kind::LocalDocker => {
-                // FIXME: Remove the unwrap by propagating errors above
-                LocalDocker::new(context.clone(), self.long_id, self.name.as_str(), metrics_registry).unwrap()
+    ) -> Result<Box<dyn build_platform::BuildPlatform>, BuildError> {
+        match self.kind {
+            build_platform::Kind::LocalDocker => Ok(Box::new(LocalDocker::new(
+                context.clone(),
+                self.long_id,
+                self.name.as_str(),
+                metrics_registry,
+            )?)),
+            build_platform::Kind::RemoteBuildkit => {
+                let invalid_config = |raw_error_message: &str| BuildError::InvalidConfig {
+                    application: self.name.to_string(),
+                    raw_error_message: raw_error_message.to_string(),
+                };
+                let address = self
+                    .options
+                    .buildkit_address
+                    .as_ref()
+                    .and_then(|addr| Url::parse(addr).ok())
+                    .ok_or_else(|| {
+                        invalid_config("Remote buildkit build platform requires a valid buildkit_address option")
+                    })?;
+                // A partial TLS configuration must not silently fall back to plaintext
+                let tls = match (
+                    &self.options.buildkit_tls_ca_cert,
+                    &self.options.buildkit_tls_client_cert,
+                    &self.options.buildkit_tls_client_key,
+                ) {
+                    (Some(ca_cert), Some(client_cert), Some(client_key)) => Some(BuildKitTls {
+                        ca_cert: ca_cert.clone(),
+                        client_cert: client_cert.clone(),
+                        client_key: client_key.clone(),
+                        server_name: self.options.buildkit_tls_server_name.clone(),
+                    }),
+                    (None, None, None) => None,
+                    _ => {
+                        return Err(invalid_config(
+                            "Remote buildkit TLS requires buildkit_tls_ca_cert, buildkit_tls_client_cert and buildkit_tls_client_key options to be all set",
+                        ))
+                    }
+                };
+
+                Ok(Box::new(RemoteBuildkit::new(
+                    context.clone(),
+                    self.long_id,
+                    self.name.as_str(),
+                    address,
+                    tls,
+                    metrics_registry,
+                )?))
             }
-        })
+        }
     }
 }
 
@@ -579,6 +702,11 @@ pub enum ContainerRegistry {
         name: String,
         options: GithubCrOptions,
     },
+    OciRegistry {
+        long_id: Uuid,
+        name: String,
+        options: OciRegistryOptions,
+    },
 }
 impl ContainerRegistry {}
 
@@ -655,6 +783,15 @@ impl ContainerRegistry {
                 options.username,
                 options.token,
             )?)),
+            ContainerRegistry::OciRegistry { long_id, name, options } => Ok(Box::new(OciRegistry::new(
+                context,
+                long_id,
+                &name,
+                options.url,
+                options.skip_tls_verify,
+                options.repository_name,
+                options.username.and_then(|l| options.password.map(|p| (l, p))),
+            )?)),
         }
     }
 }
@@ -710,6 +847,55 @@ impl DnsProvider {
 
                 None
             }
+            Kind::Route53 => {
+                let access_key_id = self.options.get("route53_access_key_id")?;
+                let secret_access_key = self.options.get("route53_secret_access_key")?;
+                let hosted_zone_id = self.options.get("route53_hosted_zone_id")?;
+                let region = self
+                    .options
+                    .get("route53_region")
+                    .map(|s| s.as_str())
+                    .unwrap_or("us-east-1");
+
+                Some(Box::new(Route53::new(
+                    context,
+                    self.long_id,
+                    self.name.as_str(),
+                    Domain::new(self.domain.clone()),
+                    access_key_id.as_str(),
+                    secret_access_key.as_str(),
+                    region,
+                    hosted_zone_id.as_str(),
+                )))
+            }
+            Kind::Rfc2136 => {
+                let nameserver_host = self.options.get("rfc2136_nameserver_host")?;
+                let nameserver_port = self
+                    .options
+                    .get("rfc2136_nameserver_port")
+                    .map(|s| s.parse::<u16>().ok())
+                    .unwrap_or(Some(53))?;
+                let zone = self.options.get("rfc2136_zone")?;
+                let tsig_key_name = self.options.get("rfc2136_tsig_key_name")?;
+                let tsig_secret = self.options.get("rfc2136_tsig_secret")?;
+                let tsig_algorithm = match self.options.get("rfc2136_tsig_algorithm") {
+                    Some(tsig_algorithm) => Rfc2136TsigAlgorithm::from_str(tsig_algorithm).ok()?,
+                    None => Rfc2136TsigAlgorithm::HmacSha256,
+                };
+
+                Some(Box::new(Rfc2136::new(
+                    context,
+                    self.long_id,
+                    self.name.as_str(),
+                    Domain::new(self.domain.clone()),
+                    nameserver_host.as_str(),
+                    nameserver_port,
+                    zone.as_str(),
+                    tsig_key_name.as_str(),
+                    tsig_secret.as_str(),
+                    tsig_algorithm,
+                )))
+            }
         }
     }
 }
@@ -739,6 +925,13 @@ pub struct Options {
     #[derivative(Debug = "ignore")]
     pub token: Option<String>,
     region: Option<String>,
+    // remote buildkit build platform, address is either tcp://host:port or unix:///path/to/buildkitd.sock
+    buildkit_address: Option<String>,
+    buildkit_tls_ca_cert: Option<String>,
+    buildkit_tls_client_cert: Option<String>,
+    #[derivative(Debug = "ignore")]
+    pub buildkit_tls_client_key: Option<String>,
+    buildkit_tls_server_name: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Derivative)]
@@ -767,6 +960,19 @@ pub struct GenericCrOptions {
     repository_name: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Derivative)]
+pub struct OciRegistryOptions {
+    // i.e: https://registry-1.docker.io for Docker Hub
+    pub url: Url,
+    pub username: Option<String>,
+    #[derivative(Debug = "ignore")]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub skip_tls_verify: bool,
+    // namespace of the images in the registry, i.e: the Docker Hub user or organization
+    repository_name: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Derivative)]
 pub struct GithubCrOptions {
     pub url: Url,
@@ -813,6 +1019,16 @@ where
 #[derivative(Debug)]
 pub struct Archive {
     pub upload_url: Url,
+    // when set, the archive is written in the cluster object storage with a multipart upload
+    // instead of a single PUT on the presigned url, which doesn't suit big workspaces
+    #[serde(default)]
+    pub bucket_location: Option<ArchiveBucketLocation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ArchiveBucketLocation {
+    pub bucket_name: String,
+    pub object_key: String,
 }
 
 impl From<GithubCrRepoType> for RegistryType {
@@ -147,6 +147,10 @@ impl EnvironmentRequest {
                             router_advanced_settings.denylist_source_range =
                                 Some(app.advanced_settings.network_ingress_denylist_source_range.clone());
                         }
+                        // certificate strict check
+                        if app.advanced_settings.network_ingress_certificate_strict_check {
+                            router_advanced_settings.certificate_strict_check = true;
+                        }
                         // basic auth
                         if app.advanced_settings.network_ingress_basic_auth_env_var != *"" {
                             match app
@@ -206,6 +210,10 @@ impl EnvironmentRequest {
                                     .clone(),
                             );
                         }
+                        // certificate strict check
+                        if container.advanced_settings.network_ingress_certificate_strict_check {
+                            router_advanced_settings.certificate_strict_check = true;
+                        }
                         // basic auth
                         if container.advanced_settings.network_ingress_basic_auth_env_var != *"" {
                             match container
@@ -254,6 +262,10 @@ impl EnvironmentRequest {
                             router_advanced_settings.denylist_source_range =
                                 Some(helm.advanced_settings.network_ingress_denylist_source_range.clone());
                         }
+                        // certificate strict check
+                        if helm.advanced_settings.network_ingress_certificate_strict_check {
+                            router_advanced_settings.certificate_strict_check = true;
+                        }
                         // basic auth
                         if helm.advanced_settings.network_ingress_basic_auth_env_var != *"" {
                             match helm
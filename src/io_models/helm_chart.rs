@@ -73,6 +73,8 @@ pub struct HelmChartAdvancedSettings {
     pub network_ingress_denylist_source_range: String,
     #[serde(alias = "network.ingress.basic_auth_env_var")]
     pub network_ingress_basic_auth_env_var: String,
+    #[serde(alias = "network.ingress.certificate_strict_check")]
+    pub network_ingress_certificate_strict_check: bool,
 
     #[serde(alias = "network.ingress.grpc_send_timeout_seconds")]
     pub network_ingress_grpc_send_timeout_seconds: u32,
@@ -103,6 +105,7 @@ impl Default for HelmChartAdvancedSettings {
             network_ingress_whitelist_source_range: "0.0.0.0/0".to_string(),
             network_ingress_denylist_source_range: "".to_string(),
             network_ingress_basic_auth_env_var: "".to_string(),
+            network_ingress_certificate_strict_check: false,
             network_ingress_grpc_send_timeout_seconds: 60,
             network_ingress_grpc_read_timeout_seconds: 60,
         }
@@ -489,6 +492,7 @@ fn test_helm_deserialization_with_env_variables_with_infos() {
         &VariableInfo {
             value: "value".to_string(),
             is_secret: false,
+            external_secret: None,
         }
     );
     assert_eq!(
@@ -496,6 +500,7 @@ fn test_helm_deserialization_with_env_variables_with_infos() {
         &VariableInfo {
             value: "my password".to_string(),
             is_secret: true,
+            external_secret: None,
         }
     );
 }
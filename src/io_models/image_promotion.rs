@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::build_platform::Image;
+use crate::cmd::vulnerability_scanner::VulnerabilitySeverity;
+use crate::container_registry::{ContainerRegistryInfo, ImagePromotion};
+use crate::io_models::application::to_application_image;
+use crate::io_models::QoveryIdentifier;
+
+/// Images already built for another cluster, to copy into the registry of the cluster.
+/// Once promoted, the applications are deployed by an environment deployment without being built.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ImagePromotionRequest {
+    // environment of the promoted applications
+    pub long_id: Uuid,
+    pub name: String,
+    pub project_long_id: Uuid,
+    pub applications: Vec<ApplicationImagePromotion>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ApplicationImagePromotion {
+    pub long_id: Uuid,
+    pub name: String,
+    // used to name the image when images are shared between the applications of a repository
+    pub git_url: String,
+    #[serde(default)]
+    pub shared_image_feature_enabled: bool,
+    // promotion is refused when the image has a vulnerability at or above this severity
+    #[serde(default)]
+    pub vulnerability_scan_fail_on_severity: Option<VulnerabilitySeverity>,
+    pub image_promotion: ImagePromotion,
+}
+
+impl ApplicationImagePromotion {
+    /// Image of the application in the cluster registry, the same one the environment deployment will look for
+    pub fn to_image(&self, cr_info: &ContainerRegistryInfo, cluster_id: &QoveryIdentifier) -> Image {
+        let mut image = to_application_image(
+            self.long_id,
+            &self.name,
+            &self.git_url,
+            &self.image_promotion.digest,
+            self.shared_image_feature_enabled,
+            cr_info,
+            cluster_id,
+        );
+        image.tag = self.image_promotion.image_tag();
+        image
+    }
+}
@@ -1,8 +1,14 @@
-use crate::build_platform::{Build, GitRepository, Image, SshKey};
+use crate::build_platform::dockerfile_linter::{DockerfileLintPolicy, LintLevel, LintRule};
+use crate::build_platform::source_archive::SourceArchive;
+use crate::build_platform::{
+    Build, BuildEnvironmentVariable, CacheSettings, GitRepository, Image, ImageTagStrategy, SshKey,
+};
 use crate::cloud_provider::kubernetes::{Kind as KubernetesKind, Kubernetes};
 use crate::cloud_provider::models::{CpuArchitecture, KubernetesCpuResourceUnit, KubernetesMemoryResourceUnit};
 use crate::cloud_provider::service::ServiceType;
 use crate::cloud_provider::{CloudProvider, Kind};
+use crate::cmd::docker::CacheMode;
+use crate::cmd::vulnerability_scanner::VulnerabilitySeverity;
 use crate::container_registry::{ContainerRegistry, ContainerRegistryInfo};
 use crate::engine_task::qovery_api::QoveryApi;
 use crate::io_models::annotations_group::AnnotationsGroup;
@@ -31,6 +37,7 @@ use base64::Engine;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use url::Url;
@@ -63,6 +70,23 @@ pub struct JobAdvancedSettings {
     pub build_cpu_max_in_milli: u32,
     #[serde(alias = "build.ram_max_in_gib")]
     pub build_ram_max_in_gib: u32,
+    // monorepo: tag images from the git tree of the root path instead of the commit, to skip builds of untouched jobs
+    #[serde(alias = "build.tag_from_git_tree")]
+    pub build_tag_from_git_tree: bool,
+    // extra paths (i.e: shared libraries) that must trigger a new build when modified
+    #[serde(alias = "build.watch_paths")]
+    pub build_watch_paths: Vec<String>,
+    #[serde(alias = "build.cache.mode")]
+    pub build_cache_mode: CacheMode,
+    #[serde(alias = "build.cache.export_enabled")]
+    pub build_cache_export_enabled: bool,
+    #[serde(alias = "build.cache.default_branch")]
+    pub build_cache_default_branch: String,
+    #[serde(alias = "build.dockerfile_lint.level")]
+    pub build_dockerfile_lint_level: LintLevel,
+    // with the error level, only findings of those rules fail the build (all rules when empty)
+    #[serde(alias = "build.dockerfile_lint.enforced_rules")]
+    pub build_dockerfile_lint_enforced_rules: BTreeSet<LintRule>,
 
     #[serde(alias = "security.service_account_name")]
     pub security_service_account_name: String,
@@ -70,6 +94,9 @@ pub struct JobAdvancedSettings {
     pub security_read_only_root_filesystem: bool,
     #[serde(alias = "security.automount_service_account_token")]
     pub security_automount_service_account_token: bool,
+    // deployment is blocked when the image has a vulnerability at or above this severity
+    #[serde(alias = "security.vulnerability_scan.fail_on_severity")]
+    pub security_vulnerability_scan_fail_on_severity: Option<VulnerabilitySeverity>,
 }
 
 impl Default for JobAdvancedSettings {
@@ -84,9 +111,17 @@ impl Default for JobAdvancedSettings {
             build_timeout_max_sec: 30 * 60, // 30 minutes
             build_cpu_max_in_milli: 4000,
             build_ram_max_in_gib: 8,
+            build_tag_from_git_tree: false,
+            build_watch_paths: vec![],
+            build_cache_mode: CacheMode::Max,
+            build_cache_export_enabled: true,
+            build_cache_default_branch: "main".to_string(),
+            build_dockerfile_lint_level: LintLevel::Warn,
+            build_dockerfile_lint_enforced_rules: BTreeSet::new(),
             security_service_account_name: "".to_string(),
             security_read_only_root_filesystem: false,
             security_automount_service_account_token: false,
+            security_vulnerability_scan_fail_on_severity: None,
         }
     }
 }
@@ -144,6 +179,13 @@ pub enum JobSource {
         dockerfile_path: Option<String>,
         dockerfile_content: Option<String>,
     },
+    // Same as Docker, but sources come from a .tar.gz archive instead of a git repository
+    Archive {
+        source_archive: SourceArchive,
+        root_path: String,
+        dockerfile_path: Option<String>,
+        dockerfile_content: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
@@ -203,33 +245,56 @@ impl Job {
         cluster_id: &QoveryIdentifier,
     ) -> Option<Build> {
         let qovery_dockerfile = Some("Dockerfile.qovery".to_string());
-        let (git_url, git_credentials, _branch, commit_id, dockerfile_path, dockerfile_content, root_path) =
-            match &self.source {
-                JobSource::Docker {
-                    git_url,
-                    git_credentials,
-                    branch,
-                    commit_id,
-                    root_path,
-                    dockerfile_path,
-                    dockerfile_content,
-                } => {
-                    if dockerfile_content.is_some() {
-                        (
-                            git_url,
-                            git_credentials,
-                            branch,
-                            commit_id,
-                            &qovery_dockerfile,
-                            dockerfile_content,
-                            root_path,
-                        )
-                    } else {
-                        (git_url, git_credentials, branch, commit_id, dockerfile_path, &None, root_path)
-                    }
-                }
-                _ => return None,
-            };
+        let (
+            git_url,
+            git_credentials,
+            branch,
+            commit_id,
+            dockerfile_path,
+            dockerfile_content,
+            root_path,
+            source_archive,
+        ) = match &self.source {
+            JobSource::Docker {
+                git_url,
+                git_credentials,
+                branch,
+                commit_id,
+                root_path,
+                dockerfile_path,
+                dockerfile_content,
+            } => (
+                git_url.clone(),
+                git_credentials,
+                branch.as_str(),
+                commit_id.clone(),
+                dockerfile_path,
+                dockerfile_content,
+                root_path,
+                None,
+            ),
+            // Image tag is computed from the archive checksum, as there is no commit for archives
+            JobSource::Archive {
+                source_archive,
+                root_path,
+                dockerfile_path,
+                dockerfile_content,
+            } => (
+                source_archive.url().to_string(),
+                &None,
+                "",
+                source_archive.sha256.clone(),
+                dockerfile_path,
+                dockerfile_content,
+                root_path,
+                Some(source_archive),
+            ),
+            JobSource::Image { .. } => return None,
+        };
+        let dockerfile_path = match dockerfile_content {
+            Some(_) => &qovery_dockerfile,
+            None => dockerfile_path,
+        };
 
         // Retrieve ssh keys from env variables
 
@@ -239,7 +304,7 @@ impl Job {
         // Convert our root path to an relative path to be able to append them correctly
         let (root_path, dockerfile_path) = normalize_root_and_dockerfile_path(root_path, dockerfile_path);
 
-        let url = Url::parse(git_url).unwrap_or_else(|_| {
+        let url = Url::parse(&git_url).unwrap_or_else(|_| {
             Url::parse("https://invalid-git-url.com").expect("Error while trying to parse invalid git url")
         });
 
@@ -259,8 +324,9 @@ impl Job {
                 dockerfile_content: dockerfile_content.clone(),
                 root_path,
                 buildpack_language: None,
+                generate_dockerfile: false,
             },
-            image: self.to_image(commit_id.to_string(), registry_url, cluster_id, git_url),
+            image: self.to_image(commit_id, registry_url, cluster_id, &git_url),
             environment_variables: self
                 .environment_vars_with_infos
                 .iter()
@@ -277,7 +343,13 @@ impl Job {
                         return None;
                     }
 
-                    Some((k.clone(), v))
+                    Some((
+                        k.clone(),
+                        BuildEnvironmentVariable {
+                            value: v,
+                            is_secret: variable_infos.is_secret,
+                        },
+                    ))
                 })
                 .collect::<BTreeMap<_, _>>(),
             disable_cache: disable_build_cache,
@@ -286,6 +358,34 @@ impl Job {
             max_cpu_in_milli: self.advanced_settings.build_cpu_max_in_milli,
             max_ram_in_gib: self.advanced_settings.build_ram_max_in_gib,
             registries: self.container_registries.registries.clone(),
+            sbom_format: None,
+            tag_strategy: if self.advanced_settings.build_tag_from_git_tree && source_archive.is_none() {
+                ImageTagStrategy::GitTree {
+                    watch_paths: self
+                        .advanced_settings
+                        .build_watch_paths
+                        .iter()
+                        .map(PathBuf::from)
+                        .collect(),
+                }
+            } else {
+                ImageTagStrategy::CommitId
+            },
+            source_tree_id: None,
+            cache_settings: CacheSettings {
+                branch: Some(branch.to_string()).filter(|branch| !branch.is_empty()),
+                default_branch: Some(self.advanced_settings.build_cache_default_branch.clone())
+                    .filter(|branch| !branch.is_empty()),
+                mode: self.advanced_settings.build_cache_mode,
+                export: self.advanced_settings.build_cache_export_enabled,
+            },
+            dockerfile_lint_policy: DockerfileLintPolicy {
+                level: self.advanced_settings.build_dockerfile_lint_level,
+                enforced_rules: self.advanced_settings.build_dockerfile_lint_enforced_rules.clone(),
+            },
+            source_archive: source_archive.cloned(),
+            image_promotion: None,
+            vulnerability_scan_fail_on_severity: self.advanced_settings.security_vulnerability_scan_fail_on_severity,
         };
 
         build.compute_image_tag();
@@ -316,6 +416,7 @@ impl Job {
             repository_name: cr_info.get_repository_name(&self.long_id.to_string()),
             shared_repository_name: cr_info.get_shared_repository_name(cluster_id, sanitized_git_url(git_url)),
             shared_image_feature_enabled: self.shared_image_feature_enabled,
+            digest: None, // It is resolved once the image is pushed
         }
     }
 
@@ -329,7 +430,7 @@ impl Job {
         labels_group: &BTreeMap<Uuid, LabelsGroup>,
     ) -> Result<Box<dyn JobService>, JobError> {
         let image_source = match self.source {
-            JobSource::Docker { .. } => {
+            JobSource::Docker { .. } | JobSource::Archive { .. } => {
                 let build = match self.to_build(
                     default_container_registry.registry_info(),
                     context.qovery_api.clone(),
@@ -19,10 +19,12 @@ pub mod application;
 pub mod container;
 pub mod context;
 pub mod database;
+pub mod database_credentials_rotation;
 pub mod engine_request;
 pub mod environment;
 mod gke;
 pub mod helm_chart;
+pub mod image_promotion;
 pub mod job;
 pub mod labels_group;
 pub mod probe;
@@ -1,12 +1,254 @@
 use serde_derive::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct VariableInfo {
     pub value: String,
     pub is_secret: bool,
+    /// When set, `value` is ignored and the secret is fetched from the referenced store at deploy time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_secret: Option<ExternalSecretReference>,
+}
+
+/// Reference to a secret stored outside of Qovery, so its value never transits in the engine request
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub enum ExternalSecretReference {
+    /// vault://mount/path#key
+    Vault { mount: String, path: String, key: String },
+    /// aws-sm://arn[#key], without key the whole secret string is used
+    AwsSecretsManager { arn: String, key: Option<String> },
+    /// gcp-sm://projects/project/secrets/secret[/versions/version][#key]
+    GcpSecretManager {
+        project: String,
+        secret: String,
+        version: Option<String>,
+        key: Option<String>,
+    },
+    /// k8s://namespace/secret#key, the namespace must be the environment one
+    Kubernetes {
+        namespace: String,
+        secret_name: String,
+        key: String,
+    },
+}
+
+impl FromStr for ExternalSecretReference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| format!("invalid external secret reference `{s}`: {reason}");
+        let (scheme, location) = s.split_once("://").ok_or_else(|| invalid("missing scheme"))?;
+        let (location, key) = match location.split_once('#') {
+            Some((_, "")) => return Err(invalid("empty key")),
+            Some((location, key)) => (location, Some(key.to_string())),
+            None => (location, None),
+        };
+        if location.is_empty() {
+            return Err(invalid("empty location"));
+        }
+
+        match scheme {
+            "vault" => {
+                let (mount, path) = location
+                    .split_once('/')
+                    .filter(|(mount, path)| !mount.is_empty() && !path.is_empty())
+                    .ok_or_else(|| invalid("expected vault://mount/path#key"))?;
+                Ok(ExternalSecretReference::Vault {
+                    mount: mount.to_string(),
+                    path: path.to_string(),
+                    key: key.ok_or_else(|| invalid("missing key"))?,
+                })
+            }
+            "aws-sm" => Ok(ExternalSecretReference::AwsSecretsManager {
+                arn: location.to_string(),
+                key,
+            }),
+            "gcp-sm" => match location.split('/').collect::<Vec<_>>().as_slice() {
+                ["projects", project, "secrets", secret] => Ok(ExternalSecretReference::GcpSecretManager {
+                    project: project.to_string(),
+                    secret: secret.to_string(),
+                    version: None,
+                    key,
+                }),
+                ["projects", project, "secrets", secret, "versions", version] => {
+                    Ok(ExternalSecretReference::GcpSecretManager {
+                        project: project.to_string(),
+                        secret: secret.to_string(),
+                        version: Some(version.to_string()),
+                        key,
+                    })
+                }
+                _ => Err(invalid("expected gcp-sm://projects/project/secrets/secret[/versions/version]")),
+            },
+            "k8s" => {
+                let (namespace, secret_name) = location
+                    .split_once('/')
+                    .filter(|(namespace, secret_name)| {
+                        !namespace.is_empty() && !secret_name.is_empty() && !secret_name.contains('/')
+                    })
+                    .ok_or_else(|| invalid("expected k8s://namespace/secret#key"))?;
+                Ok(ExternalSecretReference::Kubernetes {
+                    namespace: namespace.to_string(),
+                    secret_name: secret_name.to_string(),
+                    key: key.ok_or_else(|| invalid("missing key"))?,
+                })
+            }
+            _ => Err(invalid("unknown scheme")),
+        }
+    }
+}
+
+impl TryFrom<String> for ExternalSecretReference {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        ExternalSecretReference::from_str(&value)
+    }
+}
+
+impl From<ExternalSecretReference> for String {
+    fn from(reference: ExternalSecretReference) -> Self {
+        reference.to_string()
+    }
+}
+
+impl Display for ExternalSecretReference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let with_key = |key: &Option<String>| key.as_ref().map(|key| format!("#{key}")).unwrap_or_default();
+        match self {
+            ExternalSecretReference::Vault { mount, path, key } => write!(f, "vault://{mount}/{path}#{key}"),
+            ExternalSecretReference::AwsSecretsManager { arn, key } => write!(f, "aws-sm://{arn}{}", with_key(key)),
+            ExternalSecretReference::GcpSecretManager {
+                project,
+                secret,
+                version,
+                key,
+            } => {
+                write!(f, "gcp-sm://projects/{project}/secrets/{secret}")?;
+                if let Some(version) = version {
+                    write!(f, "/versions/{version}")?;
+                }
+                write!(f, "{}", with_key(key))
+            }
+            ExternalSecretReference::Kubernetes {
+                namespace,
+                secret_name,
+                key,
+            } => write!(f, "k8s://{namespace}/{secret_name}#{key}"),
+        }
+    }
 }
 
 pub fn default_environment_vars_with_info() -> BTreeMap<String, VariableInfo> {
     BTreeMap::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_secret_reference_from_str() {
+        let references = vec![
+            (
+                "vault://secret/my-app/db#password",
+                ExternalSecretReference::Vault {
+                    mount: "secret".to_string(),
+                    path: "my-app/db".to_string(),
+                    key: "password".to_string(),
+                },
+            ),
+            (
+                "aws-sm://arn:aws:secretsmanager:eu-west-3:123456789012:secret:my-app-db-AbCdEf#password",
+                ExternalSecretReference::AwsSecretsManager {
+                    arn: "arn:aws:secretsmanager:eu-west-3:123456789012:secret:my-app-db-AbCdEf".to_string(),
+                    key: Some("password".to_string()),
+                },
+            ),
+            (
+                "aws-sm://arn:aws:secretsmanager:eu-west-3:123456789012:secret:my-app-token-AbCdEf",
+                ExternalSecretReference::AwsSecretsManager {
+                    arn: "arn:aws:secretsmanager:eu-west-3:123456789012:secret:my-app-token-AbCdEf".to_string(),
+                    key: None,
+                },
+            ),
+            (
+                "gcp-sm://projects/my-project/secrets/my-app-token",
+                ExternalSecretReference::GcpSecretManager {
+                    project: "my-project".to_string(),
+                    secret: "my-app-token".to_string(),
+                    version: None,
+                    key: None,
+                },
+            ),
+            (
+                "gcp-sm://projects/my-project/secrets/my-app-db/versions/3#password",
+                ExternalSecretReference::GcpSecretManager {
+                    project: "my-project".to_string(),
+                    secret: "my-app-db".to_string(),
+                    version: Some("3".to_string()),
+                    key: Some("password".to_string()),
+                },
+            ),
+            (
+                "k8s://shared/my-app-db#password",
+                ExternalSecretReference::Kubernetes {
+                    namespace: "shared".to_string(),
+                    secret_name: "my-app-db".to_string(),
+                    key: "password".to_string(),
+                },
+            ),
+        ];
+
+        for (raw, expected) in references {
+            let reference = ExternalSecretReference::from_str(raw).unwrap();
+            assert_eq!(reference, expected);
+            // formatting back must give the same reference
+            assert_eq!(reference.to_string(), raw);
+        }
+
+        for invalid in [
+            "my-app/db#password",
+            "consul://my-app/db#password",
+            "vault://secret#password",
+            "vault://secret/my-app/db",
+            "vault://secret/my-app/db#",
+            "gcp-sm://my-project/my-app-token",
+            "k8s://shared/my-app-db",
+            "k8s://shared/my-app/db#password",
+        ] {
+            assert!(
+                ExternalSecretReference::from_str(invalid).is_err(),
+                "{invalid} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_variable_info_deserialization() {
+        let variables: BTreeMap<String, VariableInfo> = serde_json::from_str(
+            r#"{
+              "PLAIN": {"value": "dmFsdWU=", "is_secret": false},
+              "DB_PASSWORD": {"value": "", "is_secret": true, "external_secret": "vault://secret/my-app/db#password"}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(variables["PLAIN"].external_secret, None);
+        assert_eq!(
+            variables["DB_PASSWORD"].external_secret,
+            Some(ExternalSecretReference::Vault {
+                mount: "secret".to_string(),
+                path: "my-app/db".to_string(),
+                key: "password".to_string(),
+            })
+        );
+        assert!(serde_json::from_str::<VariableInfo>(
+            r#"{"value": "", "is_secret": true, "external_secret": "vault://secret"}"#
+        )
+        .is_err());
+    }
+}
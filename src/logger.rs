@@ -7,6 +7,8 @@ pub trait Logger: Send + Sync {
     fn log(&self, event: EngineEvent);
     fn clone_dyn(&self) -> Box<dyn Logger>;
     fn with_secrets(&self, secrets: Vec<String>) -> Box<dyn Logger>;
+    // Obfuscate those secrets too, from this logger and all its clones
+    fn register_secrets(&self, secrets: Vec<String>);
 }
 
 impl Clone for Box<dyn Logger> {
@@ -68,6 +70,8 @@ impl Logger for StdIoLogger {
     fn with_secrets(&self, _: Vec<String>) -> Box<dyn Logger> {
         Box::new(self.clone())
     }
+
+    fn register_secrets(&self, _: Vec<String>) {}
 }
 
 pub struct UnboundedSenderLogger {
@@ -114,6 +118,10 @@ impl Logger for UnboundedSenderLogger {
             obfuscation_service: self.obfuscation_service.with_secrets(secrets),
         })
     }
+
+    fn register_secrets(&self, secrets: Vec<String>) {
+        self.obfuscation_service.register_secrets(secrets)
+    }
 }
 
 #[cfg(test)]
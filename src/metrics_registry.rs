@@ -12,9 +12,13 @@ pub enum StepName {
     ProvisionBuilder,
     RegistryCreateRepository,
     GitClone,
+    SourceArchiveDownload,
     BuildQueueing,
     Build,
+    Attestation,
+    VulnerabilityScan,
     MirrorImage,
+    PromoteImage,
     DeploymentQueueing,
     Deployment,
 }
@@ -27,8 +31,12 @@ impl Display for StepName {
             StepName::RegistryCreateRepository => "RegistryCreateRepository".to_string(),
             StepName::BuildQueueing => "BuildQueueing".to_string(),
             StepName::GitClone => "GitClone".to_string(),
+            StepName::SourceArchiveDownload => "SourceArchiveDownload".to_string(),
             StepName::Build => "Build".to_string(),
+            StepName::Attestation => "Attestation".to_string(),
+            StepName::VulnerabilityScan => "VulnerabilityScan".to_string(),
             StepName::MirrorImage => "MirrorImage".to_string(),
+            StepName::PromoteImage => "PromoteImage".to_string(),
             StepName::DeploymentQueueing => "DeploymentQueueing".to_string(),
             StepName::Deployment => "Deployment".to_string(),
         };
@@ -74,6 +82,8 @@ pub trait MetricsRegistry: Send + Sync {
     fn record_is_stopped(&self, id: Uuid, deployment_step: StepName) -> bool;
     fn get_records(&self, service_id: Uuid) -> Vec<StepRecord>;
     fn clear(&self);
+    // publish a message which is not a step record, i.e: a vulnerability scan summary
+    fn publish(&self, msg: EngineMsg);
     fn clone_dyn(&self) -> Box<dyn MetricsRegistry>;
 }
 
@@ -218,6 +228,10 @@ impl MetricsRegistry for StdMetricsRegistry {
         registry.clear()
     }
 
+    fn publish(&self, msg: EngineMsg) {
+        self.message_publisher.send(msg)
+    }
+
     fn clone_dyn(&self) -> Box<dyn MetricsRegistry> {
         Box::new(self.clone())
     }
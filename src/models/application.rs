@@ -193,7 +193,7 @@ impl<T: CloudProvider> Application<T> {
                 r#type: "application",
                 name: self.kube_name().to_string(),
                 user_unsafe_name: self.name.clone(),
-                image_full: self.build.image.full_image_name_with_tag(),
+                image_full: self.build.image.full_image_reference(),
                 image_tag: self.build.image.tag.clone(),
                 version: self.version(),
                 command_args: self.command_args.clone(),
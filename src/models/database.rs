@@ -500,7 +500,19 @@ impl<C: CloudProvider, T: DatabaseType<C, Container>> Database<C, Container, T>
         Ok(context)
     }
 
-    fn get_version(&self, event_details: EventDetails) -> Result<ServiceVersionCheckResult, Box<EngineError>> {
+    /// Image running the database, also used to run tasks against it with matching clients
+    pub fn container_image(&self, version: &VersionsNumber) -> String {
+        format!(
+            "public.ecr.aws/r3m4q3r9/pub-mirror-{}:{}",
+            T::db_type().to_string().to_lowercase(),
+            version
+        )
+    }
+
+    pub(crate) fn get_version(
+        &self,
+        event_details: EventDetails,
+    ) -> Result<ServiceVersionCheckResult, Box<EngineError>> {
         let fn_version = match T::db_type() {
             service::DatabaseType::PostgreSQL => is_allowed_containered_postgres_version,
             service::DatabaseType::MongoDB => is_allowed_containered_mongodb_version,
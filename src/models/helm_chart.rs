@@ -310,6 +310,7 @@ impl<T: CloudProvider> Service for HelmChart<T> {
                 key: key.clone(),
                 value: variable_infos.value.clone(),
                 is_secret: variable_infos.is_secret,
+                external_secret: None,
             })
             .collect()
     }
@@ -193,7 +193,7 @@ impl<T: CloudProvider> Job<T> {
                 let image_full = format!("{}/{}:{}", repository, image_name, image_tag);
                 (image_full, image_tag)
             }
-            ImageSource::Build { source } => (source.image.full_image_name_with_tag(), source.image.tag.clone()),
+            ImageSource::Build { source } => (source.image.full_image_reference(), source.image.tag.clone()),
         };
 
         let ctx = JobTeraContext {
@@ -1,4 +1,5 @@
 use crate::build_platform::Build;
+use crate::cloud_provider::environment::Environment;
 use crate::cloud_provider::models::{
     CustomDomain, CustomDomainDataTemplate, EnvironmentVariable, HostDataTemplate, KubeService, KubeServicePort, Route,
 };
@@ -34,6 +35,8 @@ pub enum RouterError {
     },
     #[error("Basic Auth environment variable `{env_var_name}` not found but defined in the advanced settings")]
     BasicAuthEnvVarNotFound { env_var_name: String },
+    #[error("Certificate for domain `{domain}` has not been issued: {reason}")]
+    CertificateNotIssued { domain: String, reason: String },
 }
 
 #[derive(Default)]
@@ -41,6 +44,7 @@ pub struct RouterAdvancedSettings {
     pub whitelist_source_range: Option<String>,
     pub denylist_source_range: Option<String>,
     pub basic_auth: Option<String>,
+    pub certificate_strict_check: bool,
 }
 
 impl RouterAdvancedSettings {
@@ -55,6 +59,7 @@ impl RouterAdvancedSettings {
             whitelist_source_range: definitive_whitelist,
             denylist_source_range,
             basic_auth,
+            certificate_strict_check: false,
         }
     }
 
@@ -245,6 +250,45 @@ impl<T: CloudProvider> Router<T> {
         Ok(context)
     }
 
+    /// Domains of the router in the cluster DNS zone, the ones external-dns creates records for.
+    /// Custom domains are managed by users outside of the cluster zone.
+    pub(crate) fn cluster_zone_domains(&self, environment: &Environment) -> Vec<String> {
+        let ports = self
+            .routes
+            .first()
+            .and_then(|route| {
+                let service_id = &route.service_long_id;
+                environment
+                    .applications
+                    .iter()
+                    .find(|application| application.long_id() == service_id)
+                    .map(|application| application.public_ports())
+                    .or_else(|| {
+                        environment
+                            .containers
+                            .iter()
+                            .find(|container| container.long_id() == service_id)
+                            .map(|container| container.public_ports())
+                    })
+                    .or_else(|| {
+                        environment
+                            .helm_charts
+                            .iter()
+                            .find(|helm_chart| helm_chart.long_id() == service_id)
+                            .map(|helm_chart| helm_chart.public_ports())
+                    })
+            })
+            .unwrap_or_default();
+
+        iter::once(self.default_domain.to_string())
+            .chain(
+                ports
+                    .iter()
+                    .map(|port| format!("{}-{}", port.name, self.default_domain)),
+            )
+            .collect()
+    }
+
     pub fn helm_release_name(&self) -> String {
         crate::string::cut(format!("router-{}", self.id), 50)
     }
@@ -44,6 +44,10 @@ pub enum ObjectStorageError {
         bucket_name: String,
         raw_error_message: String,
     },
+    #[error(
+        "Object lock can only be activated at bucket creation, bucket `{bucket_name:?}` has been created without it."
+    )]
+    ObjectLockNotActivated { bucket_name: String },
     #[error("Cannot activate bucket versioning on bucket `{bucket_name:?}`: {raw_error_message:?}.")]
     CannotActivateBucketVersioning {
         bucket_name: String,
@@ -61,6 +65,23 @@ pub enum ObjectStorageError {
         object_name: String,
         raw_error_message: String,
     },
+    #[error("Cannot list objects error in `{bucket_name:?}`: {raw_error_message:?}.")]
+    CannotListObjects {
+        bucket_name: String,
+        raw_error_message: String,
+    },
+    #[error("Cannot copy object `{object_name:?}` error for `{bucket_name:?}`: {raw_error_message:?}.")]
+    CannotCopyFile {
+        bucket_name: String,
+        object_name: String,
+        raw_error_message: String,
+    },
+    #[error("Cannot presign url for object `{object_name:?}` error in `{bucket_name:?}`: {raw_error_message:?}.")]
+    CannotPresignUrl {
+        bucket_name: String,
+        object_name: String,
+        raw_error_message: String,
+    },
     #[error("Cannot delete object `{object_name:?}` error for `{bucket_name:?}`: {raw_error_message:?}.")]
     CannotDeleteFile {
         bucket_name: String,
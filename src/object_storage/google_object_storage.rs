@@ -1,13 +1,19 @@
 use crate::object_storage::errors::ObjectStorageError;
-use crate::object_storage::{Bucket, BucketDeleteStrategy, BucketObject};
+use crate::object_storage::{
+    check_presigned_url_expiration, open_file_for_multipart_upload, read_part, Bucket, BucketDeleteStrategy,
+    BucketObject, MultipartUploadConfig, ObjectListing,
+};
 use crate::object_storage::{Kind, ObjectStorage};
 use crate::services::gcp::object_storage_regions::GcpStorageRegion;
 use crate::services::gcp::object_storage_service::ObjectStorageService;
 use chrono::{DateTime, Utc};
+use google_cloud_storage::sign::SignedURLMethod;
 use std::collections::HashMap;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
+use url::Url;
 use uuid::Uuid;
 
 pub struct GoogleOS {
@@ -17,6 +23,7 @@ pub struct GoogleOS {
     project_id: String,
     region: GcpStorageRegion,
     service: Arc<ObjectStorageService>,
+    multipart_upload_config: MultipartUploadConfig,
 }
 
 impl GoogleOS {
@@ -35,8 +42,63 @@ impl GoogleOS {
             project_id: project_id.to_string(),
             region,
             service,
+            multipart_upload_config: MultipartUploadConfig::default(),
         }
     }
+
+    pub fn with_multipart_upload_config(mut self, multipart_upload_config: MultipartUploadConfig) -> Self {
+        self.multipart_upload_config = multipart_upload_config;
+        self
+    }
+
+    /// Send all the parts of a resumable upload, the next part is read before sending the current one
+    /// as the total size has to be given with the last part.
+    fn upload_chunks(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        upload_url: &str,
+        first_part: Vec<u8>,
+        reader: &mut dyn Read,
+    ) -> Result<u64, String> {
+        let mut first_byte: u64 = 0;
+        let mut part = first_part;
+        loop {
+            let next_part =
+                read_part(reader, self.multipart_upload_config.part_size_in_bytes).map_err(|e| e.to_string())?;
+            let part_size = part.len() as u64;
+            let total_size = match next_part.is_empty() {
+                true => Some(first_byte + part_size),
+                false => None,
+            };
+            self.service
+                .upload_chunk(bucket_name, object_key, upload_url, part, first_byte, total_size)
+                .map_err(|e| e.to_string())?;
+            first_byte += part_size;
+
+            if next_part.is_empty() {
+                return Ok(first_byte);
+            }
+            part = next_part;
+        }
+    }
+
+    fn presign(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        method: SignedURLMethod,
+        expires_in: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        check_presigned_url_expiration(bucket_name, object_key, expires_in)?;
+        self.service
+            .signed_url(bucket_name, object_key, method, expires_in)
+            .map_err(|e| ObjectStorageError::CannotPresignUrl {
+                bucket_name: bucket_name.to_string(),
+                object_name: object_key.to_string(),
+                raw_error_message: e.to_string(),
+            })
+    }
 }
 
 impl ObjectStorage for GoogleOS {
@@ -66,9 +128,24 @@ impl ObjectStorage for GoogleOS {
         bucket_name: &str,
         bucket_ttl: Option<Duration>,
         bucket_versioning_activated: bool,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
     ) -> Result<Bucket, ObjectStorageError> {
         if let Ok(existing_bucket) = self.get_bucket(bucket_name) {
-            return Ok(existing_bucket);
+            return match bucket_lifecycle_policy {
+                Some(lifecycle_policy) => self
+                    .service
+                    .update_bucket(
+                        bucket_name,
+                        existing_bucket.versioning_activated,
+                        existing_bucket.ttl,
+                        Some(lifecycle_policy),
+                    )
+                    .map_err(|e| ObjectStorageError::CannotUpdateBucket {
+                        bucket_name: bucket_name.to_string(),
+                        raw_error_message: e.to_string(),
+                    }),
+                None => Ok(existing_bucket),
+            };
         }
 
         let creation_date: DateTime<Utc> = Utc::now();
@@ -88,6 +165,7 @@ impl ObjectStorage for GoogleOS {
                     format!("{}", bucket_ttl.map(|ttl| ttl.as_secs()).unwrap_or(0)),
                 ),
             ])),
+            bucket_lifecycle_policy,
         ) {
             Ok(o) => Ok(o),
             Err(e) => Err(ObjectStorageError::CannotCreateBucket {
@@ -101,16 +179,25 @@ impl ObjectStorage for GoogleOS {
         &self,
         bucket_name: &str,
         bucket_versioning_activated: bool,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
     ) -> Result<Bucket, ObjectStorageError> {
-        if let Err(err) = self.get_bucket(bucket_name) {
-            return Err(ObjectStorageError::CannotUpdateBucket {
-                bucket_name: bucket_name.to_string(),
-                raw_error_message: err.to_string(),
-            });
-        }
+        let existing_bucket = match self.get_bucket(bucket_name) {
+            Ok(bucket) => bucket,
+            Err(err) => {
+                return Err(ObjectStorageError::CannotUpdateBucket {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message: err.to_string(),
+                })
+            }
+        };
 
         // Update the bucket
-        match self.service.update_bucket(bucket_name, bucket_versioning_activated) {
+        match self.service.update_bucket(
+            bucket_name,
+            bucket_versioning_activated,
+            existing_bucket.ttl,
+            bucket_lifecycle_policy,
+        ) {
             Ok(o) => Ok(o),
             Err(e) => Err(ObjectStorageError::CannotUpdateBucket {
                 bucket_name: bucket_name.to_string(),
@@ -181,6 +268,18 @@ impl ObjectStorage for GoogleOS {
         file_path: &Path,
         _tags: Option<Vec<String>>,
     ) -> Result<BucketObject, ObjectStorageError> {
+        if let Some(mut file) =
+            open_file_for_multipart_upload(bucket_name, object_key, file_path, &self.multipart_upload_config)?
+        {
+            self.write_object(bucket_name, object_key, &mut file, None)?;
+            return Ok(BucketObject {
+                bucket_name: bucket_name.to_string(),
+                key: object_key.to_string(),
+                value: vec![],
+                tags: vec![],
+            });
+        }
+
         let file_content = std::fs::read(file_path).map_err(|e| ObjectStorageError::CannotUploadFile {
             bucket_name: bucket_name.to_string(),
             object_name: object_key.to_string(),
@@ -211,23 +310,140 @@ impl ObjectStorage for GoogleOS {
                 raw_error_message: e.to_string(),
             })
     }
+
+    fn list_objects(
+        &self,
+        bucket_name: &str,
+        prefix: Option<&str>,
+        continuation_token: Option<&str>,
+    ) -> Result<ObjectListing, ObjectStorageError> {
+        self.service
+            .list_objects_page(bucket_name, prefix, continuation_token)
+            .map_err(|e| ObjectStorageError::CannotListObjects {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: e.to_string(),
+            })
+    }
+
+    fn read_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageError> {
+        let read_error = |raw_error_message: String| ObjectStorageError::CannotGetObjectFile {
+            bucket_name: bucket_name.to_string(),
+            object_name: object_key.to_string(),
+            raw_error_message,
+        };
+
+        // the object is downloaded by ranges, to not hold it entirely in memory
+        let object_size = self
+            .service
+            .get_object_size(bucket_name, object_key)
+            .map_err(|e| read_error(e.to_string()))?;
+        let mut first_byte: u64 = 0;
+        while first_byte < object_size {
+            let last_byte = (first_byte + self.multipart_upload_config.part_size_in_bytes).min(object_size) - 1;
+            let range = self
+                .service
+                .get_object_range(bucket_name, object_key, first_byte, last_byte)
+                .map_err(|e| read_error(e.to_string()))?;
+            writer.write_all(&range).map_err(|e| read_error(e.to_string()))?;
+            first_byte = last_byte + 1;
+        }
+
+        Ok(object_size)
+    }
+
+    fn write_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        reader: &mut dyn Read,
+        _tags: Option<Vec<String>>,
+    ) -> Result<u64, ObjectStorageError> {
+        let upload_error = |raw_error_message: String| ObjectStorageError::CannotUploadFile {
+            bucket_name: bucket_name.to_string(),
+            object_name: object_key.to_string(),
+            raw_error_message,
+        };
+
+        let first_part = read_part(reader, self.multipart_upload_config.part_size_in_bytes)
+            .map_err(|e| upload_error(e.to_string()))?;
+        if (first_part.len() as u64) < self.multipart_upload_config.part_size_in_bytes {
+            let size = first_part.len() as u64;
+            self.service
+                .put_object(bucket_name, object_key, first_part)
+                .map_err(|e| upload_error(e.to_string()))?;
+            return Ok(size);
+        }
+
+        let upload_url = self
+            .service
+            .start_resumable_upload(bucket_name, object_key)
+            .map_err(|e| upload_error(e.to_string()))?;
+        self.upload_chunks(bucket_name, object_key, &upload_url, first_part, reader)
+            .map_err(|e| {
+                self.service.cancel_resumable_upload(&upload_url);
+                upload_error(e)
+            })
+    }
+
+    fn copy_object(
+        &self,
+        source_bucket_name: &str,
+        source_object_key: &str,
+        target_bucket_name: &str,
+        target_object_key: &str,
+    ) -> Result<(), ObjectStorageError> {
+        self.service
+            .copy_object(source_bucket_name, source_object_key, target_bucket_name, target_object_key)
+            .map_err(|e| ObjectStorageError::CannotCopyFile {
+                bucket_name: target_bucket_name.to_string(),
+                object_name: target_object_key.to_string(),
+                raw_error_message: e.to_string(),
+            })
+    }
+
+    fn presign_get(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        expires_in: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        self.presign(bucket_name, object_key, SignedURLMethod::GET, expires_in)
+    }
+
+    fn presign_put(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        expires_in: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        self.presign(bucket_name, object_key, SignedURLMethod::PUT, expires_in)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::object_storage::errors::ObjectStorageError;
     use crate::object_storage::google_object_storage::GoogleOS;
-    use crate::object_storage::{Bucket, BucketDeleteStrategy, BucketObject, BucketRegion, ObjectStorage};
+    use crate::object_storage::{
+        Bucket, BucketDeleteStrategy, BucketLifecycleAction, BucketLifecyclePolicy, BucketLifecycleRule, BucketObject,
+        BucketRegion, MultipartUploadConfig, ObjectStorage,
+    };
     use crate::services::gcp::object_storage_regions::GcpStorageRegion;
     use crate::services::gcp::object_storage_service::{ObjectStorageService, ObjectStorageServiceError};
     use chrono::Utc;
     use itertools::izip;
     use std::collections::HashMap;
     use std::fs::File;
-    use std::io::Write;
+    use std::io::{Cursor, Write};
     use std::sync::Arc;
     use std::time::Duration;
     use tempfile::tempdir;
+    use url::Url;
     use uuid::Uuid;
 
     #[test]
@@ -282,6 +498,8 @@ mod tests {
                         format!("{}", bucket_ttl.map(|ttl| ttl.as_secs()).unwrap_or(0)),
                     ),
                 ])),
+                lifecycle_rules: vec![],
+                object_lock: None,
             };
 
             let mut service_mock = ObjectStorageService::faux();
@@ -293,6 +511,7 @@ mod tests {
                 bucket_ttl,
                 bucket_versioning,
                 _, // labels
+                _, // lifecycle policy
             ))
             .then_return(Ok(expected_bucket.clone()));
             faux::when!(service_mock.get_bucket(bucket_name,)).then_return(Err(
@@ -313,7 +532,7 @@ mod tests {
 
             // execute:
             let created_bucket = object_storage
-                .create_bucket(bucket_name, bucket_ttl, bucket_versioning)
+                .create_bucket(bucket_name, bucket_ttl, bucket_versioning, None)
                 .expect("Error creating bucket");
 
             // verify:
@@ -349,6 +568,8 @@ mod tests {
                         format!("{}", bucket_ttl.map(|ttl| ttl.as_secs()).unwrap_or(0)),
                     ),
                 ])),
+                lifecycle_rules: vec![],
+                object_lock: None,
             };
 
             let mut service_mock = ObjectStorageService::faux();
@@ -361,6 +582,7 @@ mod tests {
                 bucket_ttl,
                 bucket_versioning,
                 _, // labels
+                _, // lifecycle policy
             ))
             .then_return(Err(ObjectStorageServiceError::CannotCreateBucket {
                 bucket_name: bucket_name.to_string(),
@@ -379,7 +601,7 @@ mod tests {
 
             // execute:
             let created_bucket = object_storage
-                .create_bucket(bucket_name, bucket_ttl, bucket_versioning)
+                .create_bucket(bucket_name, bucket_ttl, bucket_versioning, None)
                 .expect("Error creating bucket");
 
             // verify:
@@ -387,6 +609,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn create_bucket_existing_with_lifecycle_policy_test() {
+        // setup:
+        let bucket_name = "test-bucket";
+        let bucket_ttl = Some(Duration::from_secs(7 * 24 * 60 * 60)); // 7 days
+        let lifecycle_policy = BucketLifecyclePolicy {
+            rules: vec![BucketLifecycleRule {
+                prefix: Some("logs/".to_string()),
+                action: BucketLifecycleAction::ExpireAfterDays(30),
+            }],
+            object_lock: None,
+        };
+        let existing_bucket = Bucket {
+            name: bucket_name.to_string(),
+            ttl: bucket_ttl,
+            versioning_activated: true,
+            location: BucketRegion::GcpRegion(GcpStorageRegion::EuropeWest9),
+            labels: None,
+            lifecycle_rules: vec![],
+            object_lock: None,
+        };
+        let expected_bucket = Bucket {
+            lifecycle_rules: lifecycle_policy.rules.clone(),
+            ..existing_bucket.clone()
+        };
+
+        let mut service_mock = ObjectStorageService::faux();
+        faux::when!(service_mock.get_bucket(bucket_name)).then_return(Ok(existing_bucket.clone()));
+        // existing TTL and versioning are kept
+        faux::when!(service_mock.update_bucket(bucket_name, true, bucket_ttl, _))
+            .once()
+            .then_return(Ok(expected_bucket.clone()));
+
+        let object_storage = GoogleOS::new(
+            "123",
+            Uuid::new_v4(),
+            "test_123",
+            "project_123",
+            GcpStorageRegion::EuropeWest9,
+            Arc::from(service_mock),
+        );
+
+        // execute:
+        let bucket = object_storage.create_bucket(bucket_name, None, false, Some(&lifecycle_policy));
+
+        // verify:
+        assert_eq!(Ok(expected_bucket), bucket);
+    }
+
     #[test]
     fn get_bucket_success_test() {
         // setup:
@@ -404,6 +675,8 @@ mod tests {
                     format!("{}", bucket_ttl.map(|ttl| ttl.as_secs()).unwrap_or(0)),
                 ),
             ])),
+            lifecycle_rules: vec![],
+            object_lock: None,
         };
 
         let mut service_mock = ObjectStorageService::faux();
@@ -482,11 +755,13 @@ mod tests {
                         format!("{}", bucket_ttl.map(|ttl| ttl.as_secs()).unwrap_or(0)),
                     ),
                 ])),
+                lifecycle_rules: vec![],
+                object_lock: None,
             };
 
             let mut service_mock = ObjectStorageService::faux();
 
-            faux::when!(service_mock.update_bucket(bucket_name, bucket_versioning))
+            faux::when!(service_mock.update_bucket(bucket_name, bucket_versioning, bucket_ttl, _))
                 .then_return(Ok(expected_updated_bucket.clone()));
             faux::when!(service_mock.get_bucket(bucket_name)).then_return(Ok(expected_updated_bucket.clone()));
 
@@ -501,7 +776,7 @@ mod tests {
 
             // execute:
             let updated_bucket = object_storage
-                .update_bucket(bucket_name, bucket_versioning)
+                .update_bucket(bucket_name, bucket_versioning, None)
                 .expect("Error updating bucket");
 
             // verify:
@@ -518,7 +793,7 @@ mod tests {
         for bucket_versioning in bucket_versioning_test_cases {
             let mut service_mock = ObjectStorageService::faux();
 
-            faux::when!(service_mock.update_bucket(bucket_name, bucket_versioning)).then_return(Err(
+            faux::when!(service_mock.update_bucket(bucket_name, bucket_versioning, _, _)).then_return(Err(
                 ObjectStorageServiceError::CannotUpdateBucket {
                     bucket_name: bucket_name.to_string(),
                     raw_error_message: ObjectStorageServiceError::CannotGetBucket {
@@ -545,7 +820,7 @@ mod tests {
             );
 
             // execute:
-            let updated_bucket = object_storage.update_bucket(bucket_name, bucket_versioning);
+            let updated_bucket = object_storage.update_bucket(bucket_name, bucket_versioning, None);
 
             // verify:
             assert_eq!(
@@ -934,4 +1209,112 @@ mod tests {
             retrieved_object.unwrap_err()
         );
     }
+
+    #[test]
+    fn write_object_resumable_upload_test() {
+        // setup:
+        let bucket_name = "test-bucket";
+        let object_key = "test-object-key";
+        let upload_url = "https://storage.googleapis.com/upload/session";
+
+        let mut service_mock = ObjectStorageService::faux();
+        faux::when!(service_mock.start_resumable_upload(bucket_name, object_key))
+            .then_return(Ok(upload_url.to_string()));
+        // 10 bytes sent by parts of 4 bytes, total size being given with the last one
+        faux::when!(service_mock.upload_chunk(bucket_name, object_key, upload_url, _, 0, None))
+            .once()
+            .then_return(Ok(()));
+        faux::when!(service_mock.upload_chunk(bucket_name, object_key, upload_url, _, 4, None))
+            .once()
+            .then_return(Ok(()));
+        faux::when!(service_mock.upload_chunk(bucket_name, object_key, upload_url, _, 8, Some(10)))
+            .once()
+            .then_return(Ok(()));
+
+        let object_storage = GoogleOS::new(
+            "123",
+            Uuid::new_v4(),
+            "test_123",
+            "project_123",
+            GcpStorageRegion::EuropeWest9,
+            Arc::from(service_mock),
+        )
+        .with_multipart_upload_config(MultipartUploadConfig {
+            threshold_in_bytes: 4,
+            part_size_in_bytes: 4,
+        });
+
+        // execute:
+        let written = object_storage.write_object(bucket_name, object_key, &mut Cursor::new(vec![1u8; 10]), None);
+
+        // verify:
+        assert_eq!(Ok(10), written);
+    }
+
+    #[test]
+    fn read_object_by_ranges_test() {
+        // setup:
+        let bucket_name = "test-bucket";
+        let object_key = "test-object-key";
+
+        let mut service_mock = ObjectStorageService::faux();
+        faux::when!(service_mock.get_object_size(bucket_name, object_key)).then_return(Ok(6));
+        faux::when!(service_mock.get_object_range(bucket_name, object_key, 0, 3)).then_return(Ok(b"abcd".to_vec()));
+        faux::when!(service_mock.get_object_range(bucket_name, object_key, 4, 5)).then_return(Ok(b"ef".to_vec()));
+
+        let object_storage = GoogleOS::new(
+            "123",
+            Uuid::new_v4(),
+            "test_123",
+            "project_123",
+            GcpStorageRegion::EuropeWest9,
+            Arc::from(service_mock),
+        )
+        .with_multipart_upload_config(MultipartUploadConfig {
+            threshold_in_bytes: 4,
+            part_size_in_bytes: 4,
+        });
+
+        // execute:
+        let mut content = vec![];
+        let read = object_storage.read_object(bucket_name, object_key, &mut content);
+
+        // verify:
+        assert_eq!(Ok(6), read);
+        assert_eq!(b"abcdef".to_vec(), content);
+    }
+
+    #[test]
+    fn presign_get_test() {
+        // setup:
+        let bucket_name = "test-bucket";
+        let object_key = "test-object-key";
+        let signed_url = Url::parse("https://storage.googleapis.com/test-bucket/test-object-key?X-Goog-Signature=abc")
+            .expect("invalid url");
+
+        let mut service_mock = ObjectStorageService::faux();
+        faux::when!(service_mock.signed_url(bucket_name, object_key, _, Duration::from_secs(900)))
+            .once()
+            .then_return(Ok(signed_url.clone()));
+
+        let object_storage = GoogleOS::new(
+            "123",
+            Uuid::new_v4(),
+            "test_123",
+            "project_123",
+            GcpStorageRegion::EuropeWest9,
+            Arc::from(service_mock),
+        );
+
+        // execute & verify:
+        assert_eq!(
+            Ok(signed_url),
+            object_storage.presign_get(bucket_name, object_key, Duration::from_secs(900))
+        );
+        // expiration is checked before calling the service
+        assert!(matches!(
+            object_storage.presign_put(bucket_name, object_key, Duration::from_secs(8 * 24 * 3600)),
+            Err(ObjectStorageError::CannotPresignUrl { .. })
+        ));
+    }
 }
@@ -1,18 +1,26 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write};
 use std::path::Path;
+use std::str::FromStr;
 use std::time::Duration;
 
 use crate::cloud_provider::aws::regions::AwsRegion;
 use crate::models::scaleway::ScwZone;
 use crate::models::ToCloudProviderFormat;
 use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::s3_compatible::S3CompatibleRegion;
 use crate::services::gcp::object_storage_regions::GcpStorageRegion;
 use enum_dispatch::enum_dispatch;
+use url::Url;
 
 pub mod errors;
 pub mod google_object_storage;
 pub mod s3;
+pub mod s3_compatible;
+mod s3_operations;
 pub mod scaleway_object_storage;
 
 #[derive(Clone)]
@@ -27,6 +35,7 @@ pub enum BucketRegion {
     AwsRegion(AwsRegion),
     ScwRegion(ScwZone),
     GcpRegion(GcpStorageRegion),
+    S3CompatibleRegion(S3CompatibleRegion),
 }
 
 #[enum_dispatch(StorageRegion)]
@@ -44,14 +53,22 @@ pub trait ObjectStorage {
         "object-storage/s3".to_string()
     }
     fn bucket_exists(&self, bucket_name: &str) -> bool;
+    /// Create the bucket, or return it if it already exists.
+    /// The lifecycle policy, when given, is applied in both cases.
     fn create_bucket(
         &self,
         bucket_name: &str,
         bucket_ttl: Option<Duration>,
         bucket_versioning_activated: bool,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
+    ) -> Result<Bucket, ObjectStorageError>;
+    /// Update the bucket, its current lifecycle rules are kept when no lifecycle policy is given
+    fn update_bucket(
+        &self,
+        bucket_name: &str,
+        bucket_versioning_activated: bool,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
     ) -> Result<Bucket, ObjectStorageError>;
-    fn update_bucket(&self, bucket_name: &str, bucket_versioning_activated: bool)
-        -> Result<Bucket, ObjectStorageError>;
     fn get_bucket(&self, bucket_name: &str) -> Result<Bucket, ObjectStorageError>;
     fn delete_bucket(
         &self,
@@ -68,6 +85,43 @@ pub trait ObjectStorage {
         tags: Option<Vec<String>>,
     ) -> Result<BucketObject, ObjectStorageError>;
     fn delete_object(&self, bucket_name: &str, object_key: &str) -> Result<(), ObjectStorageError>;
+    /// List objects page by page, `continuation_token` being the one returned with the previous page
+    fn list_objects(
+        &self,
+        bucket_name: &str,
+        prefix: Option<&str>,
+        continuation_token: Option<&str>,
+    ) -> Result<ObjectListing, ObjectStorageError>;
+    /// Stream the object content into `writer` without holding it in memory, returns the number of bytes read
+    fn read_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageError>;
+    /// Stream `reader` content into the object, using a multipart upload if it doesn't fit in a single part.
+    /// Returns the number of bytes written.
+    fn write_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        reader: &mut dyn Read,
+        tags: Option<Vec<String>>,
+    ) -> Result<u64, ObjectStorageError>;
+    /// Copy an object server side, the content doesn't go through the engine
+    fn copy_object(
+        &self,
+        source_bucket_name: &str,
+        source_object_key: &str,
+        target_bucket_name: &str,
+        target_object_key: &str,
+    ) -> Result<(), ObjectStorageError>;
+    /// Short-lived url allowing to download the object without credentials
+    fn presign_get(&self, bucket_name: &str, object_key: &str, expires_in: Duration)
+        -> Result<Url, ObjectStorageError>;
+    /// Short-lived url allowing to upload the object with a single PUT request, without credentials
+    fn presign_put(&self, bucket_name: &str, object_key: &str, expires_in: Duration)
+        -> Result<Url, ObjectStorageError>;
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -77,6 +131,8 @@ pub enum Kind {
     Spaces,
     ScalewayOs,
     GcpOs,
+    // MinIO, Ceph, R2 or any other storage exposing the S3 API
+    S3Compatible,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -86,6 +142,8 @@ pub struct Bucket {
     pub versioning_activated: bool,
     pub location: BucketRegion,
     pub labels: Option<HashMap<String, String>>,
+    pub lifecycle_rules: Vec<BucketLifecycleRule>,
+    pub object_lock: Option<BucketObjectLock>,
 }
 
 impl Bucket {
@@ -102,6 +160,69 @@ impl Bucket {
             versioning_activated,
             location,
             labels,
+            lifecycle_rules: vec![],
+            object_lock: None,
+        }
+    }
+}
+
+/// Lifecycle rules and object lock of a bucket.
+/// Rules are declarative: applying a policy replaces all the rules previously set on the bucket.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BucketLifecyclePolicy {
+    pub rules: Vec<BucketLifecycleRule>,
+    // object lock can't be deactivated once activated, None keeps the current configuration
+    pub object_lock: Option<BucketObjectLock>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BucketLifecycleRule {
+    // the rule applies to the whole bucket when not set
+    pub prefix: Option<String>,
+    pub action: BucketLifecycleAction,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BucketLifecycleAction {
+    ExpireAfterDays(u32),
+    // storage class in the provider format (i.e: GLACIER on S3 and Scaleway, COLDLINE on GCS)
+    TransitionAfterDays { days: u32, storage_class: String },
+    AbortIncompleteMultipartUploadAfterDays(u32),
+    ExpireNoncurrentVersionsAfterDays(u32),
+}
+
+/// Default retention applied to the objects written in the bucket, they can't be deleted nor overwritten before
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BucketObjectLock {
+    pub mode: BucketObjectLockMode,
+    pub retention_days: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BucketObjectLockMode {
+    // retention can be lifted by users having the permission to bypass it
+    Governance,
+    // retention can't be lifted by anyone, root account included
+    Compliance,
+}
+
+impl ToCloudProviderFormat for BucketObjectLockMode {
+    fn to_cloud_provider_format(&self) -> &str {
+        match self {
+            BucketObjectLockMode::Governance => "GOVERNANCE",
+            BucketObjectLockMode::Compliance => "COMPLIANCE",
+        }
+    }
+}
+
+impl FromStr for BucketObjectLockMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "GOVERNANCE" => Ok(BucketObjectLockMode::Governance),
+            "COMPLIANCE" => Ok(BucketObjectLockMode::Compliance),
+            _ => Err(format!("`{}` is not a valid object lock mode", s)),
         }
     }
 }
@@ -110,6 +231,115 @@ impl Bucket {
 pub struct BucketObject {
     pub bucket_name: String,
     pub key: String,
+    // empty for objects uploaded with a multipart upload, to not load them in memory
     pub value: Vec<u8>,
     pub tags: Vec<String>,
 }
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectSummary {
+    pub key: String,
+    pub size_in_bytes: u64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjectListing {
+    pub objects: Vec<ObjectSummary>,
+    // to be given to get the next page, None once the last page is reached
+    pub continuation_token: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MultipartUploadConfig {
+    // files bigger than this are uploaded with a multipart upload by `put_object`
+    pub threshold_in_bytes: u64,
+    // S3 requires at least 5MiB per part (except the last one) and GCS a multiple of 256KiB
+    pub part_size_in_bytes: u64,
+}
+
+impl Default for MultipartUploadConfig {
+    fn default() -> Self {
+        MultipartUploadConfig {
+            threshold_in_bytes: 100 * 1024 * 1024,
+            part_size_in_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+// SigV4 (S3) and V4 signing (GCS) both refuse presigned urls valid for more than 7 days
+pub const MAX_PRESIGNED_URL_EXPIRATION: Duration = Duration::from_secs(7 * 24 * 3600);
+
+pub(crate) fn check_presigned_url_expiration(
+    bucket_name: &str,
+    object_key: &str,
+    expires_in: Duration,
+) -> Result<(), ObjectStorageError> {
+    if expires_in.as_secs() == 0 || expires_in > MAX_PRESIGNED_URL_EXPIRATION {
+        return Err(ObjectStorageError::CannotPresignUrl {
+            bucket_name: bucket_name.to_string(),
+            object_name: object_key.to_string(),
+            raw_error_message: format!(
+                "expiration should be between 1 second and {} seconds, got {} seconds",
+                MAX_PRESIGNED_URL_EXPIRATION.as_secs(),
+                expires_in.as_secs()
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Read the next part of a multipart upload, smaller than `part_size_in_bytes` only at the end of the reader
+pub(crate) fn read_part(reader: &mut dyn Read, part_size_in_bytes: u64) -> std::io::Result<Vec<u8>> {
+    let mut part = Vec::with_capacity(part_size_in_bytes as usize);
+    reader.take(part_size_in_bytes).read_to_end(&mut part)?;
+    Ok(part)
+}
+
+/// Open the file if it should be uploaded with a multipart upload, None if it can be sent in a single request
+pub(crate) fn open_file_for_multipart_upload(
+    bucket_name: &str,
+    object_key: &str,
+    file_path: &Path,
+    config: &MultipartUploadConfig,
+) -> Result<Option<File>, ObjectStorageError> {
+    let upload_error = |e: std::io::Error| ObjectStorageError::CannotUploadFile {
+        bucket_name: bucket_name.to_string(),
+        object_name: object_key.to_string(),
+        raw_error_message: e.to_string(),
+    };
+
+    let file_size = std::fs::metadata(file_path).map_err(upload_error)?.len();
+    if file_size <= config.threshold_in_bytes {
+        return Ok(None);
+    }
+
+    File::open(file_path).map(Some).map_err(upload_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_part() {
+        let mut reader = Cursor::new(vec![1u8; 10]);
+        assert_eq!(read_part(&mut reader, 4).unwrap().len(), 4);
+        assert_eq!(read_part(&mut reader, 4).unwrap().len(), 4);
+        assert_eq!(read_part(&mut reader, 4).unwrap().len(), 2);
+        assert!(read_part(&mut reader, 4).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_check_presigned_url_expiration() {
+        assert!(check_presigned_url_expiration("bucket", "key", Duration::from_secs(900)).is_ok());
+        assert!(check_presigned_url_expiration("bucket", "key", MAX_PRESIGNED_URL_EXPIRATION).is_ok());
+        assert!(check_presigned_url_expiration("bucket", "key", Duration::ZERO).is_err());
+        assert!(
+            check_presigned_url_expiration("bucket", "key", MAX_PRESIGNED_URL_EXPIRATION + Duration::from_secs(1))
+                .is_err()
+        );
+    }
+}
@@ -2,25 +2,28 @@ use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use retry::delay::Fixed;
 use std::collections::HashMap;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
+use url::Url;
 
 use crate::cloud_provider::aws::regions::AwsRegion;
-use rusoto_core::credential::StaticProvider;
+use rusoto_core::credential::{AwsCredentials, StaticProvider};
 use rusoto_core::{Client, HttpClient, Region as RusotoRegion};
 use rusoto_s3::{
     CreateBucketConfiguration, CreateBucketRequest, Delete, DeleteBucketRequest, DeleteObjectRequest,
-    DeleteObjectsRequest, GetBucketLifecycleRequest, GetBucketTaggingRequest, GetBucketVersioningRequest,
-    GetObjectRequest, GetObjectTaggingRequest, HeadBucketRequest, ListObjectsRequest, ObjectIdentifier,
-    PutBucketTaggingRequest, PutBucketVersioningRequest, PutObjectRequest, S3Client, StreamingBody, Tag, Tagging,
-    S3 as RusotoS3,
+    DeleteObjectsRequest, GetBucketTaggingRequest, GetBucketVersioningRequest, GetObjectRequest,
+    GetObjectTaggingRequest, HeadBucketRequest, ListObjectsRequest, ObjectIdentifier, PutBucketTaggingRequest,
+    PutBucketVersioningRequest, PutObjectRequest, S3Client, StreamingBody, Tag, Tagging, S3 as RusotoS3,
 };
 
 use crate::models::ToCloudProviderFormat;
 use crate::object_storage::errors::ObjectStorageError;
-use crate::object_storage::{Bucket, BucketDeleteStrategy, BucketObject, BucketRegion, Kind, ObjectStorage};
+use crate::object_storage::{
+    open_file_for_multipart_upload, s3_operations, Bucket, BucketDeleteStrategy, BucketLifecycleAction,
+    BucketLifecyclePolicy, BucketObject, BucketRegion, Kind, MultipartUploadConfig, ObjectListing, ObjectStorage,
+};
 use crate::runtime::block_on;
 
 pub struct S3 {
@@ -29,6 +32,7 @@ pub struct S3 {
     access_key_id: String,
     secret_access_key: String,
     region: AwsRegion,
+    multipart_upload_config: MultipartUploadConfig,
 }
 
 impl S3 {
@@ -39,26 +43,39 @@ impl S3 {
             access_key_id,
             secret_access_key,
             region,
+            multipart_upload_config: MultipartUploadConfig::default(),
         }
     }
 
+    pub fn with_multipart_upload_config(mut self, multipart_upload_config: MultipartUploadConfig) -> Self {
+        self.multipart_upload_config = multipart_upload_config;
+        self
+    }
+
     fn get_credentials(&self) -> StaticProvider {
         StaticProvider::new(self.access_key_id.clone(), self.secret_access_key.clone(), None, None)
     }
 
-    fn get_s3_client(&self) -> S3Client {
-        let region = RusotoRegion::from_str(self.region.to_cloud_provider_format()).unwrap_or_else(|_| {
+    fn get_region(&self) -> RusotoRegion {
+        RusotoRegion::from_str(self.region.to_cloud_provider_format()).unwrap_or_else(|_| {
             panic!(
                 "S3 region `{}` doesn't seems to be valid.",
                 self.region.to_cloud_provider_format()
             )
-        });
+        })
+    }
+
+    fn get_s3_client(&self) -> S3Client {
         let client = Client::new_with(
             self.get_credentials(),
             HttpClient::new().expect("unable to create new Http client"),
         );
 
-        S3Client::new_with_client(client, region)
+        S3Client::new_with_client(client, self.get_region())
+    }
+
+    fn get_aws_credentials(&self) -> AwsCredentials {
+        AwsCredentials::new(self.access_key_id.clone(), self.secret_access_key.clone(), None, None)
     }
 
     fn is_bucket_name_valid(bucket_name: &str) -> Result<(), ObjectStorageError> {
@@ -172,6 +189,7 @@ impl ObjectStorage for S3 {
         bucket_name: &str,
         bucket_ttl: Option<Duration>,
         bucket_versioning_activated: bool,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
     ) -> Result<Bucket, ObjectStorageError> {
         S3::is_bucket_name_valid(bucket_name)?;
 
@@ -179,16 +197,34 @@ impl ObjectStorage for S3 {
 
         // check if bucket already exists, if so, no need to recreate it
         if let Ok(existing_bucket) = self.get_bucket(bucket_name) {
-            return Ok(existing_bucket);
+            return match bucket_lifecycle_policy {
+                Some(lifecycle_policy) => {
+                    s3_operations::check_object_lock_activated(&s3_client, bucket_name, lifecycle_policy)?;
+                    s3_operations::apply_lifecycle_policy(&s3_client, bucket_name, lifecycle_policy).map_err(|e| {
+                        ObjectStorageError::CannotUpdateBucket {
+                            bucket_name: bucket_name.to_string(),
+                            raw_error_message: e,
+                        }
+                    })?;
+                    self.get_bucket(bucket_name)
+                }
+                None => Ok(existing_bucket),
+            };
         }
 
-        if let Err(e) = block_on(s3_client.create_bucket(CreateBucketRequest {
-            bucket: bucket_name.to_string(),
-            create_bucket_configuration: Some(CreateBucketConfiguration {
-                location_constraint: Some(self.region.to_cloud_provider_format().to_string()),
+        if let Err(e) = block_on(
+            s3_client.create_bucket(CreateBucketRequest {
+                bucket: bucket_name.to_string(),
+                create_bucket_configuration: Some(CreateBucketConfiguration {
+                    location_constraint: Some(self.region.to_cloud_provider_format().to_string()),
+                }),
+                // object lock can only be activated at bucket creation
+                object_lock_enabled_for_bucket: bucket_lifecycle_policy
+                    .and_then(|lifecycle_policy| lifecycle_policy.object_lock)
+                    .map(|_| true),
+                ..Default::default()
             }),
-            ..Default::default()
-        })) {
+        ) {
             return Err(ObjectStorageError::CannotCreateBucket {
                 bucket_name: bucket_name.to_string(),
                 raw_error_message: e.to_string(),
@@ -227,16 +263,40 @@ impl ObjectStorage for S3 {
             }));
         }
 
+        if let Some(lifecycle_policy) = bucket_lifecycle_policy {
+            s3_operations::apply_lifecycle_policy(&s3_client, bucket_name, lifecycle_policy).map_err(|e| {
+                ObjectStorageError::CannotCreateBucket {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message: e,
+                }
+            })?;
+        }
+
         self.get_bucket(bucket_name) // TODO(benjaminch): maybe doing a get here is avoidable
     }
 
     fn update_bucket(
         &self,
-        _bucket_name: &str,
-        _bucket_versioning_activated: bool,
+        bucket_name: &str,
+        bucket_versioning_activated: bool,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
     ) -> Result<Bucket, ObjectStorageError> {
-        // TODO(benjaminch): to be implemented
-        todo!("update_bucket for S3 is not implemented")
+        S3::is_bucket_name_valid(bucket_name)?;
+
+        let s3_client = self.get_s3_client();
+        let update_error = |raw_error_message: String| ObjectStorageError::CannotUpdateBucket {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message,
+        };
+
+        s3_operations::set_bucket_versioning(&s3_client, bucket_name, bucket_versioning_activated)
+            .map_err(update_error)?;
+        if let Some(lifecycle_policy) = bucket_lifecycle_policy {
+            s3_operations::check_object_lock_activated(&s3_client, bucket_name, lifecycle_policy)?;
+            s3_operations::apply_lifecycle_policy(&s3_client, bucket_name, lifecycle_policy).map_err(update_error)?;
+        }
+
+        self.get_bucket(bucket_name)
     }
 
     fn get_bucket(&self, bucket_name: &str) -> Result<Bucket, ObjectStorageError> {
@@ -248,22 +308,16 @@ impl ObjectStorage for S3 {
             });
         }
 
-        // Get TTL
-        let mut ttl: Option<Duration> = None;
-        if let Ok(bl) = block_on(self.get_s3_client().get_bucket_lifecycle(GetBucketLifecycleRequest {
-            bucket: bucket_name.to_string(),
-            expected_bucket_owner: None,
-        })) {
-            if let Some(rules) = bl.rules {
-                for r in rules {
-                    if let Some(expiration) = r.expiration {
-                        ttl = expiration
-                            .days
-                            .map(|days| Duration::from_secs(days.unsigned_abs() * 24 * 60 * 60));
-                    }
+        // Get lifecycle rules and TTL, being the expiration applying to the whole bucket
+        let lifecycle_rules = s3_operations::get_lifecycle_rules(&self.get_s3_client(), bucket_name);
+        let ttl = lifecycle_rules
+            .iter()
+            .find_map(|rule| match (&rule.prefix, &rule.action) {
+                (None, BucketLifecycleAction::ExpireAfterDays(days)) => {
+                    Some(Duration::from_secs(*days as u64 * 24 * 60 * 60))
                 }
-            }
-        }
+                _ => None,
+            });
 
         // Get versioning
         let mut versioning_activated = false;
@@ -294,6 +348,8 @@ impl ObjectStorage for S3 {
 
             location: BucketRegion::AwsRegion(self.region.clone()),
             labels,
+            lifecycle_rules,
+            object_lock: s3_operations::get_object_lock(&self.get_s3_client(), bucket_name),
         })
     }
 
@@ -388,6 +444,18 @@ impl ObjectStorage for S3 {
     ) -> Result<BucketObject, ObjectStorageError> {
         S3::is_bucket_name_valid(bucket_name)?;
 
+        if let Some(mut file) =
+            open_file_for_multipart_upload(bucket_name, object_key, file_path, &self.multipart_upload_config)?
+        {
+            self.write_object(bucket_name, object_key, &mut file, tags)?;
+            return Ok(BucketObject {
+                bucket_name: bucket_name.to_string(),
+                key: object_key.to_string(),
+                value: vec![],
+                tags: vec![],
+            });
+        }
+
         let s3_client = self.get_s3_client();
 
         let file_content = std::fs::read(file_path).map_err(|e| ObjectStorageError::CannotUploadFile {
@@ -446,6 +514,94 @@ impl ObjectStorage for S3 {
             }),
         }
     }
+
+    fn list_objects(
+        &self,
+        bucket_name: &str,
+        prefix: Option<&str>,
+        continuation_token: Option<&str>,
+    ) -> Result<ObjectListing, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+        s3_operations::list_objects(&self.get_s3_client(), bucket_name, prefix, continuation_token)
+    }
+
+    fn read_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+        s3_operations::read_object(&self.get_s3_client(), bucket_name, object_key, writer)
+    }
+
+    fn write_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        reader: &mut dyn Read,
+        tags: Option<Vec<String>>,
+    ) -> Result<u64, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+        s3_operations::write_object(
+            &self.get_s3_client(),
+            bucket_name,
+            object_key,
+            reader,
+            tags,
+            self.multipart_upload_config.part_size_in_bytes,
+        )
+    }
+
+    fn copy_object(
+        &self,
+        source_bucket_name: &str,
+        source_object_key: &str,
+        target_bucket_name: &str,
+        target_object_key: &str,
+    ) -> Result<(), ObjectStorageError> {
+        S3::is_bucket_name_valid(source_bucket_name)?;
+        S3::is_bucket_name_valid(target_bucket_name)?;
+        s3_operations::copy_object(
+            &self.get_s3_client(),
+            source_bucket_name,
+            source_object_key,
+            target_bucket_name,
+            target_object_key,
+        )
+    }
+
+    fn presign_get(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        expires_in: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+        s3_operations::presign_get(
+            &self.get_region(),
+            &self.get_aws_credentials(),
+            bucket_name,
+            object_key,
+            expires_in,
+        )
+    }
+
+    fn presign_put(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        expires_in: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+        s3_operations::presign_put(
+            &self.get_region(),
+            &self.get_aws_credentials(),
+            bucket_name,
+            object_key,
+            expires_in,
+        )
+    }
 }
 
 #[cfg(test)]
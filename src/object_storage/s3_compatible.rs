@@ -0,0 +1,859 @@
+use chrono::{DateTime, Utc};
+use derivative::Derivative;
+use hyper::client::HttpConnector;
+use hyper_tls::{native_tls, HttpsConnector};
+use rusoto_core::{Client, HttpClient, Region as RusotoRegion};
+use rusoto_credential::{AwsCredentials, StaticProvider};
+use rusoto_s3::{
+    CreateBucketConfiguration, CreateBucketRequest, Delete, DeleteBucketRequest, DeleteObjectRequest,
+    DeleteObjectsRequest, GetBucketTaggingRequest, GetBucketVersioningRequest, GetObjectRequest,
+    GetObjectTaggingRequest, HeadBucketRequest, ObjectIdentifier, PutBucketTaggingRequest, PutObjectRequest, S3Client,
+    StreamingBody, Tag, Tagging, S3,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Duration;
+use tera::Context as TeraContext;
+use url::Url;
+
+use crate::cloud_provider::helm_charts::loki_chart::S3LokiChartConfiguration;
+use crate::models::ToCloudProviderFormat;
+use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::{
+    open_file_for_multipart_upload, s3_operations, Bucket, BucketDeleteStrategy, BucketLifecycleAction,
+    BucketLifecyclePolicy, BucketObject, BucketRegion, Kind, MultipartUploadConfig, ObjectListing, ObjectStorage,
+    StorageRegion,
+};
+use crate::runtime::block_on;
+
+// region used to sign requests when none is configured, accepted by MinIO and Ceph
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// Region of a S3-compatible storage, free form as each implementation names them its own way (i.e: `auto` for R2)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct S3CompatibleRegion(String);
+
+impl S3CompatibleRegion {
+    pub fn new(region: &str) -> Self {
+        match region.trim() {
+            "" => S3CompatibleRegion(DEFAULT_REGION.to_string()),
+            region => S3CompatibleRegion(region.to_string()),
+        }
+    }
+}
+
+impl ToCloudProviderFormat for S3CompatibleRegion {
+    fn to_cloud_provider_format(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl StorageRegion for S3CompatibleRegion {}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Derivative)]
+#[derivative(Debug)]
+pub struct S3CompatibleStorageOptions {
+    // i.e: https://minio.internal:9000
+    pub endpoint: Url,
+    #[serde(default)]
+    pub region: String,
+    pub access_key_id: String,
+    #[derivative(Debug = "ignore")]
+    pub secret_access_key: String,
+    // MinIO and Ceph are usually exposed without a wildcard DNS record, so buckets can't be used as subdomains.
+    // The engine always uses path-style requests (rusoto doesn't support virtual-hosted ones), this is for Loki and terraform.
+    #[serde(default)]
+    pub path_style: bool,
+    // PEM encoded CA of the endpoint certificate, when it is not signed by a public authority
+    #[serde(default)]
+    pub tls_ca_certificate: Option<String>,
+}
+
+// doc: https://docs.aws.amazon.com/AmazonS3/latest/API/Welcome.html
+// Only the subset of the S3 API implemented by MinIO, Ceph RGW and Cloudflare R2 is used.
+pub struct S3CompatibleOS {
+    id: String,
+    name: String,
+    access_key_id: String,
+    secret_access_key: String,
+    endpoint: Url,
+    region: S3CompatibleRegion,
+    path_style: bool,
+    tls_connector: native_tls::TlsConnector,
+    multipart_upload_config: MultipartUploadConfig,
+}
+
+impl S3CompatibleOS {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: String,
+        name: String,
+        access_key_id: String,
+        secret_access_key: String,
+        endpoint: Url,
+        region: &str,
+        path_style: bool,
+        tls_ca_certificate: Option<&str>,
+    ) -> Result<Self, ObjectStorageError> {
+        if endpoint.host_str().is_none() || !["http", "https"].contains(&endpoint.scheme()) {
+            return Err(ObjectStorageError::CannotInstantiateClient {
+                raw_error_message: format!("endpoint `{}` should be an http(s) url", endpoint),
+            });
+        }
+
+        let mut tls_connector = native_tls::TlsConnector::builder();
+        if let Some(ca) = tls_ca_certificate {
+            let certificate = native_tls::Certificate::from_pem(ca.as_bytes()).map_err(|e| {
+                ObjectStorageError::CannotInstantiateClient {
+                    raw_error_message: format!("invalid TLS CA certificate: {}", e),
+                }
+            })?;
+            tls_connector.add_root_certificate(certificate);
+        }
+        let tls_connector = tls_connector
+            .build()
+            .map_err(|e| ObjectStorageError::CannotInstantiateClient {
+                raw_error_message: e.to_string(),
+            })?;
+
+        Ok(S3CompatibleOS {
+            id,
+            name,
+            access_key_id,
+            secret_access_key,
+            endpoint,
+            region: S3CompatibleRegion::new(region),
+            path_style,
+            tls_connector,
+            multipart_upload_config: MultipartUploadConfig::default(),
+        })
+    }
+
+    pub fn with_multipart_upload_config(mut self, multipart_upload_config: MultipartUploadConfig) -> Self {
+        self.multipart_upload_config = multipart_upload_config;
+        self
+    }
+
+    pub fn from_options(
+        id: String,
+        name: String,
+        options: &S3CompatibleStorageOptions,
+    ) -> Result<Self, ObjectStorageError> {
+        S3CompatibleOS::new(
+            id,
+            name,
+            options.access_key_id.clone(),
+            options.secret_access_key.clone(),
+            options.endpoint.clone(),
+            &options.region,
+            options.path_style,
+            options.tls_ca_certificate.as_deref(),
+        )
+    }
+
+    /// Loki storage configuration, Loki connecting to the bucket with the storage credentials
+    pub fn loki_chart_configuration(&self, bucket_name: &str) -> S3LokiChartConfiguration {
+        let host = self.endpoint.host_str().unwrap_or_default();
+        let endpoint = match self.endpoint.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+
+        S3LokiChartConfiguration {
+            region: Some(self.region.to_cloud_provider_format().to_string()),
+            s3_config: Some(format!(
+                "s3://{}:{}@{}/{}",
+                urlencoding::encode(&self.access_key_id),
+                urlencoding::encode(&self.secret_access_key),
+                endpoint,
+                bucket_name
+            )),
+            bucketname: Some(bucket_name.to_string()),
+            insecure: self.endpoint.scheme() == "http",
+            use_path_style: self.path_style,
+            aws_iam_loki_role_arn: None,
+        }
+    }
+
+    /// Terraform `s3` backend settings, for terraform states to be stored in the bucket
+    pub fn insert_terraform_backend_into_teracontext<'a>(
+        &self,
+        context: &'a mut TeraContext,
+        bucket_name: &str,
+        state_key: &str,
+    ) -> &'a mut TeraContext {
+        context.insert("s3_compatible_backend_endpoint", self.endpoint.as_str().trim_end_matches('/'));
+        context.insert("s3_compatible_backend_region", self.region.to_cloud_provider_format());
+        context.insert("s3_compatible_backend_access_key", &self.access_key_id);
+        context.insert("s3_compatible_backend_secret_key", &self.secret_access_key);
+        context.insert("s3_compatible_backend_bucket", bucket_name);
+        context.insert("s3_compatible_backend_key", state_key);
+        context.insert("s3_compatible_backend_use_path_style", &self.path_style);
+        context
+    }
+
+    fn get_region(&self) -> RusotoRegion {
+        RusotoRegion::Custom {
+            name: self.region.to_cloud_provider_format().to_string(),
+            endpoint: self.endpoint.as_str().trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn get_s3_client(&self) -> S3Client {
+        // the connector is given the TLS configuration holding the custom CA, if any
+        let mut http_connector = HttpConnector::new();
+        http_connector.enforce_http(false);
+        let https_connector = HttpsConnector::from((http_connector, self.tls_connector.clone().into()));
+        let client = Client::new_with(self.get_credentials(), HttpClient::from_connector(https_connector));
+
+        S3Client::new_with_client(client, self.get_region())
+    }
+
+    fn get_credentials(&self) -> StaticProvider {
+        StaticProvider::new(self.access_key_id.clone(), self.secret_access_key.clone(), None, None)
+    }
+
+    fn get_aws_credentials(&self) -> AwsCredentials {
+        AwsCredentials::new(self.access_key_id.clone(), self.secret_access_key.clone(), None, None)
+    }
+
+    fn is_bucket_name_valid(bucket_name: &str) -> Result<(), ObjectStorageError> {
+        if bucket_name.is_empty() {
+            return Err(ObjectStorageError::InvalidBucketName {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: "bucket name cannot be empty".to_string(),
+            });
+        }
+        if !bucket_name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.')
+        {
+            return Err(ObjectStorageError::InvalidBucketName {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: "bucket name can only contain lowercase letters, digits, '-' and '.'".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    // LocationConstraint is rejected by most implementations for their default region
+    fn location_constraint(&self) -> Option<String> {
+        match self.region.to_cloud_provider_format() {
+            DEFAULT_REGION | "auto" => None,
+            region => Some(region.to_string()),
+        }
+    }
+
+    fn get_tags(&self, bucket_name: &str, object_key: &str) -> Vec<String> {
+        match block_on(self.get_s3_client().get_object_tagging(GetObjectTaggingRequest {
+            bucket: bucket_name.to_string(),
+            key: object_key.to_string(),
+            ..Default::default()
+        })) {
+            Ok(res) => res
+                .tag_set
+                .into_iter()
+                .map(|tag| format!("{}={}", tag.key, tag.value))
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    fn empty_bucket(&self, bucket_name: &str) -> Result<(), ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+
+        let s3_client = self.get_s3_client();
+
+        // objects are listed and deleted by pages of at most 1000 objects, the DeleteObjects limit
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let listing = s3_operations::list_objects(&s3_client, bucket_name, None, continuation_token.as_deref())
+                .map_err(|e| ObjectStorageError::CannotEmptyBucket {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message: e.to_string(),
+                })?;
+
+            if !listing.objects.is_empty() {
+                block_on(
+                    s3_client.delete_objects(DeleteObjectsRequest {
+                        bucket: bucket_name.to_string(),
+                        delete: Delete {
+                            objects: listing
+                                .objects
+                                .into_iter()
+                                .map(|object| ObjectIdentifier {
+                                    key: object.key,
+                                    version_id: None,
+                                })
+                                .collect(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    }),
+                )
+                .map_err(|e| ObjectStorageError::CannotEmptyBucket {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message: e.to_string(),
+                })?;
+            }
+
+            continuation_token = listing.continuation_token;
+            if continuation_token.is_none() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl ObjectStorage for S3CompatibleOS {
+    fn kind(&self) -> Kind {
+        Kind::S3Compatible
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn is_valid(&self) -> Result<(), ObjectStorageError> {
+        Ok(())
+    }
+
+    fn bucket_exists(&self, bucket_name: &str) -> bool {
+        block_on(self.get_s3_client().head_bucket(HeadBucketRequest {
+            bucket: bucket_name.to_string(),
+            expected_bucket_owner: None,
+        }))
+        .is_ok()
+    }
+
+    fn create_bucket(
+        &self,
+        bucket_name: &str,
+        bucket_ttl: Option<Duration>,
+        bucket_versioning_activated: bool,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
+    ) -> Result<Bucket, ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+
+        let s3_client = self.get_s3_client();
+
+        // check if bucket already exists, if so, no need to recreate it
+        if let Ok(existing_bucket) = self.get_bucket(bucket_name) {
+            return match bucket_lifecycle_policy {
+                Some(lifecycle_policy) => {
+                    s3_operations::check_object_lock_activated(&s3_client, bucket_name, lifecycle_policy)?;
+                    s3_operations::apply_lifecycle_policy(&s3_client, bucket_name, lifecycle_policy).map_err(|e| {
+                        ObjectStorageError::CannotUpdateBucket {
+                            bucket_name: bucket_name.to_string(),
+                            raw_error_message: e,
+                        }
+                    })?;
+                    self.get_bucket(bucket_name)
+                }
+                None => Ok(existing_bucket),
+            };
+        }
+
+        if let Err(e) = block_on(
+            s3_client.create_bucket(CreateBucketRequest {
+                bucket: bucket_name.to_string(),
+                create_bucket_configuration: self.location_constraint().map(|region| CreateBucketConfiguration {
+                    location_constraint: Some(region),
+                }),
+                // object lock can only be activated at bucket creation
+                object_lock_enabled_for_bucket: bucket_lifecycle_policy
+                    .and_then(|lifecycle_policy| lifecycle_policy.object_lock)
+                    .map(|_| true),
+                ..Default::default()
+            }),
+        ) {
+            let raw_error_message = e.to_string();
+            return Err(match raw_error_message.contains("<Code>QuotaExceeded</Code>") {
+                true => ObjectStorageError::QuotasExceeded {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message,
+                },
+                false => ObjectStorageError::CannotCreateBucket {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message,
+                },
+            });
+        }
+
+        // Note: bucket tagging is not supported everywhere (i.e: R2), tags are only informative so it's not blocking
+        let creation_date: DateTime<Utc> = Utc::now();
+        if let Err(e) = block_on(s3_client.put_bucket_tagging(PutBucketTaggingRequest {
+            bucket: bucket_name.to_string(),
+            expected_bucket_owner: None,
+            tagging: Tagging {
+                tag_set: vec![
+                    Tag {
+                        key: "CreationDate".to_string(),
+                        value: creation_date.to_rfc3339(),
+                    },
+                    Tag {
+                        key: "Ttl".to_string(),
+                        value: bucket_ttl.map(|ttl| ttl.as_secs()).unwrap_or(0).to_string(),
+                    },
+                ],
+            },
+            ..Default::default()
+        })) {
+            warn!("cannot tag bucket `{}` on {}: {}", bucket_name, self.endpoint, e);
+        }
+
+        if bucket_versioning_activated {
+            s3_operations::set_bucket_versioning(&s3_client, bucket_name, true).map_err(|e| {
+                ObjectStorageError::CannotActivateBucketVersioning {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message: e,
+                }
+            })?;
+        }
+
+        if let Some(lifecycle_policy) = bucket_lifecycle_policy {
+            s3_operations::apply_lifecycle_policy(&s3_client, bucket_name, lifecycle_policy).map_err(|e| {
+                ObjectStorageError::CannotCreateBucket {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message: e,
+                }
+            })?;
+        }
+
+        self.get_bucket(bucket_name)
+    }
+
+    fn update_bucket(
+        &self,
+        bucket_name: &str,
+        bucket_versioning_activated: bool,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
+    ) -> Result<Bucket, ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+
+        let s3_client = self.get_s3_client();
+        let update_error = |raw_error_message: String| ObjectStorageError::CannotUpdateBucket {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message,
+        };
+
+        let bucket = self.get_bucket(bucket_name).map_err(|e| update_error(e.to_string()))?;
+        // versioning can't be suspended on a bucket which has never been versioned (i.e: MinIO)
+        if bucket.versioning_activated != bucket_versioning_activated {
+            s3_operations::set_bucket_versioning(&s3_client, bucket_name, bucket_versioning_activated)
+                .map_err(update_error)?;
+        }
+        if let Some(lifecycle_policy) = bucket_lifecycle_policy {
+            s3_operations::check_object_lock_activated(&s3_client, bucket_name, lifecycle_policy)?;
+            s3_operations::apply_lifecycle_policy(&s3_client, bucket_name, lifecycle_policy).map_err(update_error)?;
+        }
+
+        self.get_bucket(bucket_name)
+    }
+
+    fn get_bucket(&self, bucket_name: &str) -> Result<Bucket, ObjectStorageError> {
+        // if bucket doesn't exist, then return an error
+        if !self.bucket_exists(bucket_name) {
+            return Err(ObjectStorageError::CannotGetBucket {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: format!("Bucket `{}` doesn't exist", bucket_name),
+            });
+        }
+
+        let s3_client = self.get_s3_client();
+
+        // Get lifecycle rules and TTL, being the expiration applying to the whole bucket
+        let lifecycle_rules = s3_operations::get_lifecycle_rules(&s3_client, bucket_name);
+        let ttl = lifecycle_rules
+            .iter()
+            .find_map(|rule| match (&rule.prefix, &rule.action) {
+                (None, BucketLifecycleAction::ExpireAfterDays(days)) => {
+                    Some(Duration::from_secs(*days as u64 * 24 * 60 * 60))
+                }
+                _ => None,
+            });
+
+        // Get versioning
+        let versioning_activated = block_on(s3_client.get_bucket_versioning(GetBucketVersioningRequest {
+            bucket: bucket_name.to_string(),
+            expected_bucket_owner: None,
+        }))
+        .ok()
+        .and_then(|versioning| versioning.status)
+        .map(|status| status.to_lowercase() == "enabled")
+        .unwrap_or(false);
+
+        // Get labels
+        let labels = block_on(s3_client.get_bucket_tagging(GetBucketTaggingRequest {
+            bucket: bucket_name.to_string(),
+            expected_bucket_owner: None,
+        }))
+        .ok()
+        .map(|tagging| HashMap::from_iter(tagging.tag_set.into_iter().map(|t| (t.key, t.value))));
+
+        Ok(Bucket {
+            name: bucket_name.to_string(),
+            ttl,
+            versioning_activated,
+            location: BucketRegion::S3CompatibleRegion(self.region.clone()),
+            labels,
+            lifecycle_rules,
+            object_lock: s3_operations::get_object_lock(&s3_client, bucket_name),
+        })
+    }
+
+    fn delete_bucket(
+        &self,
+        bucket_name: &str,
+        bucket_delete_strategy: BucketDeleteStrategy,
+    ) -> Result<(), ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+
+        // make sure to delete all bucket content before trying to delete the bucket
+        self.empty_bucket(bucket_name)?;
+
+        match bucket_delete_strategy {
+            BucketDeleteStrategy::HardDelete => block_on(self.get_s3_client().delete_bucket(DeleteBucketRequest {
+                bucket: bucket_name.to_string(),
+                ..Default::default()
+            }))
+            .map_err(|e| ObjectStorageError::CannotDeleteBucket {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: e.to_string(),
+            }),
+            BucketDeleteStrategy::Empty => Ok(()), // Do not delete the bucket
+        }
+    }
+
+    fn delete_bucket_non_blocking(&self, bucket_name: &str) -> Result<(), ObjectStorageError> {
+        // there is no asynchronous deletion in the S3 API, the bucket is deleted right away
+        self.delete_bucket(bucket_name, BucketDeleteStrategy::HardDelete)
+    }
+
+    fn get_object(&self, bucket_name: &str, object_key: &str) -> Result<BucketObject, ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+
+        let get_error = |raw_error_message: String| ObjectStorageError::CannotGetObjectFile {
+            bucket_name: bucket_name.to_string(),
+            object_name: object_key.to_string(),
+            raw_error_message,
+        };
+        let res = block_on(self.get_s3_client().get_object(GetObjectRequest {
+            bucket: bucket_name.to_string(),
+            key: object_key.to_string(),
+            ..Default::default()
+        }))
+        .map_err(|e| get_error(e.to_string()))?;
+
+        let mut body = Vec::new();
+        res.body
+            .ok_or_else(|| get_error("Cannot get response body".to_string()))?
+            .into_blocking_read()
+            .read_to_end(&mut body)
+            .map_err(|e| get_error(format!("Cannot read response body: {}", e)))?;
+
+        let tags = match res.tag_count.unwrap_or(0) {
+            0 => vec![],
+            _ => self.get_tags(bucket_name, object_key),
+        };
+
+        Ok(BucketObject {
+            bucket_name: bucket_name.to_string(),
+            key: object_key.to_string(),
+            value: body,
+            tags,
+        })
+    }
+
+    fn put_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        file_path: &Path,
+        tags: Option<Vec<String>>,
+    ) -> Result<BucketObject, ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+
+        if let Some(mut file) =
+            open_file_for_multipart_upload(bucket_name, object_key, file_path, &self.multipart_upload_config)?
+        {
+            self.write_object(bucket_name, object_key, &mut file, tags)?;
+            return Ok(BucketObject {
+                bucket_name: bucket_name.to_string(),
+                key: object_key.to_string(),
+                value: vec![],
+                tags: vec![],
+            });
+        }
+
+        let file_content = std::fs::read(file_path).map_err(|e| ObjectStorageError::CannotUploadFile {
+            bucket_name: bucket_name.to_string(),
+            object_name: object_key.to_string(),
+            raw_error_message: e.to_string(),
+        })?;
+
+        match block_on(self.get_s3_client().put_object(PutObjectRequest {
+            bucket: bucket_name.to_string(),
+            key: object_key.to_string(),
+            body: Some(StreamingBody::from(file_content.clone())),
+            tagging: tags.map(|tags| tags.join("&")),
+            ..Default::default()
+        })) {
+            Ok(_) => Ok(BucketObject {
+                bucket_name: bucket_name.to_string(),
+                key: object_key.to_string(),
+                value: file_content,
+                tags: vec![],
+            }),
+            Err(e) => Err(ObjectStorageError::CannotUploadFile {
+                bucket_name: bucket_name.to_string(),
+                object_name: object_key.to_string(),
+                raw_error_message: e.to_string(),
+            }),
+        }
+    }
+
+    fn delete_object(&self, bucket_name: &str, object_key: &str) -> Result<(), ObjectStorageError> {
+        if S3CompatibleOS::is_bucket_name_valid(bucket_name).is_err() {
+            // bucket is missing it's ok as file can't be present
+            return Ok(());
+        };
+
+        // check if file already exists
+        if self.get_object(bucket_name, object_key).is_err() {
+            return Ok(());
+        };
+
+        block_on(self.get_s3_client().delete_object(DeleteObjectRequest {
+            bucket: bucket_name.to_string(),
+            key: object_key.to_string(),
+            ..Default::default()
+        }))
+        .map(|_| ())
+        .map_err(|e| ObjectStorageError::CannotDeleteFile {
+            bucket_name: bucket_name.to_string(),
+            object_name: object_key.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
+    fn list_objects(
+        &self,
+        bucket_name: &str,
+        prefix: Option<&str>,
+        continuation_token: Option<&str>,
+    ) -> Result<ObjectListing, ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+        s3_operations::list_objects(&self.get_s3_client(), bucket_name, prefix, continuation_token)
+    }
+
+    fn read_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+        s3_operations::read_object(&self.get_s3_client(), bucket_name, object_key, writer)
+    }
+
+    fn write_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        reader: &mut dyn Read,
+        tags: Option<Vec<String>>,
+    ) -> Result<u64, ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+        s3_operations::write_object(
+            &self.get_s3_client(),
+            bucket_name,
+            object_key,
+            reader,
+            tags,
+            self.multipart_upload_config.part_size_in_bytes,
+        )
+    }
+
+    fn copy_object(
+        &self,
+        source_bucket_name: &str,
+        source_object_key: &str,
+        target_bucket_name: &str,
+        target_object_key: &str,
+    ) -> Result<(), ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(source_bucket_name)?;
+        S3CompatibleOS::is_bucket_name_valid(target_bucket_name)?;
+        s3_operations::copy_object(
+            &self.get_s3_client(),
+            source_bucket_name,
+            source_object_key,
+            target_bucket_name,
+            target_object_key,
+        )
+    }
+
+    fn presign_get(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        expires_in: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+        s3_operations::presign_get(
+            &self.get_region(),
+            &self.get_aws_credentials(),
+            bucket_name,
+            object_key,
+            expires_in,
+        )
+    }
+
+    fn presign_put(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        expires_in: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        S3CompatibleOS::is_bucket_name_valid(bucket_name)?;
+        s3_operations::presign_put(
+            &self.get_region(),
+            &self.get_aws_credentials(),
+            bucket_name,
+            object_key,
+            expires_in,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn storage(endpoint: &str, region: &str) -> S3CompatibleOS {
+        S3CompatibleOS::new(
+            "id".to_string(),
+            "minio".to_string(),
+            "access".to_string(),
+            "secret".to_string(),
+            Url::parse(endpoint).unwrap(),
+            region,
+            true,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_region() {
+        let minio = storage("http://localhost:9000/", "");
+        assert_eq!(
+            minio.get_region(),
+            RusotoRegion::Custom {
+                name: DEFAULT_REGION.to_string(),
+                endpoint: "http://localhost:9000".to_string(),
+            }
+        );
+        assert_eq!(minio.location_constraint(), None);
+        assert_eq!(storage("https://r2.example.com", "auto").location_constraint(), None);
+        assert_eq!(
+            storage("https://ceph.example.com", "eu-west").location_constraint(),
+            Some("eu-west".to_string())
+        );
+    }
+
+    #[test]
+    fn test_loki_and_terraform_configurations() {
+        let minio = S3CompatibleOS::new(
+            "id".to_string(),
+            "minio".to_string(),
+            "access".to_string(),
+            "se/cret".to_string(),
+            Url::parse("http://minio.internal:9000").unwrap(),
+            "",
+            true,
+            None,
+        )
+        .unwrap();
+
+        let loki = minio.loki_chart_configuration("logs");
+        assert_eq!(
+            loki.s3_config.as_deref(),
+            Some("s3://access:se%2Fcret@minio.internal:9000/logs")
+        );
+        assert_eq!(loki.region.as_deref(), Some(DEFAULT_REGION));
+        assert!(loki.insecure);
+        assert!(loki.use_path_style);
+
+        let mut context = TeraContext::new();
+        minio.insert_terraform_backend_into_teracontext(&mut context, "tfstates", "cluster/tfstate");
+        assert_eq!(
+            context.get("s3_compatible_backend_endpoint").and_then(|v| v.as_str()),
+            Some("http://minio.internal:9000")
+        );
+        assert_eq!(
+            context.get("s3_compatible_backend_key").and_then(|v| v.as_str()),
+            Some("cluster/tfstate")
+        );
+        assert_eq!(
+            context
+                .get("s3_compatible_backend_use_path_style")
+                .and_then(|v| v.as_bool()),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_presign() {
+        let url = storage("http://localhost:9000", "")
+            .presign_get("bucket", "dir/file.tgz", Duration::from_secs(600))
+            .unwrap();
+        assert_eq!(url.scheme(), "http");
+        assert_eq!(url.host_str(), Some("localhost"));
+        assert_eq!(url.port(), Some(9000));
+        assert_eq!(url.path(), "/bucket/dir/file.tgz");
+        let query = url.query_pairs().collect::<HashMap<_, _>>();
+        assert_eq!(query.get("X-Amz-Expires").map(|v| v.as_ref()), Some("600"));
+        assert!(query.contains_key("X-Amz-Signature"));
+
+        assert!(storage("http://localhost:9000", "")
+            .presign_get("bucket", "file.tgz", Duration::ZERO)
+            .is_err());
+        assert!(storage("http://localhost:9000", "")
+            .presign_put("Bucket", "file.tgz", Duration::from_secs(600))
+            .is_err());
+    }
+
+    #[test]
+    fn test_invalid_endpoint_or_ca() {
+        assert!(S3CompatibleOS::new(
+            "id".to_string(),
+            "minio".to_string(),
+            "access".to_string(),
+            "secret".to_string(),
+            Url::parse("ftp://localhost").unwrap(),
+            "",
+            true,
+            None,
+        )
+        .is_err());
+        assert!(S3CompatibleOS::new(
+            "id".to_string(),
+            "minio".to_string(),
+            "access".to_string(),
+            "secret".to_string(),
+            Url::parse("https://localhost").unwrap(),
+            "",
+            true,
+            Some("not a certificate"),
+        )
+        .is_err());
+    }
+}
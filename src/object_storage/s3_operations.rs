@@ -0,0 +1,590 @@
+// Operations shared by the object storages relying on rusoto S3 client (AWS S3, Scaleway and S3-compatible ones)
+use chrono::{DateTime, Utc};
+use rusoto_core::credential::AwsCredentials;
+use rusoto_core::Region as RusotoRegion;
+use rusoto_s3::util::{PreSignedRequest, PreSignedRequestOption};
+use rusoto_s3::{
+    AbortIncompleteMultipartUpload, AbortMultipartUploadRequest, BucketLifecycleConfiguration,
+    CompleteMultipartUploadRequest, CompletedMultipartUpload, CompletedPart, CopyObjectRequest,
+    CreateMultipartUploadRequest, DefaultRetention, DeleteBucketLifecycleRequest,
+    GetBucketLifecycleConfigurationRequest, GetObjectLockConfigurationRequest, GetObjectRequest, LifecycleExpiration,
+    LifecycleRule, LifecycleRuleFilter, ListObjectsV2Request, NoncurrentVersionExpiration, ObjectLockConfiguration,
+    ObjectLockRule, PutBucketLifecycleConfigurationRequest, PutBucketVersioningRequest,
+    PutObjectLockConfigurationRequest, PutObjectRequest, S3Client, StreamingBody, Transition, UploadPartRequest,
+    VersioningConfiguration, S3,
+};
+use std::io::{Read, Write};
+use std::str::FromStr;
+use std::time::Duration;
+use url::Url;
+
+use crate::models::ToCloudProviderFormat;
+use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::{
+    check_presigned_url_expiration, read_part, BucketLifecycleAction, BucketLifecyclePolicy, BucketLifecycleRule,
+    BucketObjectLock, BucketObjectLockMode, ObjectListing, ObjectSummary,
+};
+use crate::runtime::block_on;
+
+pub(super) fn list_objects(
+    s3_client: &S3Client,
+    bucket_name: &str,
+    prefix: Option<&str>,
+    continuation_token: Option<&str>,
+) -> Result<ObjectListing, ObjectStorageError> {
+    match block_on(s3_client.list_objects_v2(ListObjectsV2Request {
+        bucket: bucket_name.to_string(),
+        prefix: prefix.map(str::to_string),
+        continuation_token: continuation_token.map(str::to_string),
+        ..Default::default()
+    })) {
+        Ok(res) => Ok(ObjectListing {
+            objects: res
+                .contents
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|object| {
+                    Some(ObjectSummary {
+                        key: object.key?,
+                        size_in_bytes: object.size.unwrap_or(0).max(0) as u64,
+                        last_modified: object
+                            .last_modified
+                            .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+                            .map(|date| date.with_timezone(&Utc)),
+                    })
+                })
+                .collect(),
+            continuation_token: match res.is_truncated {
+                Some(true) => res.next_continuation_token,
+                _ => None,
+            },
+        }),
+        Err(e) => Err(ObjectStorageError::CannotListObjects {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message: e.to_string(),
+        }),
+    }
+}
+
+pub(super) fn read_object(
+    s3_client: &S3Client,
+    bucket_name: &str,
+    object_key: &str,
+    writer: &mut dyn Write,
+) -> Result<u64, ObjectStorageError> {
+    let read_error = |raw_error_message: String| ObjectStorageError::CannotGetObjectFile {
+        bucket_name: bucket_name.to_string(),
+        object_name: object_key.to_string(),
+        raw_error_message,
+    };
+
+    let res = block_on(s3_client.get_object(GetObjectRequest {
+        bucket: bucket_name.to_string(),
+        key: object_key.to_string(),
+        ..Default::default()
+    }))
+    .map_err(|e| read_error(e.to_string()))?;
+
+    let mut stream = res
+        .body
+        .ok_or_else(|| read_error("Cannot get response body".to_string()))?
+        .into_blocking_read();
+    std::io::copy(&mut stream, writer).map_err(|e| read_error(format!("Cannot read response body: {}", e)))
+}
+
+pub(super) fn write_object(
+    s3_client: &S3Client,
+    bucket_name: &str,
+    object_key: &str,
+    reader: &mut dyn Read,
+    tags: Option<Vec<String>>,
+    part_size_in_bytes: u64,
+) -> Result<u64, ObjectStorageError> {
+    let upload_error = |raw_error_message: String| ObjectStorageError::CannotUploadFile {
+        bucket_name: bucket_name.to_string(),
+        object_name: object_key.to_string(),
+        raw_error_message,
+    };
+    let tagging = tags.map(|tags| tags.join("&"));
+
+    let first_part = read_part(reader, part_size_in_bytes).map_err(|e| upload_error(e.to_string()))?;
+    if (first_part.len() as u64) < part_size_in_bytes {
+        let size = first_part.len() as u64;
+        block_on(s3_client.put_object(PutObjectRequest {
+            bucket: bucket_name.to_string(),
+            key: object_key.to_string(),
+            body: Some(StreamingBody::from(first_part)),
+            tagging,
+            ..Default::default()
+        }))
+        .map_err(|e| upload_error(e.to_string()))?;
+        return Ok(size);
+    }
+
+    let upload_id = block_on(s3_client.create_multipart_upload(CreateMultipartUploadRequest {
+        bucket: bucket_name.to_string(),
+        key: object_key.to_string(),
+        tagging,
+        ..Default::default()
+    }))
+    .map_err(|e| upload_error(e.to_string()))?
+    .upload_id
+    .ok_or_else(|| upload_error("no upload id returned for the multipart upload".to_string()))?;
+
+    let upload_result = upload_parts(
+        s3_client,
+        bucket_name,
+        object_key,
+        &upload_id,
+        first_part,
+        reader,
+        part_size_in_bytes,
+    )
+    .and_then(|(parts, size)| {
+        block_on(s3_client.complete_multipart_upload(CompleteMultipartUploadRequest {
+            bucket: bucket_name.to_string(),
+            key: object_key.to_string(),
+            upload_id: upload_id.clone(),
+            multipart_upload: Some(CompletedMultipartUpload { parts: Some(parts) }),
+            ..Default::default()
+        }))
+        .map(|_| size)
+        .map_err(|e| e.to_string())
+    });
+
+    upload_result.map_err(|e| {
+        // uploaded parts are kept (and billed) until the upload is aborted
+        let _ = block_on(s3_client.abort_multipart_upload(AbortMultipartUploadRequest {
+            bucket: bucket_name.to_string(),
+            key: object_key.to_string(),
+            upload_id: upload_id.clone(),
+            ..Default::default()
+        }));
+        upload_error(e)
+    })
+}
+
+fn upload_parts(
+    s3_client: &S3Client,
+    bucket_name: &str,
+    object_key: &str,
+    upload_id: &str,
+    first_part: Vec<u8>,
+    reader: &mut dyn Read,
+    part_size_in_bytes: u64,
+) -> Result<(Vec<CompletedPart>, u64), String> {
+    let mut completed_parts: Vec<CompletedPart> = vec![];
+    let mut size: u64 = 0;
+    let mut part = first_part;
+
+    // the last part is empty when the content size is a multiple of the part size
+    while !part.is_empty() {
+        let part_number = completed_parts.len() as i64 + 1;
+        size += part.len() as u64;
+        let res = block_on(s3_client.upload_part(UploadPartRequest {
+            bucket: bucket_name.to_string(),
+            key: object_key.to_string(),
+            upload_id: upload_id.to_string(),
+            part_number,
+            content_length: Some(part.len() as i64),
+            body: Some(StreamingBody::from(part)),
+            ..Default::default()
+        }))
+        .map_err(|e| e.to_string())?;
+        completed_parts.push(CompletedPart {
+            e_tag: res.e_tag,
+            part_number: Some(part_number),
+        });
+
+        part = read_part(reader, part_size_in_bytes).map_err(|e| e.to_string())?;
+    }
+
+    Ok((completed_parts, size))
+}
+
+pub(super) fn copy_object(
+    s3_client: &S3Client,
+    source_bucket_name: &str,
+    source_object_key: &str,
+    target_bucket_name: &str,
+    target_object_key: &str,
+) -> Result<(), ObjectStorageError> {
+    // Note: a single copy is limited to objects of 5GB
+    block_on(s3_client.copy_object(CopyObjectRequest {
+        bucket: target_bucket_name.to_string(),
+        key: target_object_key.to_string(),
+        copy_source: copy_source(source_bucket_name, source_object_key),
+        ..Default::default()
+    }))
+    .map(|_| ())
+    .map_err(|e| ObjectStorageError::CannotCopyFile {
+        bucket_name: target_bucket_name.to_string(),
+        object_name: target_object_key.to_string(),
+        raw_error_message: e.to_string(),
+    })
+}
+
+/// `x-amz-copy-source` value, the key being url encoded except its `/` separators
+pub(super) fn copy_source(bucket_name: &str, object_key: &str) -> String {
+    format!("{}/{}", bucket_name, urlencoding::encode(object_key).replace("%2F", "/"))
+}
+
+pub(super) fn presign_get(
+    region: &RusotoRegion,
+    credentials: &AwsCredentials,
+    bucket_name: &str,
+    object_key: &str,
+    expires_in: Duration,
+) -> Result<Url, ObjectStorageError> {
+    check_presigned_url_expiration(bucket_name, object_key, expires_in)?;
+    let url = GetObjectRequest {
+        bucket: bucket_name.to_string(),
+        key: object_key.to_string(),
+        ..Default::default()
+    }
+    .get_presigned_url(region, credentials, &PreSignedRequestOption { expires_in });
+    parse_presigned_url(bucket_name, object_key, &url)
+}
+
+pub(super) fn presign_put(
+    region: &RusotoRegion,
+    credentials: &AwsCredentials,
+    bucket_name: &str,
+    object_key: &str,
+    expires_in: Duration,
+) -> Result<Url, ObjectStorageError> {
+    check_presigned_url_expiration(bucket_name, object_key, expires_in)?;
+    let url = PutObjectRequest {
+        bucket: bucket_name.to_string(),
+        key: object_key.to_string(),
+        ..Default::default()
+    }
+    .get_presigned_url(region, credentials, &PreSignedRequestOption { expires_in });
+    parse_presigned_url(bucket_name, object_key, &url)
+}
+
+fn parse_presigned_url(bucket_name: &str, object_key: &str, url: &str) -> Result<Url, ObjectStorageError> {
+    Url::parse(url).map_err(|e| ObjectStorageError::CannotPresignUrl {
+        bucket_name: bucket_name.to_string(),
+        object_name: object_key.to_string(),
+        raw_error_message: e.to_string(),
+    })
+}
+
+pub(super) fn set_bucket_versioning(s3_client: &S3Client, bucket_name: &str, activated: bool) -> Result<(), String> {
+    block_on(
+        s3_client.put_bucket_versioning(PutBucketVersioningRequest {
+            bucket: bucket_name.to_string(),
+            versioning_configuration: VersioningConfiguration {
+                status: Some(
+                    match activated {
+                        true => "Enabled",
+                        false => "Suspended",
+                    }
+                    .to_string(),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Object lock can't be activated on an existing bucket, only the default retention of a bucket created
+/// with `ObjectLockEnabledForBucket` can be set.
+pub(super) fn check_object_lock_activated(
+    s3_client: &S3Client,
+    bucket_name: &str,
+    lifecycle_policy: &BucketLifecyclePolicy,
+) -> Result<(), ObjectStorageError> {
+    if lifecycle_policy.object_lock.is_none() {
+        return Ok(());
+    }
+
+    // an error is returned when object lock is not activated on the bucket
+    let object_lock_enabled = block_on(s3_client.get_object_lock_configuration(GetObjectLockConfigurationRequest {
+        bucket: bucket_name.to_string(),
+        expected_bucket_owner: None,
+    }))
+    .ok()
+    .and_then(|res| res.object_lock_configuration?.object_lock_enabled)
+    .is_some_and(|enabled| enabled == "Enabled");
+
+    match object_lock_enabled {
+        true => Ok(()),
+        false => Err(ObjectStorageError::ObjectLockNotActivated {
+            bucket_name: bucket_name.to_string(),
+        }),
+    }
+}
+
+/// Replace the bucket lifecycle rules by the policy ones, and set the object lock default retention if any.
+/// Object lock has to be activated at bucket creation, see `check_object_lock_activated` for existing buckets.
+pub(super) fn apply_lifecycle_policy(
+    s3_client: &S3Client,
+    bucket_name: &str,
+    lifecycle_policy: &BucketLifecyclePolicy,
+) -> Result<(), String> {
+    // a lifecycle configuration can't be empty, it has to be deleted instead
+    match lifecycle_policy.rules.is_empty() {
+        true => block_on(s3_client.delete_bucket_lifecycle(DeleteBucketLifecycleRequest {
+            bucket: bucket_name.to_string(),
+            expected_bucket_owner: None,
+        }))
+        .map_err(|e| format!("cannot delete lifecycle rules: {}", e))?,
+        false => block_on(
+            s3_client.put_bucket_lifecycle_configuration(PutBucketLifecycleConfigurationRequest {
+                bucket: bucket_name.to_string(),
+                lifecycle_configuration: Some(BucketLifecycleConfiguration {
+                    rules: to_s3_lifecycle_rules(&lifecycle_policy.rules),
+                }),
+                ..Default::default()
+            }),
+        )
+        .map_err(|e| format!("cannot put lifecycle rules: {}", e))?,
+    };
+
+    if let Some(object_lock) = &lifecycle_policy.object_lock {
+        block_on(s3_client.put_object_lock_configuration(PutObjectLockConfigurationRequest {
+            bucket: bucket_name.to_string(),
+            object_lock_configuration: Some(ObjectLockConfiguration {
+                object_lock_enabled: Some("Enabled".to_string()),
+                rule: Some(ObjectLockRule {
+                    default_retention: Some(DefaultRetention {
+                        days: Some(object_lock.retention_days as i64),
+                        mode: Some(object_lock.mode.to_cloud_provider_format().to_string()),
+                        years: None,
+                    }),
+                }),
+            }),
+            ..Default::default()
+        }))
+        .map_err(|e| format!("cannot put object lock configuration: {}", e))?;
+    }
+
+    Ok(())
+}
+
+pub(super) fn get_lifecycle_rules(s3_client: &S3Client, bucket_name: &str) -> Vec<BucketLifecycleRule> {
+    // an error is returned when the bucket has no lifecycle configuration
+    block_on(
+        s3_client.get_bucket_lifecycle_configuration(GetBucketLifecycleConfigurationRequest {
+            bucket: bucket_name.to_string(),
+            expected_bucket_owner: None,
+        }),
+    )
+    .ok()
+    .and_then(|lifecycle| lifecycle.rules)
+    .map(|rules| from_s3_lifecycle_rules(&rules))
+    .unwrap_or_default()
+}
+
+pub(super) fn get_object_lock(s3_client: &S3Client, bucket_name: &str) -> Option<BucketObjectLock> {
+    // an error is returned when object lock is not activated on the bucket
+    let default_retention = block_on(s3_client.get_object_lock_configuration(GetObjectLockConfigurationRequest {
+        bucket: bucket_name.to_string(),
+        expected_bucket_owner: None,
+    }))
+    .ok()?
+    .object_lock_configuration?
+    .rule?
+    .default_retention?;
+
+    Some(BucketObjectLock {
+        mode: BucketObjectLockMode::from_str(default_retention.mode?.as_str()).ok()?,
+        retention_days: match (default_retention.days, default_retention.years) {
+            (Some(days), _) => days.max(0) as u32,
+            (None, Some(years)) => years.max(0) as u32 * 365,
+            (None, None) => return None,
+        },
+    })
+}
+
+fn to_s3_lifecycle_rules(rules: &[BucketLifecycleRule]) -> Vec<LifecycleRule> {
+    rules
+        .iter()
+        .enumerate()
+        .map(|(idx, rule)| {
+            let mut s3_rule = LifecycleRule {
+                // ids are derived from the position so applying the same policy twice gives the same configuration
+                id: Some(format!("qovery-rule-{}", idx)),
+                status: "Enabled".to_string(),
+                filter: Some(LifecycleRuleFilter {
+                    prefix: Some(rule.prefix.clone().unwrap_or_default()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            match &rule.action {
+                BucketLifecycleAction::ExpireAfterDays(days) => {
+                    s3_rule.expiration = Some(LifecycleExpiration {
+                        days: Some(*days as i64),
+                        ..Default::default()
+                    })
+                }
+                BucketLifecycleAction::TransitionAfterDays { days, storage_class } => {
+                    s3_rule.transitions = Some(vec![Transition {
+                        days: Some(*days as i64),
+                        storage_class: Some(storage_class.to_string()),
+                        ..Default::default()
+                    }])
+                }
+                BucketLifecycleAction::AbortIncompleteMultipartUploadAfterDays(days) => {
+                    s3_rule.abort_incomplete_multipart_upload = Some(AbortIncompleteMultipartUpload {
+                        days_after_initiation: Some(*days as i64),
+                    })
+                }
+                BucketLifecycleAction::ExpireNoncurrentVersionsAfterDays(days) => {
+                    s3_rule.noncurrent_version_expiration = Some(NoncurrentVersionExpiration {
+                        noncurrent_days: Some(*days as i64),
+                        ..Default::default()
+                    })
+                }
+            }
+            s3_rule
+        })
+        .collect()
+}
+
+/// A S3 rule can hold several actions, each of them gives a rule. Disabled rules are ignored.
+fn from_s3_lifecycle_rules(s3_rules: &[LifecycleRule]) -> Vec<BucketLifecycleRule> {
+    let mut rules = vec![];
+    for s3_rule in s3_rules.iter().filter(|r| r.status.eq_ignore_ascii_case("enabled")) {
+        let prefix = s3_rule
+            .filter
+            .as_ref()
+            .and_then(|filter| filter.prefix.clone())
+            .or_else(|| s3_rule.prefix.clone())
+            .filter(|prefix| !prefix.is_empty());
+        let mut push = |action: BucketLifecycleAction| {
+            rules.push(BucketLifecycleRule {
+                prefix: prefix.clone(),
+                action,
+            })
+        };
+
+        if let Some(days) = s3_rule.expiration.as_ref().and_then(|e| e.days) {
+            push(BucketLifecycleAction::ExpireAfterDays(days.max(0) as u32));
+        }
+        for transition in s3_rule.transitions.iter().flatten() {
+            if let (Some(days), Some(storage_class)) = (transition.days, &transition.storage_class) {
+                push(BucketLifecycleAction::TransitionAfterDays {
+                    days: days.max(0) as u32,
+                    storage_class: storage_class.to_string(),
+                });
+            }
+        }
+        if let Some(days) = s3_rule
+            .abort_incomplete_multipart_upload
+            .as_ref()
+            .and_then(|a| a.days_after_initiation)
+        {
+            push(BucketLifecycleAction::AbortIncompleteMultipartUploadAfterDays(
+                days.max(0) as u32
+            ));
+        }
+        if let Some(days) = s3_rule
+            .noncurrent_version_expiration
+            .as_ref()
+            .and_then(|e| e.noncurrent_days)
+        {
+            push(BucketLifecycleAction::ExpireNoncurrentVersionsAfterDays(days.max(0) as u32));
+        }
+    }
+
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_source() {
+        assert_eq!(copy_source("bucket", "dir/file.tgz"), "bucket/dir/file.tgz");
+        assert_eq!(copy_source("bucket", "dir/my file+1.tgz"), "bucket/dir/my%20file%2B1.tgz");
+    }
+
+    #[test]
+    fn test_presign_get() {
+        let region = RusotoRegion::Custom {
+            name: "fr-par".to_string(),
+            endpoint: "https://s3.fr-par.scw.cloud".to_string(),
+        };
+        let credentials = AwsCredentials::new("access_key", "secret_key", None, None);
+
+        let url = presign_get(&region, &credentials, "bucket", "dir/archive.tgz", Duration::from_secs(900))
+            .expect("cannot presign url");
+        assert_eq!(url.host_str(), Some("s3.fr-par.scw.cloud"));
+        assert_eq!(url.path(), "/bucket/dir/archive.tgz");
+        let query = url.query_pairs().collect::<std::collections::HashMap<_, _>>();
+        assert_eq!(query.get("X-Amz-Expires").map(|v| v.as_ref()), Some("900"));
+        assert!(query.get("X-Amz-Credential").unwrap().starts_with("access_key/"));
+        assert!(query.contains_key("X-Amz-Signature"));
+
+        assert!(presign_put(&region, &credentials, "bucket", "key", Duration::from_secs(8 * 24 * 3600)).is_err());
+    }
+
+    #[test]
+    fn test_lifecycle_rules_conversion() {
+        let rules = vec![
+            BucketLifecycleRule {
+                prefix: Some("logs/".to_string()),
+                action: BucketLifecycleAction::ExpireAfterDays(30),
+            },
+            BucketLifecycleRule {
+                prefix: Some("archives/".to_string()),
+                action: BucketLifecycleAction::TransitionAfterDays {
+                    days: 7,
+                    storage_class: "GLACIER".to_string(),
+                },
+            },
+            BucketLifecycleRule {
+                prefix: None,
+                action: BucketLifecycleAction::AbortIncompleteMultipartUploadAfterDays(1),
+            },
+            BucketLifecycleRule {
+                prefix: None,
+                action: BucketLifecycleAction::ExpireNoncurrentVersionsAfterDays(90),
+            },
+        ];
+
+        let s3_rules = to_s3_lifecycle_rules(&rules);
+        assert_eq!(s3_rules.len(), 4);
+        assert_eq!(s3_rules[0].id.as_deref(), Some("qovery-rule-0"));
+        assert_eq!(s3_rules[2].filter.as_ref().and_then(|f| f.prefix.as_deref()), Some(""));
+        assert_eq!(from_s3_lifecycle_rules(&s3_rules), rules);
+
+        // a single S3 rule holding several actions
+        let s3_rule = LifecycleRule {
+            status: "Enabled".to_string(),
+            prefix: Some("tmp/".to_string()),
+            expiration: Some(LifecycleExpiration {
+                days: Some(2),
+                ..Default::default()
+            }),
+            abort_incomplete_multipart_upload: Some(AbortIncompleteMultipartUpload {
+                days_after_initiation: Some(1),
+            }),
+            ..Default::default()
+        };
+        let disabled_rule = LifecycleRule {
+            status: "Disabled".to_string(),
+            ..s3_rule.clone()
+        };
+        assert_eq!(
+            from_s3_lifecycle_rules(&[s3_rule, disabled_rule]),
+            vec![
+                BucketLifecycleRule {
+                    prefix: Some("tmp/".to_string()),
+                    action: BucketLifecycleAction::ExpireAfterDays(2),
+                },
+                BucketLifecycleRule {
+                    prefix: Some("tmp/".to_string()),
+                    action: BucketLifecycleAction::AbortIncompleteMultipartUploadAfterDays(1),
+                },
+            ]
+        );
+    }
+}
@@ -1,21 +1,25 @@
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::time::Duration;
+use url::Url;
 
-use crate::object_storage::{Bucket, BucketDeleteStrategy, BucketObject, BucketRegion, Kind, ObjectStorage};
+use crate::object_storage::{
+    open_file_for_multipart_upload, s3_operations, Bucket, BucketDeleteStrategy, BucketLifecycleAction,
+    BucketLifecyclePolicy, BucketObject, BucketRegion, Kind, MultipartUploadConfig, ObjectListing, ObjectStorage,
+};
 
 use crate::models::scaleway::ScwZone;
 use crate::object_storage::errors::ObjectStorageError;
 use crate::runtime::block_on;
 use rusoto_core::{Client, HttpClient, Region as RusotoRegion};
-use rusoto_credential::StaticProvider;
+use rusoto_credential::{AwsCredentials, StaticProvider};
 use rusoto_s3::{
     CreateBucketConfiguration, CreateBucketRequest, Delete, DeleteBucketRequest, DeleteObjectRequest,
-    DeleteObjectsRequest, GetBucketLifecycleRequest, GetBucketTaggingRequest, GetBucketVersioningRequest,
-    GetObjectRequest, HeadBucketRequest, ListObjectsRequest, ObjectIdentifier, PutBucketTaggingRequest,
-    PutBucketVersioningRequest, PutObjectRequest, S3Client, StreamingBody, Tag, Tagging, S3,
+    DeleteObjectsRequest, GetBucketTaggingRequest, GetBucketVersioningRequest, GetObjectRequest, HeadBucketRequest,
+    ListObjectsRequest, ObjectIdentifier, PutBucketTaggingRequest, PutBucketVersioningRequest, PutObjectRequest,
+    S3Client, StreamingBody, Tag, Tagging, S3,
 };
 
 // doc: https://www.scaleway.com/en/docs/object-storage-feature/
@@ -25,6 +29,7 @@ pub struct ScalewayOS {
     access_key: String,
     secret_token: String,
     zone: ScwZone,
+    multipart_upload_config: MultipartUploadConfig,
 }
 
 impl ScalewayOS {
@@ -35,24 +40,36 @@ impl ScalewayOS {
             access_key,
             secret_token,
             zone,
+            multipart_upload_config: MultipartUploadConfig::default(),
         }
     }
 
-    fn get_s3_client(&self) -> S3Client {
-        let region = RusotoRegion::Custom {
+    pub fn with_multipart_upload_config(mut self, multipart_upload_config: MultipartUploadConfig) -> Self {
+        self.multipart_upload_config = multipart_upload_config;
+        self
+    }
+
+    fn get_region(&self) -> RusotoRegion {
+        RusotoRegion::Custom {
             name: self.zone.region().to_string(),
             endpoint: self.get_endpoint_url_for_region(),
-        };
+        }
+    }
 
+    fn get_s3_client(&self) -> S3Client {
         let client = Client::new_with(self.get_credentials(), HttpClient::new().unwrap());
 
-        S3Client::new_with_client(client, region)
+        S3Client::new_with_client(client, self.get_region())
     }
 
     fn get_credentials(&self) -> StaticProvider {
         StaticProvider::new(self.access_key.clone(), self.secret_token.clone(), None, None)
     }
 
+    fn get_aws_credentials(&self) -> AwsCredentials {
+        AwsCredentials::new(self.access_key.clone(), self.secret_token.clone(), None, None)
+    }
+
     fn get_endpoint_url_for_region(&self) -> String {
         format!("https://s3.{}.scw.cloud", self.zone.region())
     }
@@ -157,6 +174,7 @@ impl ObjectStorage for ScalewayOS {
         bucket_name: &str,
         bucket_ttl: Option<Duration>,
         bucket_versioning_activated: bool,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
     ) -> Result<Bucket, ObjectStorageError> {
         // TODO(benjamin): switch to `scaleway-api-rs` once object storage will be supported (https://github.com/Qovery/scaleway-api-rs/issues/12).
         ScalewayOS::is_bucket_name_valid(bucket_name)?;
@@ -167,16 +185,34 @@ impl ObjectStorage for ScalewayOS {
         // note: we are not deleting buckets since it takes up to 24 hours to be taken into account
         // so we better reuse existing ones
         if let Ok(existing_bucket) = self.get_bucket(bucket_name) {
-            return Ok(existing_bucket);
+            return match bucket_lifecycle_policy {
+                Some(lifecycle_policy) => {
+                    s3_operations::check_object_lock_activated(&s3_client, bucket_name, lifecycle_policy)?;
+                    s3_operations::apply_lifecycle_policy(&s3_client, bucket_name, lifecycle_policy).map_err(|e| {
+                        ObjectStorageError::CannotUpdateBucket {
+                            bucket_name: bucket_name.to_string(),
+                            raw_error_message: e,
+                        }
+                    })?;
+                    self.get_bucket(bucket_name)
+                }
+                None => Ok(existing_bucket),
+            };
         }
 
-        if let Err(e) = block_on(s3_client.create_bucket(CreateBucketRequest {
-            bucket: bucket_name.to_string(),
-            create_bucket_configuration: Some(CreateBucketConfiguration {
-                location_constraint: Some(self.zone.region().to_string()),
+        if let Err(e) = block_on(
+            s3_client.create_bucket(CreateBucketRequest {
+                bucket: bucket_name.to_string(),
+                create_bucket_configuration: Some(CreateBucketConfiguration {
+                    location_constraint: Some(self.zone.region().to_string()),
+                }),
+                // object lock can only be activated at bucket creation
+                object_lock_enabled_for_bucket: bucket_lifecycle_policy
+                    .and_then(|lifecycle_policy| lifecycle_policy.object_lock)
+                    .map(|_| true),
+                ..Default::default()
             }),
-            ..Default::default()
-        })) {
+        ) {
             return Err(ScalewayObjectStorageErrorManager::try_extract_fully_qualified_error(
                 e.to_string().as_str(),
                 Some(bucket_name),
@@ -223,16 +259,40 @@ impl ObjectStorage for ScalewayOS {
             }
         }
 
+        if let Some(lifecycle_policy) = bucket_lifecycle_policy {
+            s3_operations::apply_lifecycle_policy(&s3_client, bucket_name, lifecycle_policy).map_err(|e| {
+                ObjectStorageError::CannotCreateBucket {
+                    bucket_name: bucket_name.to_string(),
+                    raw_error_message: e,
+                }
+            })?;
+        }
+
         self.get_bucket(bucket_name) // TODO(benjaminch): maybe doing a get here is avoidable
     }
 
     fn update_bucket(
         &self,
-        _bucket_name: &str,
-        _bucket_versioning_activated: bool,
+        bucket_name: &str,
+        bucket_versioning_activated: bool,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
     ) -> Result<Bucket, ObjectStorageError> {
-        // TODO(benjaminch): to be implemented
-        todo!("update_bucket for SCW object storage is not implemented")
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+
+        let s3_client = self.get_s3_client();
+        let update_error = |raw_error_message: String| ObjectStorageError::CannotUpdateBucket {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message,
+        };
+
+        s3_operations::set_bucket_versioning(&s3_client, bucket_name, bucket_versioning_activated)
+            .map_err(update_error)?;
+        if let Some(lifecycle_policy) = bucket_lifecycle_policy {
+            s3_operations::check_object_lock_activated(&s3_client, bucket_name, lifecycle_policy)?;
+            s3_operations::apply_lifecycle_policy(&s3_client, bucket_name, lifecycle_policy).map_err(update_error)?;
+        }
+
+        self.get_bucket(bucket_name)
     }
 
     fn get_bucket(&self, bucket_name: &str) -> Result<Bucket, ObjectStorageError> {
@@ -244,22 +304,16 @@ impl ObjectStorage for ScalewayOS {
             });
         }
 
-        // Get TTL
-        let mut ttl: Option<Duration> = None;
-        if let Ok(bl) = block_on(self.get_s3_client().get_bucket_lifecycle(GetBucketLifecycleRequest {
-            bucket: bucket_name.to_string(),
-            expected_bucket_owner: None,
-        })) {
-            if let Some(rules) = bl.rules {
-                for r in rules {
-                    if let Some(expiration) = r.expiration {
-                        ttl = expiration
-                            .days
-                            .map(|days| Duration::from_secs(days.unsigned_abs() * 24 * 60 * 60));
-                    }
+        // Get lifecycle rules and TTL, being the expiration applying to the whole bucket
+        let lifecycle_rules = s3_operations::get_lifecycle_rules(&self.get_s3_client(), bucket_name);
+        let ttl = lifecycle_rules
+            .iter()
+            .find_map(|rule| match (&rule.prefix, &rule.action) {
+                (None, BucketLifecycleAction::ExpireAfterDays(days)) => {
+                    Some(Duration::from_secs(*days as u64 * 24 * 60 * 60))
                 }
-            }
-        }
+                _ => None,
+            });
 
         // Get versioning
         let mut versioning_activated = false;
@@ -289,6 +343,8 @@ impl ObjectStorage for ScalewayOS {
             versioning_activated,
             location: BucketRegion::ScwRegion(self.zone),
             labels,
+            lifecycle_rules,
+            object_lock: s3_operations::get_object_lock(&self.get_s3_client(), bucket_name),
         })
     }
 
@@ -383,6 +439,18 @@ impl ObjectStorage for ScalewayOS {
         // TODO(benjamin): switch to `scaleway-api-rs` once object storage will be supported (https://github.com/Qovery/scaleway-api-rs/issues/12).
         ScalewayOS::is_bucket_name_valid(bucket_name)?;
 
+        if let Some(mut file) =
+            open_file_for_multipart_upload(bucket_name, object_key, file_path, &self.multipart_upload_config)?
+        {
+            self.write_object(bucket_name, object_key, &mut file, None)?;
+            return Ok(BucketObject {
+                bucket_name: bucket_name.to_string(),
+                key: object_key.to_string(),
+                value: vec![],
+                tags: vec![],
+            });
+        }
+
         let s3_client = self.get_s3_client();
 
         let file_content = std::fs::read(file_path).map_err(|e| ObjectStorageError::CannotUploadFile {
@@ -437,6 +505,94 @@ impl ObjectStorage for ScalewayOS {
             }),
         }
     }
+
+    fn list_objects(
+        &self,
+        bucket_name: &str,
+        prefix: Option<&str>,
+        continuation_token: Option<&str>,
+    ) -> Result<ObjectListing, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+        s3_operations::list_objects(&self.get_s3_client(), bucket_name, prefix, continuation_token)
+    }
+
+    fn read_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        writer: &mut dyn Write,
+    ) -> Result<u64, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+        s3_operations::read_object(&self.get_s3_client(), bucket_name, object_key, writer)
+    }
+
+    fn write_object(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        reader: &mut dyn Read,
+        tags: Option<Vec<String>>,
+    ) -> Result<u64, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+        s3_operations::write_object(
+            &self.get_s3_client(),
+            bucket_name,
+            object_key,
+            reader,
+            tags,
+            self.multipart_upload_config.part_size_in_bytes,
+        )
+    }
+
+    fn copy_object(
+        &self,
+        source_bucket_name: &str,
+        source_object_key: &str,
+        target_bucket_name: &str,
+        target_object_key: &str,
+    ) -> Result<(), ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(source_bucket_name)?;
+        ScalewayOS::is_bucket_name_valid(target_bucket_name)?;
+        s3_operations::copy_object(
+            &self.get_s3_client(),
+            source_bucket_name,
+            source_object_key,
+            target_bucket_name,
+            target_object_key,
+        )
+    }
+
+    fn presign_get(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        expires_in: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+        s3_operations::presign_get(
+            &self.get_region(),
+            &self.get_aws_credentials(),
+            bucket_name,
+            object_key,
+            expires_in,
+        )
+    }
+
+    fn presign_put(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        expires_in: Duration,
+    ) -> Result<Url, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+        s3_operations::presign_put(
+            &self.get_region(),
+            &self.get_aws_credentials(),
+            bucket_name,
+            object_key,
+            expires_in,
+        )
+    }
 }
 
 struct ScalewayObjectStorageErrorManager {}
@@ -0,0 +1,337 @@
+use crate::cloud_provider::models::ExternalSecretRemoteRef;
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::io_models::environment::EnvironmentRequest;
+use crate::io_models::variable_utils::ExternalSecretReference;
+use crate::runtime::block_on;
+use base64::engine::general_purpose;
+use base64::Engine;
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use kube::Api;
+
+const EXTERNAL_SECRETS_CRD_NAME: &str = "externalsecrets.external-secrets.io";
+// ClusterSecretStores to be declared by the cluster owner, one per secret manager
+const VAULT_CLUSTER_SECRET_STORE: &str = "qovery-vault";
+const AWS_SECRETS_MANAGER_CLUSTER_SECRET_STORE: &str = "qovery-aws-secrets-manager";
+const GCP_SECRET_MANAGER_CLUSTER_SECRET_STORE: &str = "qovery-gcp-secret-manager";
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum ExternalSecretError {
+    #[error("Secret `{reference}` not found")]
+    NotFound { reference: String },
+    #[error("Key `{key}` not found in secret `{reference}`")]
+    KeyNotFound { reference: String, key: String },
+    #[error("Cannot fetch secret `{reference}`: {raw_error_message}")]
+    CannotFetchSecret {
+        reference: String,
+        raw_error_message: String,
+    },
+    #[error("Secret `{reference}` can only be pulled by External Secrets Operator, which is not installed on the cluster or not supported by this service")]
+    ExternalSecretsOperatorRequired { reference: String },
+    #[error("Secret `{reference}` is not in the environment namespace `{environment_namespace}`")]
+    NamespaceNotAllowed {
+        reference: String,
+        environment_namespace: String,
+    },
+}
+
+/// How an external secret is resolved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Resolution {
+    /// Pulled by External Secrets Operator, through a ClusterSecretStore declared by the cluster owner
+    ExternalSecretsOperator,
+    /// Copied by the engine from a Kubernetes Secret of the environment namespace
+    KubernetesSecret,
+}
+
+/// The engine never reads secret stores with its own credentials, as they give access to other tenants' and the
+/// cluster's secrets: only Kubernetes Secrets of the environment namespace are read by the engine, everything else
+/// must go through a ClusterSecretStore.
+fn resolution(
+    reference: &ExternalSecretReference,
+    environment_namespace: &str,
+    can_use_external_secrets_operator: bool,
+) -> Result<Resolution, ExternalSecretError> {
+    match reference {
+        ExternalSecretReference::Kubernetes { namespace, .. } if namespace == environment_namespace => {
+            Ok(Resolution::KubernetesSecret)
+        }
+        ExternalSecretReference::Kubernetes { .. } => Err(ExternalSecretError::NamespaceNotAllowed {
+            reference: reference.to_string(),
+            environment_namespace: environment_namespace.to_string(),
+        }),
+        ExternalSecretReference::Vault { .. }
+        | ExternalSecretReference::AwsSecretsManager { .. }
+        | ExternalSecretReference::GcpSecretManager { .. } => match can_use_external_secrets_operator {
+            true => Ok(Resolution::ExternalSecretsOperator),
+            false => Err(ExternalSecretError::ExternalSecretsOperatorRequired {
+                reference: reference.to_string(),
+            }),
+        },
+    }
+}
+
+/// ExternalSecret remote reference, `None` when External Secrets Operator can't pull the secret
+pub fn external_secrets_operator_remote_ref(reference: &ExternalSecretReference) -> Option<ExternalSecretRemoteRef> {
+    match reference {
+        ExternalSecretReference::Vault { mount, path, key } => Some(ExternalSecretRemoteRef {
+            cluster_secret_store: VAULT_CLUSTER_SECRET_STORE.to_string(),
+            key: format!("{mount}/{path}"),
+            version: None,
+            property: Some(key.to_string()),
+        }),
+        ExternalSecretReference::AwsSecretsManager { arn, key } => Some(ExternalSecretRemoteRef {
+            cluster_secret_store: AWS_SECRETS_MANAGER_CLUSTER_SECRET_STORE.to_string(),
+            key: arn.to_string(),
+            version: None,
+            property: key.clone(),
+        }),
+        // the project is the one configured in the ClusterSecretStore
+        ExternalSecretReference::GcpSecretManager {
+            secret, version, key, ..
+        } => Some(ExternalSecretRemoteRef {
+            cluster_secret_store: GCP_SECRET_MANAGER_CLUSTER_SECRET_STORE.to_string(),
+            key: secret.to_string(),
+            version: version.clone(),
+            property: key.clone(),
+        }),
+        // secrets of the environment namespace are copied by the engine, no store is needed
+        ExternalSecretReference::Kubernetes { .. } => None,
+    }
+}
+
+pub async fn is_external_secrets_operator_installed(kube: &kube::Client) -> Result<bool, kube::Error> {
+    let crds: Api<CustomResourceDefinition> = Api::all(kube.clone());
+
+    Ok(crds.get_opt(EXTERNAL_SECRETS_CRD_NAME).await?.is_some())
+}
+
+/// Fetches `key` of a Kubernetes Secret
+pub async fn fetch_kubernetes_secret(
+    kube: &kube::Client,
+    reference: &ExternalSecretReference,
+    namespace: &str,
+    secret_name: &str,
+    key: &str,
+) -> Result<String, ExternalSecretError> {
+    let cannot_fetch = |raw_error_message: String| ExternalSecretError::CannotFetchSecret {
+        reference: reference.to_string(),
+        raw_error_message,
+    };
+    let secrets: Api<Secret> = Api::namespaced(kube.clone(), namespace);
+    let secret = secrets
+        .get_opt(secret_name)
+        .await
+        .map_err(|e| cannot_fetch(e.to_string()))?
+        .ok_or_else(|| ExternalSecretError::NotFound {
+            reference: reference.to_string(),
+        })?;
+
+    let value = secret
+        .data
+        .as_ref()
+        .and_then(|data| data.get(key))
+        .map(|value| value.0.clone())
+        .or_else(|| {
+            secret
+                .string_data
+                .as_ref()
+                .and_then(|data| data.get(key))
+                .map(|value| value.as_bytes().to_vec())
+        })
+        .ok_or_else(|| ExternalSecretError::KeyNotFound {
+            reference: reference.to_string(),
+            key: key.to_string(),
+        })?;
+
+    String::from_utf8(value).map_err(|_| cannot_fetch("value is not valid UTF-8".to_string()))
+}
+
+pub fn has_external_secrets(environment: &EnvironmentRequest) -> bool {
+    environment
+        .applications
+        .iter()
+        .map(|app| &app.environment_vars_with_infos)
+        .chain(
+            environment
+                .containers
+                .iter()
+                .map(|container| &container.environment_vars_with_infos),
+        )
+        .chain(environment.jobs.iter().map(|job| &job.environment_vars_with_infos))
+        .chain(environment.helms.iter().map(|helm| &helm.environment_vars_with_infos))
+        .flat_map(|variables| variables.values())
+        .any(|variable| variable.external_secret.is_some())
+}
+
+/// Resolves the external secrets referenced by the environment variables of the environment.
+/// Applications, containers and jobs secrets are left to External Secrets Operator, so the engine never sees them.
+/// Secrets of the environment namespace are fetched by the engine and inlined as secret variables.
+/// Returns the fetched values, so they can be obfuscated in logs.
+pub fn resolve_external_secrets(
+    environment: &mut EnvironmentRequest,
+    kube: &kube::Client,
+    event_details: &EventDetails,
+) -> Result<Vec<String>, Box<EngineError>> {
+    let is_eso_installed = block_on(is_external_secrets_operator_installed(kube)).unwrap_or_else(|err| {
+        warn!("Cannot check if External Secrets Operator is installed: {}", err);
+        false
+    });
+    let environment_namespace = environment.kube_name.to_string();
+
+    // helm charts values are rendered by the engine, External Secrets Operator can't be used for them
+    let services_variables = environment
+        .applications
+        .iter_mut()
+        .map(|app| (true, &mut app.environment_vars_with_infos))
+        .chain(
+            environment
+                .containers
+                .iter_mut()
+                .map(|container| (true, &mut container.environment_vars_with_infos)),
+        )
+        .chain(
+            environment
+                .jobs
+                .iter_mut()
+                .map(|job| (true, &mut job.environment_vars_with_infos)),
+        )
+        .chain(
+            environment
+                .helms
+                .iter_mut()
+                .map(|helm| (false, &mut helm.environment_vars_with_infos)),
+        );
+
+    let mut secrets = vec![];
+    for (can_use_eso, variables) in services_variables {
+        for (name, variable) in variables.iter_mut() {
+            let Some(reference) = &variable.external_secret else {
+                continue;
+            };
+            let cannot_be_resolved = |err: ExternalSecretError| {
+                Box::new(EngineError::new_external_secret_cannot_be_resolved(
+                    event_details.clone(),
+                    name,
+                    CommandError::new_from_safe_message(err.to_string()),
+                ))
+            };
+
+            let value = match resolution(reference, &environment_namespace, is_eso_installed && can_use_eso)
+                .map_err(cannot_be_resolved)?
+            {
+                Resolution::ExternalSecretsOperator => continue,
+                Resolution::KubernetesSecret => match reference {
+                    ExternalSecretReference::Kubernetes {
+                        namespace,
+                        secret_name,
+                        key,
+                    } => block_on(fetch_kubernetes_secret(kube, reference, namespace, secret_name, key))
+                        .map_err(cannot_be_resolved)?,
+                    _ => unreachable!("only Kubernetes references are resolved from Kubernetes Secrets"),
+                },
+            };
+
+            variable.value = general_purpose::STANDARD.encode(&value);
+            variable.is_secret = true;
+            variable.external_secret = None;
+            secrets.push(value);
+        }
+    }
+
+    Ok(secrets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_external_secrets_operator_remote_ref() {
+        let remote_ref = |reference: &str| {
+            external_secrets_operator_remote_ref(&ExternalSecretReference::from_str(reference).unwrap())
+        };
+
+        assert_eq!(
+            remote_ref("vault://secret/my-app/db#password"),
+            Some(ExternalSecretRemoteRef {
+                cluster_secret_store: "qovery-vault".to_string(),
+                key: "secret/my-app/db".to_string(),
+                version: None,
+                property: Some("password".to_string()),
+            })
+        );
+        assert_eq!(
+            remote_ref("aws-sm://arn:aws:secretsmanager:eu-west-3:123456789012:secret:my-app-token-AbCdEf"),
+            Some(ExternalSecretRemoteRef {
+                cluster_secret_store: "qovery-aws-secrets-manager".to_string(),
+                key: "arn:aws:secretsmanager:eu-west-3:123456789012:secret:my-app-token-AbCdEf".to_string(),
+                version: None,
+                property: None,
+            })
+        );
+        assert_eq!(
+            remote_ref("gcp-sm://projects/my-project/secrets/my-app-db/versions/3#password"),
+            Some(ExternalSecretRemoteRef {
+                cluster_secret_store: "qovery-gcp-secret-manager".to_string(),
+                key: "my-app-db".to_string(),
+                version: Some("3".to_string()),
+                property: Some("password".to_string()),
+            })
+        );
+        assert_eq!(remote_ref("k8s://shared/my-app-db#password"), None);
+    }
+
+    #[test]
+    fn test_resolution() {
+        let resolve = |reference: &str, can_use_eso: bool| {
+            resolution(
+                &ExternalSecretReference::from_str(reference).unwrap(),
+                "z1234-zabcd",
+                can_use_eso,
+            )
+        };
+
+        // kubernetes secrets are only read in the environment namespace
+        assert_eq!(
+            resolve("k8s://z1234-zabcd/my-app-db#password", false),
+            Ok(Resolution::KubernetesSecret)
+        );
+        assert_eq!(
+            resolve("k8s://qovery/engine-secrets#vault_token", true),
+            Err(ExternalSecretError::NamespaceNotAllowed {
+                reference: "k8s://qovery/engine-secrets#vault_token".to_string(),
+                environment_namespace: "z1234-zabcd".to_string(),
+            })
+        );
+        assert!(matches!(
+            resolve("k8s://z5678-zefgh/other-app-db#password", true),
+            Err(ExternalSecretError::NamespaceNotAllowed { .. })
+        ));
+
+        // secret stores are never read with the engine credentials, only through External Secrets Operator
+        assert_eq!(
+            resolve("vault://official-clusters-access/z1234#kubeconfig_b64", false),
+            Err(ExternalSecretError::ExternalSecretsOperatorRequired {
+                reference: "vault://official-clusters-access/z1234#kubeconfig_b64".to_string(),
+            })
+        );
+        assert_eq!(
+            resolve("vault://secret/my-app/db#password", true),
+            Ok(Resolution::ExternalSecretsOperator)
+        );
+        assert!(matches!(
+            resolve(
+                "aws-sm://arn:aws:secretsmanager:eu-west-3:123456789012:secret:my-app-token-AbCdEf",
+                false
+            ),
+            Err(ExternalSecretError::ExternalSecretsOperatorRequired { .. })
+        ));
+        assert_eq!(
+            resolve("gcp-sm://projects/my-project/secrets/my-app-token", true),
+            Ok(Resolution::ExternalSecretsOperator)
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use crate::runtime::block_on;
+use crate::secret_manager::{SecretBackend, SecretBackendError, SecretBackendKind, SecretMetadata};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::ByteString;
+use kube::api::{ListParams, ObjectMeta, PostParams};
+use kube::Api;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+const MOUNT_LABEL: &str = "qovery.com/secret-backend-mount";
+const SECRET_NAME_ANNOTATION: &str = "qovery.com/secret-backend-name";
+const VERSION_ANNOTATION: &str = "qovery.com/secret-backend-version";
+const UPDATED_AT_ANNOTATION: &str = "qovery.com/secret-backend-updated-at";
+const CONTENT_KEY: &str = "content";
+
+/// Stores secrets as Kubernetes Secrets of a management namespace, one Kubernetes Secret per secret
+pub struct KubernetesSecretBackend {
+    client: kube::Client,
+    namespace: String,
+}
+
+impl KubernetesSecretBackend {
+    pub fn new(client: kube::Client, namespace: &str) -> Self {
+        KubernetesSecretBackend {
+            client,
+            namespace: namespace.to_string(),
+        }
+    }
+
+    fn api(&self) -> Api<Secret> {
+        Api::namespaced(self.client.clone(), &self.namespace)
+    }
+}
+
+/// Kubernetes Secret name, only lowercase alphanumeric characters and `-` are allowed
+fn kube_secret_name(mount: &str, secret_name: &str) -> String {
+    format!("{mount}-{secret_name}")
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '-' })
+        .collect()
+}
+
+fn annotation<'a>(secret: &'a Secret, key: &str) -> Option<&'a str> {
+    secret
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(key))
+        .map(|value| value.as_str())
+}
+
+fn secret_metadata(secret: &Secret) -> SecretMetadata {
+    SecretMetadata {
+        name: annotation(secret, SECRET_NAME_ANNOTATION)
+            .or(secret.metadata.name.as_deref())
+            .unwrap_or_default()
+            .to_string(),
+        version: annotation(secret, VERSION_ANNOTATION)
+            .and_then(|version| version.parse().ok())
+            .unwrap_or(1),
+        created_at: secret.metadata.creation_timestamp.as_ref().map(|time| time.0),
+        updated_at: annotation(secret, UPDATED_AT_ANNOTATION)
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| time.with_timezone(&Utc)),
+    }
+}
+
+fn is_not_found(error: &kube::Error) -> bool {
+    matches!(error, kube::Error::Api(api_error) if api_error.code == 404)
+}
+
+impl SecretBackend for KubernetesSecretBackend {
+    fn kind(&self) -> SecretBackendKind {
+        SecretBackendKind::Kubernetes
+    }
+
+    fn get(&self, mount: &str, secret_name: &str) -> Result<Value, SecretBackendError> {
+        let cannot_get = |raw_error_message: String| SecretBackendError::CannotGetSecret {
+            mount: mount.to_string(),
+            secret_name: secret_name.to_string(),
+            raw_error_message,
+        };
+
+        let secret = block_on(self.api().get_opt(&kube_secret_name(mount, secret_name)))
+            .map_err(|e| cannot_get(e.to_string()))?
+            .ok_or_else(|| SecretBackendError::NotFound {
+                mount: mount.to_string(),
+                secret_name: secret_name.to_string(),
+            })?;
+        let content = secret
+            .data
+            .as_ref()
+            .and_then(|data| data.get(CONTENT_KEY))
+            .ok_or_else(|| cannot_get(format!("`{CONTENT_KEY}` key is missing")))?;
+
+        serde_json::from_slice(&content.0).map_err(|e| cannot_get(e.to_string()))
+    }
+
+    fn put(&self, mount: &str, secret_name: &str, content: &Value) -> Result<SecretMetadata, SecretBackendError> {
+        let cannot_put = |raw_error_message: String| SecretBackendError::CannotPutSecret {
+            mount: mount.to_string(),
+            secret_name: secret_name.to_string(),
+            raw_error_message,
+        };
+        let api = self.api();
+        let name = kube_secret_name(mount, secret_name);
+
+        let existing_secret = block_on(api.get_opt(&name)).map_err(|e| cannot_put(e.to_string()))?;
+        let version = existing_secret
+            .as_ref()
+            .map(|secret| secret_metadata(secret).version + 1)
+            .unwrap_or(1);
+        let secret = Secret {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some(self.namespace.to_string()),
+                // concurrent updates are rejected by kubernetes
+                resource_version: existing_secret
+                    .as_ref()
+                    .and_then(|secret| secret.metadata.resource_version.clone()),
+                labels: Some(BTreeMap::from([(MOUNT_LABEL.to_string(), mount.to_string())])),
+                annotations: Some(BTreeMap::from([
+                    (SECRET_NAME_ANNOTATION.to_string(), secret_name.to_string()),
+                    (VERSION_ANNOTATION.to_string(), version.to_string()),
+                    (UPDATED_AT_ANNOTATION.to_string(), Utc::now().to_rfc3339()),
+                ])),
+                ..Default::default()
+            },
+            data: Some(BTreeMap::from([(
+                CONTENT_KEY.to_string(),
+                ByteString(serde_json::to_vec(content).map_err(|e| cannot_put(e.to_string()))?),
+            )])),
+            type_: Some("Opaque".to_string()),
+            ..Default::default()
+        };
+
+        let secret = match existing_secret {
+            Some(_) => block_on(api.replace(&name, &PostParams::default(), &secret)),
+            None => block_on(api.create(&PostParams::default(), &secret)),
+        }
+        .map_err(|e| cannot_put(e.to_string()))?;
+
+        Ok(secret_metadata(&secret))
+    }
+
+    fn delete(&self, mount: &str, secret_name: &str) -> Result<(), SecretBackendError> {
+        match block_on(
+            self.api()
+                .delete(&kube_secret_name(mount, secret_name), &Default::default()),
+        ) {
+            Ok(_) => Ok(()),
+            Err(e) if is_not_found(&e) => Ok(()),
+            Err(e) => Err(SecretBackendError::CannotDeleteSecret {
+                mount: mount.to_string(),
+                secret_name: secret_name.to_string(),
+                raw_error_message: e.to_string(),
+            }),
+        }
+    }
+
+    fn list_metadata(&self, mount: &str) -> Result<Vec<SecretMetadata>, SecretBackendError> {
+        let secrets = block_on(
+            self.api()
+                .list(&ListParams::default().labels(&format!("{MOUNT_LABEL}={mount}"))),
+        )
+        .map_err(|e| SecretBackendError::CannotListSecrets {
+            mount: mount.to_string(),
+            raw_error_message: e.to_string(),
+        })?;
+
+        Ok(secrets.items.iter().map(secret_metadata).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kube_secret_name() {
+        assert_eq!(
+            kube_secret_name("engine-unit-test", "z1234"),
+            "engine-unit-test-z1234".to_string()
+        );
+        assert_eq!(
+            kube_secret_name("official-clusters-access", "Cluster_Id.42"),
+            "official-clusters-access-cluster-id-42".to_string()
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use crate::secret_manager::{SecretBackend, SecretBackendError, SecretBackendKind, SecretMetadata};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+const SECRET_FILE_EXTENSION: &str = "secret";
+// AES-GCM standard nonce size, the nonce is stored in front of the encrypted content
+const NONCE_SIZE_IN_BYTES: usize = 12;
+
+/// Stores secrets in local files encrypted with AES-256-GCM, i.e: for tests and air-gapped installs
+pub struct LocalFileSecretBackend {
+    root_dir: PathBuf,
+    cipher: Aes256Gcm,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SecretFile {
+    version: u64,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    content: Value,
+}
+
+impl LocalFileSecretBackend {
+    /// `key` must be 32 bytes long
+    pub fn new(root_dir: PathBuf, key: &[u8]) -> Result<Self, String> {
+        if key.len() != 32 {
+            return Err(format!("Secret backend key must be 32 bytes long, got {} bytes", key.len()));
+        }
+
+        Ok(LocalFileSecretBackend {
+            root_dir,
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        })
+    }
+
+    fn secret_path(&self, mount: &str, secret_name: &str) -> Result<PathBuf, String> {
+        // mount and secret name must not allow to escape the root directory
+        for part in [mount, secret_name] {
+            if part.is_empty() || part.contains(['/', '\\']) || part.starts_with('.') {
+                return Err(format!("`{part}` is not a valid secret path part"));
+            }
+        }
+
+        Ok(self
+            .root_dir
+            .join(mount)
+            .join(format!("{secret_name}.{SECRET_FILE_EXTENSION}")))
+    }
+
+    fn read(&self, path: &Path) -> Result<SecretFile, String> {
+        let encrypted = fs::read(path).map_err(|e| e.to_string())?;
+        if encrypted.len() < NONCE_SIZE_IN_BYTES {
+            return Err("secret file is truncated".to_string());
+        }
+        let (nonce, encrypted) = encrypted.split_at(NONCE_SIZE_IN_BYTES);
+        let decrypted = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|_| "cannot decrypt secret file, the key may be wrong".to_string())?;
+
+        serde_json::from_slice(&decrypted).map_err(|e| e.to_string())
+    }
+
+    fn write(&self, path: &Path, secret: &SecretFile) -> Result<(), String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let decrypted = serde_json::to_vec(secret).map_err(|e| e.to_string())?;
+        let encrypted = self
+            .cipher
+            .encrypt(&nonce, decrypted.as_slice())
+            .map_err(|_| "cannot encrypt secret".to_string())?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        // written next to the secret then renamed, so a failure never leaves a corrupted secret
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, [nonce.as_slice(), encrypted.as_slice()].concat()).map_err(|e| e.to_string())?;
+        fs::rename(&tmp_path, path).map_err(|e| e.to_string())
+    }
+}
+
+fn secret_metadata(name: &str, secret: &SecretFile) -> SecretMetadata {
+    SecretMetadata {
+        name: name.to_string(),
+        version: secret.version,
+        created_at: Some(secret.created_at),
+        updated_at: Some(secret.updated_at),
+    }
+}
+
+impl SecretBackend for LocalFileSecretBackend {
+    fn kind(&self) -> SecretBackendKind {
+        SecretBackendKind::LocalFile
+    }
+
+    fn get(&self, mount: &str, secret_name: &str) -> Result<Value, SecretBackendError> {
+        let cannot_get = |raw_error_message: String| SecretBackendError::CannotGetSecret {
+            mount: mount.to_string(),
+            secret_name: secret_name.to_string(),
+            raw_error_message,
+        };
+        let path = self.secret_path(mount, secret_name).map_err(cannot_get)?;
+        if !path.exists() {
+            return Err(SecretBackendError::NotFound {
+                mount: mount.to_string(),
+                secret_name: secret_name.to_string(),
+            });
+        }
+
+        Ok(self.read(&path).map_err(cannot_get)?.content)
+    }
+
+    fn put(&self, mount: &str, secret_name: &str, content: &Value) -> Result<SecretMetadata, SecretBackendError> {
+        let cannot_put = |raw_error_message: String| SecretBackendError::CannotPutSecret {
+            mount: mount.to_string(),
+            secret_name: secret_name.to_string(),
+            raw_error_message,
+        };
+        let path = self.secret_path(mount, secret_name).map_err(cannot_put)?;
+
+        let now = Utc::now();
+        let secret = match path.exists() {
+            true => {
+                let previous = self.read(&path).map_err(cannot_put)?;
+                SecretFile {
+                    version: previous.version + 1,
+                    created_at: previous.created_at,
+                    updated_at: now,
+                    content: content.clone(),
+                }
+            }
+            false => SecretFile {
+                version: 1,
+                created_at: now,
+                updated_at: now,
+                content: content.clone(),
+            },
+        };
+        self.write(&path, &secret).map_err(cannot_put)?;
+
+        Ok(secret_metadata(secret_name, &secret))
+    }
+
+    fn delete(&self, mount: &str, secret_name: &str) -> Result<(), SecretBackendError> {
+        let cannot_delete = |raw_error_message: String| SecretBackendError::CannotDeleteSecret {
+            mount: mount.to_string(),
+            secret_name: secret_name.to_string(),
+            raw_error_message,
+        };
+        let path = self.secret_path(mount, secret_name).map_err(cannot_delete)?;
+
+        match fs::remove_file(path) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(cannot_delete(e.to_string())),
+        }
+    }
+
+    fn list_metadata(&self, mount: &str) -> Result<Vec<SecretMetadata>, SecretBackendError> {
+        let cannot_list = |raw_error_message: String| SecretBackendError::CannotListSecrets {
+            mount: mount.to_string(),
+            raw_error_message,
+        };
+        let mount_dir = self.root_dir.join(mount);
+        let entries = match fs::read_dir(&mount_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(cannot_list(e.to_string())),
+        };
+
+        let mut secrets = vec![];
+        for entry in entries {
+            let path = entry.map_err(|e| cannot_list(e.to_string()))?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(SECRET_FILE_EXTENSION) {
+                continue;
+            }
+            let name = path.file_stem().and_then(|name| name.to_str()).unwrap_or_default();
+            let secret = self
+                .read(&path)
+                .map_err(|e| cannot_list(format!("cannot read secret `{name}`: {e}")))?;
+            secrets.push(secret_metadata(name, &secret));
+        }
+        secrets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(secrets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_local_file_secret_backend() {
+        let root_dir = TempDir::new().unwrap();
+        let backend = LocalFileSecretBackend::new(root_dir.path().to_path_buf(), &[42; 32]).unwrap();
+
+        // create and update
+        assert_eq!(
+            backend.get("engine-unit-test", "z1234"),
+            Err(SecretBackendError::NotFound {
+                mount: "engine-unit-test".to_string(),
+                secret_name: "z1234".to_string(),
+            })
+        );
+        let created = backend
+            .put("engine-unit-test", "z1234", &json!({"kubeconfig_b64": "a3ViZWNvbmZpZw=="}))
+            .unwrap();
+        assert_eq!(created.version, 1);
+        let updated = backend
+            .put("engine-unit-test", "z1234", &json!({"kubeconfig_b64": "bmV3"}))
+            .unwrap();
+        assert_eq!(updated.version, 2);
+        assert_eq!(updated.created_at, created.created_at);
+        assert_eq!(backend.get("engine-unit-test", "z1234"), Ok(json!({"kubeconfig_b64": "bmV3"})));
+        backend.put("engine-unit-test", "z5678", &json!({})).unwrap();
+        assert_eq!(
+            backend
+                .list_metadata("engine-unit-test")
+                .unwrap()
+                .iter()
+                .map(|secret| (secret.name.as_str(), secret.version))
+                .collect::<Vec<_>>(),
+            vec![("z1234", 2), ("z5678", 1)]
+        );
+        assert_eq!(backend.list_metadata("official-clusters-access"), Ok(vec![]));
+
+        // content is encrypted, and can't be read with another key
+        let raw_content = fs::read(root_dir.path().join("engine-unit-test").join("z1234.secret")).unwrap();
+        assert!(!String::from_utf8_lossy(&raw_content).contains("bmV3"));
+        let other_backend = LocalFileSecretBackend::new(root_dir.path().to_path_buf(), &[24; 32]).unwrap();
+        assert!(matches!(
+            other_backend.get("engine-unit-test", "z1234"),
+            Err(SecretBackendError::CannotGetSecret { .. })
+        ));
+
+        // delete
+        assert_eq!(backend.delete("engine-unit-test", "z1234"), Ok(()));
+        assert_eq!(backend.delete("engine-unit-test", "z1234"), Ok(()));
+        assert!(matches!(
+            backend.get("engine-unit-test", "z1234"),
+            Err(SecretBackendError::NotFound { .. })
+        ));
+
+        // invalid paths and keys
+        assert!(backend.put("engine-unit-test", "../escape", &json!({})).is_err());
+        assert!(LocalFileSecretBackend::new(root_dir.path().to_path_buf(), &[42; 16]).is_err());
+    }
+}
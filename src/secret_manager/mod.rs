@@ -1 +1,140 @@
+pub mod external_secret;
+pub mod kubernetes;
+pub mod local_file;
 pub mod vault;
+
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::runtime::block_on;
+use crate::secret_manager::kubernetes::KubernetesSecretBackend;
+use crate::secret_manager::local_file::LocalFileSecretBackend;
+use crate::secret_manager::vault::QVaultClient;
+use base64::engine::general_purpose;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const DEFAULT_KUBERNETES_SECRET_BACKEND_NAMESPACE: &str = "qovery";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecretBackendKind {
+    Vault,
+    Kubernetes,
+    LocalFile,
+}
+
+impl FromStr for SecretBackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "vault" => Ok(SecretBackendKind::Vault),
+            "kubernetes" => Ok(SecretBackendKind::Kubernetes),
+            "local_file" => Ok(SecretBackendKind::LocalFile),
+            _ => Err(format!("unknown secret backend `{s}`")),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum SecretBackendError {
+    #[error("Secret `{secret_name}` not found in `{mount}`")]
+    NotFound { mount: String, secret_name: String },
+    #[error("Cannot get secret `{secret_name}` from `{mount}`: {raw_error_message}")]
+    CannotGetSecret {
+        mount: String,
+        secret_name: String,
+        raw_error_message: String,
+    },
+    #[error("Cannot put secret `{secret_name}` in `{mount}`: {raw_error_message}")]
+    CannotPutSecret {
+        mount: String,
+        secret_name: String,
+        raw_error_message: String,
+    },
+    #[error("Cannot delete secret `{secret_name}` from `{mount}`: {raw_error_message}")]
+    CannotDeleteSecret {
+        mount: String,
+        secret_name: String,
+        raw_error_message: String,
+    },
+    #[error("Cannot list secrets of `{mount}`: {raw_error_message}")]
+    CannotListSecrets { mount: String, raw_error_message: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SecretMetadata {
+    pub name: String,
+    pub version: u64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Stores JSON secrets, grouped by mount (i.e: one mount for test clusters, one for official ones)
+pub trait SecretBackend: Send + Sync {
+    fn kind(&self) -> SecretBackendKind;
+    fn get(&self, mount: &str, secret_name: &str) -> Result<Value, SecretBackendError>;
+    /// Creates or replaces the secret, returning the metadata of the new version
+    fn put(&self, mount: &str, secret_name: &str, content: &Value) -> Result<SecretMetadata, SecretBackendError>;
+    fn delete(&self, mount: &str, secret_name: &str) -> Result<(), SecretBackendError>;
+    fn list_metadata(&self, mount: &str) -> Result<Vec<SecretMetadata>, SecretBackendError>;
+}
+
+fn get_env_var(env_var: &str, event_details: &EventDetails) -> Result<String, Box<EngineError>> {
+    env::var(env_var).map_err(|_| {
+        Box::new(EngineError::new_missing_required_env_variable(
+            event_details.clone(),
+            env_var.to_string(),
+        ))
+    })
+}
+
+/// Instantiates the secret backend configured by `SECRET_BACKEND` env var, Vault by default
+pub fn secret_backend_from_env(event_details: EventDetails) -> Result<Box<dyn SecretBackend>, Box<EngineError>> {
+    let invalid_configuration = |message: String| {
+        Box::new(EngineError::new_secret_backend_invalid_configuration(
+            event_details.clone(),
+            CommandError::new_from_safe_message(message),
+        ))
+    };
+    let kind = match env::var("SECRET_BACKEND") {
+        Ok(kind) => SecretBackendKind::from_str(&kind).map_err(invalid_configuration)?,
+        Err(_) => SecretBackendKind::Vault,
+    };
+
+    match kind {
+        SecretBackendKind::Vault => Ok(Box::new(QVaultClient::new(event_details)?)),
+        SecretBackendKind::Kubernetes => {
+            let namespace = env::var("SECRET_BACKEND_KUBERNETES_NAMESPACE")
+                .unwrap_or_else(|_| DEFAULT_KUBERNETES_SECRET_BACKEND_NAMESPACE.to_string());
+            let client = block_on(kube::Client::try_default())
+                .map_err(|e| invalid_configuration(format!("Cannot create kubernetes client: {e}")))?;
+            Ok(Box::new(KubernetesSecretBackend::new(client, &namespace)))
+        }
+        SecretBackendKind::LocalFile => {
+            let root_dir = get_env_var("SECRET_BACKEND_LOCAL_FILE_DIR", &event_details)?;
+            let key = general_purpose::STANDARD
+                .decode(get_env_var("SECRET_BACKEND_LOCAL_FILE_KEY", &event_details)?)
+                .map_err(|e| invalid_configuration(format!("Secret backend key is not valid base64: {e}")))?;
+            Ok(Box::new(
+                LocalFileSecretBackend::new(PathBuf::from(root_dir), &key).map_err(invalid_configuration)?,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_backend_kind_from_str() {
+        assert_eq!(SecretBackendKind::from_str("Vault"), Ok(SecretBackendKind::Vault));
+        assert_eq!(SecretBackendKind::from_str("kubernetes"), Ok(SecretBackendKind::Kubernetes));
+        assert_eq!(SecretBackendKind::from_str("local_file"), Ok(SecretBackendKind::LocalFile));
+        assert!(SecretBackendKind::from_str("consul").is_err());
+    }
+}
@@ -1,8 +1,11 @@
 use crate::errors::{CommandError, EngineError};
 use crate::events::{EventDetails, Transmitter};
 use crate::runtime::block_on;
+use crate::secret_manager::{SecretBackend, SecretBackendError, SecretBackendKind, SecretMetadata};
+use chrono::{DateTime, Utc};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::env;
 use uuid::Uuid;
 use vaultrs::api::kv2::responses::SecretVersionMetadata;
@@ -145,6 +148,87 @@ impl QVaultClient {
     }
 }
 
+fn parse_vault_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+impl SecretBackend for QVaultClient {
+    fn kind(&self) -> SecretBackendKind {
+        SecretBackendKind::Vault
+    }
+
+    fn get(&self, mount: &str, secret_name: &str) -> Result<Value, SecretBackendError> {
+        self.get_secret(mount, secret_name).map_err(|e| match e {
+            ClientError::APIError { code: 404, .. } => SecretBackendError::NotFound {
+                mount: mount.to_string(),
+                secret_name: secret_name.to_string(),
+            },
+            e => SecretBackendError::CannotGetSecret {
+                mount: mount.to_string(),
+                secret_name: secret_name.to_string(),
+                raw_error_message: e.to_string(),
+            },
+        })
+    }
+
+    fn put(&self, mount: &str, secret_name: &str, content: &Value) -> Result<SecretMetadata, SecretBackendError> {
+        let metadata =
+            self.crate_update_secret(mount, secret_name, content)
+                .map_err(|e| SecretBackendError::CannotPutSecret {
+                    mount: mount.to_string(),
+                    secret_name: secret_name.to_string(),
+                    raw_error_message: e.to_string(),
+                })?;
+
+        Ok(SecretMetadata {
+            name: secret_name.to_string(),
+            version: metadata.version,
+            created_at: parse_vault_time(&metadata.created_time),
+            updated_at: parse_vault_time(&metadata.created_time),
+        })
+    }
+
+    fn delete(&self, mount: &str, secret_name: &str) -> Result<(), SecretBackendError> {
+        self.delete_secret(mount, secret_name)
+            .map_err(|e| SecretBackendError::CannotDeleteSecret {
+                mount: mount.to_string(),
+                secret_name: secret_name.to_string(),
+                raw_error_message: e.to_string(),
+            })
+    }
+
+    fn list_metadata(&self, mount: &str) -> Result<Vec<SecretMetadata>, SecretBackendError> {
+        let cannot_list = |e: ClientError| SecretBackendError::CannotListSecrets {
+            mount: mount.to_string(),
+            raw_error_message: e.to_string(),
+        };
+        let secret_names = match block_on(kv2::list(&self.connection, mount, "")) {
+            Ok(secret_names) => secret_names,
+            // Vault answers 404 when there is nothing to list
+            Err(ClientError::APIError { code: 404, .. }) => return Ok(vec![]),
+            Err(e) => return Err(cannot_list(e)),
+        };
+
+        secret_names
+            .into_iter()
+            // sub paths are not secrets
+            .filter(|secret_name| !secret_name.ends_with('/'))
+            .map(|secret_name| {
+                let metadata =
+                    block_on(kv2::read_metadata(&self.connection, mount, &secret_name)).map_err(cannot_list)?;
+                Ok(SecretMetadata {
+                    version: metadata.current_version,
+                    created_at: parse_vault_time(&metadata.created_time),
+                    updated_at: parse_vault_time(&metadata.updated_time),
+                    name: secret_name,
+                })
+            })
+            .collect()
+    }
+}
+
 pub fn get_vault_mount_name(is_test_cluster: bool) -> String {
     match is_test_cluster {
         false => "official-clusters-access",
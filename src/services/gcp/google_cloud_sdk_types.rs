@@ -3,15 +3,18 @@ use crate::container_registry::{DockerImage, Repository};
 use crate::models::gcp::io::JsonCredentials as JsonCredentialsIo;
 use crate::models::gcp::{CredentialsError, JsonCredentials};
 use crate::models::ToCloudProviderFormat;
-use crate::object_storage::{Bucket, BucketRegion};
+use crate::object_storage::{
+    Bucket, BucketLifecycleAction, BucketLifecycleRule, BucketObjectLock, BucketObjectLockMode, BucketRegion,
+};
 use crate::runtime::block_on;
 use crate::services::gcp::object_storage_regions::GcpStorageRegion;
 use google_cloud_auth::credentials::CredentialsFile;
 use google_cloud_googleapis::devtools::artifact_registry::v1::{
     DockerImage as GcpDockerImage, Package as GcpPackage, Repository as GcpRepository,
 };
-use google_cloud_storage::http::buckets::lifecycle::rule::ActionType;
-use google_cloud_storage::http::buckets::Bucket as GcpBucket;
+use google_cloud_storage::http::buckets::lifecycle::rule::{Action, ActionType, Condition};
+use google_cloud_storage::http::buckets::lifecycle::Rule as GcpLifecycleRule;
+use google_cloud_storage::http::buckets::{Bucket as GcpBucket, RetentionPolicyCreationConfig};
 use regex::Regex;
 use std::str::FromStr;
 use std::time::Duration;
@@ -44,31 +47,137 @@ impl TryFrom<GcpBucket> for Bucket {
             Ok(r) => r,
             Err(e) => return Err(e),
         };
+        let gcp_rules = gcp_bucket
+            .lifecycle
+            .as_ref()
+            .map(|lifecycle| lifecycle.rule.as_slice())
+            .unwrap_or_default();
 
         Ok(Bucket {
             name: gcp_bucket.name,
-            ttl: match &gcp_bucket.lifecycle {
-                Some(lifecycle) => lifecycle
-                    .rule
-                    .iter()
-                    .find(|r| match &r.action {
-                        Some(action) => action.r#type == ActionType::Delete,
-                        _ => false,
-                    })
-                    .and_then(|r| r.condition.clone())
-                    .map(|c| Duration::from_secs(c.age as u64 * 60 * 60 * 24)),
-                None => None,
-            },
+            ttl: gcp_rules
+                .iter()
+                .find(|r| is_bucket_ttl_rule(r))
+                .and_then(|r| r.condition.clone())
+                .map(|c| Duration::from_secs(c.age as u64 * 60 * 60 * 24)),
             versioning_activated: match gcp_bucket.versioning {
                 None => false,
                 Some(v) => v.enabled,
             },
             location: BucketRegion::GcpRegion(gcp_storage_region.clone()),
             labels: gcp_bucket.labels,
+            lifecycle_rules: gcp_rules
+                .iter()
+                .filter(|r| !is_bucket_ttl_rule(r))
+                .filter_map(from_gcp_lifecycle_rule)
+                .collect(),
+            object_lock: gcp_bucket.retention_policy.map(|retention_policy| BucketObjectLock {
+                // a locked retention policy can't be removed nor shortened, as the compliance mode
+                mode: match retention_policy.is_locked {
+                    Some(true) => BucketObjectLockMode::Compliance,
+                    _ => BucketObjectLockMode::Governance,
+                },
+                retention_days: (retention_policy.retention_period / (60 * 60 * 24)) as u32,
+            }),
         })
     }
 }
 
+/// Bucket TTL is a delete rule applying to all the live objects of the bucket
+pub fn new_gcp_bucket_ttl_rule(ttl_in_days: i32) -> GcpLifecycleRule {
+    GcpLifecycleRule {
+        action: Some(Action {
+            r#type: ActionType::Delete,
+            storage_class: None,
+        }),
+        condition: Some(Condition {
+            age: ttl_in_days,
+            ..Default::default()
+        }),
+    }
+}
+
+fn is_bucket_ttl_rule(rule: &GcpLifecycleRule) -> bool {
+    match (&rule.action, &rule.condition) {
+        (Some(action), Some(condition)) => {
+            action.r#type == ActionType::Delete
+                && condition
+                    .matches_prefix
+                    .as_ref()
+                    .map_or(true, |prefixes| prefixes.is_empty())
+                && condition.days_since_noncurrent_time.is_none()
+        }
+        _ => false,
+    }
+}
+
+pub fn to_gcp_lifecycle_rule(rule: &BucketLifecycleRule) -> GcpLifecycleRule {
+    let (action_type, storage_class, age, days_since_noncurrent_time) = match &rule.action {
+        BucketLifecycleAction::ExpireAfterDays(days) => (ActionType::Delete, None, *days, None),
+        BucketLifecycleAction::TransitionAfterDays { days, storage_class } => {
+            (ActionType::SetStorageClass, Some(storage_class.to_string()), *days, None)
+        }
+        BucketLifecycleAction::AbortIncompleteMultipartUploadAfterDays(days) => {
+            (ActionType::AbortIncompleteMultipartUpload, None, *days, None)
+        }
+        BucketLifecycleAction::ExpireNoncurrentVersionsAfterDays(days) => (ActionType::Delete, None, 0, Some(*days)),
+    };
+
+    GcpLifecycleRule {
+        action: Some(Action {
+            r#type: action_type,
+            storage_class,
+        }),
+        condition: Some(Condition {
+            age: age as i32,
+            days_since_noncurrent_time: days_since_noncurrent_time.map(|days| days as i32),
+            matches_prefix: rule.prefix.as_ref().map(|prefix| vec![prefix.to_string()]),
+            ..Default::default()
+        }),
+    }
+}
+
+fn from_gcp_lifecycle_rule(gcp_rule: &GcpLifecycleRule) -> Option<BucketLifecycleRule> {
+    let action = gcp_rule.action.as_ref()?;
+    let condition = gcp_rule.condition.as_ref()?;
+    let days = condition.age.max(0) as u32;
+
+    Some(BucketLifecycleRule {
+        // rules with several prefixes are not created by the engine, only the first one is reported
+        prefix: condition
+            .matches_prefix
+            .as_ref()
+            .and_then(|prefixes| prefixes.first().cloned()),
+        action: match (&action.r#type, condition.days_since_noncurrent_time) {
+            (ActionType::Delete, Some(noncurrent_days)) => {
+                BucketLifecycleAction::ExpireNoncurrentVersionsAfterDays(noncurrent_days.max(0) as u32)
+            }
+            (ActionType::Delete, None) => BucketLifecycleAction::ExpireAfterDays(days),
+            (ActionType::SetStorageClass, _) => BucketLifecycleAction::TransitionAfterDays {
+                days,
+                storage_class: action.storage_class.clone()?,
+            },
+            (ActionType::AbortIncompleteMultipartUpload, _) => {
+                BucketLifecycleAction::AbortIncompleteMultipartUploadAfterDays(days)
+            }
+        },
+    })
+}
+
+/// Object lock is a bucket retention policy on GCS. Locking it is irreversible (it can't be removed anymore,
+/// even if the bucket is deleted), so only the governance mode, being an unlocked retention policy, is supported.
+pub fn to_gcp_retention_policy(object_lock: &BucketObjectLock) -> Result<RetentionPolicyCreationConfig, String> {
+    match object_lock.mode {
+        BucketObjectLockMode::Governance => Ok(RetentionPolicyCreationConfig {
+            retention_period: object_lock.retention_days as u64 * 60 * 60 * 24,
+        }),
+        BucketObjectLockMode::Compliance => Err(
+            "compliance object lock mode is not supported on GCS, retention policy has to be locked manually"
+                .to_string(),
+        ),
+    }
+}
+
 // TODO(ENG-1811): stick a test
 pub fn from_gcp_repository(
     project_id: &str,
@@ -191,3 +300,61 @@ impl TryFrom<GcpPackage> for DockerImage {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcp_lifecycle_rules_conversion() {
+        let rules = vec![
+            BucketLifecycleRule {
+                prefix: Some("logs/".to_string()),
+                action: BucketLifecycleAction::ExpireAfterDays(30),
+            },
+            BucketLifecycleRule {
+                prefix: None,
+                action: BucketLifecycleAction::TransitionAfterDays {
+                    days: 7,
+                    storage_class: "COLDLINE".to_string(),
+                },
+            },
+            BucketLifecycleRule {
+                prefix: None,
+                action: BucketLifecycleAction::AbortIncompleteMultipartUploadAfterDays(1),
+            },
+            BucketLifecycleRule {
+                prefix: None,
+                action: BucketLifecycleAction::ExpireNoncurrentVersionsAfterDays(90),
+            },
+        ];
+
+        let gcp_rules = rules.iter().map(to_gcp_lifecycle_rule).collect::<Vec<_>>();
+        assert!(gcp_rules.iter().all(|r| !is_bucket_ttl_rule(r)));
+        assert_eq!(gcp_rules.iter().filter_map(from_gcp_lifecycle_rule).collect::<Vec<_>>(), rules);
+
+        // an expiration of the whole bucket is its TTL
+        assert!(is_bucket_ttl_rule(&new_gcp_bucket_ttl_rule(7)));
+        assert!(is_bucket_ttl_rule(&to_gcp_lifecycle_rule(&BucketLifecycleRule {
+            prefix: None,
+            action: BucketLifecycleAction::ExpireAfterDays(7),
+        })));
+    }
+
+    #[test]
+    fn test_gcp_retention_policy() {
+        assert_eq!(
+            to_gcp_retention_policy(&BucketObjectLock {
+                mode: BucketObjectLockMode::Governance,
+                retention_days: 2,
+            })
+            .map(|retention_policy| retention_policy.retention_period),
+            Ok(2 * 24 * 60 * 60)
+        );
+        assert!(to_gcp_retention_policy(&BucketObjectLock {
+            mode: BucketObjectLockMode::Compliance,
+            retention_days: 2,
+        })
+        .is_err());
+    }
+}
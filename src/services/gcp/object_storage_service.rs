@@ -1,27 +1,30 @@
 use crate::cloud_provider::gcp::locations::GcpRegion as GcpCloudJobRegion;
 use crate::models::gcp::JsonCredentials;
 use crate::models::ToCloudProviderFormat;
-use crate::object_storage::{Bucket, BucketObject};
+use crate::object_storage::{Bucket, BucketLifecyclePolicy, BucketObject, ObjectListing, ObjectSummary};
 use crate::runtime::block_on;
 use crate::services::gcp::cloud_job_service::CloudJobService;
-use crate::services::gcp::google_cloud_sdk_types::new_gcp_credentials_file_from_credentials;
+use crate::services::gcp::google_cloud_sdk_types::{
+    new_gcp_bucket_ttl_rule, new_gcp_credentials_file_from_credentials, to_gcp_lifecycle_rule, to_gcp_retention_policy,
+};
 use crate::services::gcp::object_storage_regions::GcpStorageRegion;
 use google_cloud_storage::client::{Client, ClientConfig};
 use google_cloud_storage::http::buckets::delete::DeleteBucketRequest;
 use google_cloud_storage::http::buckets::get::GetBucketRequest;
 use google_cloud_storage::http::buckets::insert::{BucketCreationConfig, InsertBucketParam, InsertBucketRequest};
-use google_cloud_storage::http::buckets::lifecycle::rule::{Action, ActionType, Condition};
-use google_cloud_storage::http::buckets::lifecycle::Rule;
 use google_cloud_storage::http::buckets::list::ListBucketsRequest;
 use google_cloud_storage::http::buckets::patch::{BucketPatchConfig, PatchBucketRequest};
 use google_cloud_storage::http::buckets::Lifecycle;
 use google_cloud_storage::http::buckets::{Bucket as GcpBucket, Versioning};
+use google_cloud_storage::http::objects::copy::CopyObjectRequest;
 use google_cloud_storage::http::objects::delete::DeleteObjectRequest;
 use google_cloud_storage::http::objects::download::Range;
 use google_cloud_storage::http::objects::get::GetObjectRequest;
 use google_cloud_storage::http::objects::list::ListObjectsRequest;
 use google_cloud_storage::http::objects::upload::{UploadObjectRequest, UploadType};
 use google_cloud_storage::http::objects::Object as GcpObject;
+use google_cloud_storage::http::resumable_upload_client::{ChunkSize, UploadStatus};
+use google_cloud_storage::sign::{SignedURLMethod, SignedURLOptions};
 use governor::middleware::NoOpMiddleware;
 use governor::state::{InMemoryState, NotKeyed};
 use governor::{clock, RateLimiter};
@@ -34,6 +37,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
+use url::Url;
 
 #[derive(Clone, Error, Debug, PartialEq, Eq)]
 pub enum ObjectStorageServiceError {
@@ -84,6 +88,21 @@ pub enum ObjectStorageServiceError {
         bucket_name: String,
         raw_error_message: String,
     },
+    #[error(
+        "Cannot copy object `{source_object_key}` to `{object_key}` in bucket `{bucket_name}`: {raw_error_message:?}"
+    )]
+    CannotCopyObject {
+        source_object_key: String,
+        object_key: String,
+        bucket_name: String,
+        raw_error_message: String,
+    },
+    #[error("Cannot sign url for object `{object_key}` in bucket `{bucket_name}`: {raw_error_message:?}")]
+    CannotSignUrl {
+        object_key: String,
+        bucket_name: String,
+        raw_error_message: String,
+    },
     #[error("Cannot proceed, admission control blocked after several tries")]
     AdmissionControlCannotProceedAfterSeveralTries,
 }
@@ -101,11 +120,37 @@ impl ObjectStorageServiceError {
             ObjectStorageServiceError::CannotListObjects { raw_error_message, .. } => raw_error_message,
             ObjectStorageServiceError::CannotPutObjectToBucket { raw_error_message, .. } => raw_error_message,
             ObjectStorageServiceError::CannotGetObject { raw_error_message, .. } => raw_error_message,
+            ObjectStorageServiceError::CannotCopyObject { raw_error_message, .. } => raw_error_message,
+            ObjectStorageServiceError::CannotSignUrl { raw_error_message, .. } => raw_error_message,
             ObjectStorageServiceError::AdmissionControlCannotProceedAfterSeveralTries => "".to_string(),
         }
     }
 }
 
+/// Bucket TTL rule followed by the lifecycle policy rules
+fn bucket_lifecycle(
+    bucket_ttl: Option<Duration>,
+    bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
+) -> Result<Lifecycle, String> {
+    let mut rules = vec![];
+    if let Some(ttl) = bucket_ttl {
+        // Minimal TTL is 1 day for Google storage
+        let ttl_in_days = max(ttl, Duration::from_secs(60 * 60 * 24)).as_secs() / 60 / 60 / 24;
+        let bucket_ttl_max_age = i32::try_from(ttl_in_days).map_err(|_e| {
+            format!(
+                "Cannot convert bucket TTL value `{}` to fit i32 as required by Google API",
+                ttl_in_days
+            )
+        })?;
+        rules.push(new_gcp_bucket_ttl_rule(bucket_ttl_max_age));
+    }
+    if let Some(lifecycle_policy) = bucket_lifecycle_policy {
+        rules.extend(lifecycle_policy.rules.iter().map(to_gcp_lifecycle_rule));
+    }
+
+    Ok(Lifecycle { rule: rules })
+}
+
 enum StorageResourceKind {
     Bucket,
     Object,
@@ -212,6 +257,7 @@ impl ObjectStorageService {
         bucket_ttl: Option<Duration>,
         bucket_versioning_activated: bool,
         bucket_labels: Option<HashMap<String, String>>,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
     ) -> Result<Bucket, ObjectStorageServiceError> {
         // Minimal TTL is 1 day for Google storage
         let bucket_ttl = bucket_ttl.map(|ttl| max(ttl, Duration::from_secs(60 * 60 * 24)));
@@ -242,29 +288,19 @@ impl ObjectStorageService {
             },
         };
 
-        if let Some(ttl) = bucket_ttl {
-            let bucket_ttl_max_age = i32::try_from(ttl.as_secs() / 60 / 60 / 24).map_err(|_e| {
-                ObjectStorageServiceError::CannotCreateBucket {
-                    bucket_name: bucket_name.to_string(),
-                    raw_error_message: format!(
-                        "Cannot convert bucket TTL value `{}` to fit i32 as required by Google API",
-                        ttl.as_secs() / 60 / 60 / 24
-                    ),
-                }
-            })?;
-            create_bucket_request.bucket.lifecycle = Some(Lifecycle {
-                rule: vec![Rule {
-                    action: Some(Action {
-                        r#type: ActionType::Delete,
-                        storage_class: None,
-                    }),
-                    condition: Some(Condition {
-                        age: bucket_ttl_max_age,
-                        ..Default::default()
-                    }),
-                }],
-            });
+        let create_error = |raw_error_message: String| ObjectStorageServiceError::CannotCreateBucket {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message,
+        };
+        let lifecycle = bucket_lifecycle(bucket_ttl, bucket_lifecycle_policy).map_err(create_error)?;
+        if !lifecycle.rule.is_empty() {
+            create_bucket_request.bucket.lifecycle = Some(lifecycle);
         }
+        create_bucket_request.bucket.retention_policy = bucket_lifecycle_policy
+            .and_then(|lifecycle_policy| lifecycle_policy.object_lock.as_ref())
+            .map(to_gcp_retention_policy)
+            .transpose()
+            .map_err(create_error)?;
 
         self.wait_for_a_slot_in_admission_control(Duration::from_secs(10 * 60), StorageResourceKind::Bucket)?;
         match block_on(self.client.insert_bucket(&create_bucket_request)) {
@@ -281,19 +317,39 @@ impl ObjectStorageService {
         }
     }
 
+    /// Update the bucket versioning, and replace its lifecycle rules by the policy ones if any.
+    /// `bucket_ttl` is the current bucket TTL, kept as a lifecycle rule when rules are replaced.
     pub fn update_bucket(
         &self,
         bucket_name: &str,
         bucket_versioning_activated: bool,
+        bucket_ttl: Option<Duration>,
+        bucket_lifecycle_policy: Option<&BucketLifecyclePolicy>,
     ) -> Result<Bucket, ObjectStorageServiceError> {
+        let update_error = |raw_error_message: String| ObjectStorageServiceError::CannotUpdateBucket {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message,
+        };
+        let mut bucket_patch_config = BucketPatchConfig {
+            versioning: Some(Versioning {
+                enabled: bucket_versioning_activated,
+            }),
+            ..Default::default()
+        };
+        if let Some(lifecycle_policy) = bucket_lifecycle_policy {
+            bucket_patch_config.lifecycle =
+                Some(bucket_lifecycle(bucket_ttl, Some(lifecycle_policy)).map_err(update_error)?);
+            bucket_patch_config.retention_policy = lifecycle_policy
+                .object_lock
+                .as_ref()
+                .map(to_gcp_retention_policy)
+                .transpose()
+                .map_err(update_error)?;
+        }
+
         let patch_bucket_request = PatchBucketRequest {
             bucket: bucket_name.to_string(),
-            metadata: Some(BucketPatchConfig {
-                versioning: Some(Versioning {
-                    enabled: bucket_versioning_activated,
-                }),
-                ..Default::default()
-            }),
+            metadata: Some(bucket_patch_config),
             ..Default::default()
         };
 
@@ -588,4 +644,190 @@ impl ObjectStorageService {
 
         Ok(objects)
     }
+
+    /// List a single page of objects, without fetching their content
+    pub fn list_objects_page(
+        &self,
+        bucket_name: &str,
+        object_id_prefix: Option<&str>,
+        page_token: Option<&str>,
+    ) -> Result<ObjectListing, ObjectStorageServiceError> {
+        match block_on(self.client.list_objects(&ListObjectsRequest {
+            page_token: page_token.map(str::to_string),
+            bucket: bucket_name.to_string(),
+            prefix: object_id_prefix.map(str::to_string),
+            max_results: Some(1000),
+            ..Default::default()
+        })) {
+            Ok(objects_list_response) => Ok(ObjectListing {
+                objects: objects_list_response
+                    .items
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|object| ObjectSummary {
+                        key: object.name,
+                        size_in_bytes: object.size.max(0) as u64,
+                        last_modified: object
+                            .updated
+                            .and_then(|updated| DateTime::from_timestamp(updated.unix_timestamp(), 0)),
+                    })
+                    .collect(),
+                continuation_token: objects_list_response.next_page_token,
+            }),
+            Err(e) => Err(ObjectStorageServiceError::CannotListObjects {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: e.to_string(),
+            }),
+        }
+    }
+
+    pub fn get_object_size(&self, bucket_name: &str, object_key: &str) -> Result<u64, ObjectStorageServiceError> {
+        block_on(self.client.get_object(&GetObjectRequest {
+            bucket: bucket_name.to_string(),
+            object: object_key.to_string(),
+            ..Default::default()
+        }))
+        .map(|object| object.size.max(0) as u64)
+        .map_err(|e| ObjectStorageServiceError::CannotGetObject {
+            bucket_name: bucket_name.to_string(),
+            object_key: object_key.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
+    /// Download bytes from `first_byte` to `last_byte` (included) of an object
+    pub fn get_object_range(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        first_byte: u64,
+        last_byte: u64,
+    ) -> Result<Vec<u8>, ObjectStorageServiceError> {
+        block_on(self.client.download_object(
+            &GetObjectRequest {
+                bucket: bucket_name.to_string(),
+                object: object_key.to_string(),
+                ..Default::default()
+            },
+            &Range(Some(first_byte), Some(last_byte)),
+        ))
+        .map_err(|e| ObjectStorageServiceError::CannotGetObject {
+            bucket_name: bucket_name.to_string(),
+            object_key: object_key.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
+    /// Start a resumable upload, returns its session url to be given to `upload_chunk`
+    pub fn start_resumable_upload(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+    ) -> Result<String, ObjectStorageServiceError> {
+        self.wait_for_a_slot_in_admission_control(Duration::from_secs(10 * 60), StorageResourceKind::Object)?;
+        block_on(self.client.prepare_resumable_upload(
+            &UploadObjectRequest {
+                bucket: bucket_name.to_string(),
+                ..Default::default()
+            },
+            &UploadType::Multipart(Box::new(GcpObject {
+                name: object_key.to_string(),
+                ..Default::default()
+            })),
+        ))
+        .map(|upload_client| upload_client.url().to_string())
+        .map_err(|e| ObjectStorageServiceError::CannotPutObjectToBucket {
+            bucket_name: bucket_name.to_string(),
+            object_key: object_key.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
+    /// Upload a chunk of a resumable upload, all chunks but the last one should be a multiple of 256KiB.
+    /// `total_size` is only known, and has to be set, when sending the last chunk.
+    pub fn upload_chunk(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        upload_url: &str,
+        chunk: Vec<u8>,
+        first_byte: u64,
+        total_size: Option<u64>,
+    ) -> Result<(), ObjectStorageServiceError> {
+        let upload_error = |raw_error_message: String| ObjectStorageServiceError::CannotPutObjectToBucket {
+            bucket_name: bucket_name.to_string(),
+            object_key: object_key.to_string(),
+            raw_error_message,
+        };
+        let last_byte = first_byte + chunk.len() as u64 - 1;
+        let upload_client = self.client.get_resumable_upload(upload_url.to_string());
+        match block_on(upload_client.upload_multiple_chunk(chunk, &ChunkSize::new(first_byte, last_byte, total_size))) {
+            Ok(UploadStatus::Ok(_)) | Ok(UploadStatus::ResumeIncomplete(_)) => Ok(()),
+            Ok(UploadStatus::NotStarted) => Err(upload_error(format!(
+                "chunk starting at byte {first_byte} has not been taken into account"
+            ))),
+            Err(e) => Err(upload_error(e.to_string())),
+        }
+    }
+
+    pub fn cancel_resumable_upload(&self, upload_url: &str) {
+        let upload_client = self.client.get_resumable_upload(upload_url.to_string());
+        if let Err(e) = block_on(upload_client.cancel()) {
+            warn!("Cannot cancel resumable upload: {}", e);
+        }
+    }
+
+    pub fn copy_object(
+        &self,
+        source_bucket_name: &str,
+        source_object_key: &str,
+        target_bucket_name: &str,
+        target_object_key: &str,
+    ) -> Result<(), ObjectStorageServiceError> {
+        self.wait_for_a_slot_in_admission_control(Duration::from_secs(10 * 60), StorageResourceKind::Object)?;
+        block_on(self.client.copy_object(&CopyObjectRequest {
+            destination_bucket: target_bucket_name.to_string(),
+            destination_object: target_object_key.to_string(),
+            source_bucket: source_bucket_name.to_string(),
+            source_object: source_object_key.to_string(),
+            ..Default::default()
+        }))
+        .map(|_| ())
+        .map_err(|e| ObjectStorageServiceError::CannotCopyObject {
+            source_object_key: format!("{source_bucket_name}/{source_object_key}"),
+            object_key: target_object_key.to_string(),
+            bucket_name: target_bucket_name.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
+    /// V4 signed url, signed with the service account private key
+    pub fn signed_url(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        method: SignedURLMethod,
+        expires_in: Duration,
+    ) -> Result<Url, ObjectStorageServiceError> {
+        let sign_error = |raw_error_message: String| ObjectStorageServiceError::CannotSignUrl {
+            object_key: object_key.to_string(),
+            bucket_name: bucket_name.to_string(),
+            raw_error_message,
+        };
+
+        let url = block_on(self.client.signed_url(
+            bucket_name,
+            object_key,
+            None,
+            None,
+            SignedURLOptions {
+                method,
+                expires: expires_in,
+                ..Default::default()
+            },
+        ))
+        .map_err(|e| sign_error(e.to_string()))?;
+
+        Url::parse(&url).map_err(|e| sign_error(e.to_string()))
+    }
 }
@@ -292,6 +292,7 @@ fn postgresql_deploy_a_working_environment_and_redeploy() {
             database_instance_type: None,
             annotations_group_ids: btreeset! {},
             labels_group_ids: btreeset! {},
+            previous_password: None,
         }];
         environment.applications = environment
             .applications
@@ -311,11 +312,11 @@ fn postgresql_deploy_a_working_environment_and_redeploy() {
                     additional_service: None,
                 }];
                 app.environment_vars_with_infos = btreemap! {
-                     "PG_DBNAME".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode(database_db_name.clone()), is_secret: false},
-                     "PG_HOST".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode(database_host.clone()), is_secret: false},
-                     "PG_PORT".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret: false},
-                     "PG_USERNAME".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode(database_username.clone()), is_secret: false},
-                     "PG_PASSWORD".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode(database_password.clone()), is_secret: false},
+                     "PG_DBNAME".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode(database_db_name.clone()), is_secret: false, external_secret: None},
+                     "PG_HOST".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode(database_host.clone()), is_secret: false, external_secret: None},
+                     "PG_PORT".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret: false, external_secret: None},
+                     "PG_USERNAME".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode(database_username.clone()), is_secret: false, external_secret: None},
+                     "PG_PASSWORD".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode(database_password.clone()), is_secret: false, external_secret: None},
                 };
                 app.readiness_probe = Some(Probe {
                     r#type: ProbeType::Tcp { host: None },
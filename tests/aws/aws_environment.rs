@@ -1638,7 +1638,7 @@ fn deploy_container_with_no_router_and_affinitiy_on_aws_eks() {
                 },
             ],
             storages: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo { value: general_purpose::STANDARD.encode("my_value"), is_secret: false}},
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo { value: general_purpose::STANDARD.encode("my_value"), is_secret: false, external_secret: None}},
             mounted_files: vec![],
             readiness_probe: Some(Probe {
                 r#type: ProbeType::Tcp { host: None },
@@ -1850,7 +1850,7 @@ fn deploy_container_with_no_router_on_aws_eks() {
                 },
             ],
             storages: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo { value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo { value: general_purpose::STANDARD.encode("my_value"), is_secret: false, external_secret: None} },
             mounted_files: vec![],
             readiness_probe: Some(Probe {
                 r#type: ProbeType::Tcp { host: None },
@@ -2134,7 +2134,7 @@ fn deploy_container_on_aws_eks_with_mounted_files_as_volume() {
                 failure_threshold: 5,
             }),
             storages: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false, external_secret: None} },
             mounted_files: vec![mounted_file.clone()],
             advanced_settings: Default::default(),
             annotations_group_ids: btreeset! {},
@@ -2292,7 +2292,7 @@ fn deploy_container_with_router_on_aws_eks() {
                 failure_threshold: 5,
             }),
             storages: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret:false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret:false, external_secret: None} },
             mounted_files: vec![],
             advanced_settings: Default::default(),
             annotations_group_ids: btreeset! { annotations_group_id },
@@ -3040,7 +3040,7 @@ fn test_restart_deployment() {
             }),
             storages: vec![],
             mounted_files: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false, external_secret: None} },
             advanced_settings: Default::default(),
             annotations_group_ids: btreeset! {},
             labels_group_ids: btreeset! {},
@@ -3175,7 +3175,7 @@ fn test_restart_statefulset() {
                 success_threshold: 1,
                 failure_threshold: 5,
             }),
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false, external_secret: None} },
             advanced_settings: Default::default(),
             annotations_group_ids: btreeset! {},
             labels_group_ids: btreeset! {},
@@ -3593,7 +3593,7 @@ fn deploy_container_with_udp_tcp_public_ports() {
                 },
             ],
             storages: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret: false, external_secret: None} },
             mounted_files: vec![],
             readiness_probe: Some(Probe {
                 r#type: ProbeType::Tcp { host: None },
@@ -3719,7 +3719,7 @@ fn deploy_helm_chart() {
             command_args: vec!["--install".to_string()],
             timeout_sec: 60,
             allow_cluster_wide_resources: false,
-            environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false, external_secret: None} },
             advanced_settings: Default::default(),
             ports: vec![],
         }];
@@ -3795,7 +3795,7 @@ fn deploy_helm_chart_twice_to_check_admission_controller_config_map_is_well_crea
             command_args: vec!["--install".to_string()],
             timeout_sec: 60,
             allow_cluster_wide_resources: false,
-            environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false, external_secret: None} },
             advanced_settings: Default::default(),
             ports: vec![],
         }];
@@ -3865,7 +3865,7 @@ fn deploy_helm_chart_twice_to_check_admission_controller_config_map_is_well_crea
             command_args: vec!["--install".to_string()],
             timeout_sec: 60,
             allow_cluster_wide_resources: false,
-            environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false, external_secret: None} },
             advanced_settings: Default::default(),
             ports: vec![],
         }];
@@ -3965,7 +3965,7 @@ fn deploy_helm_chart_and_pause_it() {
                 command_args: vec!["--install".to_string()],
                 timeout_sec: 60,
                 allow_cluster_wide_resources,
-                environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false} },
+                environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false, external_secret: None} },
                 advanced_settings: Default::default(),
                 ports: vec![],
             }];
@@ -4072,7 +4072,7 @@ fn deploy_helm_chart_and_restart_it() {
                 command_args: vec!["--install".to_string()],
                 timeout_sec: 60,
                 allow_cluster_wide_resources,
-                environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false} },
+                environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false, external_secret: None} },
                 advanced_settings: Default::default(),
                 ports: vec![],
             }];
@@ -4159,7 +4159,7 @@ fn deploy_helm_chart_with_router() {
             command_args: vec![],
             timeout_sec: 60,
             allow_cluster_wide_resources: true,
-            environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "TOTO".to_string() => VariableInfo {value: "Salut".to_string(), is_secret: false, external_secret: None} },
             advanced_settings: Default::default(),
             ports: vec![
                 Port {
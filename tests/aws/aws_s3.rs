@@ -41,6 +41,7 @@ fn test_delete_hard_strategy_bucket() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
                 false,
+                None,
             )
             .unwrap_or_else(|_| {
                 panic!("error while creating S3 bucket in `{}`", aws_region.to_cloud_provider_format())
@@ -98,6 +99,7 @@ fn test_delete_empty_strategy_bucket() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
                 false,
+                None,
             )
             .unwrap_or_else(|_| {
                 panic!("error while creating S3 bucket in `{}`", aws_region.to_cloud_provider_format())
@@ -149,6 +151,7 @@ fn test_create_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
             false,
+            None,
         );
 
         // validate:
@@ -201,6 +204,7 @@ fn test_get_bucket() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
                 false,
+                None,
             )
             .expect("Cannot create bucket");
 
@@ -248,6 +252,7 @@ fn test_recreate_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
             false,
+            None,
         );
         assert!(create_result.is_ok());
         assert!(aws_os.bucket_exists(bucket_name.as_str()));
@@ -268,6 +273,7 @@ fn test_recreate_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
             false,
+            None,
         );
         assert!(recreate_result.is_ok());
         // retry to check if bucket exists, there is a lag / cache after bucket deletion
@@ -320,6 +326,7 @@ fn test_put_file() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
                 false,
+                None,
             )
             .expect("error while creating object-storage bucket");
 
@@ -378,6 +385,7 @@ fn test_get_file() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(AWS_RESOURCE_TTL_IN_SECONDS.into())),
                 false,
+                None,
             )
             .expect("error while creating object-storage bucket");
 
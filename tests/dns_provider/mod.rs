@@ -0,0 +1,4 @@
+#[cfg(feature = "test-local-docker")]
+mod rfc2136;
+#[cfg(feature = "test-local-docker")]
+mod route53;
@@ -0,0 +1,62 @@
+use crate::helpers::bind::{init_bind_testcontainer, BIND_TSIG_KEY_NAME, BIND_TSIG_SECRET, BIND_ZONE};
+use crate::helpers::utilities::{context_for_resource, engine_run_test, generate_id, init};
+use function_name::named;
+use qovery_engine::dns_provider::errors::DnsProviderError;
+use qovery_engine::dns_provider::rfc2136::{Rfc2136, Rfc2136TsigAlgorithm};
+use qovery_engine::dns_provider::DnsProvider;
+use qovery_engine::models::domain::Domain;
+use tracing::{span, Level};
+use uuid::Uuid;
+
+#[cfg(feature = "test-local-docker")]
+#[named]
+#[test]
+fn test_rfc2136_is_valid() {
+    let test_name = function_name!();
+    engine_run_test(|| {
+        init();
+        let span = span!(Level::INFO, "test", name = test_name);
+        let _enter = span.enter();
+
+        // setup:
+        let (_bind, port) = init_bind_testcontainer();
+        let context = context_for_resource(Uuid::new_v4(), Uuid::new_v4());
+        let rfc2136 = |domain: &str, zone: &str, tsig_key_name: &str, tsig_secret: &str| {
+            Rfc2136::new(
+                context.clone(),
+                Uuid::new_v4(),
+                "Qovery Test RFC2136",
+                Domain::new(domain.to_string()),
+                "127.0.0.1",
+                port,
+                zone,
+                tsig_key_name,
+                tsig_secret,
+                Rfc2136TsigAlgorithm::HmacSha256,
+            )
+        };
+        let cluster_domain = format!("z{}.{}", generate_id(), BIND_ZONE);
+
+        // execute & verify:
+        assert_eq!(
+            rfc2136(cluster_domain.as_str(), BIND_ZONE, BIND_TSIG_KEY_NAME, BIND_TSIG_SECRET).is_valid(),
+            Ok(())
+        );
+        // unknown key, BIND answers unsigned errors which may be rejected before reading the response code
+        assert!(rfc2136(cluster_domain.as_str(), BIND_ZONE, "unknown-key", BIND_TSIG_SECRET)
+            .is_valid()
+            .is_err());
+        // domain not in the zone
+        assert!(matches!(
+            rfc2136("qovery-test.org", BIND_ZONE, BIND_TSIG_KEY_NAME, BIND_TSIG_SECRET).is_valid(),
+            Err(DnsProviderError::InvalidHostedZone { .. })
+        ));
+        // zone not served by the nameserver
+        assert!(matches!(
+            rfc2136("z.qovery-test.org", "qovery-test.org", BIND_TSIG_KEY_NAME, BIND_TSIG_SECRET).is_valid(),
+            Err(DnsProviderError::InvalidHostedZone { .. })
+        ));
+
+        test_name.to_string()
+    })
+}
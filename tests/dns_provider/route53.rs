@@ -0,0 +1,61 @@
+use crate::helpers::moto::{create_moto_hosted_zone, init_moto_testcontainer, MOTO_ACCESS_KEY, MOTO_SECRET_KEY};
+use crate::helpers::utilities::{context_for_resource, engine_run_test, generate_id, init};
+use function_name::named;
+use qovery_engine::dns_provider::errors::DnsProviderError;
+use qovery_engine::dns_provider::route53::Route53;
+use qovery_engine::dns_provider::DnsProvider;
+use qovery_engine::models::domain::Domain;
+use tracing::{span, Level};
+use uuid::Uuid;
+
+#[cfg(feature = "test-local-docker")]
+#[named]
+#[test]
+fn test_route53_is_valid() {
+    let test_name = function_name!();
+    engine_run_test(|| {
+        init();
+        let span = span!(Level::INFO, "test", name = test_name);
+        let _enter = span.enter();
+
+        // setup:
+        let (_moto, endpoint) = init_moto_testcontainer();
+        let zone_name = format!("{}.qovery-test.com", generate_id());
+        let hosted_zone_id = create_moto_hosted_zone(&endpoint, zone_name.as_str());
+        let context = context_for_resource(Uuid::new_v4(), Uuid::new_v4());
+        let route53 = |domain: &str, access_key_id: &str, hosted_zone_id: &str| {
+            Route53::new(
+                context.clone(),
+                Uuid::new_v4(),
+                "Qovery Test Route53",
+                Domain::new(domain.to_string()),
+                access_key_id,
+                MOTO_SECRET_KEY,
+                "eu-west-3",
+                hosted_zone_id,
+            )
+            .with_api_endpoint(endpoint.clone())
+        };
+
+        // execute & verify:
+        let cluster_domain = format!("z{}.{}", generate_id(), zone_name);
+        assert_eq!(
+            route53(cluster_domain.as_str(), MOTO_ACCESS_KEY, hosted_zone_id.as_str()).is_valid(),
+            Ok(())
+        );
+        assert_eq!(
+            route53(cluster_domain.as_str(), "", hosted_zone_id.as_str()).is_valid(),
+            Err(DnsProviderError::InvalidCredentials)
+        );
+        assert!(matches!(
+            route53("qovery-test.org", MOTO_ACCESS_KEY, hosted_zone_id.as_str()).is_valid(),
+            Err(DnsProviderError::InvalidHostedZone { .. })
+        ));
+        assert!(matches!(
+            route53(cluster_domain.as_str(), MOTO_ACCESS_KEY, "ZUNKNOWN").is_valid(),
+            Err(DnsProviderError::InvalidHostedZone { .. })
+        ));
+
+        test_name.to_string()
+    })
+}
@@ -594,7 +594,7 @@ fn gcp_gke_deploy_container_with_router() {
                 failure_threshold: 5,
             }),
             storages: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret:false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{value: general_purpose::STANDARD.encode("my_value"), is_secret:false, external_secret: None} },
             mounted_files: vec![],
             advanced_settings: Default::default(),
             annotations_group_ids: btreeset! { annotations_group_id },
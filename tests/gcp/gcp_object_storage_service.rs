@@ -89,6 +89,7 @@ fn test_bucket_exists() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -138,6 +139,7 @@ fn test_get_bucket() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -253,6 +255,7 @@ fn test_create_bucket_success() {
                 tc.input.bucket_ttl,
                 tc.input.bucket_versioning,
                 tc.input.bucket_labels.clone(),
+                None,
             )
             .unwrap_or_else(|_| panic!("Cannot create bucket for test `{}`", tc.description));
         // stick a guard on the bucket to delete bucket after test
@@ -303,6 +306,7 @@ fn test_update_bucket() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket");
     // stick a guard on the bucket to delete bucket after test
@@ -316,7 +320,7 @@ fn test_update_bucket() {
     // Bucket versioning
     for versioning in [true, false].iter() {
         // execute:
-        match service.update_bucket(existing_bucket.name.as_str(), *versioning) {
+        match service.update_bucket(existing_bucket.name.as_str(), *versioning, existing_bucket.ttl, None) {
             // verify:
             Ok(updated_bucket_result) => assert_eq!(versioning, &updated_bucket_result.versioning_activated),
             Err(e) => panic!("Cannot update bucket versioning: {}", e),
@@ -356,6 +360,7 @@ fn test_delete_bucket_using_run_job() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -420,6 +425,7 @@ fn test_delete_bucket_with_objects() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -474,6 +480,7 @@ fn test_empty_bucket_with_objects() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -539,6 +546,7 @@ fn test_list_bucket() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -592,6 +600,7 @@ fn test_list_bucket_from_prefix() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -644,6 +653,7 @@ fn test_put_object() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -704,6 +714,7 @@ fn test_get_object() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -767,6 +778,7 @@ fn test_list_objects_keys_only() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -844,6 +856,7 @@ fn test_list_objects_keys_only_with_prefix() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -938,6 +951,7 @@ fn test_list_objects() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -1015,6 +1029,7 @@ fn test_list_objects_with_prefix() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -1109,6 +1124,7 @@ fn test_delete_object() {
             Some(*GCP_RESOURCE_TTL),
             false,
             Some(HashMap::from([("test_name".to_string(), function_name!().to_string())])),
+            None,
         )
         .expect("Cannot create bucket")
         .name;
@@ -3,7 +3,9 @@ use crate::helpers::utilities::{context_for_cluster, logger, metrics_registry, F
 use base64::engine::general_purpose;
 use base64::Engine;
 use chrono::Utc;
-use qovery_engine::build_platform::{Build, GitRepository, Image, SshKey};
+use qovery_engine::build_platform::attestation::SbomFormat;
+use qovery_engine::build_platform::dockerfile_linter::{DockerfileLintPolicy, LintLevel};
+use qovery_engine::build_platform::{Build, CacheSettings, GitRepository, Image, ImageTagStrategy, SshKey};
 use qovery_engine::cloud_provider::aws::database_instance_type::AwsDatabaseInstanceType;
 use qovery_engine::cloud_provider::aws::{
     kubernetes::eks::EKS,
@@ -20,6 +22,7 @@ use qovery_engine::cloud_provider::models::{
 use qovery_engine::cloud_provider::qovery::EngineLocation;
 use qovery_engine::cloud_provider::service::{Action, Service};
 use qovery_engine::cloud_provider::{CloudProvider, DeploymentTarget};
+use qovery_engine::cmd::docker::CacheMode;
 use qovery_engine::engine::InfrastructureContext;
 use qovery_engine::events::{EnvironmentStep, EventDetails, Stage};
 use qovery_engine::fs::workspace_directory;
@@ -41,7 +44,7 @@ use qovery_engine::models::registry_image_source::RegistryImageSource;
 use qovery_engine::models::router::{Router, RouterAdvancedSettings};
 use qovery_engine::models::types::{VersionsNumber, AWS as AWSType};
 use qovery_engine::utilities::to_short_id;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::PathBuf;
 use std::string::ToString;
 use std::time::Duration;
@@ -188,6 +191,7 @@ fn test_env_var() -> EnvironmentVariable {
         key: "my_env_var_key".to_string(),
         value: "my_env_var_value".to_string(),
         is_secret: false,
+        external_secret: None,
     }
 }
 
@@ -248,6 +252,7 @@ pub fn test_application(test_kube: &dyn Kubernetes, domain: &str) -> Application
                 dockerfile_content: None,
                 root_path: PathBuf::from("my_root_path"),
                 buildpack_language: Some("my_language".to_string()),
+                generate_dockerfile: false,
             },
             image: Image {
                 service_id: "my_application_id".to_string(),
@@ -263,6 +268,7 @@ pub fn test_application(test_kube: &dyn Kubernetes, domain: &str) -> Application
                 repository_name: "my_image_repository_name".to_string(),
                 shared_repository_name: "my_image_shared_repository_name".to_string(),
                 shared_image_feature_enabled: false,
+                digest: None,
             },
             environment_variables: BTreeMap::new(),
             disable_cache: false,
@@ -271,6 +277,14 @@ pub fn test_application(test_kube: &dyn Kubernetes, domain: &str) -> Application
             max_cpu_in_milli: 2000,
             max_ram_in_gib: 4,
             registries: vec![],
+            sbom_format: None,
+            tag_strategy: ImageTagStrategy::CommitId,
+            source_tree_id: None,
+            cache_settings: CacheSettings::default(),
+            dockerfile_lint_policy: DockerfileLintPolicy::default(),
+            source_archive: None,
+            image_promotion: None,
+            vulnerability_scan_fail_on_severity: None,
         },
         vec![],
         None,
@@ -302,6 +316,7 @@ pub fn test_application(test_kube: &dyn Kubernetes, domain: &str) -> Application
             security_service_account_name: "".to_string(),
             security_read_only_root_filesystem: false,
             security_automount_service_account_token: false,
+            security_vulnerability_scan_fail_on_severity: None,
             deployment_termination_grace_period_seconds: 60,
             deployment_update_strategy_type: UpdateStrategy::RollingUpdate,
             deployment_update_strategy_rolling_update_max_unavailable_percent: 25,
@@ -311,6 +326,15 @@ pub fn test_application(test_kube: &dyn Kubernetes, domain: &str) -> Application
             build_timeout_max_sec: 2,
             build_cpu_max_in_milli: 2000,
             build_ram_max_in_gib: 4,
+            build_attestation_enabled: false,
+            build_attestation_sbom_format: SbomFormat::Spdx,
+            build_tag_from_git_tree: false,
+            build_watch_paths: vec![],
+            build_cache_mode: CacheMode::Max,
+            build_cache_export_enabled: true,
+            build_cache_default_branch: "main".to_string(),
+            build_dockerfile_lint_level: LintLevel::Warn,
+            build_dockerfile_lint_enforced_rules: BTreeSet::new(),
             network_ingress_proxy_body_size_mb: 3,
             network_ingress_cors_enable: true,
             network_ingress_sticky_session_enable: false,
@@ -331,6 +355,7 @@ pub fn test_application(test_kube: &dyn Kubernetes, domain: &str) -> Application
             network_ingress_whitelist_source_range: "my_network_ingress_whitelist_source_range".to_string(),
             network_ingress_denylist_source_range: "".to_string(),
             network_ingress_basic_auth_env_var: "".to_string(),
+            network_ingress_certificate_strict_check: false,
             network_ingress_grpc_send_timeout_seconds: 60,
             network_ingress_grpc_read_timeout_seconds: 60,
             hpa_cpu_average_utilization_percent: 31,
@@ -432,6 +457,7 @@ pub fn test_container(test_kube: &dyn Kubernetes) -> Container<AWSType> {
             network_ingress_whitelist_source_range: "my_network_ingress_whitelist_source_range".to_string(),
             network_ingress_denylist_source_range: "".to_string(),
             network_ingress_basic_auth_env_var: "".to_string(),
+            network_ingress_certificate_strict_check: false,
             network_ingress_grpc_send_timeout_seconds: 60,
             network_ingress_grpc_read_timeout_seconds: 60,
             hpa_cpu_average_utilization_percent: 41,
@@ -439,6 +465,7 @@ pub fn test_container(test_kube: &dyn Kubernetes) -> Container<AWSType> {
             security_service_account_name: "".to_string(),
             security_read_only_root_filesystem: false,
             security_automount_service_account_token: false,
+            security_vulnerability_scan_fail_on_severity: None,
         },
         AwsAppExtraSettings {},
         |transmitter| test_kube.context().get_event_details(transmitter),
@@ -512,6 +539,7 @@ pub fn test_managed_database(test_kube: &dyn Kubernetes) -> Database<AWSType, Ma
         DatabaseOptions {
             login: "my_managed_db_login".to_string(),
             password: "my_managed_db_password".to_string(),
+            previous_password: None,
             host: "my_managed_db_host".to_string(),
             port: 11,
             mode: DatabaseMode::MANAGED,
@@ -552,6 +580,7 @@ pub fn test_container_database(test_kube: &dyn Kubernetes) -> Database<AWSType,
         DatabaseOptions {
             login: "my_container_db_login".to_string(),
             password: "my_container_db_password".to_string(),
+            previous_password: None,
             host: "my_container_db_host".to_string(),
             port: 11,
             mode: DatabaseMode::CONTAINER,
@@ -586,6 +615,7 @@ pub fn test_router(test_kube: &dyn Kubernetes, app_id: Uuid) -> Router<AWSType>
             whitelist_source_range: None,
             denylist_source_range: None,
             basic_auth: None,
+            certificate_strict_check: false,
         },
         |transmitter| test_kube.context().get_event_details(transmitter),
         vec![],
@@ -639,9 +669,17 @@ fn test_job(test_kube: &dyn Kubernetes) -> Job<AWSType> {
             build_timeout_max_sec: 30 * 60,
             build_cpu_max_in_milli: 2000,
             build_ram_max_in_gib: 4,
+            build_tag_from_git_tree: false,
+            build_watch_paths: vec![],
+            build_cache_mode: CacheMode::Max,
+            build_cache_export_enabled: true,
+            build_cache_default_branch: "main".to_string(),
+            build_dockerfile_lint_level: LintLevel::Warn,
+            build_dockerfile_lint_enforced_rules: BTreeSet::new(),
             security_service_account_name: "".to_string(),
             security_read_only_root_filesystem: false,
             security_automount_service_account_token: false,
+            security_vulnerability_scan_fail_on_severity: None,
         },
         Some(Probe {
             r#type: ProbeType::Http {
@@ -138,6 +138,7 @@ impl Cluster<AWS, Options> for AWS {
             dns_provider,
             kubernetes,
             metrics_registry,
+            None,
             true,
         )
     }
@@ -0,0 +1,56 @@
+use testcontainers::core::{IntoContainerPort, WaitFor};
+use testcontainers::runners::SyncRunner;
+use testcontainers::{Container, GenericImage, ImageExt};
+
+pub const BIND_ZONE: &str = "qovery-test.com";
+pub const BIND_TSIG_KEY_NAME: &str = "qovery-key";
+// base64 encoded, as generated by `tsig-keygen`
+pub const BIND_TSIG_SECRET: &str = "cW92ZXJ5LWJpbmQtdHNpZy1zZWNyZXQtZm9yLXRlc3Rz";
+
+pub fn init_bind_testcontainer() -> (Container<GenericImage>, u16) {
+    let named_conf = format!(
+        r#"
+key "{key_name}" {{
+    algorithm hmac-sha256;
+    secret "{secret}";
+}};
+
+options {{
+    directory "/var/cache/bind";
+    listen-on {{ any; }};
+    allow-query {{ any; }};
+    recursion no;
+    dnssec-validation no;
+}};
+
+zone "{zone}" {{
+    type primary;
+    file "/var/lib/bind/db.{zone}";
+    update-policy {{ grant {key_name} zonesub ANY; }};
+}};
+"#,
+        key_name = BIND_TSIG_KEY_NAME,
+        secret = BIND_TSIG_SECRET,
+        zone = BIND_ZONE,
+    );
+    let zone_file = format!(
+        r#"$TTL 60
+@       IN SOA  ns1.{zone}. admin.{zone}. ( 1 60 60 60 60 )
+@       IN NS   ns1.{zone}.
+ns1     IN A    127.0.0.1
+"#,
+        zone = BIND_ZONE,
+    );
+
+    // see https://hub.docker.com/r/ubuntu/bind9
+    let container = GenericImage::new("ubuntu/bind9", "9.18-22.04_beta")
+        .with_exposed_port(53.udp())
+        .with_wait_for(WaitFor::message_on_stderr("running"))
+        .with_copy_to("/etc/bind/named.conf", named_conf.into_bytes())
+        .with_copy_to(format!("/var/lib/bind/db.{}", BIND_ZONE), zone_file.into_bytes())
+        .start()
+        .expect("BIND Started");
+    let port = container.get_host_port_ipv4(53.udp()).expect("BIND port exposed");
+
+    (container, port)
+}
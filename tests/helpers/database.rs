@@ -242,16 +242,19 @@ pub fn environment_3_apps_3_databases(
                 command_args: vec![],
                 entrypoint: None,
                 buildpack_language: None,
+                zero_config_build: false,
+                source_archive: None,
+                image_promotion: None,
                 root_path: "/".to_string(),
                 action: Action::Create,
                 git_credentials: None,
                 storage: vec![],
                 environment_vars_with_infos: btreemap! {
-                     "PG_DBNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_name.clone()), is_secret: false},
-                     "PG_HOST".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(fqdn.clone()),is_secret: false},
-                     "PG_PORT".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret: false},
-                     "PG_USERNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_username.clone()), is_secret: false},
-                     "PG_PASSWORD".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_password.clone()), is_secret: false},
+                     "PG_DBNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_name.clone()), is_secret: false, external_secret: None},
+                     "PG_HOST".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(fqdn.clone()),is_secret: false, external_secret: None},
+                     "PG_PORT".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret: false, external_secret: None},
+                     "PG_USERNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_username.clone()), is_secret: false, external_secret: None},
+                     "PG_PASSWORD".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_password.clone()), is_secret: false, external_secret: None},
                 },
                 mounted_files: vec![],
                 public_domain: format!("{}.example.com", app_id),
@@ -311,16 +314,19 @@ pub fn environment_3_apps_3_databases(
                 command_args: vec![],
                 entrypoint: None,
                 buildpack_language: None,
+                zero_config_build: false,
+                source_archive: None,
+                image_promotion: None,
                 root_path: String::from("/"),
                 action: Action::Create,
                 git_credentials: None,
                 storage: vec![],
                 environment_vars_with_infos: btreemap! {
-                     "PG_DBNAME".to_string() => VariableInfo {value: general_purpose::STANDARD.encode(database_name_2.clone()), is_secret: false },
-                     "PG_HOST".to_string() =>VariableInfo {value: general_purpose::STANDARD.encode(fqdn_2.clone()), is_secret: false },
-                     "PG_PORT".to_string() => VariableInfo {value:general_purpose::STANDARD.encode(database_port.to_string()), is_secret: false },
-                     "PG_USERNAME".to_string() =>VariableInfo {value: general_purpose::STANDARD.encode(database_username_2.clone()), is_secret: false },
-                     "PG_PASSWORD".to_string() => VariableInfo {value:general_purpose::STANDARD.encode(database_password.clone()), is_secret: false },
+                     "PG_DBNAME".to_string() => VariableInfo {value: general_purpose::STANDARD.encode(database_name_2.clone()), is_secret: false, external_secret: None },
+                     "PG_HOST".to_string() =>VariableInfo {value: general_purpose::STANDARD.encode(fqdn_2.clone()), is_secret: false, external_secret: None },
+                     "PG_PORT".to_string() => VariableInfo {value:general_purpose::STANDARD.encode(database_port.to_string()), is_secret: false, external_secret: None },
+                     "PG_USERNAME".to_string() =>VariableInfo {value: general_purpose::STANDARD.encode(database_username_2.clone()), is_secret: false, external_secret: None },
+                     "PG_PASSWORD".to_string() => VariableInfo {value:general_purpose::STANDARD.encode(database_password.clone()), is_secret: false, external_secret: None },
                 },
                 mounted_files: vec![],
                 ports: vec![Port {
@@ -380,18 +386,21 @@ pub fn environment_3_apps_3_databases(
                 command_args: vec![],
                 entrypoint: None,
                 buildpack_language: None,
+                zero_config_build: false,
+                source_archive: None,
+                image_promotion: None,
                 action: Action::Create,
                 root_path: String::from("/"),
                 git_credentials: None,
                 storage: vec![],
                 environment_vars_with_infos: btreemap! {
-                    "IS_DOCUMENTDB".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(false.to_string()), is_secret:false},
-                    "QOVERY_DATABASE_TESTING_DATABASE_FQDN".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(&database_host_mongo), is_secret:false},
-                    "QOVERY_DATABASE_MY_DDB_CONNECTION_URI".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_uri_mongo), is_secret:false},
-                    "QOVERY_DATABASE_TESTING_DATABASE_PORT".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_port_mongo.to_string()), is_secret:false},
-                    "MONGODB_DBNAME".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(&database_db_name_mongo), is_secret:false},
-                    "QOVERY_DATABASE_TESTING_DATABASE_USERNAME".to_string() =>VariableInfo { value:  general_purpose::STANDARD.encode(&database_username_mongo), is_secret:false},
-                    "QOVERY_DATABASE_TESTING_DATABASE_PASSWORD".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(&database_password_mongo), is_secret:false},
+                    "IS_DOCUMENTDB".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(false.to_string()), is_secret:false, external_secret: None},
+                    "QOVERY_DATABASE_TESTING_DATABASE_FQDN".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(&database_host_mongo), is_secret:false, external_secret: None},
+                    "QOVERY_DATABASE_MY_DDB_CONNECTION_URI".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_uri_mongo), is_secret:false, external_secret: None},
+                    "QOVERY_DATABASE_TESTING_DATABASE_PORT".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_port_mongo.to_string()), is_secret:false, external_secret: None},
+                    "MONGODB_DBNAME".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(&database_db_name_mongo), is_secret:false, external_secret: None},
+                    "QOVERY_DATABASE_TESTING_DATABASE_USERNAME".to_string() =>VariableInfo { value:  general_purpose::STANDARD.encode(&database_username_mongo), is_secret:false, external_secret: None},
+                    "QOVERY_DATABASE_TESTING_DATABASE_PASSWORD".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(&database_password_mongo), is_secret:false, external_secret: None},
                 },
                 mounted_files: vec![],
                 public_domain: format!("{}.example.com", app_id),
@@ -472,6 +481,7 @@ pub fn environment_3_apps_3_databases(
                 mode: CONTAINER,
                 annotations_group_ids: btreeset! {},
                 labels_group_ids: btreeset! {},
+                previous_password: None,
             },
             Database {
                 kind: DatabaseKind::Postgresql,
@@ -500,6 +510,7 @@ pub fn environment_3_apps_3_databases(
                 mode: CONTAINER,
                 annotations_group_ids: btreeset! {},
                 labels_group_ids: btreeset! {},
+                previous_password: None,
             },
             Database {
                 kind: DatabaseKind::Mongodb,
@@ -528,6 +539,7 @@ pub fn environment_3_apps_3_databases(
                 mode: CONTAINER,
                 annotations_group_ids: btreeset! {},
                 labels_group_ids: btreeset! {},
+                previous_password: None,
             },
         ],
         helms: vec![],
@@ -562,6 +574,9 @@ pub fn database_test_environment(context: &Context) -> EnvironmentRequest {
             command_args: vec![],
             entrypoint: None,
             buildpack_language: None,
+            zero_config_build: false,
+            source_archive: None,
+            image_promotion: None,
             root_path: String::from("/"),
             action: Action::Create,
             git_credentials: None,
@@ -620,6 +635,9 @@ pub fn database_test_environment_on_upgrade(context: &Context) -> EnvironmentReq
             command_args: vec![],
             entrypoint: None,
             buildpack_language: None,
+            zero_config_build: false,
+            source_archive: None,
+            image_promotion: None,
             root_path: String::from("/"),
             action: Action::Create,
             git_credentials: None,
@@ -763,6 +781,7 @@ pub fn test_db(
         mode: database_mode.clone(),
         annotations_group_ids: btreeset! {},
         labels_group_ids: btreeset! {},
+        previous_password: None,
     };
 
     environment.databases = vec![db.clone()];
@@ -1127,6 +1146,7 @@ pub fn test_pause_managed_db(
         mode: database_mode.clone(),
         annotations_group_ids: btreeset! {},
         labels_group_ids: btreeset! {},
+        previous_password: None,
     };
 
     environment.databases = vec![db];
@@ -1416,6 +1436,7 @@ pub fn test_db_on_upgrade(
         mode: database_mode.clone(),
         annotations_group_ids: btreeset! {},
         labels_group_ids: btreeset! {},
+        previous_password: None,
     };
 
     environment.databases = vec![db];
@@ -59,6 +59,9 @@ pub fn working_environment(
             command_args: vec![],
             entrypoint: None,
             buildpack_language: None,
+            zero_config_build: false,
+            source_archive: None,
+            image_promotion: None,
             root_path: String::from("/"),
             action: Action::Create,
             git_credentials: None,
@@ -189,6 +192,7 @@ pub fn working_environment_with_application_and_stateful_crashing_if_file_doesnt
             VariableInfo {
                 value: general_purpose::STANDARD.encode(&mount_file_env_var_value),
                 is_secret: false,
+                external_secret: None,
             }, // TODO check secret value
         ), // <- https://github.com/Qovery/engine-testing/blob/app-crashing-if-file-doesnt-exist/src/main.rs#L19
         (
@@ -196,6 +200,7 @@ pub fn working_environment_with_application_and_stateful_crashing_if_file_doesnt
             VariableInfo {
                 value: general_purpose::STANDARD.encode(&mount_file_env_var_value),
                 is_secret: false,
+                external_secret: None,
             },
         ), // <- mounted file PATH
     ]);
@@ -281,6 +286,7 @@ pub fn environment_2_app_2_routers_1_psql(
             mode: CONTAINER,
             annotations_group_ids: btreeset! {},
             labels_group_ids: btreeset! {},
+            previous_password: None,
         }],
         applications: vec![
             Application {
@@ -294,16 +300,19 @@ pub fn environment_2_app_2_routers_1_psql(
                 command_args: vec![],
                 entrypoint: None,
                 buildpack_language: None,
+                zero_config_build: false,
+                source_archive: None,
+                image_promotion: None,
                 root_path: String::from("/"),
                 action: Action::Create,
                 git_credentials: None,
                 storage: vec![],
                 environment_vars_with_infos: btreemap! {
-                     "PG_DBNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_name.clone()), is_secret: false},
-                     "PG_HOST".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(fqdn.clone()),is_secret: false},
-                     "PG_PORT".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret: false},
-                     "PG_USERNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_username.clone()), is_secret: false},
-                     "PG_PASSWORD".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_password.clone()), is_secret: false},
+                     "PG_DBNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_name.clone()), is_secret: false, external_secret: None},
+                     "PG_HOST".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(fqdn.clone()),is_secret: false, external_secret: None},
+                     "PG_PORT".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret: false, external_secret: None},
+                     "PG_USERNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_username.clone()), is_secret: false, external_secret: None},
+                     "PG_PASSWORD".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_password.clone()), is_secret: false, external_secret: None},
                 },
                 mounted_files: vec![],
                 ports: vec![Port {
@@ -363,16 +372,19 @@ pub fn environment_2_app_2_routers_1_psql(
                 command_args: vec![],
                 entrypoint: None,
                 buildpack_language: None,
+                zero_config_build: false,
+                source_archive: None,
+                image_promotion: None,
                 root_path: String::from("/"),
                 action: Action::Create,
                 git_credentials: None,
                 storage: vec![],
                 environment_vars_with_infos: btreemap! {
-                     "PG_DBNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_name.clone()), is_secret: false},
-                     "PG_HOST".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(fqdn.clone()),is_secret: false},
-                     "PG_PORT".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret: false},
-                     "PG_USERNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_username.clone()), is_secret: false},
-                     "PG_PASSWORD".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_password.clone()), is_secret: false},
+                     "PG_DBNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_name.clone()), is_secret: false, external_secret: None},
+                     "PG_HOST".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(fqdn.clone()),is_secret: false, external_secret: None},
+                     "PG_PORT".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret: false, external_secret: None},
+                     "PG_USERNAME".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_username.clone()), is_secret: false, external_secret: None},
+                     "PG_PASSWORD".to_string() => VariableInfo{value: general_purpose::STANDARD.encode(database_password.clone()), is_secret: false, external_secret: None},
                 },
                 mounted_files: vec![],
                 public_domain: format!("{}.{}", application_id2, test_domain),
@@ -503,12 +515,15 @@ pub fn echo_app_environment(context: &Context, test_domain: &str) -> Environment
             command_args: vec![],
             entrypoint: None,
             buildpack_language: None,
+            zero_config_build: false,
+            source_archive: None,
+            image_promotion: None,
             root_path: String::from("/"),
             action: Action::Create,
             git_credentials: None,
             storage: vec![],
             environment_vars_with_infos: btreemap! {
-                "ECHO_TEXT".to_string() => VariableInfo {value: general_purpose::STANDARD.encode("42"), is_secret: false},
+                "ECHO_TEXT".to_string() => VariableInfo {value: general_purpose::STANDARD.encode("42"), is_secret: false, external_secret: None},
             },
             mounted_files: vec![],
             branch: "echo-app".to_string(),
@@ -620,6 +635,9 @@ pub fn environment_only_http_server(
             command_args: vec![],
             entrypoint: None,
             buildpack_language: None,
+            zero_config_build: false,
+            source_archive: None,
+            image_promotion: None,
             root_path: String::from("/"),
             action: Action::Create,
             git_credentials: None,
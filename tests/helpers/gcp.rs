@@ -200,6 +200,7 @@ impl Cluster<Google, GkeOptions> for Gke {
             dns_provider,
             cluster,
             metrics_registry,
+            None,
             true,
         )
     }
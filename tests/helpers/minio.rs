@@ -0,0 +1,23 @@
+use testcontainers::core::{IntoContainerPort, WaitFor};
+use testcontainers::runners::SyncRunner;
+use testcontainers::{Container, GenericImage, ImageExt};
+use url::Url;
+
+pub const MINIO_ACCESS_KEY: &str = "qovery";
+pub const MINIO_SECRET_KEY: &str = "qovery-secret";
+
+pub fn init_minio_testcontainer() -> (Container<GenericImage>, Url) {
+    // see https://min.io/docs/minio/container/index.html
+    let container = GenericImage::new("minio/minio", "RELEASE.2024-10-13T13-34-11Z")
+        .with_exposed_port(9000.tcp())
+        .with_wait_for(WaitFor::message_on_stdout("API:"))
+        .with_env_var("MINIO_ROOT_USER", MINIO_ACCESS_KEY)
+        .with_env_var("MINIO_ROOT_PASSWORD", MINIO_SECRET_KEY)
+        .with_cmd(vec!["server", "/data"])
+        .start()
+        .expect("MinIO Started");
+    let port = container.get_host_port_ipv4(9000).expect("MinIO port exposed");
+    let endpoint = Url::parse(&format!("http://127.0.0.1:{}", port)).expect("valid MinIO endpoint");
+
+    (container, endpoint)
+}
@@ -1,5 +1,6 @@
 pub mod aws;
 pub mod aws_ec2;
+pub mod bind;
 pub mod common;
 pub mod database;
 pub mod dns;
@@ -7,6 +8,8 @@ pub mod environment;
 pub mod gcp;
 pub mod git_server;
 pub mod kubernetes;
+pub mod minio;
+pub mod moto;
 mod on_premise;
 pub mod scaleway;
 pub mod utilities;
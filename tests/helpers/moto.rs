@@ -0,0 +1,46 @@
+use qovery_engine::runtime::block_on;
+use rusoto_core::{HttpClient, Region};
+use rusoto_credential::StaticProvider;
+use rusoto_route53::{CreateHostedZoneRequest, Route53, Route53Client};
+use testcontainers::core::{IntoContainerPort, WaitFor};
+use testcontainers::runners::SyncRunner;
+use testcontainers::{Container, GenericImage};
+use url::Url;
+
+// moto doesn't check credentials, any non empty value is accepted
+pub const MOTO_ACCESS_KEY: &str = "qovery";
+pub const MOTO_SECRET_KEY: &str = "qovery-secret";
+
+pub fn init_moto_testcontainer() -> (Container<GenericImage>, Url) {
+    // see https://docs.getmoto.org/en/latest/docs/server_mode.html
+    let container = GenericImage::new("motoserver/moto", "5.0.18")
+        .with_exposed_port(5000.tcp())
+        .with_wait_for(WaitFor::message_on_stderr("Running on"))
+        .start()
+        .expect("moto Started");
+    let port = container.get_host_port_ipv4(5000).expect("moto port exposed");
+    let endpoint = Url::parse(&format!("http://127.0.0.1:{}", port)).expect("valid moto endpoint");
+
+    (container, endpoint)
+}
+
+/// Creates a Route53 hosted zone in moto and returns its id
+pub fn create_moto_hosted_zone(endpoint: &Url, zone_name: &str) -> String {
+    let client = Route53Client::new_with(
+        HttpClient::new().expect("cannot instantiate http client"),
+        StaticProvider::new(MOTO_ACCESS_KEY.to_string(), MOTO_SECRET_KEY.to_string(), None, None),
+        Region::Custom {
+            name: "us-east-1".to_string(),
+            endpoint: endpoint.as_str().trim_end_matches('/').to_string(),
+        },
+    );
+
+    block_on(client.create_hosted_zone(CreateHostedZoneRequest {
+        name: zone_name.to_string(),
+        caller_reference: zone_name.to_string(),
+        ..Default::default()
+    }))
+    .expect("cannot create hosted zone")
+    .hosted_zone
+    .id
+}
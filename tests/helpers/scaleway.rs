@@ -152,6 +152,7 @@ impl Cluster<Scaleway, KapsuleOptions> for Scaleway {
             dns_provider,
             cluster,
             metrics_registry,
+            None,
             true,
         )
     }
@@ -829,13 +829,13 @@ pub fn db_infos(
                 db_name: database_db_name.to_string(),
                 app_commit: "ff9028ee18177daed83393c158dac6059824573b".to_string(),
                 app_env_vars: btreemap! {
-                    "IS_DOCUMENTDB".to_string() => VariableInfo { value: general_purpose::STANDARD.encode((database_mode == DatabaseMode::MANAGED).to_string()), is_secret:false},
-                    "QOVERY_DATABASE_TESTING_DATABASE_FQDN".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(db_fqdn), is_secret:false},
-                    "QOVERY_DATABASE_MY_DDB_CONNECTION_URI".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_uri), is_secret:false},
-                    "QOVERY_DATABASE_TESTING_DATABASE_PORT".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret:false},
-                    "MONGODB_DBNAME".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_db_name), is_secret:false},
-                    "QOVERY_DATABASE_TESTING_DATABASE_USERNAME".to_string() =>VariableInfo { value:  general_purpose::STANDARD.encode(database_username), is_secret:false},
-                    "QOVERY_DATABASE_TESTING_DATABASE_PASSWORD".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_password), is_secret:false},
+                    "IS_DOCUMENTDB".to_string() => VariableInfo { value: general_purpose::STANDARD.encode((database_mode == DatabaseMode::MANAGED).to_string()), is_secret:false, external_secret: None},
+                    "QOVERY_DATABASE_TESTING_DATABASE_FQDN".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(db_fqdn), is_secret:false, external_secret: None},
+                    "QOVERY_DATABASE_MY_DDB_CONNECTION_URI".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_uri), is_secret:false, external_secret: None},
+                    "QOVERY_DATABASE_TESTING_DATABASE_PORT".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret:false, external_secret: None},
+                    "MONGODB_DBNAME".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_db_name), is_secret:false, external_secret: None},
+                    "QOVERY_DATABASE_TESTING_DATABASE_USERNAME".to_string() =>VariableInfo { value:  general_purpose::STANDARD.encode(database_username), is_secret:false, external_secret: None},
+                    "QOVERY_DATABASE_TESTING_DATABASE_PASSWORD".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_password), is_secret:false, external_secret: None},
                 },
             }
         }
@@ -847,11 +847,11 @@ pub fn db_infos(
                 db_name: database_db_name.to_string(),
                 app_commit: "ef8df03b56d942424dc4943ffb9d8d69431e72bb".to_string(),
                 app_env_vars: btreemap! {
-                    "MYSQL_HOST".to_string() =>VariableInfo { value: general_purpose::STANDARD.encode(db_fqdn), is_secret:false},
-                    "MYSQL_PORT".to_string() => VariableInfo { value:general_purpose::STANDARD.encode(database_port.to_string()), is_secret:false},
-                    "MYSQL_DBNAME".to_string()   => VariableInfo { value:general_purpose::STANDARD.encode(database_db_name), is_secret:false},
-                    "MYSQL_USERNAME".to_string() => VariableInfo { value:general_purpose::STANDARD.encode(database_username), is_secret:false},
-                    "MYSQL_PASSWORD".to_string() => VariableInfo { value:general_purpose::STANDARD.encode(database_password), is_secret:false},
+                    "MYSQL_HOST".to_string() =>VariableInfo { value: general_purpose::STANDARD.encode(db_fqdn), is_secret:false, external_secret: None},
+                    "MYSQL_PORT".to_string() => VariableInfo { value:general_purpose::STANDARD.encode(database_port.to_string()), is_secret:false, external_secret: None},
+                    "MYSQL_DBNAME".to_string()   => VariableInfo { value:general_purpose::STANDARD.encode(database_db_name), is_secret:false, external_secret: None},
+                    "MYSQL_USERNAME".to_string() => VariableInfo { value:general_purpose::STANDARD.encode(database_username), is_secret:false, external_secret: None},
+                    "MYSQL_PASSWORD".to_string() => VariableInfo { value:general_purpose::STANDARD.encode(database_password), is_secret:false, external_secret: None},
                 },
             }
         }
@@ -867,11 +867,11 @@ pub fn db_infos(
                 db_name: database_db_name.to_string(),
                 app_commit: "f379e5b937c743adf96f9484956260da170bb93c".to_string(),
                 app_env_vars: btreemap! {
-                     "PG_DBNAME".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_db_name), is_secret:false},
-                     "PG_HOST".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(db_fqdn), is_secret:false},
-                     "PG_PORT".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret:false},
-                     "PG_USERNAME".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_username), is_secret:false},
-                     "PG_PASSWORD".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_password), is_secret:false},
+                     "PG_DBNAME".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_db_name), is_secret:false, external_secret: None},
+                     "PG_HOST".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(db_fqdn), is_secret:false, external_secret: None},
+                     "PG_PORT".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_port.to_string()), is_secret:false, external_secret: None},
+                     "PG_USERNAME".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_username), is_secret:false, external_secret: None},
+                     "PG_PASSWORD".to_string() => VariableInfo { value: general_purpose::STANDARD.encode(database_password), is_secret:false, external_secret: None},
                 },
             }
         }
@@ -883,11 +883,11 @@ pub fn db_infos(
                 db_name: database_db_name,
                 app_commit: "c8dd8b57a4ebafabc860f0b948f881dad5ab632e".to_string(),
                 app_env_vars: btreemap! {
-                "IS_ELASTICCACHE".to_string() => VariableInfo { value: general_purpose::STANDARD.encode((database_mode == DatabaseMode::MANAGED && database_username == "default").to_string()), is_secret:false},
-                "REDIS_HOST".to_string()      => VariableInfo { value: general_purpose::STANDARD.encode(db_fqdn), is_secret:false},
-                "REDIS_PORT".to_string()      =>VariableInfo { value:  general_purpose::STANDARD.encode(database_port.to_string()), is_secret:false},
-                "REDIS_USERNAME".to_string()  => VariableInfo { value: general_purpose::STANDARD.encode(database_username), is_secret:false},
-                "REDIS_PASSWORD".to_string()  =>VariableInfo { value:  general_purpose::STANDARD.encode(database_password), is_secret:false},
+                "IS_ELASTICCACHE".to_string() => VariableInfo { value: general_purpose::STANDARD.encode((database_mode == DatabaseMode::MANAGED && database_username == "default").to_string()), is_secret:false, external_secret: None},
+                "REDIS_HOST".to_string()      => VariableInfo { value: general_purpose::STANDARD.encode(db_fqdn), is_secret:false, external_secret: None},
+                "REDIS_PORT".to_string()      =>VariableInfo { value:  general_purpose::STANDARD.encode(database_port.to_string()), is_secret:false, external_secret: None},
+                "REDIS_USERNAME".to_string()  => VariableInfo { value: general_purpose::STANDARD.encode(database_username), is_secret:false, external_secret: None},
+                "REDIS_PASSWORD".to_string()  =>VariableInfo { value:  general_purpose::STANDARD.encode(database_password), is_secret:false, external_secret: None},
                 },
             }
         }
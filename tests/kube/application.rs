@@ -70,6 +70,7 @@ fn should_increase_app_storage_size() {
                 key: k.to_string(),
                 value: variable_infos.value.to_string(),
                 is_secret: variable_infos.is_secret,
+                external_secret: None,
             })
             .collect::<Vec<EnvironmentVariable>>();
         let app: Application<AWS> = Application::new(
@@ -234,6 +235,7 @@ fn should_have_mounted_files_as_volume() {
                 VariableInfo {
                     value: general_purpose::STANDARD.encode(&mount_file_env_var_value),
                     is_secret: false,
+                    external_secret: None,
                 },
             ), // <- https://github.com/Qovery/engine-testing/blob/app-crashing-if-file-doesnt-exist/src/main.rs#L19
             (
@@ -241,6 +243,7 @@ fn should_have_mounted_files_as_volume() {
                 VariableInfo {
                     value: general_purpose::STANDARD.encode(&mount_file_env_var_value),
                     is_secret: false,
+                    external_secret: None,
                 },
             ), // <- mounted file PATH
         ]);
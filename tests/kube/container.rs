@@ -72,6 +72,7 @@ fn should_increase_container_storage_size() {
                 key: k.to_string(),
                 value: variable_infos.value.to_string(),
                 is_secret: variable_infos.is_secret,
+                external_secret: None,
             })
             .collect::<Vec<EnvironmentVariable>>();
         let container: Container<AWS> = Container::new(
@@ -236,6 +237,7 @@ fn should_have_mounted_files_as_volume() {
                 VariableInfo {
                     value: general_purpose::STANDARD.encode(mount_file_env_var_value),
                     is_secret: false,
+                    external_secret: None,
                 },
             ), // <- mounted file PATH
         ]);
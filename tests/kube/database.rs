@@ -72,6 +72,7 @@ fn should_increase_db_storage_size() {
             DatabaseOptions {
                 login: resized_db.username.to_string(),
                 password: resized_db.password.to_string(),
+                previous_password: None,
                 host: resized_db.fqdn.to_string(),
                 port: resized_db.port,
                 mode: resized_db.mode.clone(),
@@ -76,6 +76,7 @@ fn should_have_mounted_files_as_volume() {
                 VariableInfo {
                     value: general_purpose::STANDARD.encode(mount_file_env_var_value),
                     is_secret: false,
+                    external_secret: None,
                 },
             ), // <- mounted file PATH
         ]);
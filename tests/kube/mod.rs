@@ -102,6 +102,7 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
                 database_instance_type: None,
                 annotations_group_ids: btreeset! {},
                 labels_group_ids: btreeset! {},
+                previous_password: None,
             };
             environment.databases = vec![db];
         }
@@ -214,6 +215,9 @@ pub fn kube_test_env(options: TestEnvOption) -> (InfrastructureContext, Environm
                 command_args: vec![],
                 entrypoint: None,
                 buildpack_language: None,
+                zero_config_build: false,
+                source_archive: None,
+                image_promotion: None,
                 root_path: String::from("/"),
                 action: Action::Create,
                 git_credentials: None,
@@ -4,8 +4,10 @@ extern crate core;
 
 mod aws;
 mod container_registries;
+mod dns_provider;
 mod gcp;
 mod helm;
 pub mod helpers;
 mod kube;
+mod object_storage;
 mod scaleway;
@@ -0,0 +1,2 @@
+#[cfg(feature = "test-local-docker")]
+mod s3_compatible;
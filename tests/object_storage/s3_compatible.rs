@@ -0,0 +1,252 @@
+use crate::helpers::minio::{init_minio_testcontainer, MINIO_ACCESS_KEY, MINIO_SECRET_KEY};
+use crate::helpers::utilities::{engine_run_test, generate_id, init};
+use function_name::named;
+use qovery_engine::object_storage::errors::ObjectStorageError;
+use qovery_engine::object_storage::s3_compatible::S3CompatibleOS;
+use qovery_engine::object_storage::{
+    BucketDeleteStrategy, BucketLifecycleAction, BucketLifecyclePolicy, BucketLifecycleRule, BucketObjectLock,
+    BucketObjectLockMode, MultipartUploadConfig, ObjectStorage,
+};
+use std::io::{Cursor, Write};
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tracing::{span, Level};
+
+#[cfg(feature = "test-local-docker")]
+#[named]
+#[test]
+fn test_s3_compatible_bucket_and_object_lifecycle() {
+    let test_name = function_name!();
+    engine_run_test(|| {
+        init();
+        let span = span!(Level::INFO, "test", name = test_name);
+        let _enter = span.enter();
+
+        // setup:
+        let (_minio, endpoint) = init_minio_testcontainer();
+        let minio = S3CompatibleOS::new(
+            generate_id().to_string(),
+            "test".to_string(),
+            MINIO_ACCESS_KEY.to_string(),
+            MINIO_SECRET_KEY.to_string(),
+            endpoint,
+            "",
+            true,
+            None,
+        )
+        .expect("cannot instantiate S3-compatible client");
+        let bucket_name = format!("qovery-test-bucket-{}", generate_id());
+
+        // create bucket:
+        let bucket = minio
+            .create_bucket(bucket_name.as_str(), Some(Duration::from_secs(3600)), true, None)
+            .expect("cannot create bucket");
+        assert!(bucket.versioning_activated);
+        assert_eq!(
+            bucket.labels.unwrap_or_default().get("Ttl").map(|ttl| ttl.as_str()),
+            Some("3600")
+        );
+        assert!(minio.bucket_exists(bucket_name.as_str()));
+
+        let bucket = minio
+            .update_bucket(bucket_name.as_str(), false, None)
+            .expect("cannot update bucket");
+        assert!(!bucket.versioning_activated);
+
+        // put and get object:
+        let mut file = NamedTempFile::new().expect("cannot create temp file");
+        file.write_all(b"hello from qovery").unwrap();
+        let object_key = "archives/my file.txt";
+        minio
+            .put_object(
+                bucket_name.as_str(),
+                object_key,
+                file.path(),
+                Some(vec!["service=test".to_string()]),
+            )
+            .expect("cannot put object");
+
+        let object = minio
+            .get_object(bucket_name.as_str(), object_key)
+            .expect("cannot get object");
+        assert_eq!(object.value, b"hello from qovery".to_vec());
+        assert_eq!(object.tags, vec!["service=test".to_string()]);
+
+        // delete object:
+        assert!(minio.delete_object(bucket_name.as_str(), object_key).is_ok());
+        assert!(minio.get_object(bucket_name.as_str(), object_key).is_err());
+
+        // delete bucket, even when not empty:
+        minio
+            .put_object(bucket_name.as_str(), "another-file", file.path(), None)
+            .expect("cannot put object");
+        assert!(minio
+            .delete_bucket(bucket_name.as_str(), BucketDeleteStrategy::HardDelete)
+            .is_ok());
+        assert!(!minio.bucket_exists(bucket_name.as_str()));
+
+        test_name.to_string()
+    })
+}
+
+#[cfg(feature = "test-local-docker")]
+#[named]
+#[test]
+fn test_s3_compatible_list_stream_and_copy_objects() {
+    let test_name = function_name!();
+    engine_run_test(|| {
+        init();
+        let span = span!(Level::INFO, "test", name = test_name);
+        let _enter = span.enter();
+
+        // setup:
+        let (_minio, endpoint) = init_minio_testcontainer();
+        let part_size_in_bytes = 5 * 1024 * 1024; // minimum part size accepted by MinIO
+        let minio = S3CompatibleOS::new(
+            generate_id().to_string(),
+            "test".to_string(),
+            MINIO_ACCESS_KEY.to_string(),
+            MINIO_SECRET_KEY.to_string(),
+            endpoint,
+            "",
+            true,
+            None,
+        )
+        .expect("cannot instantiate S3-compatible client")
+        .with_multipart_upload_config(MultipartUploadConfig {
+            threshold_in_bytes: part_size_in_bytes,
+            part_size_in_bytes,
+        });
+        let bucket_name = format!("qovery-test-bucket-{}", generate_id());
+        minio
+            .create_bucket(bucket_name.as_str(), None, false, None)
+            .expect("cannot create bucket");
+
+        // write with a multipart upload and read it back as a stream:
+        let content: Vec<u8> = (0..(2 * part_size_in_bytes + 42)).map(|i| (i % 251) as u8).collect();
+        let written = minio
+            .write_object(
+                bucket_name.as_str(),
+                "archives/big.tgz",
+                &mut Cursor::new(content.clone()),
+                None,
+            )
+            .expect("cannot write object");
+        assert_eq!(written, content.len() as u64);
+
+        let mut read_content = vec![];
+        let read = minio
+            .read_object(bucket_name.as_str(), "archives/big.tgz", &mut read_content)
+            .expect("cannot read object");
+        assert_eq!(read, content.len() as u64);
+        assert_eq!(read_content, content);
+
+        // copy and list:
+        minio
+            .copy_object(bucket_name.as_str(), "archives/big.tgz", bucket_name.as_str(), "copies/big.tgz")
+            .expect("cannot copy object");
+        let mut small_file = NamedTempFile::new().expect("cannot create temp file");
+        small_file.write_all(b"small").unwrap();
+        minio
+            .put_object(bucket_name.as_str(), "archives/small.txt", small_file.path(), None)
+            .expect("cannot put object");
+
+        let archives = minio
+            .list_objects(bucket_name.as_str(), Some("archives/"), None)
+            .expect("cannot list objects");
+        assert_eq!(
+            archives
+                .objects
+                .iter()
+                .map(|object| (object.key.as_str(), object.size_in_bytes))
+                .collect::<Vec<_>>(),
+            vec![("archives/big.tgz", content.len() as u64), ("archives/small.txt", 5)]
+        );
+        assert!(archives.continuation_token.is_none());
+
+        let copies = minio
+            .list_objects(bucket_name.as_str(), Some("copies/"), None)
+            .expect("cannot list objects");
+        assert_eq!(copies.objects.len(), 1);
+        assert_eq!(copies.objects[0].size_in_bytes, content.len() as u64);
+
+        assert!(minio
+            .delete_bucket(bucket_name.as_str(), BucketDeleteStrategy::HardDelete)
+            .is_ok());
+
+        test_name.to_string()
+    })
+}
+
+#[cfg(feature = "test-local-docker")]
+#[named]
+#[test]
+fn test_s3_compatible_lifecycle_policy_and_object_lock() {
+    let test_name = function_name!();
+    engine_run_test(|| {
+        init();
+        let span = span!(Level::INFO, "test", name = test_name);
+        let _enter = span.enter();
+
+        // setup:
+        let (_minio, endpoint) = init_minio_testcontainer();
+        let minio = S3CompatibleOS::new(
+            generate_id().to_string(),
+            "test".to_string(),
+            MINIO_ACCESS_KEY.to_string(),
+            MINIO_SECRET_KEY.to_string(),
+            endpoint,
+            "",
+            true,
+            None,
+        )
+        .expect("cannot instantiate S3-compatible client");
+        let bucket_name = format!("qovery-test-bucket-{}", generate_id());
+        let lifecycle_policy = BucketLifecyclePolicy {
+            rules: vec![BucketLifecycleRule {
+                prefix: Some("archives/".to_string()),
+                action: BucketLifecycleAction::ExpireAfterDays(7),
+            }],
+            object_lock: Some(BucketObjectLock {
+                mode: BucketObjectLockMode::Governance,
+                retention_days: 1,
+            }),
+        };
+
+        // execute:
+        let bucket = minio
+            .create_bucket(bucket_name.as_str(), None, true, Some(&lifecycle_policy))
+            .expect("cannot create bucket");
+
+        // verify:
+        assert_eq!(bucket.lifecycle_rules, lifecycle_policy.rules);
+        assert_eq!(bucket.object_lock, lifecycle_policy.object_lock);
+
+        let bucket = minio
+            .update_bucket(
+                bucket_name.as_str(),
+                true,
+                Some(&BucketLifecyclePolicy {
+                    rules: vec![],
+                    object_lock: None,
+                }),
+            )
+            .expect("cannot update bucket");
+        assert!(bucket.lifecycle_rules.is_empty());
+        assert_eq!(bucket.object_lock, lifecycle_policy.object_lock);
+
+        // object lock can't be activated on an existing bucket:
+        let unlocked_bucket_name = format!("qovery-test-bucket-{}", generate_id());
+        minio
+            .create_bucket(unlocked_bucket_name.as_str(), None, true, None)
+            .expect("cannot create bucket");
+        assert_eq!(
+            minio.update_bucket(unlocked_bucket_name.as_str(), true, Some(&lifecycle_policy)),
+            Err(ObjectStorageError::ObjectLockNotActivated {
+                bucket_name: unlocked_bucket_name.clone(),
+            })
+        );
+
+        test_name.to_string()
+    })
+}
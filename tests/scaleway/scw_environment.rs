@@ -1632,7 +1632,7 @@ fn deploy_container_with_no_router_on_scw() {
                 failure_threshold: 5,
             }),
             storages: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode("my_value"), is_secret: false, external_secret: None} },
             mounted_files: vec![],
             advanced_settings: Default::default(),
             annotations_group_ids: BTreeSet::new(),
@@ -1749,7 +1749,7 @@ fn deploy_container_on_scw_with_mounted_files_as_volume() {
                 },
             ],
             storages: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() =>  VariableInfo{ value: general_purpose::STANDARD.encode("my_value"), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() =>  VariableInfo{ value: general_purpose::STANDARD.encode("my_value"), is_secret: false, external_secret: None} },
             mounted_files: vec![mounted_file.clone()],
             advanced_settings: Default::default(),
             readiness_probe: Some(Probe {
@@ -1906,7 +1906,7 @@ fn deploy_container_with_router_on_scw() {
                 },
             ],
             storages: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{ value:general_purpose::STANDARD.encode("my_value"), is_secret: false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{ value:general_purpose::STANDARD.encode("my_value"), is_secret: false, external_secret: None} },
             mounted_files: vec![],
             advanced_settings: Default::default(),
             readiness_probe: Some(Probe {
@@ -2666,7 +2666,7 @@ fn deploy_container_with_tcp_public_port() {
                 failure_threshold: 50,
             }),
             storages: vec![],
-            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode("my_value"), is_secret:false} },
+            environment_vars_with_infos: btreemap! { "MY_VAR".to_string() => VariableInfo{ value: general_purpose::STANDARD.encode("my_value"), is_secret:false, external_secret: None} },
             mounted_files: vec![],
             advanced_settings: Default::default(),
             annotations_group_ids: BTreeSet::new(),
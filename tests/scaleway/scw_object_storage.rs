@@ -42,6 +42,7 @@ fn test_delete_bucket_hard_delete_strategy() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            None,
         );
         assert!(create_result.is_ok());
         info!("Bucket {} created.", bucket_name);
@@ -86,6 +87,7 @@ fn test_delete_bucket_empty_strategy() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            None,
         );
         assert!(create_result.is_ok());
         info!("Bucket {} created.", bucket_name);
@@ -131,6 +133,7 @@ fn test_create_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            None,
         );
 
         // validate:
@@ -178,6 +181,7 @@ fn test_get_bucket() {
                 bucket_name.as_str(),
                 Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
                 false,
+                None,
             )
             .expect("Cannot create bucket");
 
@@ -228,6 +232,7 @@ fn test_recreate_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            None,
         );
         assert!(create_result.is_ok());
         info!("Bucket {} created.", bucket_name);
@@ -243,6 +248,7 @@ fn test_recreate_bucket() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            None,
         );
         assert!(recreate_result.is_ok());
         info!("Bucket {} recreated.", bucket_name);
@@ -288,6 +294,7 @@ fn test_file_handling() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            None,
         );
         assert!(create_result.is_ok());
         info!("Bucket {} created.", bucket_name);
@@ -348,6 +355,7 @@ fn test_ensure_file_is_absent() {
             bucket_name.as_str(),
             Some(Duration::from_secs(SCW_BUCKET_TTL_IN_SECONDS)),
             false,
+            None,
         );
         assert!(create_result.is_ok());
         info!("Bucket {} created.", bucket_name);